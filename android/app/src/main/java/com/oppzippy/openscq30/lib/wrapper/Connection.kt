@@ -13,6 +13,9 @@ enum class ConnectionStatus {
 
     @SerialName("disconnected")
     Disconnected,
+
+    @SerialName("reconnecting")
+    Reconnecting,
 }
 
 @Serializable
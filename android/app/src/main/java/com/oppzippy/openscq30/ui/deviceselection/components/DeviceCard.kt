@@ -132,6 +132,8 @@ private fun DeviceModelIcon(modifier: Modifier, model: String) {
         "SoundcoreA3035" -> HeadphonesIcon(modifier)
         "SoundcoreA3040" -> HeadphonesIcon(modifier)
         "SoundcoreA3116" -> SpeakerIcon(modifier)
+        "SoundcoreA3130" -> SpeakerIcon(modifier)
+        "SoundcoreA3330" -> EarbudsIcon(modifier)
         "SoundcoreA3926" -> EarbudsIcon(modifier)
         "SoundcoreA3930" -> EarbudsIcon(modifier)
         "SoundcoreA3931" -> EarbudsIcon(modifier)
@@ -145,7 +147,9 @@ private fun DeviceModelIcon(modifier: Modifier, model: String) {
         "SoundcoreA3949" -> EarbudsIcon(modifier)
         "SoundcoreA3951" -> EarbudsIcon(modifier)
         "SoundcoreA3955" -> EarbudsIcon(modifier)
+        "SoundcoreA3958" -> EarbudsIcon(modifier)
         "SoundcoreA3959" -> EarbudsIcon(modifier)
+        "SoundcoreA6611" -> EarbudsIcon(modifier)
         "SoundcoreDevelopment" -> HeadphonesIcon(modifier)
         else -> HeadphonesIcon(modifier)
     }
@@ -25,6 +25,7 @@ sealed class Screen {
                 "buttonConfiguration" -> R.drawable.radio_button_checked_24px
                 "deviceInformation" -> R.drawable.info_24px
                 "equalizerImportExport" -> R.drawable.swap_vert_24px
+                "perChannelEqualizer" -> R.drawable.equalizer_24px
                 "dualConnections" -> R.drawable.devices_other_24px
                 "limitHighVolume" -> R.drawable.volume_down_24px
                 "case" -> R.drawable.earbud_case_24px
@@ -47,7 +47,9 @@ import androidx.compose.ui.res.painterResource
 import androidx.compose.ui.res.stringResource
 import androidx.compose.ui.unit.dp
 import com.oppzippy.openscq30.R
+import com.oppzippy.openscq30.lib.bindings.equalizerFrequencyResponse
 import com.oppzippy.openscq30.lib.bindings.translateSettingId
+import com.oppzippy.openscq30.lib.wrapper.Equalizer as EqualizerLayout
 import com.oppzippy.openscq30.lib.wrapper.ModifiableSelectCommandInner
 import com.oppzippy.openscq30.lib.wrapper.Setting
 import com.oppzippy.openscq30.lib.wrapper.Value
@@ -420,17 +422,26 @@ private fun EqualizerLine(modifier: Modifier = Modifier, volumeAdjustments: List
     )
 }
 
+private val presetEqualizerLayout = EqualizerLayout(
+    bandHz = listOf(100, 200, 400, 800, 1600, 3200, 6400, 12800).map { it.toUShort() },
+    fractionDigits = 1,
+    min = -120,
+    max = 134,
+)
+
 private fun equalizerLinePoints(width: Float, height: Float, padding: Float, values: List<Int>): List<Offset> {
     val widthWithoutPadding = width - padding * 2
     val heightWithoutPadding = height - padding * 2
-    val minVolume = -60
-    val maxVolume = 60
-    val range = maxVolume - minVolume
+    val minDb = -6.0
+    val maxDb = 6.0
+    val range = maxDb - minDb
 
-    val points = values.mapIndexed { index, value ->
-        val normalizedX = index.toFloat() / (values.size - 1).toFloat()
+    // Points are evenly spaced on a logarithmic scale, so they can be drawn evenly spaced too
+    val frequencyResponse = equalizerFrequencyResponse(presetEqualizerLayout, values.map { it.toShort() })
+    val points = frequencyResponse.mapIndexed { index, point ->
+        val normalizedX = index.toFloat() / (frequencyResponse.size - 1).toFloat()
         val x = normalizedX * widthWithoutPadding + padding
-        val normalizedY = 1F - ((value - minVolume) / range.toFloat())
+        val normalizedY = 1F - ((point.db - minDb) / range).coerceIn(0.0, 1.0).toFloat()
         val y = normalizedY * heightWithoutPadding + padding
         Offset(x, y)
     }
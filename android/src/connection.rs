@@ -7,6 +7,7 @@ use openscq30_lib::{
     connection::{
         self, ConnectionStatus, RfcommBackend, RfcommConnection, RfcommServiceSelectionStrategy,
     },
+    unsupported::UnsupportedGattBackend,
 };
 use thiserror::Error;
 use tokio::sync::{mpsc, watch};
@@ -29,12 +30,18 @@ impl ManualConnectionBackends {
 
 impl ConnectionBackends for ManualConnectionBackends {
     type Rfcomm = ManualRfcommConnectionBackend;
+    // Only RFCOMM connections are made from the Android side so far
+    type Gatt = UnsupportedGattBackend;
 
     async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
         Ok(ManualRfcommConnectionBackend {
             inner: self.rfcomm.to_owned(),
         })
     }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        Ok(UnsupportedGattBackend)
+    }
 }
 
 #[uniffi::export(with_foreign)]
@@ -0,0 +1,22 @@
+use crate::serializable;
+
+#[derive(uniffi::Record)]
+pub struct FrequencyResponsePoint {
+    hz: f64,
+    db: f64,
+}
+
+/// Approximate frequency response for plotting, evenly spaced on a logarithmic scale from 20hz to 20khz.
+#[uniffi::export]
+pub fn equalizer_frequency_response(
+    equalizer: serializable::Equalizer,
+    volume_adjustments: Vec<i16>,
+) -> Vec<FrequencyResponsePoint> {
+    equalizer
+        .0
+        .frequency_response(&volume_adjustments)
+        .points
+        .into_iter()
+        .map(|(hz, db)| FrequencyResponsePoint { hz, db })
+        .collect()
+}
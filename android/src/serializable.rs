@@ -58,6 +58,12 @@ uniffi::custom_type!(Setting, String, {
     lower: |val| serde_json::to_string(&val.0).expect("json serialization shouldn't fail"),
 });
 
+pub struct Equalizer(pub settings::Equalizer);
+uniffi::custom_type!(Equalizer, String, {
+    try_lift: |json| Ok(Equalizer(serde_json::from_str(&json)?)),
+    lower: |val| serde_json::to_string(&val.0).expect("json serialization shouldn't fail"),
+});
+
 pub struct Value(pub settings::Value);
 uniffi::custom_type!(Value, String, {
     try_lift: |json| Ok(Value(serde_json::from_str(&json)?)),
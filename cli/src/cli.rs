@@ -1,11 +1,18 @@
+mod backup;
 mod completions;
+mod debug;
 mod device;
+mod list_adapters;
 mod list_models;
 mod pair;
 
+use std::path::PathBuf;
+
 use clap::{ArgAction, ArgMatches, Command, arg, value_parser};
 use macaddr::MacAddr6;
-use openscq30_lib::DeviceModel;
+use openscq30_lib::{
+    DeviceModel, quick_presets::ImportConflictResolution, storage::BackupImportMode,
+};
 
 pub fn build() -> Command {
     let mac_address_arg = arg!(-a --"mac-address" <MAC_ADDRESS> "Device's mac address")
@@ -15,6 +22,7 @@ pub fn build() -> Command {
         .required(true)
         .value_parser(value_parser!(DeviceModel));
     let json_arg = arg!(-j --json "Output as JSON");
+    let quick_preset_name_arg = arg!(name: <NAME> "Quick preset name");
     Command::new(env!("CARGO_BIN_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
         .about("Application for managing Soundcore's bluetooth headphones, earbuds, and speakers")
@@ -32,6 +40,10 @@ Once the device is paired, see `openscq30 device` to interact with device settin
             arg!(-v --verbose "Enables logging with warn log level. Using this argument multiple times will decrease min log level, up to -vvvv.")
                 .action(ArgAction::Count),
         )
+        .arg(
+            arg!(--adapter <NAME_OR_ADDRESS> "Only use the bluetooth adapter with this name (such as hci0) or address. Devices paired while this is set will remember the adapter. Linux only.")
+                .global(true),
+        )
         .subcommand_required(true)
         .subcommand(
             Command::new("paired-devices")
@@ -43,11 +55,23 @@ Once the device is paired, see `openscq30 device` to interact with device settin
                         .after_help(
 "openscq30 needs to know what model your device is. This is where you provide the necessary information to be able to connect to a device. It should already be paired using bluetooth at this point.
 
+If --model is omitted, openscq30 will try to detect the model by looking at the device's bluetooth services, and if that doesn't work, by connecting to it and checking its serial number. Detection requires the device to be connected, so --model is required with --demo.
+
 See `openscq30 list-models` for a list of supported device models. This should also be used to find the model id if you only know the name. Soundcore Life Q30 is SoundcoreA3028 for example."
                         )
                         .arg(mac_address_arg.to_owned())
-                        .arg(device_model_arg.to_owned())
-                        .arg(arg!(--"demo" "Enable demo mode for the device")),
+                        .arg(
+                            device_model_arg
+                                .to_owned()
+                                .required(false)
+                                .required_if_eq("demo", "true"),
+                        )
+                        .arg(arg!(--"demo" "Enable demo mode for the device"))
+                        .arg(
+                            arg!(--"device-definition" <FILE> "Detect the model of a fake device served from a soundcore-device-faker TOML file rather than over bluetooth")
+                                .value_parser(value_parser!(PathBuf))
+                                .conflicts_with("model"),
+                        ),
                 )
                 .subcommand(
                     Command::new("remove")
@@ -62,7 +86,7 @@ See `openscq30 list-models` for a list of supported device models. This should a
                         .after_help(
 r#"By default, this will output a table with a column for device model, mac address, and if it's a demo or real device.
 
-JSON output (--json) is recommended for scripts. It will instead output an array of objects with the previously mentioned columns as keys instead.
+JSON output (--json) is recommended for scripts. It will instead output an array of objects with the previously mentioned columns as keys instead. `adapterAddress` is only present for devices that were paired with --adapter.
 
 Here is example output with the --json flag:
 [
@@ -85,6 +109,21 @@ Here is example output with the --json flag:
             Command::new("device")
                 .about("Device settings")
                 .arg(mac_address_arg.to_owned())
+                .arg(
+                    arg!(--"device-definition" <FILE> "Connect to a fake device served from a soundcore-device-faker TOML file rather than over bluetooth")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(--"demo-events" <EVENT> "Make a demo device change on its own. May be used multiple times")
+                        .value_parser([
+                            "single-battery-drain",
+                            "dual-battery-drain",
+                            "case-cycle",
+                            "tws-disconnect",
+                            "wind-noise",
+                        ])
+                        .action(ArgAction::Append),
+                )
                 .subcommand_required(true)
                 .subcommand(
                     Command::new("list-settings")
@@ -201,6 +240,166 @@ Equalizers: All bands must be specified, and numbers should be separated with ',
                         )
                         .arg(json_arg.clone()),
                 )
+                .subcommand(
+                    Command::new("watch")
+                        .about("Stream setting changes as JSON lines")
+                        .after_help(
+r#"Stays connected to the device, printing one JSON object per line whenever something changes. The first line contains the current value of every watched setting, and each following settingsChanged line contains only the settings that changed. A setting that becomes unavailable will have a value of null. If the connection is lost, openscq30 will try to reconnect, and will exit with an error if it gives up.
+
+`--setting` may be used multiple times to only watch specific settings. By default, all settings are watched.
+
+Values are in the same format as `openscq30 device setting --json`. Example output:
+{"type":"settingsChanged","settings":{"ambientSoundMode":{"type":"string","value":"NoiseCanceling"}}}
+{"type":"connectionStatusChanged","connectionStatus":"reconnecting"}
+{"type":"connectionStatusChanged","connectionStatus":"connected"}
+{"type":"settingsChanged","settings":{"ambientSoundMode":{"type":"string","value":"Normal"}}}"#
+                        )
+                        .arg(
+                            arg!(-s --setting <SETTING_ID> "Only watch this setting")
+                                .action(ArgAction::Append),
+                        ),
+                )
+                .subcommand(
+                    Command::new("quick-preset")
+                        .about("Manage quick presets")
+                        .after_help(
+"A quick preset is a saved set of setting values that can be applied all at once. Saving a quick preset stores the current value of every setting, but only the settings that are enabled will be set when the quick preset is activated. Newly saved quick presets have no settings enabled, so use `openscq30 device quick-preset edit` to choose which ones should be set.
+
+Saving over an existing quick preset updates its values but keeps the same settings enabled.
+
+Quick presets are specific to a device model, so they are shared between all paired devices of the same model."
+                        )
+                        .subcommand_required(true)
+                        .subcommand(
+                            Command::new("list")
+                                .alias("ls")
+                                .about("List all quick presets for the device's model")
+                                .arg(json_arg.clone()),
+                        )
+                        .subcommand(
+                            Command::new("save")
+                                .about("Save the device's current settings to a quick preset")
+                                .arg(quick_preset_name_arg.clone())
+                                .arg(json_arg.clone()),
+                        )
+                        .subcommand(
+                            Command::new("activate")
+                                .about("Set all enabled settings of a quick preset")
+                                .arg(quick_preset_name_arg.clone())
+                                .arg(json_arg.clone()),
+                        )
+                        .subcommand(
+                            Command::new("delete")
+                                .alias("remove")
+                                .about("Delete a quick preset")
+                                .arg(quick_preset_name_arg.clone())
+                                .arg(json_arg.clone()),
+                        )
+                        .subcommand(
+                            Command::new("export")
+                                .about("Export all quick presets for the device's model as JSON")
+                                .arg(
+                                    arg!(-o --output <FILE> "Write to a file rather than stdout")
+                                        .value_parser(value_parser!(PathBuf)),
+                                ),
+                        )
+                        .subcommand(
+                            Command::new("import")
+                                .about("Import quick presets exported with `openscq30 device quick-preset export`")
+                                .after_help(
+"Only quick presets for the device's model are imported. Each setting is checked against the device, and settings that the device would not accept, such as options that don't exist, are left out and reported.
+
+When a quick preset with the same name already exists, `--on-conflict` decides what happens:
+- skip: keep the existing quick preset (default)
+- overwrite: replace the existing quick preset
+- rename: import with a number appended to the name, such as \"Commute (2)\""
+                                )
+                                .arg(
+                                    arg!(file: <FILE> "File to import from, or - for stdin")
+                                        .value_parser(value_parser!(PathBuf)),
+                                )
+                                .arg(
+                                    arg!(-c --"on-conflict" <RESOLUTION> "skip, overwrite, or rename")
+                                        .value_parser(value_parser!(ImportConflictResolution))
+                                        .default_value("skip"),
+                                )
+                                .arg(json_arg.clone()),
+                        )
+                        .subcommand(
+                            Command::new("edit")
+                                .about("Choose which settings a quick preset will set when activated")
+                                .arg(quick_preset_name_arg)
+                                .arg(json_arg.clone())
+                                .arg(
+                                    arg!(-e --enable <SETTING_ID> "Set this setting when the quick preset is activated")
+                                        .action(ArgAction::Append),
+                                )
+                                .arg(
+                                    arg!(-d --disable <SETTING_ID> "Don't set this setting when the quick preset is activated")
+                                        .action(ArgAction::Append),
+                                ),
+                        ),
+                )
+        )
+        .subcommand(
+            Command::new("backup")
+                .about("Back up and restore paired devices, quick presets, and custom equalizer profiles")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("export")
+                        .about("Export everything openscq30 has saved as JSON")
+                        .arg(
+                            arg!(-o --output <FILE> "Write to a file rather than stdout")
+                                .value_parser(value_parser!(PathBuf)),
+                        ),
+                )
+                .subcommand(
+                    Command::new("import")
+                        .about("Restore a backup created with `openscq30 backup export`")
+                        .after_help(
+"Either the entire backup is restored, or if something goes wrong, nothing is changed.
+
+--mode decides what happens to what is already saved:
+- merge: keep existing data, overwriting paired devices with the same mac address and quick presets or equalizer profiles with the same name (default)
+- replace: delete all existing data before restoring the backup"
+                        )
+                        .arg(
+                            arg!(file: <FILE> "File to import from, or - for stdin")
+                                .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            arg!(--mode <MODE> "merge or replace")
+                                .value_parser(value_parser!(BackupImportMode))
+                                .default_value("merge"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("debug")
+                .about("Tools for debugging device communication")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("decode")
+                        .about("Decode raw Soundcore packets")
+                        .after_help(
+"Packets are split out of the input, and the body of each packet is decoded with the parsers used for the specified model. Checksum failures, unknown commands, and bytes that are not part of a packet are reported rather than skipped.
+
+--format decides how the input is read:
+- hex: hex bytes, optionally separated by whitespace, commas, or colons, with or without 0x prefixes (default)
+- capture: a capture recorded by openscq30
+- btsnoop: a btsnoop HCI log, such as btsnoop_hci.log from an Android bug report. Only the first RFCOMM channel with Soundcore packets is decoded."
+                        )
+                        .arg(device_model_arg.to_owned())
+                        .arg(
+                            arg!(file: <FILE> "File to decode, or - for stdin")
+                                .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            arg!(--format <FORMAT> "hex, capture, or btsnoop")
+                                .value_parser(["hex", "capture", "btsnoop"])
+                                .default_value("hex"),
+                        ),
+                ),
         )
         .subcommand(
             Command::new("list-models")
@@ -208,6 +407,14 @@ Equalizers: All bands must be specified, and numbers should be separated with ',
                 .after_help(
                     "Device models are locale-independent identifiers for each device. This command will list all models as well as their names in English. Used for `openscq30 paired-devices add --model`."
                 )
+                .arg(json_arg.clone())
+        )
+        .subcommand(
+            Command::new("list-adapters")
+                .about("List bluetooth adapters that can be used with --adapter")
+                .after_help(
+                    "Only supported on Linux. On other platforms, the list will always be empty."
+                )
                 .arg(json_arg)
         )
         .subcommand(
@@ -225,8 +432,11 @@ pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
     match matches.subcommand().unwrap() {
         ("paired-devices", matches) => pair::handle(matches).await?,
         ("device", matches) => device::handle(matches).await?,
+        ("backup", matches) => backup::handle(matches).await?,
+        ("debug", matches) => debug::handle(matches)?,
         ("completions", matches) => completions::handle(matches)?,
         ("list-models", matches) => list_models::handle(matches)?,
+        ("list-adapters", matches) => list_adapters::handle(matches).await?,
         _ => (),
     }
     Ok(())
@@ -0,0 +1,91 @@
+use std::{io::Read, path::PathBuf};
+
+use anyhow::Context;
+use clap::ArgMatches;
+use openscq30_lib::{
+    InvalidBackupQuickPreset, OpenSCQ30Session,
+    storage::{Backup, BackupImportMode},
+};
+use tabled::{Table, Tabled};
+
+use crate::openscq30_session;
+
+pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
+    let session = openscq30_session(matches).await?;
+    match matches.subcommand().unwrap() {
+        ("export", matches) => handle_export(matches, &session).await?,
+        ("import", matches) => handle_import(matches, &session).await?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+async fn handle_export(matches: &ArgMatches, session: &OpenSCQ30Session) -> anyhow::Result<()> {
+    let json = session.export_backup().await?.to_json();
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => {
+            std::fs::write(path, json).with_context(|| format!("writing {}", path.display()))?;
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+async fn handle_import(matches: &ArgMatches, session: &OpenSCQ30Session) -> anyhow::Result<()> {
+    let path = matches.get_one::<PathBuf>("file").unwrap();
+    let json = if path.as_os_str() == "-" {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .context("reading stdin")?;
+        json
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+    };
+    let backup = Backup::from_json(&json)?;
+    let mode = *matches.get_one::<BackupImportMode>("mode").unwrap();
+    let report = session
+        .import_backup(backup, mode)
+        .await
+        .context("restoring backup")?;
+    if !report.invalid_quick_presets.is_empty() {
+        println!(
+            "Some quick preset settings were left out since their device model would not accept them:"
+        );
+        let mut table = Table::new(
+            report
+                .invalid_quick_presets
+                .into_iter()
+                .map(InvalidQuickPresetTableItem::from),
+        );
+        crate::fmt::apply_tabled_settings(&mut table);
+        println!("{table}");
+    }
+    println!("OK");
+    Ok(())
+}
+
+#[derive(Tabled)]
+struct InvalidQuickPresetTableItem {
+    #[tabled(rename = "Model")]
+    device_model: String,
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Invalid Settings")]
+    invalid_settings: String,
+}
+
+impl From<InvalidBackupQuickPreset> for InvalidQuickPresetTableItem {
+    fn from(invalid_quick_preset: InvalidBackupQuickPreset) -> Self {
+        Self {
+            device_model: invalid_quick_preset.device_model.to_string(),
+            name: invalid_quick_preset.name,
+            invalid_settings: invalid_quick_preset
+                .invalid_fields
+                .into_iter()
+                .map(|field| format!("{}: {}", field.setting_id, field.reason))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, anyhow};
+use clap::ArgMatches;
+use openscq30_lib::{
+    DeviceModel,
+    btsnoop::BtsnoopLog,
+    capture::{Capture, CaptureDirection},
+    decode::{Checksum, DecodedItem, DecodedPacket, Decoder, Direction, PacketContents},
+};
+
+pub fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
+    match matches.subcommand().unwrap() {
+        ("decode", matches) => handle_decode(matches)?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn handle_decode(matches: &ArgMatches) -> anyhow::Result<()> {
+    let model = *matches.get_one::<DeviceModel>("model").unwrap();
+    let path = matches.get_one::<PathBuf>("file").unwrap();
+    let input = if path.as_os_str() == "-" {
+        let mut input = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut input)
+            .context("reading stdin")?;
+        input
+    } else {
+        std::fs::read(path).with_context(|| format!("reading {}", path.display()))?
+    };
+
+    let decoder = Decoder::new(model);
+    match matches.get_one::<String>("format").unwrap().as_str() {
+        "hex" => {
+            let data = parse_hex(&String::from_utf8_lossy(&input))?;
+            for item in decoder.decode(&data) {
+                print_item(&format!("{:>6}", item_offset(&item)), &item);
+            }
+        }
+        "capture" => {
+            let capture = Capture::from_reader(BufReader::new(input.as_slice()))
+                .context("parsing capture")?;
+            print_capture(&decoder, capture);
+        }
+        "btsnoop" => {
+            let capture = BtsnoopLog::from_bytes(&input)
+                .context("parsing btsnoop log")?
+                .to_capture(decoder.checksum_kind())
+                .ok_or_else(|| anyhow!("no Soundcore packets found in btsnoop log"))?;
+            print_capture(&decoder, capture);
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn print_capture(decoder: &Decoder, capture: Capture) {
+    // Packets can be split across multiple reads, so the end of a read is held on to until the next read in the
+    // same direction
+    let mut pending = HashMap::<CaptureDirection, Vec<u8>>::new();
+    for entry in capture.entries {
+        let timestamp = format!(
+            "{}.{:06}s",
+            entry.elapsed_micros / 1_000_000,
+            entry.elapsed_micros % 1_000_000,
+        );
+        let buffer = pending.entry(entry.direction).or_default();
+        buffer.extend(entry.data);
+        let mut items = decoder.decode(buffer);
+        buffer.clear();
+        if let Some(DecodedItem::Truncated { bytes, .. }) = items.last() {
+            buffer.extend(bytes);
+            items.pop();
+        }
+        for item in items {
+            if let DecodedItem::Packet { packet, .. } = &item
+                && direction_mismatch(entry.direction, packet.direction)
+            {
+                println!("{timestamp} warning: packet direction does not match capture");
+            }
+            print_item(&timestamp, &item);
+        }
+    }
+    for direction in [CaptureDirection::Outbound, CaptureDirection::Inbound] {
+        if let Some(bytes) = pending.remove(&direction)
+            && !bytes.is_empty()
+        {
+            print_item("end", &DecodedItem::Truncated { offset: 0, bytes });
+        }
+    }
+}
+
+fn direction_mismatch(capture_direction: CaptureDirection, packet_direction: Direction) -> bool {
+    !matches!(
+        (capture_direction, packet_direction),
+        (CaptureDirection::Inbound, Direction::Inbound)
+            | (CaptureDirection::Outbound, Direction::Outbound)
+    )
+}
+
+fn parse_hex(input: &str) -> anyhow::Result<Vec<u8>> {
+    let digits = input
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+        .map(|token| {
+            token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token)
+        })
+        .collect::<String>();
+    if let Some(invalid) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(anyhow!("invalid hex digit {invalid:?}"));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(anyhow!("odd number of hex digits"));
+    }
+    // every digit is ascii, so slicing by byte offset can't split a char
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .with_context(|| format!("invalid hex byte {}", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+fn item_offset(item: &DecodedItem) -> usize {
+    match item {
+        DecodedItem::Packet { offset, .. }
+        | DecodedItem::Garbage { offset, .. }
+        | DecodedItem::Truncated { offset, .. } => *offset,
+    }
+}
+
+fn print_item(prefix: &str, item: &DecodedItem) {
+    match item {
+        DecodedItem::Packet { packet, .. } => print_packet(prefix, packet),
+        DecodedItem::Garbage { bytes, .. } => {
+            println!("{prefix} not a packet: {}", hex(bytes));
+        }
+        DecodedItem::Truncated { bytes, .. } => {
+            println!("{prefix} truncated packet: {}", hex(bytes));
+        }
+    }
+}
+
+fn print_packet(prefix: &str, packet: &DecodedPacket) {
+    let direction = match packet.direction {
+        Direction::Inbound => "inbound ",
+        Direction::Outbound => "outbound",
+    };
+    let command = hex(&packet.command.0);
+    let description = match &packet.contents {
+        PacketContents::Ack { name } => format!("ack ({name})"),
+        PacketContents::Decoded { name, .. }
+        | PacketContents::Undecoded { name }
+        | PacketContents::Invalid { name, .. } => name.to_string(),
+        PacketContents::UnknownCommand => "unknown command".to_owned(),
+    };
+    println!("{prefix} {direction} {command} {description}");
+
+    if let Checksum::Invalid { expected, actual } = packet.checksum {
+        println!("    checksum mismatch: expected {expected:02x}, got {actual:02x}");
+    }
+    match &packet.contents {
+        PacketContents::Decoded { fields, .. } => {
+            for line in fields.lines() {
+                println!("    {line}");
+            }
+        }
+        PacketContents::Invalid { error, .. } => {
+            println!("    failed to parse body: {error}");
+            println!("    body: {}", hex(&packet.body));
+        }
+        PacketContents::Undecoded { .. } | PacketContents::UnknownCommand
+            if !packet.body.is_empty() =>
+        {
+            println!("    body: {}", hex(&packet.body));
+        }
+        _ => (),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,14 +1,22 @@
-use anyhow::{Context, anyhow};
+mod quick_preset;
+
+use std::{collections::HashSet, path::PathBuf, time::Duration};
+
+use anyhow::{Context, anyhow, bail};
 use clap::ArgMatches;
 use indexmap::IndexMap;
 use macaddr::MacAddr6;
 use openscq30_lib::{
-    device::OpenSCQ30Device,
+    DemoEvents, OpenSCQ30Session,
+    connection::ConnectionStatus,
+    device::{OpenSCQ30Device, ReconnectPolicy},
+    device_definition::DeviceDefinition,
     settings::{self, CategoryId, SettingId},
 };
 use serde::Serialize;
 use strum::VariantArray;
 use tabled::{Table, Tabled};
+use tokio::select;
 
 use crate::{
     fmt::{CustomDisplaySetting, DisplayableValue},
@@ -16,11 +24,27 @@ use crate::{
 };
 
 pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
-    let session = openscq30_session().await?;
+    let mut session = openscq30_session(matches).await?;
+    if matches.subcommand_name() == Some("watch") {
+        session = session.with_reconnect_policy(ReconnectPolicy::default());
+    }
+    if let Some(path) = matches.get_one::<PathBuf>("device-definition") {
+        let device_definition = DeviceDefinition::load(path)
+            .with_context(|| format!("loading device definition {}", path.display()))?;
+        session = session.with_device_definition(device_definition);
+    }
     let mac_address = matches
         .get_one::<MacAddr6>("mac-address")
         .unwrap()
         .to_owned();
+    if let Some(event_names) = matches.get_many::<String>("demo-events") {
+        let mut demo_events = DemoEvents::new();
+        for event_name in event_names {
+            demo_events =
+                demo_events.chain(demo_events_by_name(&session, mac_address, event_name).await?);
+        }
+        session = session.with_demo_events(demo_events.repeat());
+    }
     let device = session.connect(mac_address).await?;
     match matches.subcommand().unwrap() {
         ("list-settings", matches) => {
@@ -29,11 +53,45 @@ pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
         ("setting", matches) => {
             handle_setting(matches, device.as_ref()).await?;
         }
+        ("watch", matches) => {
+            handle_watch(matches, device.as_ref()).await?;
+        }
+        ("quick-preset", matches) => {
+            quick_preset::handle(matches, session.quick_preset_handler(), device.as_ref()).await?;
+        }
         _ => unreachable!(),
     }
     Ok(())
 }
 
+async fn demo_events_by_name(
+    session: &OpenSCQ30Session,
+    mac_address: MacAddr6,
+    event_name: &str,
+) -> anyhow::Result<DemoEvents> {
+    Ok(match event_name {
+        "single-battery-drain" => DemoEvents::single_battery_drain(5, Duration::from_secs(10)),
+        "dual-battery-drain" => DemoEvents::dual_battery_drain(5, Duration::from_secs(10)),
+        "case-cycle" => DemoEvents::case_cycle(Duration::from_secs(10), Duration::from_secs(10)),
+        "tws-disconnect" => {
+            DemoEvents::tws_disconnect(Duration::from_secs(10), Duration::from_secs(5))
+        }
+        "wind-noise" => {
+            let model = session
+                .paired_devices()
+                .await?
+                .into_iter()
+                .find(|paired_device| paired_device.mac_address == mac_address)
+                .map(|paired_device| paired_device.model)
+                .ok_or_else(|| anyhow!("no paired device with mac address {mac_address}"))?;
+            model
+                .demo_wind_noise_detected_events(Duration::from_secs(5), Duration::from_secs(5))
+                .ok_or_else(|| anyhow!("{model} does not detect wind noise"))?
+        }
+        _ => unreachable!(),
+    })
+}
+
 async fn handle_list_settings(
     matches: &ArgMatches,
     device: &dyn OpenSCQ30Device,
@@ -337,3 +395,104 @@ impl From<SettingIdValue> for SettingIdValueTableItem {
         }
     }
 }
+
+async fn handle_watch(matches: &ArgMatches, device: &dyn OpenSCQ30Device) -> anyhow::Result<()> {
+    let setting_id_filter = matches
+        .get_many::<String>("setting")
+        .map(|setting_ids| {
+            setting_ids
+                .map(|setting_id| setting_id_from_str(setting_id))
+                .collect::<anyhow::Result<HashSet<_>>>()
+        })
+        .transpose()?;
+    let watched_settings = || {
+        device
+            .categories()
+            .into_iter()
+            .flat_map(|category_id| device.settings_in_category(&category_id))
+            .filter(|setting_id| {
+                setting_id_filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.contains(setting_id))
+            })
+            .filter_map(|setting_id| Some((setting_id, device.setting(&setting_id)?.into())))
+            .collect::<IndexMap<SettingId, settings::Value>>()
+    };
+
+    // subscribe before reading the initial state so that no changes are missed in between
+    let mut changes = device.watch_for_changes();
+    let mut connection_status = device.connection_status();
+    let mut settings = watched_settings();
+    print_watch_event(&WatchEvent::SettingsChanged {
+        settings: settings
+            .iter()
+            .map(|(setting_id, value)| (*setting_id, Some(value.clone())))
+            .collect(),
+    })?;
+
+    loop {
+        select! {
+            result = changes.changed() => {
+                if result.is_err() {
+                    bail!("{} disconnected", device.model());
+                }
+                let new_settings = watched_settings();
+                let changed_settings = diff_settings(&settings, &new_settings);
+                settings = new_settings;
+                if !changed_settings.is_empty() {
+                    print_watch_event(&WatchEvent::SettingsChanged {
+                        settings: changed_settings,
+                    })?;
+                }
+            }
+            result = connection_status.changed() => {
+                if result.is_err() {
+                    bail!("{} disconnected", device.model());
+                }
+                let status = *connection_status.borrow_and_update();
+                print_watch_event(&WatchEvent::ConnectionStatusChanged {
+                    connection_status: status,
+                })?;
+                if status == ConnectionStatus::Disconnected {
+                    bail!("{} disconnected", device.model());
+                }
+            }
+        }
+    }
+}
+
+/// Settings that were added or changed map to their new value, and settings that were removed map to `None`.
+fn diff_settings(
+    old: &IndexMap<SettingId, settings::Value>,
+    new: &IndexMap<SettingId, settings::Value>,
+) -> IndexMap<SettingId, Option<settings::Value>> {
+    let changed = new
+        .iter()
+        .filter(|(setting_id, value)| old.get(*setting_id) != Some(*value))
+        .map(|(setting_id, value)| (*setting_id, Some(value.clone())));
+    let removed = old
+        .keys()
+        .filter(|setting_id| !new.contains_key(*setting_id))
+        .map(|setting_id| (*setting_id, None));
+    changed.chain(removed).collect()
+}
+
+fn print_watch_event(event: &WatchEvent) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string(event)?);
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum WatchEvent {
+    SettingsChanged {
+        settings: IndexMap<SettingId, Option<settings::Value>>,
+    },
+    ConnectionStatusChanged {
+        connection_status: ConnectionStatus,
+    },
+}
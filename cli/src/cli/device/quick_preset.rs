@@ -0,0 +1,265 @@
+use std::{io::Read, path::PathBuf};
+
+use anyhow::Context;
+use clap::ArgMatches;
+use openscq30_lib::{
+    device::OpenSCQ30Device,
+    quick_presets::{
+        ImportConflictResolution, ImportedQuickPreset, QuickPresetsExport, QuickPresetsHandler,
+        QuickPresetsImportReport,
+    },
+    settings::{SettingId, Value},
+    storage::QuickPreset,
+};
+use serde::Serialize;
+use tabled::{Table, Tabled};
+
+use crate::fmt::DisplayableValue;
+
+use super::setting_id_from_str;
+
+pub async fn handle(
+    matches: &ArgMatches,
+    handler: QuickPresetsHandler,
+    device: &(dyn OpenSCQ30Device + Send + Sync),
+) -> anyhow::Result<()> {
+    match matches.subcommand().unwrap() {
+        ("list", matches) => {
+            let quick_presets = handler.quick_presets(device).await?;
+            if matches.get_flag("json") {
+                println!("{}", serde_json::to_string_pretty(&quick_presets)?);
+            } else {
+                let mut table =
+                    Table::new(quick_presets.into_iter().map(QuickPresetTableItem::from));
+                crate::fmt::apply_tabled_settings(&mut table);
+                println!("{table}");
+            }
+        }
+        ("save", matches) => {
+            let name = matches.get_one::<String>("name").unwrap();
+            handler
+                .save(device, name.to_owned())
+                .await
+                .with_context(|| format!("saving quick preset {name}"))?;
+            print_result(matches, &handler, device, name).await?;
+        }
+        ("activate", matches) => {
+            let name = matches.get_one::<String>("name").unwrap();
+            handler
+                .activate(device, name)
+                .await
+                .with_context(|| format!("activating quick preset {name}"))?;
+            print_result(matches, &handler, device, name).await?;
+        }
+        ("delete", matches) => {
+            let name = matches.get_one::<String>("name").unwrap();
+            // Fetch before deleting so that the deleted quick preset can be printed
+            let quick_preset = find_quick_preset(&handler, device, name).await?;
+            handler
+                .delete(device, name.to_owned())
+                .await
+                .with_context(|| format!("deleting quick preset {name}"))?;
+            if matches.get_flag("json") {
+                println!("{}", serde_json::to_string_pretty(&quick_preset)?);
+            } else {
+                println!("OK");
+            }
+        }
+        ("export", matches) => {
+            let json = handler.export([device.model()]).await?.to_json();
+            match matches.get_one::<PathBuf>("output") {
+                Some(path) => std::fs::write(path, json)
+                    .with_context(|| format!("writing {}", path.display()))?,
+                None => println!("{json}"),
+            }
+        }
+        ("import", matches) => {
+            let path = matches.get_one::<PathBuf>("file").unwrap();
+            let json = if path.as_os_str() == "-" {
+                let mut json = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut json)
+                    .context("reading stdin")?;
+                json
+            } else {
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?
+            };
+            let export = QuickPresetsExport::from_json(&json)?;
+            let conflict_resolution = *matches
+                .get_one::<ImportConflictResolution>("on-conflict")
+                .unwrap();
+            let report = handler.import(device, export, conflict_resolution).await?;
+            if matches.get_flag("json") {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&JsonImportReport::from(report))?
+                );
+            } else {
+                let mut table = Table::new(ImportTableItem::from_report(report));
+                crate::fmt::apply_tabled_settings(&mut table);
+                println!("{table}");
+            }
+        }
+        ("edit", matches) => {
+            let name = matches.get_one::<String>("name").unwrap();
+            let enable = matches
+                .get_many::<String>("enable")
+                .unwrap_or_default()
+                .map(|setting_id| Ok((setting_id_from_str(setting_id)?, true)));
+            let disable = matches
+                .get_many::<String>("disable")
+                .unwrap_or_default()
+                .map(|setting_id| Ok((setting_id_from_str(setting_id)?, false)));
+            let toggles = enable.chain(disable).collect::<anyhow::Result<Vec<_>>>()?;
+            for (setting_id, is_enabled) in toggles {
+                handler
+                    .toggle_field(device, name.to_owned(), setting_id, is_enabled)
+                    .await
+                    .with_context(|| format!("toggling {setting_id} in quick preset {name}"))?;
+            }
+            print_result(matches, &handler, device, name).await?;
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+async fn find_quick_preset(
+    handler: &QuickPresetsHandler,
+    device: &(dyn OpenSCQ30Device + Send + Sync),
+    name: &str,
+) -> anyhow::Result<Option<QuickPreset>> {
+    Ok(handler
+        .quick_presets(device)
+        .await?
+        .into_iter()
+        .find(|quick_preset| quick_preset.name == name))
+}
+
+/// Prints the quick preset in the same format as `list --json` if --json was passed, otherwise OK.
+async fn print_result(
+    matches: &ArgMatches,
+    handler: &QuickPresetsHandler,
+    device: &(dyn OpenSCQ30Device + Send + Sync),
+    name: &str,
+) -> anyhow::Result<()> {
+    if matches.get_flag("json") {
+        let quick_preset = find_quick_preset(handler, device, name).await?;
+        println!("{}", serde_json::to_string_pretty(&quick_preset)?);
+    } else {
+        println!("OK");
+    }
+    Ok(())
+}
+
+#[derive(Tabled)]
+struct QuickPresetTableItem {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Enabled Settings")]
+    enabled_settings: String,
+}
+
+impl From<QuickPreset> for QuickPresetTableItem {
+    fn from(quick_preset: QuickPreset) -> Self {
+        Self {
+            name: quick_preset.name,
+            enabled_settings: quick_preset
+                .fields
+                .into_iter()
+                .filter(|field| field.is_enabled)
+                .map(|field| format!("{}: {}", field.setting_id, DisplayableValue(field.value)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonImportReport {
+    imported: Vec<JsonImportedQuickPreset>,
+    skipped: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonImportedQuickPreset {
+    name: String,
+    original_name: Option<String>,
+    invalid_fields: Vec<JsonInvalidField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonInvalidField {
+    setting_id: SettingId,
+    value: Value,
+    reason: String,
+}
+
+impl From<QuickPresetsImportReport> for JsonImportReport {
+    fn from(report: QuickPresetsImportReport) -> Self {
+        Self {
+            imported: report
+                .imported
+                .into_iter()
+                .map(|imported| JsonImportedQuickPreset {
+                    name: imported.name,
+                    original_name: imported.original_name,
+                    invalid_fields: imported
+                        .invalid_fields
+                        .into_iter()
+                        .map(|field| JsonInvalidField {
+                            setting_id: field.setting_id,
+                            value: field.value,
+                            reason: field.reason.to_string(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            skipped: report.skipped,
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct ImportTableItem {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Result")]
+    result: String,
+    #[tabled(rename = "Invalid Settings")]
+    invalid_settings: String,
+}
+
+impl ImportTableItem {
+    fn from_report(report: QuickPresetsImportReport) -> impl Iterator<Item = Self> {
+        let imported = report.imported.into_iter().map(|imported| {
+            let ImportedQuickPreset {
+                name,
+                original_name,
+                invalid_fields,
+            } = imported;
+            Self {
+                result: match original_name {
+                    Some(original_name) => format!("Imported (renamed from {original_name})"),
+                    None => "Imported".to_owned(),
+                },
+                name,
+                invalid_settings: invalid_fields
+                    .into_iter()
+                    .map(|field| format!("{}: {}", field.setting_id, field.reason))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }
+        });
+        let skipped = report.skipped.into_iter().map(|name| Self {
+            name,
+            result: "Skipped".to_owned(),
+            invalid_settings: String::new(),
+        });
+        imported.chain(skipped)
+    }
+}
@@ -0,0 +1,31 @@
+use clap::ArgMatches;
+use macaddr::MacAddr6;
+use tabled::{Table, Tabled};
+
+use crate::openscq30_session;
+
+pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
+    let adapters = openscq30_session(matches).await?.adapters().await?;
+
+    if matches.get_flag("json") {
+        let json = serde_json::to_string_pretty(&adapters)?;
+        println!("{json}");
+    } else {
+        let mut table = Table::new(adapters.into_iter().map(|adapter| AdapterTableItem {
+            name: adapter.name,
+            address: adapter.address,
+        }));
+        crate::fmt::apply_tabled_settings(&mut table);
+        println!("{table}");
+    }
+
+    Ok(())
+}
+
+#[derive(Tabled)]
+struct AdapterTableItem {
+    #[tabled(rename = "Name")]
+    pub name: String,
+    #[tabled(rename = "Address")]
+    pub address: MacAddr6,
+}
@@ -1,12 +1,24 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, anyhow};
 use clap::ArgMatches;
 use macaddr::MacAddr6;
-use openscq30_lib::{DeviceModel, OpenSCQ30Session, storage::PairedDevice};
+use openscq30_lib::{
+    DeviceModel, OpenSCQ30Session, device_definition::DeviceDefinition, storage::PairedDevice,
+};
 use tabled::{Table, Tabled};
 
 use crate::{fmt::YesOrNo, openscq30_session};
 
 pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
-    let session = openscq30_session().await?;
+    let mut session = openscq30_session(matches).await?;
+    if let Some(("add", matches)) = matches.subcommand()
+        && let Some(path) = matches.get_one::<PathBuf>("device-definition")
+    {
+        let device_definition = DeviceDefinition::load(path)
+            .with_context(|| format!("loading device definition {}", path.display()))?;
+        session = session.with_device_definition(device_definition);
+    }
     match matches.subcommand().unwrap() {
         ("add", matches) => handle_add(matches, &session).await?,
         ("remove", matches) => handle_remove(matches, &session).await?,
@@ -17,14 +29,26 @@ pub async fn handle(matches: &ArgMatches) -> anyhow::Result<()> {
 }
 
 async fn handle_add(matches: &ArgMatches, session: &OpenSCQ30Session) -> anyhow::Result<()> {
+    let mac_address = matches
+        .get_one::<MacAddr6>("mac-address")
+        .unwrap()
+        .to_owned();
+    let model = match matches.get_one::<DeviceModel>("model") {
+        Some(model) => model.to_owned(),
+        None => {
+            let model = session.detect_model(mac_address).await?.ok_or_else(|| {
+                anyhow!("unable to detect device model, please specify it with --model")
+            })?;
+            println!("Detected {model}");
+            model
+        }
+    };
     session
         .pair(PairedDevice {
-            mac_address: matches
-                .get_one::<MacAddr6>("mac-address")
-                .unwrap()
-                .to_owned(),
-            model: matches.get_one::<DeviceModel>("model").unwrap().to_owned(),
+            mac_address,
+            model,
             is_demo: matches.get_flag("demo"),
+            adapter_address: None,
         })
         .await?;
     println!("Paired");
@@ -65,6 +89,12 @@ struct PairedDeviceTableItem {
     mac_address: MacAddr6,
     #[tabled(rename = "Demo Mode")]
     demo_mode: YesOrNo,
+    #[tabled(rename = "Adapter", display = "display_adapter")]
+    adapter_address: Option<MacAddr6>,
+}
+
+fn display_adapter(adapter_address: &Option<MacAddr6>) -> String {
+    adapter_address.map_or_else(|| "Any".to_owned(), |address| address.to_string())
 }
 
 impl From<PairedDevice> for PairedDeviceTableItem {
@@ -73,6 +103,7 @@ impl From<PairedDevice> for PairedDeviceTableItem {
             mac_address: value.mac_address,
             model: value.model,
             demo_mode: value.is_demo.into(),
+            adapter_address: value.adapter_address,
         }
     }
 }
@@ -33,7 +33,7 @@ async fn main() -> ExitCode {
     }
 }
 
-pub async fn openscq30_session() -> anyhow::Result<OpenSCQ30Session> {
+pub async fn openscq30_session(matches: &ArgMatches) -> anyhow::Result<OpenSCQ30Session> {
     let db_path = match std::env::var_os("OPENSCQ30_DATABASE_PATH") {
         Some(path) => PathBuf::from(path),
         None => config_dir()
@@ -41,7 +41,11 @@ pub async fn openscq30_session() -> anyhow::Result<OpenSCQ30Session> {
             .join("openscq30")
             .join("database.sqlite"),
     };
-    OpenSCQ30Session::new(db_path).await.map_err(Into::into)
+    let mut session = OpenSCQ30Session::new(db_path).await?;
+    if let Some(adapter) = matches.get_one::<String>("adapter") {
+        session = session.with_adapter(adapter);
+    }
+    Ok(session)
 }
 
 fn initialize_logging(matches: &ArgMatches) -> anyhow::Result<()> {
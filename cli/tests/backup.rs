@@ -0,0 +1,279 @@
+use std::{path::Path, process::Command};
+
+use insta_cmd::{assert_cmd_snapshot, get_cargo_bin};
+use tempfile::tempdir;
+
+fn cli(dir: &Path) -> Command {
+    let mut cmd = Command::new(get_cargo_bin("openscq30"));
+    cmd.env("XDG_CONFIG_HOME", dir.to_str().unwrap());
+    cmd
+}
+
+fn add_device(dir: &Path, mac_address: &str, model: &str) {
+    let output = cli(dir)
+        .arg("paired-devices")
+        .arg("add")
+        .arg("--mac-address")
+        .arg(mac_address)
+        .arg("--model")
+        .arg(model)
+        .arg("--demo")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+fn save_quick_preset(dir: &Path, mac_address: &str, name: &str) {
+    let output = cli(dir)
+        .arg("device")
+        .arg("--mac-address")
+        .arg(mac_address)
+        .arg("quick-preset")
+        .arg("save")
+        .arg(name)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn export() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "00:00:00:00:00:00", "SoundcoreA3027");
+    save_quick_preset(dir.path(), "00:00:00:00:00:00", "Commute");
+    assert_cmd_snapshot!(cli(dir.path()).arg("backup").arg("export"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "version": 2,
+      "pairedDevices": [
+        {
+          "macAddress": "00:00:00:00:00:00",
+          "model": "SoundcoreA3027",
+          "isDemo": true
+        }
+      ],
+      "quickPresets": {
+        "SoundcoreA3027": [
+          {
+            "name": "Commute",
+            "fields": [
+              {
+                "settingId": "ambientSoundMode",
+                "value": {
+                  "type": "string",
+                  "value": "NoiseCanceling"
+                },
+                "isEnabled": false
+              },
+              {
+                "settingId": "noiseCancelingMode",
+                "value": {
+                  "type": "string",
+                  "value": "Transport"
+                },
+                "isEnabled": false
+              },
+              {
+                "settingId": "presetEqualizerProfile",
+                "value": {
+                  "type": "optionalString",
+                  "value": "SoundcoreSignature"
+                },
+                "isEnabled": false
+              },
+              {
+                "settingId": "customEqualizerProfile",
+                "value": {
+                  "type": "optionalString",
+                  "value": null
+                },
+                "isEnabled": false
+              },
+              {
+                "settingId": "volumeAdjustments",
+                "value": {
+                  "type": "i16Vec",
+                  "value": [
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0
+                  ]
+                },
+                "isEnabled": false
+              },
+              {
+                "settingId": "wearingDetection",
+                "value": {
+                  "type": "bool",
+                  "value": false
+                },
+                "isEnabled": false
+              }
+            ]
+          }
+        ]
+      },
+      "equalizerProfiles": {}
+    }
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn import_merge() {
+    let source = tempdir().unwrap();
+    add_device(source.path(), "00:00:00:00:00:00", "SoundcoreA3027");
+    save_quick_preset(source.path(), "00:00:00:00:00:00", "Commute");
+    let backup_path = source.path().join("backup.json");
+    let output = cli(source.path())
+        .arg("backup")
+        .arg("export")
+        .arg("--output")
+        .arg(&backup_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let destination = tempdir().unwrap();
+    add_device(destination.path(), "00:00:00:00:00:01", "SoundcoreA3028");
+    assert_cmd_snapshot!(cli(destination.path()).arg("backup").arg("import").arg(&backup_path), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(cli(destination.path()).arg("paired-devices").arg("list"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Device Model  	MAC Address      	Demo Mode	Adapter
+    SoundcoreA3027	00:00:00:00:00:00	Yes      	Any    
+    SoundcoreA3028	00:00:00:00:00:01	Yes      	Any    
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        cli(destination.path())
+            .arg("device")
+            .arg("--mac-address")
+            .arg("00:00:00:00:00:00")
+            .arg("quick-preset")
+            .arg("list"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Name   	Enabled Settings
+    Commute	                
+
+    ----- stderr -----
+    "
+    );
+}
+
+#[test]
+fn import_replace() {
+    let source = tempdir().unwrap();
+    add_device(source.path(), "00:00:00:00:00:00", "SoundcoreA3027");
+    let backup_path = source.path().join("backup.json");
+    let output = cli(source.path())
+        .arg("backup")
+        .arg("export")
+        .arg("--output")
+        .arg(&backup_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let destination = tempdir().unwrap();
+    add_device(destination.path(), "00:00:00:00:00:01", "SoundcoreA3028");
+    assert_cmd_snapshot!(
+        cli(destination.path())
+            .arg("backup")
+            .arg("import")
+            .arg(&backup_path)
+            .arg("--mode")
+            .arg("replace"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    "
+    );
+    assert_cmd_snapshot!(cli(destination.path()).arg("paired-devices").arg("list"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Device Model  	MAC Address      	Demo Mode	Adapter
+    SoundcoreA3027	00:00:00:00:00:00	Yes      	Any    
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn import_unsupported_version() {
+    let dir = tempdir().unwrap();
+    let backup_path = dir.path().join("backup.json");
+    std::fs::write(&backup_path, r#"{"version":3}"#).unwrap();
+    assert_cmd_snapshot!(cli(dir.path()).arg("backup").arg("import").arg(&backup_path), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: backup version 3 is not supported, the newest supported version is 2
+    ");
+}
+
+#[test]
+fn import_leaves_out_invalid_quick_preset_fields() {
+    let dir = tempdir().unwrap();
+    let backup_path = dir.path().join("backup.json");
+    std::fs::write(
+        &backup_path,
+        r#"{"version":2,"pairedDevices":[{"macAddress":"00:00:00:00:00:00","model":"SoundcoreA3027","isDemo":true}],"quickPresets":{"SoundcoreA3027":[{"name":"Commute","fields":[{"settingId":"ambientSoundMode","value":{"type":"string","value":"Bogus"},"isEnabled":true},{"settingId":"noiseCancelingMode","value":{"type":"string","value":"Transport"},"isEnabled":true}]}]},"equalizerProfiles":{}}"#,
+    )
+    .unwrap();
+    assert_cmd_snapshot!(cli(dir.path()).arg("backup").arg("import").arg(&backup_path), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Some quick preset settings were left out since their device model would not accept them:
+    Model         	Name   	Invalid Settings                                           
+    SoundcoreA3027	Commute	ambientSoundMode: Bogus is not one of the available options
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        cli(dir.path())
+            .arg("device")
+            .arg("--mac-address")
+            .arg("00:00:00:00:00:00")
+            .arg("quick-preset")
+            .arg("list"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Name   	Enabled Settings             
+    Commute	noiseCancelingMode: Transport
+
+    ----- stderr -----
+    "
+    );
+}
@@ -0,0 +1,238 @@
+use std::process::Command;
+
+use insta_cmd::{assert_cmd_snapshot, get_cargo_bin};
+use tempfile::tempdir;
+
+fn cli() -> Command {
+    Command::new(get_cargo_bin("openscq30"))
+}
+
+#[test]
+fn decode_hex() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("packets.txt");
+    std::fs::write(
+        &path,
+        "08 ee 00 00 00 01 01 0a 00 02
+        0x09,0xff,0x00,0x00,0x01,0x06,0x01,0x0e,0x00,0x01,0x00,0x00,0x00,0x1f
+        09:ff:00:00:01:01:03:0c:00:03:04:20
+        aa bb
+        09ff0000017f7f0d00010203ff
+        08ee000000010100",
+    )
+    .unwrap();
+    assert_cmd_snapshot!(
+        cli()
+            .arg("debug")
+            .arg("decode")
+            .arg("--model")
+            .arg("SoundcoreA3028")
+            .arg(&path),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+         0 outbound 01 01 request state
+        10 inbound  06 01 sound modes
+        SoundModes(
+            SoundModes {
+                ambient_sound_mode: Transparency,
+                noise_canceling_mode: Transport,
+                transparency_mode: FullyTransparent,
+                custom_noise_canceling: CustomNoiseCanceling {
+                    value: 0,
+                },
+            },
+        )
+        24 inbound  01 03 battery level
+        DualBatteryLevel {
+            left: BatteryLevel(
+                3,
+            ),
+            right: BatteryLevel(
+                4,
+            ),
+        }
+        36 not a packet: aa bb
+        38 inbound  7f 7f unknown command
+        checksum mismatch: expected 1a, got ff
+        body: 01 02 03
+        51 truncated packet: 08 ee 00 00 00 01 01 00
+
+    ----- stderr -----
+    "
+    );
+}
+
+#[test]
+fn decode_capture() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("capture.jsonl");
+    std::fs::write(
+        &path,
+        r#"{"version":1,"macAddress":"00:11:22:33:44:55","startedAt":0}
+{"elapsedMicros":1500,"direction":"outbound","data":[8,238,0,0,0,1,1,10,0,2]}
+{"elapsedMicros":2000000,"direction":"inbound","data":[9,255,0,0,1,1,1,10,0,21]}
+"#,
+    )
+    .unwrap();
+    assert_cmd_snapshot!(
+        cli()
+            .arg("debug")
+            .arg("decode")
+            .arg("--model")
+            .arg("SoundcoreA3028")
+            .arg("--format")
+            .arg("capture")
+            .arg(&path),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    0.001500s outbound 01 01 request state
+    2.000000s inbound  01 01 ack (request state)
+
+    ----- stderr -----
+    "
+    );
+}
+
+#[test]
+fn decode_invalid_hex() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("packets.txt");
+    std::fs::write(&path, "08 ee 0").unwrap();
+    assert_cmd_snapshot!(
+        cli()
+            .arg("debug")
+            .arg("decode")
+            .arg("--model")
+            .arg("SoundcoreA3028")
+            .arg(&path),
+        @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: odd number of hex digits
+    "
+    );
+}
+
+#[test]
+fn decode_non_ascii_hex() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("packets.txt");
+    std::fs::write(&path, "aé0").unwrap();
+    assert_cmd_snapshot!(
+        cli()
+            .arg("debug")
+            .arg("decode")
+            .arg("--model")
+            .arg("SoundcoreA3004")
+            .arg(&path),
+        @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: invalid hex digit 'é'
+    "
+    );
+}
+
+/// A btsnoop log with each packet sent in its own RFCOMM frame on dlci 2, with the last packet split in half
+fn btsnoop_log(packets: &[(bool, &[u8])]) -> Vec<u8> {
+    let mut log = b"btsnoop\0".to_vec();
+    log.extend(1u32.to_be_bytes());
+    log.extend(1002u32.to_be_bytes());
+    let mut timestamp: i64 = 0x00dc_ddb3_0f2f_8000;
+    let mut frames = Vec::new();
+    for (i, (is_inbound, data)) in packets.iter().enumerate() {
+        if i == packets.len() - 1 {
+            let (first, second) = data.split_at(data.len() / 2);
+            frames.push((*is_inbound, first));
+            frames.push((*is_inbound, second));
+        } else {
+            frames.push((*is_inbound, *data));
+        }
+    }
+    for (is_inbound, data) in frames {
+        // rfcomm uih frame: address, control, length, payload, fcs
+        let mut rfcomm = vec![(2 << 2) | 0b11, 0xEF, ((data.len() as u8) << 1) | 1];
+        rfcomm.extend(data);
+        rfcomm.push(0);
+        // l2cap: length, cid
+        let mut l2cap = (rfcomm.len() as u16).to_le_bytes().to_vec();
+        l2cap.extend(0x40u16.to_le_bytes());
+        l2cap.extend(rfcomm);
+        // h4 acl: packet type, handle with packet boundary flags, length
+        let mut acl = vec![0x02];
+        acl.extend((0x0001u16 | (0b10 << 12)).to_le_bytes());
+        acl.extend((l2cap.len() as u16).to_le_bytes());
+        acl.extend(l2cap);
+
+        log.extend((acl.len() as u32).to_be_bytes());
+        log.extend((acl.len() as u32).to_be_bytes());
+        log.extend(u32::from(is_inbound).to_be_bytes());
+        log.extend(0u32.to_be_bytes());
+        log.extend(timestamp.to_be_bytes());
+        log.extend(acl);
+        timestamp += 250_000;
+    }
+    log
+}
+
+#[test]
+fn decode_btsnoop() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("btsnoop_hci.log");
+    std::fs::write(
+        &path,
+        btsnoop_log(&[
+            (
+                false,
+                &[0x08, 0xee, 0x00, 0x00, 0x00, 0x01, 0x01, 0x0a, 0x00, 0x02],
+            ),
+            (
+                true,
+                &[
+                    0x09, 0xff, 0x00, 0x00, 0x01, 0x06, 0x01, 0x0e, 0x00, 0x01, 0x00, 0x00, 0x00,
+                    0x1f,
+                ],
+            ),
+        ]),
+    )
+    .unwrap();
+    assert_cmd_snapshot!(
+        cli()
+            .arg("debug")
+            .arg("decode")
+            .arg("--model")
+            .arg("SoundcoreA3028")
+            .arg("--format")
+            .arg("btsnoop")
+            .arg(&path),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    0.000000s outbound 01 01 request state
+    0.500000s inbound  06 01 sound modes
+        SoundModes(
+            SoundModes {
+                ambient_sound_mode: Transparency,
+                noise_canceling_mode: Transport,
+                transparency_mode: FullyTransparent,
+                custom_noise_canceling: CustomNoiseCanceling {
+                    value: 0,
+                },
+            },
+        )
+
+    ----- stderr -----
+    "
+    );
+}
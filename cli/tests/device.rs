@@ -1,4 +1,9 @@
-use std::{path::Path, process::Command, thread};
+use std::{
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
 
 use insta_cmd::{assert_cmd_snapshot, get_cargo_bin};
 use tempfile::tempdir;
@@ -56,6 +61,14 @@ fn list_settings() {
     importCustomEqualizerProfiles: import string
     exportCustomEqualizerProfiles: multi select ([])
     exportCustomEqualizerProfilesOutput: information (read only)
+    shareCustomEqualizerProfile: optional select ([])
+    shareCustomEqualizerProfileOutput: information (read only)
+    importSharedEqualizerProfile: import string
+    importSharedEqualizerProfileResult: information (read only)
+    importEqualizerApoProfile: import string
+    importEqualizerApoProfileResult: information (read only)
+    exportEqualizerApoProfile: information (read only)
+    copyCustomEqualizerProfilesFrom: optional select ([])
     -- deviceInformation --
     isCharging: information (read only)
     batteryLevel: information (read only)
@@ -84,6 +97,14 @@ fn list_settings_no_categories() {
     importCustomEqualizerProfiles: import string
     exportCustomEqualizerProfiles: multi select ([])
     exportCustomEqualizerProfilesOutput: information (read only)
+    shareCustomEqualizerProfile: optional select ([])
+    shareCustomEqualizerProfileOutput: information (read only)
+    importSharedEqualizerProfile: import string
+    importSharedEqualizerProfileResult: information (read only)
+    importEqualizerApoProfile: import string
+    importEqualizerApoProfileResult: information (read only)
+    exportEqualizerApoProfile: information (read only)
+    copyCustomEqualizerProfilesFrom: optional select ([])
     isCharging: information (read only)
     batteryLevel: information (read only)
     serialNumber: information (read only)
@@ -113,6 +134,14 @@ fn list_settings_no_extended_info() {
     importCustomEqualizerProfiles
     exportCustomEqualizerProfiles
     exportCustomEqualizerProfilesOutput
+    shareCustomEqualizerProfile
+    shareCustomEqualizerProfileOutput
+    importSharedEqualizerProfile
+    importSharedEqualizerProfileResult
+    importEqualizerApoProfile
+    importEqualizerApoProfileResult
+    exportEqualizerApoProfile
+    copyCustomEqualizerProfilesFrom
     -- deviceInformation --
     isCharging
     batteryLevel
@@ -141,6 +170,14 @@ fn list_settings_no_categories_and_no_extended_info() {
     importCustomEqualizerProfiles
     exportCustomEqualizerProfiles
     exportCustomEqualizerProfilesOutput
+    shareCustomEqualizerProfile
+    shareCustomEqualizerProfileOutput
+    importSharedEqualizerProfile
+    importSharedEqualizerProfileResult
+    importEqualizerApoProfile
+    importEqualizerApoProfileResult
+    exportEqualizerApoProfile
+    copyCustomEqualizerProfilesFrom
     isCharging
     batteryLevel
     serialNumber
@@ -300,6 +337,46 @@ fn list_settings_json() {
           {
             "settingId": "exportCustomEqualizerProfilesOutput",
             "type": "information"
+          },
+          {
+            "settingId": "shareCustomEqualizerProfile",
+            "type": "optionalSelect",
+            "setting": {
+              "options": [],
+              "localizedOptions": []
+            }
+          },
+          {
+            "settingId": "shareCustomEqualizerProfileOutput",
+            "type": "information"
+          },
+          {
+            "settingId": "importSharedEqualizerProfile",
+            "type": "importString"
+          },
+          {
+            "settingId": "importSharedEqualizerProfileResult",
+            "type": "information"
+          },
+          {
+            "settingId": "importEqualizerApoProfile",
+            "type": "importString"
+          },
+          {
+            "settingId": "importEqualizerApoProfileResult",
+            "type": "information"
+          },
+          {
+            "settingId": "exportEqualizerApoProfile",
+            "type": "information"
+          },
+          {
+            "settingId": "copyCustomEqualizerProfilesFrom",
+            "type": "optionalSelect",
+            "setting": {
+              "options": [],
+              "localizedOptions": []
+            }
           }
         ]
       },
@@ -469,6 +546,38 @@ fn list_settings_json_no_categories() {
       "exportCustomEqualizerProfilesOutput": {
         "type": "information"
       },
+      "shareCustomEqualizerProfile": {
+        "type": "optionalSelect",
+        "setting": {
+          "options": [],
+          "localizedOptions": []
+        }
+      },
+      "shareCustomEqualizerProfileOutput": {
+        "type": "information"
+      },
+      "importSharedEqualizerProfile": {
+        "type": "importString"
+      },
+      "importSharedEqualizerProfileResult": {
+        "type": "information"
+      },
+      "importEqualizerApoProfile": {
+        "type": "importString"
+      },
+      "importEqualizerApoProfileResult": {
+        "type": "information"
+      },
+      "exportEqualizerApoProfile": {
+        "type": "information"
+      },
+      "copyCustomEqualizerProfilesFrom": {
+        "type": "optionalSelect",
+        "setting": {
+          "options": [],
+          "localizedOptions": []
+        }
+      },
       "isCharging": {
         "type": "information"
       },
@@ -519,7 +628,15 @@ fn list_settings_json_no_extended_info() {
         "settingIds": [
           "importCustomEqualizerProfiles",
           "exportCustomEqualizerProfiles",
-          "exportCustomEqualizerProfilesOutput"
+          "exportCustomEqualizerProfilesOutput",
+          "shareCustomEqualizerProfile",
+          "shareCustomEqualizerProfileOutput",
+          "importSharedEqualizerProfile",
+          "importSharedEqualizerProfileResult",
+          "importEqualizerApoProfile",
+          "importEqualizerApoProfileResult",
+          "exportEqualizerApoProfile",
+          "copyCustomEqualizerProfilesFrom"
         ]
       },
       {
@@ -560,6 +677,14 @@ fn list_settings_json_no_categories_and_no_extended_info() {
       "importCustomEqualizerProfiles",
       "exportCustomEqualizerProfiles",
       "exportCustomEqualizerProfilesOutput",
+      "shareCustomEqualizerProfile",
+      "shareCustomEqualizerProfileOutput",
+      "importSharedEqualizerProfile",
+      "importSharedEqualizerProfileResult",
+      "importEqualizerApoProfile",
+      "importEqualizerApoProfileResult",
+      "exportEqualizerApoProfile",
+      "copyCustomEqualizerProfilesFrom",
       "isCharging",
       "batteryLevel",
       "serialNumber",
@@ -1269,3 +1394,138 @@ fn setting_json() {
     ----- stderr -----
     "#);
 }
+
+#[test]
+fn watch_prints_initial_state() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let mut child = cli(dir.path())
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("watch")
+        .arg("--setting")
+        .arg("ambientSoundMode")
+        .arg("--setting")
+        .arg("wearingDetection")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    insta::assert_snapshot!(line.trim_end(), @r#"{"type":"settingsChanged","settings":{"ambientSoundMode":{"type":"string","value":"NoiseCanceling"},"wearingDetection":{"type":"bool","value":false}}}"#);
+}
+
+#[test]
+fn watch_demo_events() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3936");
+    let mut child = cli(dir.path())
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("--demo-events")
+        .arg("wind-noise")
+        .arg("watch")
+        .arg("--setting")
+        .arg("windNoiseDetected")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let lines = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .take(2)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    insta::assert_snapshot!(lines.join("\n"), @r#"
+    {"type":"settingsChanged","settings":{"windNoiseDetected":{"type":"string","value":"false"}}}
+    {"type":"settingsChanged","settings":{"windNoiseDetected":{"type":"string","value":"true"}}}
+    "#);
+}
+
+#[test]
+fn demo_events_unsupported_by_model() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let mut command = cli(dir.path());
+    command
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("--demo-events")
+        .arg("wind-noise")
+        .arg("list-settings");
+    assert_cmd_snapshot!(command, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: SoundcoreA3027 does not detect wind noise
+    ");
+}
+
+#[test]
+fn watch_invalid_setting() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let mut command = cli(dir.path());
+    command
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("watch")
+        .arg("--setting")
+        .arg("invalid");
+    assert_cmd_snapshot!(command, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: setting id invalid does not exist
+    ");
+}
+
+#[test]
+fn device_definition() {
+    let dir = tempdir().unwrap();
+    let output = cli(dir.path())
+        .arg("paired-devices")
+        .arg("add")
+        .arg("--mac-address")
+        .arg("00:00:00:00:30:28")
+        .arg("--model")
+        .arg("SoundcoreA3028")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let mut command = cli(dir.path());
+    command
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:30:28")
+        .arg("--device-definition")
+        .arg(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../tools/soundcore-device-faker/devices/a3028.toml"),
+        )
+        .arg("setting")
+        .arg("--get")
+        .arg("volumeAdjustments");
+    assert_cmd_snapshot!(command, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Setting ID       	Value                          
+    volumeAdjustments	[0, 30, 30, 20, 40, 50, 30, 40]
+
+    ----- stderr -----
+    ");
+}
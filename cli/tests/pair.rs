@@ -33,8 +33,8 @@ fn add_device() {
     success: true
     exit_code: 0
     ----- stdout -----
-    Device Model  	MAC Address      	Demo Mode
-    SoundcoreA3027	00:00:00:00:00:00	No       
+    Device Model  	MAC Address      	Demo Mode	Adapter
+    SoundcoreA3027	00:00:00:00:00:00	No       	Any    
 
     ----- stderr -----
     ");
@@ -65,13 +65,62 @@ fn add_demo_device() {
     success: true
     exit_code: 0
     ----- stdout -----
-    Device Model  	MAC Address      	Demo Mode
-    SoundcoreA3027	00:00:00:00:00:00	Yes      
+    Device Model  	MAC Address      	Demo Mode	Adapter
+    SoundcoreA3027	00:00:00:00:00:00	Yes      	Any    
 
     ----- stderr -----
     ");
 }
 
+#[test]
+fn add_device_with_detected_model() {
+    let dir = tempdir().unwrap();
+    assert_cmd_snapshot!(
+        cli(dir.path())
+            .arg("paired-devices")
+            .arg("add")
+            .arg("--mac-address")
+            .arg("00:00:00:00:30:28")
+            .arg("--device-definition")
+            .arg(
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("../tools/soundcore-device-faker/devices/a3028.toml"),
+            ),
+            @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Detected SoundcoreA3028
+    Paired
+
+    ----- stderr -----
+    "
+    );
+    assert_cmd_snapshot!(cli(dir.path()).arg("paired-devices").arg("list"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Device Model  	MAC Address      	Demo Mode	Adapter
+    SoundcoreA3028	00:00:00:00:30:28	No       	Any    
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn add_demo_device_requires_model() {
+    let dir = tempdir().unwrap();
+    let output = cli(dir.path())
+        .arg("paired-devices")
+        .arg("add")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("--demo")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
 #[test]
 fn remove_device() {
     let dir = tempdir().unwrap();
@@ -111,7 +160,7 @@ fn remove_device() {
     success: true
     exit_code: 0
     ----- stdout -----
-    Device Model	MAC Address	Demo Mode
+    Device Model	MAC Address	Demo Mode	Adapter
 
     ----- stderr -----
     ");
@@ -0,0 +1,717 @@
+use std::{path::Path, process::Command};
+
+use insta_cmd::{assert_cmd_snapshot, get_cargo_bin};
+use tempfile::tempdir;
+
+fn cli(dir: &Path) -> Command {
+    let mut cmd = Command::new(get_cargo_bin("openscq30"));
+    cmd.env("XDG_CONFIG_HOME", dir.to_str().unwrap());
+    cmd
+}
+
+fn add_device(dir: &Path, model: &str) {
+    let output = cli(dir)
+        .arg("paired-devices")
+        .arg("add")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("--model")
+        .arg(model)
+        .arg("--demo")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
+
+fn quick_preset(dir: &Path) -> Command {
+    let mut command = cli(dir);
+    command
+        .arg("device")
+        .arg("--mac-address")
+        .arg("00:00:00:00:00:00")
+        .arg("quick-preset");
+    command
+}
+
+#[test]
+fn save_edit_and_list() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        quick_preset(dir.path())
+            .arg("edit")
+            .arg("Commute")
+            .arg("--enable")
+            .arg("ambientSoundMode")
+            .arg("--enable")
+            .arg("noiseCancelingMode")
+            .arg("--disable")
+            .arg("noiseCancelingMode"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    "
+    );
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("list"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Name   	Enabled Settings                
+    Commute	ambientSoundMode: NoiseCanceling
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn list_json() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        quick_preset(dir.path())
+            .arg("edit")
+            .arg("Commute")
+            .arg("--enable")
+            .arg("wearingDetection"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    "
+    );
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("list").arg("--json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "name": "Commute",
+        "fields": [
+          {
+            "settingId": "ambientSoundMode",
+            "value": {
+              "type": "string",
+              "value": "NoiseCanceling"
+            },
+            "isEnabled": false
+          },
+          {
+            "settingId": "noiseCancelingMode",
+            "value": {
+              "type": "string",
+              "value": "Transport"
+            },
+            "isEnabled": false
+          },
+          {
+            "settingId": "presetEqualizerProfile",
+            "value": {
+              "type": "optionalString",
+              "value": "SoundcoreSignature"
+            },
+            "isEnabled": false
+          },
+          {
+            "settingId": "customEqualizerProfile",
+            "value": {
+              "type": "optionalString",
+              "value": null
+            },
+            "isEnabled": false
+          },
+          {
+            "settingId": "volumeAdjustments",
+            "value": {
+              "type": "i16Vec",
+              "value": [
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0
+              ]
+            },
+            "isEnabled": false
+          },
+          {
+            "settingId": "wearingDetection",
+            "value": {
+              "type": "bool",
+              "value": false
+            },
+            "isEnabled": true
+          }
+        ]
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn activate() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        quick_preset(dir.path())
+            .arg("edit")
+            .arg("Commute")
+            .arg("--enable")
+            .arg("ambientSoundMode"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    "
+    );
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("activate").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn activate_nonexistent() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("activate").arg("Commute"), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: activating quick preset Commute: storage: not found
+    ");
+}
+
+#[test]
+fn edit_unknown_field() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        quick_preset(dir.path())
+            .arg("edit")
+            .arg("Commute")
+            .arg("--enable")
+            .arg("batteryLevel"),
+        @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: toggling batteryLevel in quick preset Commute: not found
+    "
+    );
+}
+
+#[test]
+fn delete() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("delete").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("list").arg("--json"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    []
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn save_edit_activate_and_delete_json() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute").arg("--json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "name": "Commute",
+      "fields": [
+        {
+          "settingId": "ambientSoundMode",
+          "value": {
+            "type": "string",
+            "value": "NoiseCanceling"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "noiseCancelingMode",
+          "value": {
+            "type": "string",
+            "value": "Transport"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "presetEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": "SoundcoreSignature"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "customEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": null
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "volumeAdjustments",
+          "value": {
+            "type": "i16Vec",
+            "value": [
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0
+            ]
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "wearingDetection",
+          "value": {
+            "type": "bool",
+            "value": false
+          },
+          "isEnabled": false
+        }
+      ]
+    }
+
+    ----- stderr -----
+    "#);
+    assert_cmd_snapshot!(
+        quick_preset(dir.path())
+            .arg("edit")
+            .arg("Commute")
+            .arg("--enable")
+            .arg("wearingDetection")
+            .arg("--json"),
+        @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "name": "Commute",
+      "fields": [
+        {
+          "settingId": "ambientSoundMode",
+          "value": {
+            "type": "string",
+            "value": "NoiseCanceling"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "noiseCancelingMode",
+          "value": {
+            "type": "string",
+            "value": "Transport"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "presetEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": "SoundcoreSignature"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "customEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": null
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "volumeAdjustments",
+          "value": {
+            "type": "i16Vec",
+            "value": [
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0
+            ]
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "wearingDetection",
+          "value": {
+            "type": "bool",
+            "value": false
+          },
+          "isEnabled": true
+        }
+      ]
+    }
+
+    ----- stderr -----
+    "#
+    );
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("activate").arg("Commute").arg("--json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "name": "Commute",
+      "fields": [
+        {
+          "settingId": "ambientSoundMode",
+          "value": {
+            "type": "string",
+            "value": "NoiseCanceling"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "noiseCancelingMode",
+          "value": {
+            "type": "string",
+            "value": "Transport"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "presetEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": "SoundcoreSignature"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "customEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": null
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "volumeAdjustments",
+          "value": {
+            "type": "i16Vec",
+            "value": [
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0
+            ]
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "wearingDetection",
+          "value": {
+            "type": "bool",
+            "value": false
+          },
+          "isEnabled": true
+        }
+      ]
+    }
+
+    ----- stderr -----
+    "#);
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("delete").arg("Commute").arg("--json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "name": "Commute",
+      "fields": [
+        {
+          "settingId": "ambientSoundMode",
+          "value": {
+            "type": "string",
+            "value": "NoiseCanceling"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "noiseCancelingMode",
+          "value": {
+            "type": "string",
+            "value": "Transport"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "presetEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": "SoundcoreSignature"
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "customEqualizerProfile",
+          "value": {
+            "type": "optionalString",
+            "value": null
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "volumeAdjustments",
+          "value": {
+            "type": "i16Vec",
+            "value": [
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0,
+              0
+            ]
+          },
+          "isEnabled": false
+        },
+        {
+          "settingId": "wearingDetection",
+          "value": {
+            "type": "bool",
+            "value": false
+          },
+          "isEnabled": true
+        }
+      ]
+    }
+
+    ----- stderr -----
+    "#);
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("list").arg("--json"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    []
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn export_and_import() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let export_path = dir.path().join("quick-presets.json");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("export").arg("--output").arg(&export_path), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    let other_dir = tempdir().unwrap();
+    add_device(other_dir.path(), "SoundcoreA3027");
+    assert_cmd_snapshot!(quick_preset(other_dir.path()).arg("save").arg("Commute"), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    OK
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(quick_preset(other_dir.path()).arg("import").arg(&export_path), @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Name   	Result 	Invalid Settings
+    Commute	Skipped	                
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(
+        quick_preset(other_dir.path())
+            .arg("import")
+            .arg(&export_path)
+            .arg("--on-conflict")
+            .arg("rename"),
+        @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Name       	Result                         	Invalid Settings
+    Commute (2)	Imported (renamed from Commute)	                
+
+    ----- stderr -----
+    "
+    );
+}
+
+#[test]
+fn import_invalid_fields() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let export_path = dir.path().join("quick-presets.json");
+    std::fs::write(
+        &export_path,
+        r#"{
+            "version": 1,
+            "quickPresets": {
+                "SoundcoreA3027": [
+                    {
+                        "name": "Commute",
+                        "fields": [
+                            {
+                                "settingId": "ambientSoundMode",
+                                "value": { "type": "string", "value": "Transparency" },
+                                "isEnabled": true
+                            },
+                            {
+                                "settingId": "noiseCancelingMode",
+                                "value": { "type": "string", "value": "Underwater" },
+                                "isEnabled": true
+                            }
+                        ]
+                    }
+                ]
+            }
+        }"#,
+    )
+    .unwrap();
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("import").arg(&export_path).arg("--json"), @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {
+      "imported": [
+        {
+          "name": "Commute",
+          "originalName": null,
+          "invalidFields": [
+            {
+              "settingId": "noiseCancelingMode",
+              "value": {
+                "type": "string",
+                "value": "Underwater"
+              },
+              "reason": "Underwater is not one of the available options"
+            }
+          ]
+        }
+      ],
+      "skipped": []
+    }
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn import_unsupported_version() {
+    let dir = tempdir().unwrap();
+    add_device(dir.path(), "SoundcoreA3027");
+    let export_path = dir.path().join("quick-presets.json");
+    std::fs::write(&export_path, r#"{ "version": 1000 }"#).unwrap();
+    assert_cmd_snapshot!(quick_preset(dir.path()).arg("import").arg(&export_path), @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: export version 1000 is not supported, the newest supported version is 1
+    ");
+}
@@ -0,0 +1,671 @@
+//! Publishes every device connected through the daemon as a D-Bus object, so that desktop integrations such as panel
+//! applets and key bindings can read and change settings without going through the unix socket.
+//!
+//! The manager object at [`MANAGER_PATH`] can connect to and disconnect from paired devices. Each connected device is
+//! published at [`device_path`] with one read only property per setting, named after the `SettingId` in
+//! UpperCamelCase. Changes are announced with `PropertiesChanged`, and settings are changed with the
+//! `SetSettingValues` method. Settings that the device doesn't have can't be read, and are left out of `GetAll`.
+//!
+//! Every device shares the same interface, so setting properties are declared as variants. The value inside the
+//! variant follows the setting's value:
+//!
+//! - Toggles are booleans
+//! - Ranges are int32
+//! - Selects are strings, with an empty string meaning nothing is selected
+//! - Multi selects are string arrays
+//! - Equalizers are int16 arrays
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    marker::PhantomData,
+    str::FromStr,
+    sync::{Arc, Mutex, Weak},
+};
+
+use dbus::{
+    MethodErr, Path,
+    arg::{RefArg, Variant},
+    channel::{MatchingReceiver, Sender},
+    message::{MatchRule, SignalArgs},
+    nonblock::{SyncConnection, stdintf::org_freedesktop_dbus::PropertiesPropertiesChanged},
+};
+use dbus_crossroads::{Crossroads, IfaceBuilder, IfaceToken, PropContext};
+use heck::ToUpperCamelCase;
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    connection::ConnectionStatus,
+    settings::{SettingId, Value},
+    util::AbortOnDropHandle,
+};
+use strum::VariantArray;
+use tokio::select;
+
+use crate::server::{Device, Server, all_settings};
+
+pub const BUS_NAME: &str = "com.oppzippy.OpenSCQ30";
+pub const MANAGER_PATH: &str = "/com/oppzippy/OpenSCQ30";
+pub const MANAGER_INTERFACE: &str = "com.oppzippy.OpenSCQ30.Manager";
+pub const DEVICE_INTERFACE: &str = "com.oppzippy.OpenSCQ30.Device";
+
+type PropertyValues = Vec<(String, Variant<Box<dyn RefArg>>)>;
+
+pub fn device_path(mac_address: MacAddr6) -> Path<'static> {
+    format!(
+        "{MANAGER_PATH}/devices/dev_{}",
+        mac_address.to_string().replace(':', "_")
+    )
+    .into()
+}
+
+/// Connects to the session bus, claims [`BUS_NAME`], and serves until the returned handle is dropped.
+pub async fn start(server: Arc<Server>) -> anyhow::Result<AbortOnDropHandle<()>> {
+    let (resource, connection) = dbus_tokio::connection::new_session_sync()?;
+    let resource_handle = AbortOnDropHandle::new(tokio::spawn(async move {
+        let err = resource.await;
+        tracing::error!("lost connection to D-Bus: {err:?}");
+    }));
+    connection.request_name(BUS_NAME, false, true, true).await?;
+    Ok(AbortOnDropHandle::new(tokio::spawn(async move {
+        let _resource_handle = resource_handle;
+        serve(server, connection).await;
+    })))
+}
+
+/// Handles method calls on `connection` and keeps the published objects in sync with the server's connected devices.
+pub async fn serve(server: Arc<Server>, connection: Arc<SyncConnection>) {
+    let mut crossroads = Crossroads::new();
+    crossroads.set_async_support(Some((
+        connection.clone(),
+        Box::new(|future| {
+            tokio::spawn(future);
+        }),
+    )));
+    let manager_interface = register_manager_interface(&mut crossroads);
+    let device_interface = register_device_interface(&mut crossroads);
+    let service = Arc::new(DBusService {
+        server,
+        connection: connection.clone(),
+        crossroads: Mutex::new(crossroads),
+        device_interface,
+        published: Mutex::default(),
+    });
+    service.crossroads.lock().unwrap().insert(
+        MANAGER_PATH,
+        &[manager_interface],
+        Arc::downgrade(&service),
+    );
+
+    let receive_token = connection.start_receive(
+        MatchRule::new_method_call(),
+        Box::new({
+            let service = Arc::downgrade(&service);
+            move |message, connection| {
+                let Some(service) = service.upgrade() else {
+                    return false;
+                };
+                // Only errors if the message isn't a method call, which the match rule already filters out
+                let _ = service
+                    .crossroads
+                    .lock()
+                    .unwrap()
+                    .handle_message(message, connection);
+                true
+            }
+        }),
+    );
+
+    let mut devices = service.server.watch_devices();
+    loop {
+        service.sync_devices();
+        if devices.changed().await.is_err() {
+            break;
+        }
+    }
+    connection.stop_receive(receive_token);
+}
+
+struct DBusService {
+    server: Arc<Server>,
+    connection: Arc<SyncConnection>,
+    crossroads: Mutex<Crossroads>,
+    device_interface: IfaceToken<Device>,
+    published: Mutex<HashMap<MacAddr6, PublishedDevice>>,
+}
+
+struct PublishedDevice {
+    device: Device,
+    _changes_handle: AbortOnDropHandle<()>,
+}
+
+impl DBusService {
+    /// Publishes newly connected devices and removes disconnected ones.
+    fn sync_devices(&self) {
+        let devices = self.server.watch_devices().borrow().clone();
+        let mut published = self.published.lock().unwrap();
+        let mut crossroads = self.crossroads.lock().unwrap();
+
+        published.retain(|mac_address, published_device| {
+            let is_connected = devices
+                .get(mac_address)
+                .is_some_and(|device| Arc::ptr_eq(device, &published_device.device));
+            if !is_connected {
+                tracing::debug!("removing D-Bus object for {mac_address}");
+                crossroads.remove::<Device>(&device_path(*mac_address));
+            }
+            is_connected
+        });
+
+        for (mac_address, device) in devices {
+            if published.contains_key(&mac_address) {
+                continue;
+            }
+            tracing::debug!("publishing D-Bus object for {mac_address}");
+            let path = device_path(mac_address);
+            crossroads.insert(path.clone(), &[self.device_interface], device.clone());
+            published.insert(
+                mac_address,
+                PublishedDevice {
+                    _changes_handle: AbortOnDropHandle::new(tokio::spawn(emit_property_changes(
+                        self.connection.clone(),
+                        path,
+                        device.clone(),
+                    ))),
+                    device,
+                },
+            );
+        }
+    }
+}
+
+fn register_manager_interface(crossroads: &mut Crossroads) -> IfaceToken<Weak<DBusService>> {
+    crossroads.register(
+        MANAGER_INTERFACE,
+        |b: &mut IfaceBuilder<Weak<DBusService>>| {
+            b.method_with_cr_async(
+                "Connect",
+                ("mac_address",),
+                ("device",),
+                |mut ctx, crossroads, (mac_address,): (String,)| {
+                    let service = manager_service(&ctx, crossroads);
+                    async move {
+                        let result = async {
+                            let service = service?;
+                            let mac_address = parse_mac_address(&mac_address)?;
+                            service
+                                .server
+                                .device(mac_address)
+                                .await
+                                .map_err(|err| MethodErr::failed(&err.message))?;
+                            // Publish right away rather than waiting for the watcher so the path is usable immediately
+                            service.sync_devices();
+                            Ok((device_path(mac_address),))
+                        }
+                        .await;
+                        ctx.reply(result)
+                    }
+                },
+            );
+            b.method(
+                "Disconnect",
+                ("mac_address",),
+                (),
+                |_, service, (mac_address,): (String,)| {
+                    let service = service
+                        .upgrade()
+                        .ok_or_else(|| MethodErr::failed("service is shutting down"))?;
+                    service.server.disconnect(parse_mac_address(&mac_address)?);
+                    Ok(())
+                },
+            );
+        },
+    )
+}
+
+fn manager_service(
+    ctx: &dbus_crossroads::Context,
+    crossroads: &mut Crossroads,
+) -> Result<Arc<DBusService>, MethodErr> {
+    crossroads
+        .data_mut::<Weak<DBusService>>(ctx.path())
+        .and_then(|service| service.upgrade())
+        .ok_or_else(|| MethodErr::failed("service is shutting down"))
+}
+
+fn register_device_interface(crossroads: &mut Crossroads) -> IfaceToken<Device> {
+    crossroads.register(DEVICE_INTERFACE, |b: &mut IfaceBuilder<Device>| {
+        b.property::<String, _>("Model")
+            .emits_changed_const()
+            .get(|_, device| Ok(device.model().to_string()));
+        b.property::<String, _>("ConnectionStatus")
+            .get(|_, device| Ok(connection_status_name(*device.connection_status().borrow())));
+        for setting_id in SettingId::VARIANTS {
+            setting_property(b, *setting_id);
+        }
+        // An array of pairs rather than a dict since the order settings are set in can matter
+        b.method_with_cr_async(
+            "SetSettingValues",
+            ("values",),
+            (),
+            |mut ctx, crossroads, (values,): (PropertyValues,)| {
+                let result = crossroads
+                    .data_mut::<Device>(ctx.path())
+                    .cloned()
+                    .ok_or_else(|| MethodErr::no_path(ctx.path()))
+                    .and_then(|device| {
+                        let values = values
+                            .iter()
+                            .map(|(name, value)| from_property(device.as_ref(), name, value))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        Ok((device, values))
+                    });
+                async move {
+                    let result = match result {
+                        Ok((device, values)) => {
+                            device.set_setting_values(values).await.map_err(|err| {
+                                MethodErr::failed(&format!("{:#}", anyhow::Error::from(err)))
+                            })
+                        }
+                        Err(err) => Err(err),
+                    };
+                    ctx.reply(result)
+                }
+            },
+        );
+    })
+}
+
+fn setting_property(b: &mut IfaceBuilder<Device>, setting_id: SettingId) {
+    // Variant is only used for its signature, the reply contains whichever type the setting's value has
+    b.property::<Variant<String>, _>(property_name(setting_id))
+        .get_async(move |mut ctx, device| {
+            let value = device.setting(&setting_id).map(Value::from).ok_or_else(|| {
+                MethodErr::failed(&format!("{setting_id} is not currently available"))
+            });
+            async move {
+                reply_with_value(&mut ctx, value);
+                PhantomData
+            }
+        });
+}
+
+/// Replies with the value converted to the same type as [`to_variant`] would.
+fn reply_with_value(ctx: &mut PropContext, value: Result<Value, MethodErr>) {
+    // reply returns PhantomData of whatever type it was given, so each arm has to discard it
+    match value {
+        Ok(Value::Bool(value)) => {
+            ctx.reply(Ok(value));
+        }
+        Ok(Value::U16(value)) => {
+            ctx.reply(Ok(value));
+        }
+        Ok(Value::U16Vec(values)) => {
+            ctx.reply(Ok(values));
+        }
+        Ok(Value::OptionalU16(value)) => {
+            ctx.reply(Ok(value.into_iter().collect::<Vec<_>>()));
+        }
+        Ok(Value::I16Vec(values)) => {
+            ctx.reply(Ok(values));
+        }
+        Ok(Value::I32(value)) => {
+            ctx.reply(Ok(value));
+        }
+        Ok(Value::String(value)) => {
+            ctx.reply(Ok(value.into_owned()));
+        }
+        Ok(Value::StringVec(values)) => {
+            ctx.reply(Ok(values
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect::<Vec<_>>()));
+        }
+        Ok(Value::OptionalString(value)) => {
+            ctx.reply(Ok(value.map(Cow::into_owned).unwrap_or_default()));
+        }
+        Ok(
+            value @ (Value::ModifiableSelectCommand(_) | Value::MultiSelectWithRemoveCommand(_)),
+        ) => {
+            ctx.reply(Ok(
+                serde_json::to_string(&value).expect("json serialization shouldn't fail")
+            ));
+        }
+        Err(err) => {
+            ctx.reply::<String>(Err(err));
+        }
+    }
+}
+
+async fn emit_property_changes(
+    connection: Arc<SyncConnection>,
+    path: Path<'static>,
+    device: Device,
+) {
+    let mut changes = device.watch_for_changes();
+    let mut connection_status = device.connection_status();
+    let mut settings = all_settings(device.as_ref());
+    loop {
+        let changed_properties = select! {
+            result = changes.changed() => {
+                if result.is_err() {
+                    return;
+                }
+                let new_settings = all_settings(device.as_ref());
+                let changed_properties = new_settings
+                    .iter()
+                    .filter(|(setting_id, setting)| settings.get(*setting_id) != Some(*setting))
+                    .map(|(setting_id, setting)| {
+                        (property_name(*setting_id), to_variant(Value::from(setting.clone())))
+                    })
+                    .collect::<dbus::arg::PropMap>();
+                settings = new_settings;
+                changed_properties
+            }
+            result = connection_status.changed() => {
+                if result.is_err() {
+                    return;
+                }
+                let status = *connection_status.borrow_and_update();
+                dbus::arg::PropMap::from([(
+                    "ConnectionStatus".to_owned(),
+                    Variant(Box::new(connection_status_name(status)) as Box<dyn RefArg>),
+                )])
+            }
+        };
+        if changed_properties.is_empty() {
+            continue;
+        }
+        let signal = PropertiesPropertiesChanged {
+            interface_name: DEVICE_INTERFACE.to_owned(),
+            changed_properties,
+            invalidated_properties: Vec::new(),
+        };
+        if connection.send(signal.to_emit_message(&path)).is_err() {
+            tracing::warn!("failed to send PropertiesChanged signal for {path}");
+        }
+    }
+}
+
+fn parse_mac_address(mac_address: &str) -> Result<MacAddr6, MethodErr> {
+    MacAddr6::from_str(mac_address).map_err(|err| MethodErr::invalid_arg(&err))
+}
+
+fn property_name(setting_id: SettingId) -> String {
+    setting_id.to_string().to_upper_camel_case()
+}
+
+fn connection_status_name(connection_status: ConnectionStatus) -> String {
+    serde_json::to_value(connection_status)
+        .ok()
+        .and_then(|value| value.as_str().map(ToOwned::to_owned))
+        .expect("connection status should serialize to a string")
+}
+
+fn to_variant(value: Value) -> Variant<Box<dyn RefArg>> {
+    Variant(match value {
+        Value::Bool(value) => Box::new(value),
+        Value::U16(value) => Box::new(value),
+        Value::U16Vec(values) => Box::new(values),
+        Value::OptionalU16(value) => Box::new(value.into_iter().collect::<Vec<_>>()),
+        Value::I16Vec(values) => Box::new(values),
+        Value::I32(value) => Box::new(value),
+        Value::String(value) => Box::new(value.into_owned()),
+        Value::StringVec(values) => {
+            Box::new(values.into_iter().map(Cow::into_owned).collect::<Vec<_>>())
+        }
+        Value::OptionalString(value) => Box::new(value.map(Cow::into_owned).unwrap_or_default()),
+        // Settings never have commands as their value, they are only used for setting values
+        Value::ModifiableSelectCommand(_) | Value::MultiSelectWithRemoveCommand(_) => {
+            Box::new(serde_json::to_string(&value).expect("json serialization shouldn't fail"))
+        }
+    })
+}
+
+/// Converts a property value to the same type of `Value` that the setting currently has.
+fn from_property(
+    device: &dyn openscq30_lib::device::OpenSCQ30Device,
+    name: &str,
+    value: &Variant<Box<dyn RefArg>>,
+) -> Result<(SettingId, Value), MethodErr> {
+    let setting_id = SettingId::VARIANTS
+        .iter()
+        .copied()
+        .find(|setting_id| property_name(*setting_id) == name)
+        .ok_or_else(|| MethodErr::invalid_arg(&format!("unknown setting {name}")))?;
+    let current_value = device
+        .setting(&setting_id)
+        .map(Value::from)
+        .ok_or_else(|| MethodErr::failed(&format!("{setting_id} is not currently available")))?;
+
+    let arg = value.0.as_ref();
+    let wrong_type =
+        || MethodErr::invalid_arg(&format!("{name} has the wrong type {}", arg.signature()));
+    let strings = || -> Option<Vec<Cow<'static, str>>> {
+        arg.as_iter()?
+            .map(|item| item.as_str().map(|item| Cow::Owned(item.to_owned())))
+            .collect()
+    };
+    let integers = || -> Option<Vec<i64>> { arg.as_iter()?.map(|item| item.as_i64()).collect() };
+
+    let new_value = match current_value {
+        Value::Bool(_) => dbus::arg::cast::<bool>(arg).copied().map(Value::Bool),
+        Value::U16(_) => arg
+            .as_u64()
+            .and_then(|value| u16::try_from(value).ok())
+            .map(Value::U16),
+        Value::I32(_) => arg
+            .as_i64()
+            .and_then(|value| i32::try_from(value).ok())
+            .map(Value::I32),
+        Value::String(_) => arg
+            .as_str()
+            .map(|value| Value::String(Cow::Owned(value.to_owned()))),
+        Value::OptionalString(_) => arg.as_str().map(|value| {
+            Value::OptionalString((!value.is_empty()).then(|| Cow::Owned(value.to_owned())))
+        }),
+        Value::StringVec(_) => strings().map(Value::StringVec),
+        Value::I16Vec(_) => integers()
+            .and_then(|values| values.into_iter().map(|v| i16::try_from(v).ok()).collect())
+            .map(Value::I16Vec),
+        Value::U16Vec(_) => integers()
+            .and_then(|values| values.into_iter().map(|v| u16::try_from(v).ok()).collect())
+            .map(Value::U16Vec),
+        Value::OptionalU16(_) => integers()
+            .and_then(|values| match values.as_slice() {
+                [] => Some(None),
+                [value] => u16::try_from(*value).ok().map(Some),
+                _ => None,
+            })
+            .map(Value::OptionalU16),
+        Value::ModifiableSelectCommand(_) | Value::MultiSelectWithRemoveCommand(_) => None,
+    };
+    new_value
+        .map(|value| (setting_id, value))
+        .ok_or_else(wrong_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+        time::Duration,
+    };
+
+    use dbus::{channel::Channel, nonblock::Proxy};
+    use openscq30_lib::{DeviceModel, OpenSCQ30Session, storage::PairedDevice};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// A private bus so tests don't depend on or interfere with the user's session bus.
+    struct TestBus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl TestBus {
+        fn new() -> Self {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("dbus-daemon should be installed");
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            Self {
+                daemon,
+                address: address.trim().to_owned(),
+            }
+        }
+
+        fn connect(&self) -> (AbortOnDropHandle<()>, Arc<SyncConnection>) {
+            let mut channel = Channel::open_private(&self.address).unwrap();
+            channel.register().unwrap();
+            let (resource, connection) =
+                dbus_tokio::connection::from_channel::<SyncConnection>(channel).unwrap();
+            let handle = AbortOnDropHandle::new(tokio::spawn(async move {
+                resource.await;
+            }));
+            (handle, connection)
+        }
+    }
+
+    impl Drop for TestBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn publishes_device_settings() {
+        let bus = TestBus::new();
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        let mac_address = DeviceModel::SoundcoreA3028.demo_mac_address();
+        session
+            .pair(PairedDevice {
+                mac_address,
+                model: DeviceModel::SoundcoreA3028,
+                is_demo: true,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        let server = Arc::new(Server::new(session));
+
+        let (_service_resource, service_connection) = bus.connect();
+        let service_name = service_connection.unique_name().to_string();
+        let _service = AbortOnDropHandle::new(tokio::spawn(serve(server, service_connection)));
+
+        let (_client_resource, client_connection) = bus.connect();
+        let manager = Proxy::new(
+            &service_name,
+            MANAGER_PATH,
+            Duration::from_secs(5),
+            client_connection.clone(),
+        );
+        let (path,): (Path<'static>,) = manager
+            .method_call(MANAGER_INTERFACE, "Connect", (mac_address.to_string(),))
+            .await
+            .unwrap();
+        assert_eq!(device_path(mac_address), path);
+
+        let device = Proxy::new(
+            &service_name,
+            path.clone(),
+            Duration::from_secs(5),
+            client_connection.clone(),
+        );
+        let ambient_sound_mode: Variant<String> = device
+            .method_call(
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                (DEVICE_INTERFACE, "AmbientSoundMode"),
+            )
+            .await
+            .map(|(value,): (Variant<String>,)| value)
+            .unwrap();
+        assert_eq!("NoiseCanceling", ambient_sound_mode.0);
+        let (properties,): (dbus::arg::PropMap,) = device
+            .method_call(
+                "org.freedesktop.DBus.Properties",
+                "GetAll",
+                (DEVICE_INTERFACE,),
+            )
+            .await
+            .unwrap();
+        assert!(properties.contains_key("AmbientSoundMode"));
+        assert!(
+            !properties.contains_key("LeftSinglePress"),
+            "settings the device doesn't have should be left out",
+        );
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let _match = client_connection
+            .add_match(PropertiesPropertiesChanged::match_rule(None, Some(&path)).static_clone())
+            .await
+            .unwrap()
+            .cb(move |_, signal: PropertiesPropertiesChanged| sender.send(signal).is_ok());
+
+        let values: PropertyValues = vec![(
+            "AmbientSoundMode".to_owned(),
+            Variant(Box::new("Transparency".to_owned())),
+        )];
+        let () = device
+            .method_call(DEVICE_INTERFACE, "SetSettingValues", (values,))
+            .await
+            .unwrap();
+
+        let signal = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .expect("should receive PropertiesChanged")
+            .unwrap();
+        assert_eq!(DEVICE_INTERFACE, signal.interface_name);
+        assert_eq!(
+            Some("Transparency"),
+            signal.changed_properties["AmbientSoundMode"].0.as_str(),
+        );
+
+        let err = device
+            .method_call::<(), _, _, _>(
+                DEVICE_INTERFACE,
+                "SetSettingValues",
+                ({
+                    let values: PropertyValues =
+                        vec![("AmbientSoundMode".to_owned(), Variant(Box::new(1i32)))];
+                    values
+                },),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(Some("org.freedesktop.DBus.Error.InvalidArgs"), err.name());
+
+        // The device interface is shared, so it should still work for the new connection
+        let () = manager
+            .method_call(MANAGER_INTERFACE, "Disconnect", (mac_address.to_string(),))
+            .await
+            .unwrap();
+        let (path,): (Path<'static>,) = manager
+            .method_call(MANAGER_INTERFACE, "Connect", (mac_address.to_string(),))
+            .await
+            .unwrap();
+        let device = Proxy::new(
+            &service_name,
+            path,
+            Duration::from_secs(5),
+            client_connection.clone(),
+        );
+        let (model,): (Variant<String>,) = device
+            .method_call(
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                (DEVICE_INTERFACE, "Model"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(DeviceModel::SoundcoreA3028.to_string(), model.0);
+    }
+}
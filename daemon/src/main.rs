@@ -0,0 +1,154 @@
+#[cfg(feature = "dbus")]
+mod dbus_service;
+mod rpc;
+mod server;
+
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
+
+use anyhow::{Context, anyhow, bail};
+use clap::{ArgAction, ArgMatches, Command, arg, value_parser};
+use dirs::{config_dir, runtime_dir};
+use openscq30_lib::{OpenSCQ30Session, device::ReconnectPolicy};
+use tokio::{
+    net::{UnixListener, UnixStream},
+    signal::unix::{SignalKind, signal},
+};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+use crate::server::Server;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let matches = build_cli().get_matches();
+
+    if let Err(err) = initialize_logging(&matches) {
+        eprintln!("Logging error: {err:?}");
+        return ExitCode::FAILURE;
+    }
+
+    if let Err(err) = run(&matches).await {
+        eprintln!("Error: {err:#}");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn build_cli() -> Command {
+    Command::new(env!("CARGO_BIN_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Keeps connections to Soundcore devices open, sharing them with other programs over a unix socket")
+        .max_term_width(100)
+        .after_help(
+r#"Clients communicate with the daemon using JSON-RPC 2.0, with one message per line. Devices are connected to on demand, and stay connected until disconnected by a client or the daemon exits. If a device's connection is lost, it will be reconnected automatically.
+
+Methods:
+  pairedDevices                                 List devices paired with openscq30
+  connectedDevices                              List devices the daemon is connected to
+  connect { macAddress }                        Connect to a paired device
+  disconnect { macAddress }                     Disconnect from a device
+  settings { macAddress }                       Get all settings, grouped by category
+  setSettings { macAddress, settings }          Set settings, where settings is an object of setting ids to values
+  watch { macAddress }                          Subscribe to settingsChanged and connectionStatusChanged notifications
+  unwatch { macAddress }                        Unsubscribe from notifications
+
+Example:
+  --> {"jsonrpc":"2.0","id":1,"method":"setSettings","params":{"macAddress":"00:00:00:00:00:02","settings":{"ambientSoundMode":{"type":"string","value":"NoiseCanceling"}}}}
+  <-- {"jsonrpc":"2.0","id":1,"result":null}"#,
+        )
+        .arg(
+            arg!(-s --socket <PATH> "Path of the unix socket to listen on. Defaults to openscq30.sock in the runtime directory.")
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            arg!(-v --verbose "Enables logging with warn log level. Using this argument multiple times will decrease min log level, up to -vvvv.")
+                .action(ArgAction::Count),
+        )
+}
+
+async fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    let socket_path = match matches.get_one::<PathBuf>("socket") {
+        Some(path) => path.to_owned(),
+        None => runtime_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("openscq30.sock"),
+    };
+    let listener = bind(&socket_path).await?;
+    tracing::info!("listening on {}", socket_path.display());
+
+    let session = openscq30_session()
+        .await?
+        .with_reconnect_policy(ReconnectPolicy {
+            max_attempts: None,
+            ..Default::default()
+        });
+    let server = Arc::new(Server::new(session));
+    #[cfg(feature = "dbus")]
+    let _dbus_handle = dbus_service::start(server.clone())
+        .await
+        .context("starting D-Bus service")?;
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let result = tokio::select! {
+        result = server.serve(listener) => result.context("accepting connection"),
+        result = tokio::signal::ctrl_c() => result.context("waiting for ctrl+c"),
+        _ = sigterm.recv() => Ok(()),
+    };
+    if let Err(err) = std::fs::remove_file(&socket_path) {
+        tracing::warn!("failed to remove socket {}: {err:?}", socket_path.display());
+    }
+    result
+}
+
+async fn bind(socket_path: &PathBuf) -> anyhow::Result<UnixListener> {
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).await.is_ok() {
+            bail!(
+                "another instance is already listening on {}",
+                socket_path.display()
+            );
+        }
+        // Left behind by an instance that didn't shut down cleanly
+        std::fs::remove_file(socket_path)
+            .with_context(|| format!("removing stale socket {}", socket_path.display()))?;
+    }
+    UnixListener::bind(socket_path).with_context(|| format!("binding {}", socket_path.display()))
+}
+
+async fn openscq30_session() -> anyhow::Result<OpenSCQ30Session> {
+    let db_path = match std::env::var_os("OPENSCQ30_DATABASE_PATH") {
+        Some(path) => PathBuf::from(path),
+        None => config_dir()
+            .ok_or_else(|| anyhow!("failed to find config dir"))?
+            .join("openscq30")
+            .join("database.sqlite"),
+    };
+    OpenSCQ30Session::new(db_path).await.map_err(Into::into)
+}
+
+fn initialize_logging(matches: &ArgMatches) -> anyhow::Result<()> {
+    let log_level_filter = match matches.get_count("verbose") {
+        0 => None,
+        1 => Some(LevelFilter::WARN),
+        2 => Some(LevelFilter::INFO),
+        3 => Some(LevelFilter::DEBUG),
+        _ => Some(LevelFilter::TRACE),
+    };
+
+    if let Some(log_level_filter) = log_level_filter {
+        tracing_subscriber::fmt()
+            .with_file(true)
+            .with_line_number(true)
+            .with_target(true)
+            .with_env_filter(
+                EnvFilter::builder()
+                    .with_default_directive(log_level_filter.into())
+                    .from_env()?,
+            )
+            .with_writer(std::io::stderr)
+            .pretty()
+            .init();
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! JSON-RPC 2.0 message types. Each message is a single line of JSON.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    /// Requests without an id are notifications, and will not be responded to.
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(flatten)]
+    body: ResponseBody,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ResponseBody {
+    Result(Value),
+    Error(Error),
+}
+
+impl Response {
+    pub fn new(id: Value, result: Result<Value, Error>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            body: match result {
+                Ok(value) => ResponseBody::Result(value),
+                Err(err) => ResponseBody::Error(err),
+            },
+        }
+    }
+}
+
+/// A message sent from the server without a corresponding request.
+#[derive(Debug, Serialize)]
+pub struct Notification<T> {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: T,
+}
+
+impl<T> Notification<T> {
+    pub fn new(method: &'static str, params: T) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            method,
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Error {
+    pub code: i32,
+    pub message: String,
+}
+
+impl Error {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const INVALID_REQUEST: i32 = -32600;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    /// Something went wrong while communicating with a device or the database
+    pub const DEVICE_ERROR: i32 = -32000;
+
+    pub fn parse_error(err: serde_json::Error) -> Self {
+        Self {
+            code: Self::PARSE_ERROR,
+            message: err.to_string(),
+        }
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            code: Self::INVALID_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: Self::METHOD_NOT_FOUND,
+            message: format!("method not found: {method}"),
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: Self::INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+
+    pub fn device_error(err: impl Into<anyhow::Error>) -> Self {
+        Self {
+            code: Self::DEVICE_ERROR,
+            // display anyhow context chain on one line
+            message: format!("{:#}", err.into()),
+        }
+    }
+}
@@ -0,0 +1,640 @@
+use std::{collections::HashMap, io, sync::Arc};
+
+use indexmap::IndexMap;
+use macaddr::MacAddr6;
+use openscq30_lib::{
+    DeviceModel, OpenSCQ30Session,
+    connection::ConnectionStatus,
+    device::OpenSCQ30Device,
+    serialization::mac_addr,
+    settings::{Setting, SettingId, Value},
+    util::AbortOnDropHandle,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    select,
+    sync::{mpsc, watch},
+};
+
+use crate::rpc::{self, JSONRPC_VERSION, Notification, Request, Response};
+
+pub type Device = Arc<dyn OpenSCQ30Device + Send + Sync>;
+
+/// Owns all device connections, sharing them between every client connected to the socket.
+pub struct Server {
+    session: OpenSCQ30Session,
+    devices: watch::Sender<HashMap<MacAddr6, Device>>,
+}
+
+impl Server {
+    pub fn new(session: OpenSCQ30Session) -> Self {
+        Self {
+            session,
+            devices: watch::Sender::default(),
+        }
+    }
+
+    /// Fires whenever a device is connected or disconnected.
+    #[cfg(feature = "dbus")]
+    pub fn watch_devices(&self) -> watch::Receiver<HashMap<MacAddr6, Device>> {
+        self.devices.subscribe()
+    }
+
+    /// Returns the existing connection to the device, connecting first if necessary.
+    pub async fn device(&self, mac_address: MacAddr6) -> Result<Device, rpc::Error> {
+        if let Some(device) = self.devices.borrow().get(&mac_address)
+            && *device.connection_status().borrow() != ConnectionStatus::Disconnected
+        {
+            return Ok(device.clone());
+        }
+        // The session shares one connection per device, so clients racing to connect end up with the same device
+        let device = self
+            .session
+            .connect(mac_address)
+            .await
+            .map_err(rpc::Error::device_error)?;
+        self.devices.send_if_modified(|devices| {
+            let is_new = devices
+                .get(&mac_address)
+                .is_none_or(|existing| !Arc::ptr_eq(existing, &device));
+            if is_new {
+                devices.insert(mac_address, device.clone());
+            }
+            is_new
+        });
+        Ok(device)
+    }
+
+    /// Stops sharing the device with clients. Once every watch and D-Bus object using it has been dropped, the
+    /// connection is closed.
+    pub fn disconnect(&self, mac_address: MacAddr6) {
+        self.devices
+            .send_if_modified(|devices| devices.remove(&mac_address).is_some());
+    }
+
+    pub async fn serve(self: Arc<Self>, listener: UnixListener) -> io::Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = server.handle_client(stream).await {
+                    tracing::warn!("client connection closed with error: {err:?}");
+                }
+            });
+        }
+    }
+
+    async fn handle_client(&self, stream: UnixStream) -> io::Result<()> {
+        let (read_half, mut write_half) = stream.into_split();
+        let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+        // Responses and notifications are both sent through the channel so that they don't interleave
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let result = async {
+                    write_half.write_all(message.as_bytes()).await?;
+                    write_half.write_all(b"\n").await
+                }
+                .await;
+                if let Err(err) = result {
+                    tracing::debug!("failed to write to client: {err:?}");
+                    break;
+                }
+            }
+        });
+
+        let mut client = Client {
+            sender,
+            devices: self.devices.subscribe(),
+            watches: HashMap::new(),
+        };
+        let mut lines = BufReader::new(read_half).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&mut client, &line).await {
+                client.send(&response);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_message(&self, client: &mut Client, message: &str) -> Option<Response> {
+        let request = match serde_json::from_str::<serde_json::Value>(message) {
+            Ok(json) => json,
+            Err(err) => {
+                return Some(Response::new(
+                    serde_json::Value::Null,
+                    Err(rpc::Error::parse_error(err)),
+                ));
+            }
+        };
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) if request.jsonrpc == JSONRPC_VERSION => request,
+            Ok(request) => {
+                return Some(Response::new(
+                    request.id.unwrap_or_default(),
+                    Err(rpc::Error::invalid_request(format!(
+                        "unsupported jsonrpc version {}",
+                        request.jsonrpc
+                    ))),
+                ));
+            }
+            Err(err) => {
+                return Some(Response::new(
+                    serde_json::Value::Null,
+                    Err(rpc::Error::invalid_request(err.to_string())),
+                ));
+            }
+        };
+
+        tracing::debug!("handling {} request", request.method);
+        let result = self
+            .handle_request(client, &request.method, request.params)
+            .await;
+        request.id.map(|id| Response::new(id, result))
+    }
+
+    async fn handle_request(
+        &self,
+        client: &mut Client,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, rpc::Error> {
+        let result = match method {
+            "pairedDevices" => to_value(
+                self.session
+                    .paired_devices()
+                    .await
+                    .map_err(rpc::Error::device_error)?,
+            ),
+            "connectedDevices" => {
+                let devices = self.devices.borrow();
+                to_value(
+                    devices
+                        .iter()
+                        .map(|(mac_address, device)| DeviceInfo::new(*mac_address, device))
+                        .collect::<Vec<_>>(),
+                )
+            }
+            "connect" => {
+                let DeviceParams { mac_address } = parse_params(params)?;
+                let device = self.device(mac_address).await?;
+                to_value(DeviceInfo::new(mac_address, &device))
+            }
+            "disconnect" => {
+                let DeviceParams { mac_address } = parse_params(params)?;
+                self.disconnect(mac_address);
+                serde_json::Value::Null
+            }
+            "settings" => {
+                let DeviceParams { mac_address } = parse_params(params)?;
+                to_value(self.device(mac_address).await?.settings_by_category())
+            }
+            "setSettings" => {
+                let SetSettingsParams {
+                    mac_address,
+                    settings,
+                } = parse_params(params)?;
+                self.device(mac_address)
+                    .await?
+                    .set_setting_values(settings.into_iter().collect())
+                    .await
+                    .map_err(rpc::Error::device_error)?;
+                serde_json::Value::Null
+            }
+            "watch" => {
+                let DeviceParams { mac_address } = parse_params(params)?;
+                client.watch(mac_address, self.device(mac_address).await?);
+                serde_json::Value::Null
+            }
+            "unwatch" => {
+                let DeviceParams { mac_address } = parse_params(params)?;
+                client.watches.remove(&mac_address);
+                serde_json::Value::Null
+            }
+            _ => return Err(rpc::Error::method_not_found(method)),
+        };
+        Ok(result)
+    }
+}
+
+struct Client {
+    sender: mpsc::UnboundedSender<String>,
+    devices: watch::Receiver<HashMap<MacAddr6, Device>>,
+    watches: HashMap<MacAddr6, AbortOnDropHandle<()>>,
+}
+
+impl Client {
+    fn send(&self, message: &impl Serialize) {
+        let message = serde_json::to_string(message).expect("json serialization shouldn't fail");
+        // If the writer is gone, the client disconnected, and we'll find out when reading the next line fails
+        let _ = self.sender.send(message);
+    }
+
+    /// Sends `settingsChanged` notifications containing only the settings that changed, and `connectionStatusChanged`
+    /// notifications, until unwatched, the device is disconnected from the server, or the client disconnects.
+    fn watch(&mut self, mac_address: MacAddr6, device: Device) {
+        let sender = self.sender.clone();
+        let mut devices = self.devices.clone();
+        let send = move |method, params: serde_json::Value| {
+            let message = serde_json::to_string(&Notification::new(method, params))
+                .expect("json serialization shouldn't fail");
+            sender.send(message).is_ok()
+        };
+        let handle = AbortOnDropHandle::new(tokio::spawn(async move {
+            let mut changes = device.watch_for_changes();
+            let mut connection_status = device.connection_status();
+            let mut settings = all_settings(device.as_ref());
+            loop {
+                let is_client_connected = select! {
+                    result = changes.changed() => {
+                        if result.is_err() {
+                            return;
+                        }
+                        let new_settings = all_settings(device.as_ref());
+                        let changed_settings = new_settings
+                            .iter()
+                            .filter(|(setting_id, setting)| settings.get(*setting_id) != Some(*setting))
+                            .map(|(setting_id, setting)| (*setting_id, setting.clone()))
+                            .collect::<IndexMap<_, _>>();
+                        settings = new_settings;
+                        changed_settings.is_empty()
+                            || send(
+                                "settingsChanged",
+                                to_value(SettingsChanged {
+                                    mac_address,
+                                    settings: changed_settings,
+                                }),
+                            )
+                    }
+                    result = connection_status.changed() => {
+                        if result.is_err() {
+                            return;
+                        }
+                        let connection_status = *connection_status.borrow_and_update();
+                        send(
+                            "connectionStatusChanged",
+                            to_value(ConnectionStatusChanged {
+                                mac_address,
+                                connection_status,
+                            }),
+                        )
+                    }
+                    result = devices.changed() => {
+                        if result.is_err() {
+                            return;
+                        }
+                        let is_shared = devices
+                            .borrow_and_update()
+                            .get(&mac_address)
+                            .is_some_and(|shared_device| Arc::ptr_eq(shared_device, &device));
+                        if !is_shared {
+                            // Holding on to the device would keep its connection open
+                            send(
+                                "connectionStatusChanged",
+                                to_value(ConnectionStatusChanged {
+                                    mac_address,
+                                    connection_status: ConnectionStatus::Disconnected,
+                                }),
+                            );
+                            return;
+                        }
+                        true
+                    }
+                };
+                if !is_client_connected {
+                    return;
+                }
+            }
+        }));
+        self.watches.insert(mac_address, handle);
+    }
+}
+
+pub fn all_settings(device: &(dyn OpenSCQ30Device + Send + Sync)) -> IndexMap<SettingId, Setting> {
+    device
+        .settings_by_category()
+        .into_values()
+        .flatten()
+        .collect()
+}
+
+fn to_value(value: impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).expect("json serialization shouldn't fail")
+}
+
+fn parse_params<T: DeserializeOwned>(params: serde_json::Value) -> Result<T, rpc::Error> {
+    serde_json::from_value(params).map_err(|err| rpc::Error::invalid_params(err.to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceParams {
+    #[serde(with = "mac_addr")]
+    mac_address: MacAddr6,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetSettingsParams {
+    #[serde(with = "mac_addr")]
+    mac_address: MacAddr6,
+    /// Settings are set in order
+    settings: IndexMap<SettingId, Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceInfo {
+    #[serde(with = "mac_addr")]
+    mac_address: MacAddr6,
+    model: DeviceModel,
+    connection_status: ConnectionStatus,
+}
+
+impl DeviceInfo {
+    fn new(mac_address: MacAddr6, device: &Device) -> Self {
+        Self {
+            mac_address,
+            model: device.model(),
+            connection_status: *device.connection_status().borrow(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChanged {
+    #[serde(with = "mac_addr")]
+    mac_address: MacAddr6,
+    settings: IndexMap<SettingId, Setting>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionStatusChanged {
+    #[serde(with = "mac_addr")]
+    mac_address: MacAddr6,
+    connection_status: ConnectionStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use openscq30_lib::storage::PairedDevice;
+    use serde_json::json;
+    use tokio::{
+        io::Lines,
+        net::unix::{OwnedReadHalf, OwnedWriteHalf},
+    };
+
+    use super::*;
+
+    struct TestClient {
+        lines: Lines<BufReader<OwnedReadHalf>>,
+        writer: OwnedWriteHalf,
+    }
+
+    impl TestClient {
+        async fn send(&mut self, message: &str) {
+            self.writer.write_all(message.as_bytes()).await.unwrap();
+            self.writer.write_all(b"\n").await.unwrap();
+        }
+
+        async fn receive(&mut self) -> serde_json::Value {
+            let line = tokio::time::timeout(Duration::from_secs(5), self.lines.next_line())
+                .await
+                .expect("should receive a message")
+                .unwrap()
+                .expect("connection should not be closed");
+            serde_json::from_str(&line).unwrap()
+        }
+
+        async fn request(
+            &mut self,
+            id: u64,
+            method: &str,
+            params: serde_json::Value,
+        ) -> serde_json::Value {
+            self.send(
+                &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+                    .to_string(),
+            )
+            .await;
+            self.receive().await
+        }
+    }
+
+    struct TestServer {
+        server: Arc<Server>,
+        socket_path: std::path::PathBuf,
+        mac_address: MacAddr6,
+        _directory: tempfile::TempDir,
+        _handle: AbortOnDropHandle<io::Result<()>>,
+    }
+
+    impl TestServer {
+        async fn new() -> Self {
+            let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+            let mac_address = DeviceModel::SoundcoreA3028.demo_mac_address();
+            session
+                .pair(PairedDevice {
+                    mac_address,
+                    model: DeviceModel::SoundcoreA3028,
+                    is_demo: true,
+                    adapter_address: None,
+                })
+                .await
+                .unwrap();
+
+            let directory = tempfile::tempdir().unwrap();
+            let socket_path = directory.path().join("openscq30.sock");
+            let listener = UnixListener::bind(&socket_path).unwrap();
+            let server = Arc::new(Server::new(session));
+            let handle = AbortOnDropHandle::new(tokio::spawn(server.clone().serve(listener)));
+            Self {
+                server,
+                socket_path,
+                mac_address,
+                _directory: directory,
+                _handle: handle,
+            }
+        }
+
+        async fn client(&self) -> TestClient {
+            let (reader, writer) = UnixStream::connect(&self.socket_path)
+                .await
+                .unwrap()
+                .into_split();
+            TestClient {
+                lines: BufReader::new(reader).lines(),
+                writer,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_and_get_settings() {
+        let server = TestServer::new().await;
+        let mut client = server.client().await;
+        let mac_address = server.mac_address.to_string();
+
+        assert_eq!(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": [{ "macAddress": mac_address, "model": "SoundcoreA3028", "isDemo": true }],
+            }),
+            client.request(1, "pairedDevices", json!(null)).await,
+        );
+        assert_eq!(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "result": { "macAddress": mac_address, "model": "SoundcoreA3028", "connectionStatus": "connected" },
+            }),
+            client
+                .request(2, "connect", json!({ "macAddress": mac_address }))
+                .await,
+        );
+
+        let response = client
+            .request(3, "settings", json!({ "macAddress": mac_address }))
+            .await;
+        assert_eq!(
+            json!("NoiseCanceling"),
+            response["result"]["soundModes"]["ambientSoundMode"]["value"],
+        );
+    }
+
+    #[tokio::test]
+    async fn clients_share_device_and_receive_changes() {
+        let server = TestServer::new().await;
+        let mut watcher = server.client().await;
+        let mut setter = server.client().await;
+        let mac_address = server.mac_address.to_string();
+
+        assert_eq!(
+            json!({ "jsonrpc": "2.0", "id": 1, "result": null }),
+            watcher
+                .request(1, "watch", json!({ "macAddress": mac_address }))
+                .await,
+        );
+        assert_eq!(
+            json!({ "jsonrpc": "2.0", "id": 1, "result": null }),
+            setter
+                .request(
+                    1,
+                    "setSettings",
+                    json!({
+                        "macAddress": mac_address,
+                        "settings": {
+                            "ambientSoundMode": { "type": "string", "value": "Transparency" },
+                        },
+                    }),
+                )
+                .await,
+        );
+
+        let notification = watcher.receive().await;
+        assert_eq!(json!("settingsChanged"), notification["method"]);
+        assert_eq!(json!(mac_address), notification["params"]["macAddress"]);
+        let settings = notification["params"]["settings"].as_object().unwrap();
+        assert_eq!(json!("Transparency"), settings["ambientSoundMode"]["value"]);
+        assert!(
+            !settings.contains_key("serialNumber"),
+            "only changed settings should be included",
+        );
+
+        let response = setter.request(2, "connectedDevices", json!(null)).await;
+        assert_eq!(1, response["result"].as_array().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn disconnect_ends_watches_and_closes_connection() {
+        let server = TestServer::new().await;
+        let mut watcher = server.client().await;
+        let mut disconnecter = server.client().await;
+        let mac_address = server.mac_address.to_string();
+
+        watcher
+            .request(1, "watch", json!({ "macAddress": mac_address }))
+            .await;
+        assert_eq!(
+            json!({ "jsonrpc": "2.0", "id": 1, "result": null }),
+            disconnecter
+                .request(1, "disconnect", json!({ "macAddress": mac_address }))
+                .await,
+        );
+
+        let notification = watcher.receive().await;
+        assert_eq!(json!("connectionStatusChanged"), notification["method"]);
+        assert_eq!(
+            json!("disconnected"),
+            notification["params"]["connectionStatus"]
+        );
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server
+                .server
+                .session
+                .connected_device(server.mac_address)
+                .is_some()
+            {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("connection should be closed once nothing is using it");
+        assert_eq!(
+            json!([]),
+            disconnecter
+                .request(2, "connectedDevices", json!(null))
+                .await["result"],
+        );
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let server = TestServer::new().await;
+        let mut client = server.client().await;
+
+        client.send("not json").await;
+        assert_eq!(
+            json!(rpc::Error::PARSE_ERROR),
+            client.receive().await["error"]["code"],
+        );
+        client.send(r#"{"jsonrpc":"2.0","id":1}"#).await;
+        assert_eq!(
+            json!(rpc::Error::INVALID_REQUEST),
+            client.receive().await["error"]["code"],
+        );
+        assert_eq!(
+            json!(rpc::Error::METHOD_NOT_FOUND),
+            client.request(2, "doesNotExist", json!(null)).await["error"]["code"],
+        );
+        assert_eq!(
+            json!(rpc::Error::INVALID_PARAMS),
+            client
+                .request(3, "connect", json!({ "macAddress": "invalid" }))
+                .await["error"]["code"],
+        );
+        assert_eq!(
+            json!(rpc::Error::DEVICE_ERROR),
+            client
+                .request(4, "connect", json!({ "macAddress": "00:11:22:33:44:55" }))
+                .await["error"]["code"],
+        );
+        // notifications don't get a response, so the next response should be for id 5
+        client
+            .send(r#"{"jsonrpc":"2.0","method":"pairedDevices"}"#)
+            .await;
+        assert_eq!(
+            json!(5),
+            client.request(5, "pairedDevices", json!(null)).await["id"],
+        );
+    }
+}
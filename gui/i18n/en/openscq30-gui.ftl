@@ -11,6 +11,9 @@ cancel = Cancel
 add-device = Add Device
 device-model = Device Model
 select-device-model = Select Device Model
+detect-model = Detect Model
+select-device-to-detect = Select Your Device
+model-detection-failed = Unable to detect the device model. Please select it manually.
 select-your = Select Your { $name }
 device-name = Device Name
 error-loading-devices = Error Loading Devices
@@ -45,3 +48,6 @@ execute = Execute
 default = Default
 settings = Settings
 preferred-language = Preferred Language
+bluetooth-adapter = Bluetooth Adapter
+takes-effect-after-restart = Takes effect after restarting
+any = Any
@@ -22,6 +22,7 @@ pub struct AddDeviceModel {
 
 enum Stage {
     ModelSelection(ModelSelectionModel),
+    DetectModel(DetectModelModel),
     SelectDevice(SelectDeviceModel),
     Error(String),
 }
@@ -29,6 +30,11 @@ enum Stage {
 struct ModelSelectionModel {
     search_query: String,
     search_widget_id: widget::Id,
+    is_detection_failed: bool,
+}
+struct DetectModelModel {
+    devices: Option<Vec<ConnectionDescriptor>>,
+    is_detecting: bool,
 }
 struct SelectDeviceModel {
     search_query: String,
@@ -43,6 +49,10 @@ struct SelectDeviceModel {
 pub enum Message {
     SetDeviceModelSearchQuery(String),
     SelectModel(DeviceModel, bool),
+    StartModelDetection,
+    SetDetectionDeviceList(Vec<ConnectionDescriptor>),
+    DetectModel(usize),
+    SetDetectedModel(Option<DeviceModel>),
     SelectDevice(usize, bool),
     SetDeviceList(Vec<ConnectionDescriptor>, bool),
     SetDeviceNameSearchQuery(String),
@@ -63,6 +73,7 @@ impl AddDeviceModel {
             stage: Stage::ModelSelection(ModelSelectionModel {
                 search_query: String::new(),
                 search_widget_id: widget::Id::unique(),
+                is_detection_failed: false,
             }),
             key_binds: key_binds(),
         }
@@ -71,6 +82,7 @@ impl AddDeviceModel {
     pub fn view(&self) -> Element<'_, Message> {
         match &self.stage {
             Stage::ModelSelection(ui_model) => Self::device_model_selection(ui_model),
+            Stage::DetectModel(ui_model) => Self::detect_model(ui_model),
             Stage::SelectDevice(ui_model) => Self::select_device(ui_model),
             Stage::Error(message) => Self::error(message),
         }
@@ -80,10 +92,21 @@ impl AddDeviceModel {
         widget::column![
             widget::column![
                 widget::text::title2(fl!("select-device-model")),
-                widget::search_input(fl!("device-model"), &ui_model.search_query)
-                    .id(ui_model.search_widget_id.clone())
-                    .on_input(Message::SetDeviceModelSearchQuery),
+                widget::row![
+                    widget::search_input(fl!("device-model"), &ui_model.search_query)
+                        .id(ui_model.search_widget_id.clone())
+                        .on_input(Message::SetDeviceModelSearchQuery),
+                    widget::button::standard(fl!("detect-model"))
+                        .on_press(Message::StartModelDetection),
+                ]
+                .align_y(alignment::Vertical::Center)
+                .spacing(8),
             ]
+            .push_maybe(
+                ui_model
+                    .is_detection_failed
+                    .then(|| widget::text(fl!("model-detection-failed"))),
+            )
             .spacing(8)
             // padding should not apply to the list of devices, since those are buttons with their own padding
             .padding([0, 10]),
@@ -109,6 +132,38 @@ impl AddDeviceModel {
         .into()
     }
 
+    fn detect_model(ui_model: &DetectModelModel) -> Element<'_, Message> {
+        let devices = match &ui_model.devices {
+            Some(devices) if !ui_model.is_detecting => devices,
+            _ => return Self::loading(),
+        };
+        widget::column![
+            widget::text::title2(fl!("select-device-to-detect"))
+                .apply(widget::container)
+                .padding([0, 10]),
+            widget::scrollable(widget::column(devices.iter().enumerate().map(
+                |(index, device)| {
+                    widget::button::custom(
+                        widget::row![
+                            widget::text(&device.name),
+                            widget::text(device.mac_address.to_string())
+                                .align_x(alignment::Horizontal::Right)
+                                .width(Length::Fill),
+                        ]
+                        .align_y(alignment::Vertical::Center),
+                    )
+                    .name(&device.name)
+                    .class(widget::button::ButtonClass::Text)
+                    .width(Length::Fill)
+                    .on_press(Message::DetectModel(index))
+                    .into()
+                }
+            ))),
+        ]
+        .spacing(8)
+        .into()
+    }
+
     fn select_device(ui_model: &SelectDeviceModel) -> Element<'_, Message> {
         widget::responsive(|size| {
             widget::column![
@@ -257,6 +312,62 @@ impl AddDeviceModel {
                     },
                 ));
             }
+            Message::StartModelDetection => {
+                self.stage = Stage::DetectModel(DetectModelModel {
+                    devices: None,
+                    is_detecting: false,
+                });
+                let session = self.session.clone();
+                return Action::Task(Task::perform(
+                    // Connected devices are listed the same way regardless of model
+                    async move {
+                        session
+                            .list_devices(DeviceModel::SoundcoreDevelopment)
+                            .await
+                    },
+                    |result| match result {
+                        Ok(devices) => Message::SetDetectionDeviceList(devices),
+                        Err(err) => {
+                            error!("fetching devices for model detection: {err:?}");
+                            Message::SetErrorMessage(format!("{err}"))
+                        }
+                    },
+                ));
+            }
+            Message::SetDetectionDeviceList(devices) => {
+                if let Stage::DetectModel(ref mut ui_model) = self.stage {
+                    ui_model.devices = Some(devices);
+                }
+            }
+            Message::DetectModel(index) => {
+                if let Stage::DetectModel(ref mut ui_model) = self.stage
+                    && let Some(devices) = &ui_model.devices
+                {
+                    let mac_address = devices[index].mac_address;
+                    ui_model.is_detecting = true;
+                    let session = self.session.clone();
+                    return Action::Task(Task::perform(
+                        async move { session.detect_model(mac_address).await },
+                        move |result| match result {
+                            Ok(model) => Message::SetDetectedModel(model),
+                            Err(err) => {
+                                error!("detecting model of {mac_address}: {err:?}");
+                                Message::SetDetectedModel(None)
+                            }
+                        },
+                    ));
+                }
+            }
+            Message::SetDetectedModel(model) => {
+                if let Some(model) = model {
+                    return self.update(Message::SelectModel(model, false));
+                }
+                self.stage = Stage::ModelSelection(ModelSelectionModel {
+                    search_query: String::new(),
+                    search_widget_id: widget::Id::unique(),
+                    is_detection_failed: true,
+                });
+            }
             Message::SetDeviceList(devices, is_demo_mode) => {
                 if let Stage::SelectDevice(ref mut ui_model) = self.stage {
                     ui_model.devices = Some(devices);
@@ -278,6 +389,7 @@ impl AddDeviceModel {
                         mac_address: descriptor.mac_address,
                         model: ui_model.device_model,
                         is_demo,
+                        adapter_address: None,
                     });
                 }
             }
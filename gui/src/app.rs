@@ -17,7 +17,10 @@ use cosmic::{
 use i18n_embed::unic_langid::LanguageIdentifier;
 use macaddr::MacAddr6;
 use openscq30_i18n::Translate;
-use openscq30_lib::{OpenSCQ30Session, device::OpenSCQ30Device, storage::PairedDevice};
+use openscq30_lib::{
+    OpenSCQ30Session, connection::BluetoothAdapter, device::OpenSCQ30Device,
+    device_definition::DeviceDefinition, storage::PairedDevice,
+};
 use tokio::{select, sync::Semaphore};
 
 use crate::{
@@ -40,6 +43,8 @@ pub struct AppModel {
     context_drawer_screen: Option<ContextDrawerScreen>,
     available_language_names: Vec<Cow<'static, str>>,
     available_languages: Vec<Option<LanguageIdentifier>>,
+    available_adapter_names: Vec<String>,
+    available_adapters: Vec<BluetoothAdapter>,
     key_binds: HashMap<KeyBind, KeyBindAction>,
 }
 
@@ -52,6 +57,7 @@ enum KeyBindAction {
 pub struct AppFlags {
     pub config: Config,
     pub config_dir: PathBuf,
+    pub device_definition: Option<DeviceDefinition>,
 }
 
 enum ContextDrawerScreen {
@@ -80,6 +86,8 @@ pub enum Message {
     ToggleSettings,
     None,
     SetPreferredLanguage(usize),
+    SetAvailableAdapters(Vec<BluetoothAdapter>),
+    SetBluetoothAdapter(usize),
     KeyPressed {
         modifiers: keyboard::Modifiers,
         key: keyboard::Key,
@@ -168,12 +176,28 @@ impl Application for AppModel {
             .license(env!("CARGO_PKG_LICENSE"))
             .links([(env!("CARGO_PKG_REPOSITORY"), env!("CARGO_PKG_REPOSITORY"))]);
 
-        let session = Arc::new(
-            futures::executor::block_on(OpenSCQ30Session::new(
-                flags.config_dir.join("database.sqlite"),
-            ))
-            .expect("database is required to run"),
-        );
+        let mut session = futures::executor::block_on(OpenSCQ30Session::new(
+            flags.config_dir.join("database.sqlite"),
+        ))
+        .expect("database is required to run");
+        if let Some(device_definition) = flags.device_definition {
+            session = session.with_device_definition(device_definition);
+        }
+        if let Some(adapter) = &flags.config.get().bluetooth_adapter {
+            session = session.with_adapter(adapter);
+        }
+        let session = Arc::new(session);
+        let adapters_task = {
+            let session = session.clone();
+            Task::future(async move {
+                session
+                    .adapters()
+                    .await
+                    .map(Message::SetAvailableAdapters)
+                    .map_err(handle_soft_error!())
+            })
+            .map(coalesce_result)
+        };
         let (model, task) = DeviceSelectionModel::new(session.clone());
         let (available_languages, available_language_names) =
             iter::once((None, Cow::Owned(fl!("default"))))
@@ -194,6 +218,8 @@ impl Application for AppModel {
             context_drawer_screen: None,
             available_language_names,
             available_languages,
+            available_adapter_names: vec![fl!("any")],
+            available_adapters: Vec::new(),
             key_binds: key_binds(),
         };
         let command = app.update_title();
@@ -202,6 +228,7 @@ impl Application for AppModel {
             cosmic::Task::batch([
                 command,
                 task.map(Message::DeviceSelectionScreen).map(Into::into),
+                adapters_task.map(Into::into),
             ]),
         )
     }
@@ -352,7 +379,33 @@ impl Application for AppModel {
                                     ),
                                     Message::SetPreferredLanguage,
                                 )),
-                        ],
+                        ]
+                        // Platforms that don't support choosing an adapter have none listed
+                        .push_maybe(
+                            (!self.available_adapters.is_empty()).then(|| {
+                                widget::settings::item::builder(fl!("bluetooth-adapter"))
+                                    .description(fl!("takes-effect-after-restart"))
+                                    .flex_control(widget::dropdown(
+                                        &self.available_adapter_names,
+                                        Some(
+                                            self.config
+                                                .get()
+                                                .bluetooth_adapter
+                                                .as_ref()
+                                                .and_then(|selected| {
+                                                    self.available_adapters
+                                                        .iter()
+                                                        .position(|adapter| {
+                                                            adapter.matches(selected)
+                                                        })
+                                                        .map(|index| index + 1)
+                                                })
+                                                .unwrap_or_default(),
+                                        ),
+                                        Message::SetBluetoothAdapter,
+                                    ))
+                            }),
+                        ),
                         Message::CloseContextDrawer,
                     )
                     .title(fl!("settings")),
@@ -577,6 +630,34 @@ impl Application for AppModel {
                 }
             }
             Message::ToggleSettings => self.toggle_settings(),
+            Message::SetAvailableAdapters(adapters) => {
+                self.available_adapter_names = iter::once(fl!("any"))
+                    .chain(
+                        adapters
+                            .iter()
+                            .map(|adapter| format!("{} ({})", adapter.name, adapter.address)),
+                    )
+                    .collect();
+                self.available_adapters = adapters;
+            }
+            Message::SetBluetoothAdapter(adapter_index) => {
+                // The address is stored since names can change between boots
+                let result_receiver = self.config.modify(|inner| {
+                    inner.bluetooth_adapter = adapter_index
+                        .checked_sub(1)
+                        .map(|index| self.available_adapters[index].address.to_string());
+                });
+
+                return Task::future(async move {
+                    if let Err(err) = result_receiver.await.unwrap() {
+                        tracing::error!("error writing to config file: {err:?}");
+                        Message::Warning(err.to_string())
+                    } else {
+                        Message::None
+                    }
+                })
+                .map(Into::into);
+            }
             Message::SetPreferredLanguage(language_index) => {
                 let result_receiver = self.config.modify(|inner| {
                     inner.preferred_language = self.available_languages[language_index]
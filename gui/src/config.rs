@@ -19,6 +19,8 @@ pub struct Config {
 #[serde(default)]
 pub struct ConfigInner {
     pub preferred_language: Option<String>,
+    /// Name or address of the bluetooth adapter to connect through, or `None` for any adapter.
+    pub bluetooth_adapter: Option<String>,
 }
 
 impl Config {
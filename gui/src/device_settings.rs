@@ -13,7 +13,7 @@ use std::{borrow::Cow, collections::HashMap, path::PathBuf};
 use cosmic::{
     Element, Task,
     app::context_drawer::ContextDrawer,
-    iced::keyboard,
+    iced::{keyboard, widget::qr_code},
     widget::{self, menu::KeyBind, nav_bar},
 };
 use legacy_migration::LegacyMigrationModel;
@@ -33,6 +33,9 @@ use crate::{
     utils::coalesce_result,
 };
 
+/// Information settings that should also be shown as a QR code
+const QR_CODE_SETTINGS: &[SettingId] = &[SettingId::ShareCustomEqualizerProfileOutput];
+
 #[derive(Debug, Clone)]
 pub enum Message {
     QuickPresets(quick_presets::Message),
@@ -84,6 +87,7 @@ pub struct DeviceSettingsModel {
     dialog: Option<Dialog>,
     legacy_equalizer_migration: Option<legacy_migration::LegacyMigrationModel>,
     import_strings: HashMap<SettingId, String>,
+    qr_codes: HashMap<SettingId, qr_code::Data>,
     quick_presets_model: quick_presets::QuickPresetsModel,
     throttle: throttle::Throttle,
     key_binds: HashMap<KeyBind, KeyBindAction>,
@@ -191,6 +195,7 @@ impl DeviceSettingsModel {
             dialog: None,
             legacy_equalizer_migration: None,
             import_strings: HashMap::new(),
+            qr_codes: HashMap::new(),
             quick_presets_model,
             key_binds: key_binds(),
         };
@@ -243,6 +248,25 @@ impl DeviceSettingsModel {
                         .map(|value| (setting_id, value))
                 })
                 .collect();
+            self.qr_codes = self
+                .settings
+                .iter()
+                .filter(|(setting_id, _)| QR_CODE_SETTINGS.contains(setting_id))
+                .filter_map(|(setting_id, setting)| match setting {
+                    Setting::Information { value, .. } if !value.is_empty() => {
+                        match qr_code::Data::new(value) {
+                            Ok(data) => Some((*setting_id, data)),
+                            Err(err) => {
+                                tracing::warn!(
+                                    "failed to generate qr code for {setting_id}: {err:?}"
+                                );
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                })
+                .collect();
         }
         Task::none()
     }
@@ -430,12 +454,17 @@ impl DeviceSettingsModel {
             Setting::Information {
                 value: _,
                 translated_value: translated_text,
-            } => information::information(
-                setting_id,
-                Cow::Borrowed(translated_text),
-                Message::CopyToClipboard(translated_text.to_owned()),
-            )
-            .into(),
+            } => {
+                let information = information::information(
+                    setting_id,
+                    Cow::Borrowed(translated_text),
+                    Message::CopyToClipboard(translated_text.to_owned()),
+                );
+                match self.qr_codes.get(&setting_id) {
+                    Some(data) => vec![information, information::qr_code(data)].into(),
+                    None => information.into(),
+                }
+            }
             Setting::ImportString {
                 confirmation_message: _,
             } => import_string::input(
@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-use cosmic::{Element, iced::core::text::Wrapping, widget};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length, core::text::Wrapping, widget::qr_code},
+    widget,
+};
 use openscq30_i18n::Translate;
 use openscq30_lib::settings::SettingId;
 
@@ -16,3 +20,14 @@ where
         .flex_control(widget::text(text).wrapping(Wrapping::WordOrGlyph))
         .into()
 }
+
+/// Shown below the information item for values that are meant to be scanned by another device
+pub fn qr_code<M>(data: &qr_code::Data) -> Element<'_, M>
+where
+    M: 'static,
+{
+    widget::container(qr_code(data).cell_size(4))
+        .width(Length::Fill)
+        .align_x(Alignment::Center)
+        .into()
+}
@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use cosmic::{
     Apply, Element,
     iced::alignment::{Horizontal, Vertical},
     widget::{self},
 };
+use openscq30_lib::settings::Equalizer;
 
 use crate::{equalizer_line::EqualizerLine, fl, openscq30_v1_migration::LegacyEqualizerProfile};
 
@@ -24,12 +25,19 @@ pub enum Message {
 
 impl LegacyMigrationModel {
     pub fn new(profiles: HashMap<String, LegacyEqualizerProfile>) -> Self {
+        // openscq30 v1 only supported devices with this layout
+        let legacy_equalizer = Equalizer {
+            band_hz: Cow::Borrowed(&[100, 200, 400, 800, 1600, 3200, 6400, 12800]),
+            fraction_digits: 1,
+            min: -120,
+            max: 134,
+        };
         let mut profiles = profiles
             .into_iter()
             .map(|(name, profile)| LegacyProfileInfo {
                 name: name.to_owned(),
                 values: profile.volume_offsets.to_owned(),
-                visualization: EqualizerLine::new(-120, 135, profile.volume_offsets),
+                visualization: EqualizerLine::new(&legacy_equalizer, &profile.volume_offsets),
             })
             .collect::<Vec<_>>();
         profiles.sort_unstable_by(|a, b| a.name.cmp(&b.name));
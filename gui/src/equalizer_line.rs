@@ -7,23 +7,25 @@ use cosmic::{
     widget::{self, canvas},
 };
 use itertools::Itertools;
+use openscq30_lib::settings::{Equalizer, equalizer_apo::GraphicEq};
 
 pub struct EqualizerLine<Message> {
     _message: PhantomData<Message>,
     cache: canvas::Cache,
-    min: i16,
-    max: i16,
-    values: Vec<i16>,
+    min_db: f32,
+    max_db: f32,
+    frequency_response: GraphicEq,
 }
 
 impl<Message> EqualizerLine<Message> {
-    pub fn new(min: i16, max: i16, values: Vec<i16>) -> Self {
+    pub fn new(equalizer: &Equalizer, values: &[i16]) -> Self {
+        let scale = 10f32.powi(equalizer.fraction_digits.into());
         Self {
             _message: PhantomData,
             cache: Default::default(),
-            min,
-            max,
-            values,
+            min_db: f32::from(equalizer.min) / scale,
+            max_db: f32::from(equalizer.max) / scale,
+            frequency_response: equalizer.frequency_response(values),
         }
     }
 
@@ -37,11 +39,13 @@ impl<Message> EqualizerLine<Message> {
     fn points(&self, width: f32, height: f32, padding: f32) -> impl Iterator<Item = Point<f32>> {
         let width_without_padding = width - padding * 2.0;
         let height_without_padding = height - padding * 2.0;
-        let range = self.max - self.min;
-        self.values.iter().enumerate().map(move |(i, v)| {
-            let normalized_x = i as f32 / (self.values.len() - 1) as f32;
+        let range = self.max_db - self.min_db;
+        let points = &self.frequency_response.points;
+        // Points are evenly spaced on a logarithmic scale, so they can be drawn evenly spaced too
+        points.iter().enumerate().map(move |(i, (_, db))| {
+            let normalized_x = i as f32 / (points.len() - 1) as f32;
             let x = normalized_x * width_without_padding + padding;
-            let normalized_y = 1f32 - ((v - self.min) as f32 / range as f32);
+            let normalized_y = 1f32 - ((*db as f32 - self.min_db) / range).clamp(0.0, 1.0);
             let y = normalized_y * height_without_padding + padding;
             Point::new(x, y)
         })
@@ -1,6 +1,9 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
+
+use anyhow::Context;
 
 use i18n_embed::unic_langid::LanguageIdentifier;
+use openscq30_lib::device_definition::DeviceDefinition;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
@@ -39,6 +42,13 @@ fn main() -> anyhow::Result<()> {
         Err(err) => tracing::error!("error checking if we're attached to a console: {err:?}"),
     }
 
+    let device_definition = device_definition_arg()
+        .map(|path| {
+            DeviceDefinition::load(&path)
+                .with_context(|| format!("loading device definition {}", path.display()))
+        })
+        .transpose()?;
+
     let config_dir = dirs::config_dir()
         .expect("failed to find config dir")
         .join("openscq30");
@@ -62,11 +72,30 @@ fn main() -> anyhow::Result<()> {
     openscq30_lib::i18n::init(&requested_languages);
 
     let settings = cosmic::app::Settings::default();
-    cosmic::app::run::<app::AppModel>(settings, app::AppFlags { config, config_dir })?;
+    cosmic::app::run::<app::AppModel>(
+        settings,
+        app::AppFlags {
+            config,
+            config_dir,
+            device_definition,
+        },
+    )?;
 
     Ok(())
 }
 
+/// `--device-definition <FILE>` connects to a fake device served from a soundcore-device-faker TOML file rather than
+/// over bluetooth. This is meant for development, so there is no need for full argument parsing.
+fn device_definition_arg() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--device-definition" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
+
 #[cfg(windows)]
 fn is_launched_from_console() -> anyhow::Result<bool> {
     use sysinfo::{ProcessRefreshKind, RefreshKind, System};
@@ -96,6 +96,7 @@ mod tests {
                 mac_address: MacAddr6::nil(),
                 model: DeviceModel::SoundcoreA3027,
                 is_demo: true,
+                adapter_address: None,
             })
             .await
             .unwrap();
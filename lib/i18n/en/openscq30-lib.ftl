@@ -25,6 +25,10 @@ soundcore-a3939 = Soundcore Life P3
 soundcore-a3935 = Soundcore Life A2 NC
 soundcore-a3959 = Soundcore P30i / R50i NC
 soundcore-a3968 = Soundcore Sport X20
+soundcore-a6611 = Soundcore Sleep A20
+soundcore-a3330 = Soundcore C30i
+soundcore-a3958 = Soundcore A30i
+soundcore-a3130 = Soundcore Motion X600
 soundcore-a3954 = Soundcore Liberty 4 Pro
 soundcore-a3955 = Soundcore P40i
 soundcore-a3957 = Soundcore Liberty 5
@@ -34,6 +38,7 @@ general = General
 sound-modes = Sound Modes
 equalizer = Equalizer
 equalizer-import-export = Equalizer Import/Export
+per-channel-equalizer = Per-Channel Equalizer
 device-information = Device Information
 miscellaneous = Miscellaneous
 
@@ -44,10 +49,27 @@ custom-noise-canceling = Custom Noise Canceling
 preset-profile = Preset Profile
 custom-profile = Custom Profile
 volume-adjustments = Volume Adjustments
+volume-adjustments-left = Left Volume Adjustments
+volume-adjustments-right = Right Volume Adjustments
 import-custom-equalizer-profiles = Import Custom Profiles
 import-custom-equalizer-profiles-confirm = This will overwrite existing profiles that share the same names.
 export-custom-equalizer-profiles = Export Custom Profiles
 export-custom-equalizer-profiles-output = Export Custom Profiles Output
+share-custom-equalizer-profile = Share Custom Profile
+share-custom-equalizer-profile-output = Share Custom Profile Output
+import-shared-equalizer-profile = Import Shared Profile
+import-shared-equalizer-profile-confirm = An existing profile with the same name will be overwritten.
+import-shared-equalizer-profile-result = Shared Profile Import Result
+shared-equalizer-profile-imported = Imported { $name }.
+shared-equalizer-profile-converted = Imported { $name }. It was made for the { $model }, so it was converted to this device's equalizer bands and may sound slightly different.
+shared-equalizer-profile-converted-with-clipping = Imported { $name }. It was made for the { $model }, so it was converted to this device's equalizer bands, but some bands needed more adjustment than the device supports: { $bands }
+import-equalizer-apo-profile = Import Equalizer APO / AutoEQ Profile
+import-equalizer-apo-profile-confirm = The profile will be named after the first comment, such as "# Sennheiser HD 600", or "{ $name }" if there is none. An existing profile with the same name will be overwritten.
+import-equalizer-apo-profile-result = Equalizer APO / AutoEQ Import Result
+equalizer-apo-profile-imported = Imported { $name }.
+equalizer-apo-profile-imported-with-clipping = Imported { $name }, but some bands needed more adjustment than the device supports: { $bands }
+copy-custom-equalizer-profiles-from = Copy Custom Profiles From
+export-equalizer-apo-profile = Export Equalizer APO / AutoEQ Profile
 
 volume = Volume
 
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, panic::Location, sync::Arc};
+use std::{collections::HashSet, panic::Location, str::FromStr, sync::Arc};
 
 use async_trait::async_trait;
 use macaddr::MacAddr6;
@@ -31,6 +31,8 @@ pub enum Error {
 
     #[error("{action} timed out")]
     TimedOut { action: &'static str },
+    #[error("{transport} is not supported by this backend")]
+    UnsupportedTransport { transport: &'static str },
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -40,6 +42,14 @@ pub trait RfcommBackend {
     /// this may include devices that are not currently connected.
     async fn devices(&self) -> Result<HashSet<ConnectionDescriptor>>;
 
+    /// Lists the uuids of the RFCOMM services offered by the device, as found through SDP. The device should already be
+    /// paired.
+    ///
+    /// Backends that are unable to look up services without connecting may return an empty set.
+    async fn service_uuids(&self, _mac_address: MacAddr6) -> Result<HashSet<Uuid>> {
+        Ok(HashSet::new())
+    }
+
     /// Connect via RFCOMM to the device. It should already be paired.
     ///
     /// The RFCOMM UUID to connect to may depend on what is available, so a `select_uuid` function should be passed that
@@ -71,6 +81,126 @@ pub trait RfcommConnection {
     fn connection_status(&self) -> watch::Receiver<ConnectionStatus>;
 }
 
+#[async_trait]
+pub trait GattBackend {
+    /// List all devices that are currently connected to the bluetooth adapter. On platforms where this isn't practical,
+    /// this may include devices that are not currently connected.
+    async fn devices(&self) -> Result<HashSet<ConnectionDescriptor>>;
+
+    /// Connect to the device's GATT server and subscribe to notifications from `service`'s notify characteristic.
+    ///
+    /// This method is cancel safe. Implementers should make sure that is the case.
+    async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        service: GattService,
+    ) -> Result<Arc<dyn GattConnection + Send + Sync>>;
+}
+
+/// The characteristics that packets are exchanged through. Packets are written to `write_characteristic`, and
+/// received as notifications from `notify_characteristic`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GattService {
+    pub service: Uuid,
+    pub write_characteristic: Uuid,
+    pub notify_characteristic: Uuid,
+}
+
+#[async_trait]
+pub trait GattConnection {
+    /// Writes `data` to the write characteristic.
+    async fn write(&self, data: &[u8]) -> Result<()>;
+
+    /// Returns a channel that will receive the values of notifications from the notify characteristic.
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>>;
+
+    /// Returns a `tokio::sync::watch::Receiver` for tracking when the connection disconnects.
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus>;
+}
+
+/// Which transport a device's control protocol runs over, along with what is needed to connect using it.
+#[derive(Copy, Clone)]
+pub enum Transport {
+    Rfcomm(RfcommServiceSelectionStrategy),
+    Gatt(GattService),
+}
+
+impl Transport {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Rfcomm(_) => "RFCOMM",
+            Self::Gatt(_) => "GATT",
+        }
+    }
+}
+
+/// A backend for one of the transports in [`Transport`].
+#[derive(Clone)]
+pub enum TransportBackend {
+    Rfcomm(Arc<dyn RfcommBackend + Send + Sync>),
+    Gatt(Arc<dyn GattBackend + Send + Sync>),
+}
+
+impl TransportBackend {
+    pub async fn devices(&self) -> Result<HashSet<ConnectionDescriptor>> {
+        match self {
+            Self::Rfcomm(backend) => backend.devices().await,
+            Self::Gatt(backend) => backend.devices().await,
+        }
+    }
+
+    /// Fails with [`Error::UnsupportedTransport`] if `transport` is not the one this backend is for.
+    pub async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        transport: Transport,
+    ) -> Result<TransportConnection> {
+        match (self, transport) {
+            (Self::Rfcomm(backend), Transport::Rfcomm(service_selection_strategy)) => backend
+                .connect(mac_address, service_selection_strategy)
+                .await
+                .map(TransportConnection::Rfcomm),
+            (Self::Gatt(backend), Transport::Gatt(service)) => backend
+                .connect(mac_address, service)
+                .await
+                .map(TransportConnection::Gatt),
+            (_, transport) => Err(Error::UnsupportedTransport {
+                transport: transport.name(),
+            }),
+        }
+    }
+}
+
+/// A connection over one of the transports in [`Transport`].
+#[derive(Clone)]
+pub enum TransportConnection {
+    Rfcomm(Arc<dyn RfcommConnection + Send + Sync>),
+    Gatt(Arc<dyn GattConnection + Send + Sync>),
+}
+
+impl TransportConnection {
+    pub async fn write(&self, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Rfcomm(connection) => connection.write(data).await,
+            Self::Gatt(connection) => connection.write(data).await,
+        }
+    }
+
+    pub fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        match self {
+            Self::Rfcomm(connection) => connection.read_channel(),
+            Self::Gatt(connection) => connection.read_channel(),
+        }
+    }
+
+    pub fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        match self {
+            Self::Rfcomm(connection) => connection.connection_status(),
+            Self::Gatt(connection) => connection.connection_status(),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectionDescriptor {
@@ -79,11 +209,32 @@ pub struct ConnectionDescriptor {
     pub mac_address: MacAddr6,
 }
 
+/// A bluetooth adapter that devices can be connected through.
+#[derive(PartialEq, Eq, Debug, Clone, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BluetoothAdapter {
+    /// Platform specific name, such as hci0 on Linux. Unlike the address, this may change between boots.
+    pub name: String,
+    #[serde(with = "crate::serialization::mac_addr")]
+    pub address: MacAddr6,
+}
+
+impl BluetoothAdapter {
+    /// Adapters can be selected by either their name or address.
+    pub fn matches(&self, name_or_address: &str) -> bool {
+        self.name == name_or_address
+            || MacAddr6::from_str(name_or_address).is_ok_and(|address| address == self.address)
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ConnectionStatus {
     Connected,
     Disconnected,
+    /// The connection was lost and is being reestablished. This will be followed by either `Connected` or
+    /// `Disconnected`.
+    Reconnecting,
 }
 
 #[cfg(test)]
@@ -133,3 +284,47 @@ pub mod test_stub {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::connection_backend::mock::rfcomm::MockRfcommBackend;
+
+    use super::*;
+
+    #[test]
+    fn adapter_matches_name_or_address() {
+        let adapter = BluetoothAdapter {
+            name: "hci1".to_owned(),
+            address: MacAddr6::new(0x00, 0x1a, 0x7d, 0xda, 0x71, 0x13),
+        };
+        assert!(adapter.matches("hci1"));
+        assert!(adapter.matches("00:1A:7D:DA:71:13"));
+        assert!(adapter.matches("00:1a:7d:da:71:13"));
+        assert!(!adapter.matches("hci0"));
+        assert!(!adapter.matches("00:1A:7D:DA:71:14"));
+    }
+
+    #[tokio::test]
+    async fn connecting_with_a_different_transport_than_the_backend_fails() {
+        let backend = TransportBackend::Rfcomm(Arc::new(MockRfcommBackend::new(
+            mpsc::channel(1).1,
+            mpsc::channel(1).0,
+        )));
+        let result = backend
+            .connect(
+                MacAddr6::nil(),
+                Transport::Gatt(GattService {
+                    service: Uuid::nil(),
+                    write_characteristic: Uuid::nil(),
+                    notify_characteristic: Uuid::nil(),
+                }),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedTransport { transport: "GATT" })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{panic::Location, sync::Arc};
+use std::{panic::Location, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use indexmap::IndexMap;
@@ -39,6 +39,8 @@ pub enum Error {
     ActionTimedOut { action: &'static str },
     #[error("device with mac address {mac_address} not found")]
     DeviceNotFound { mac_address: MacAddr6 },
+    #[error("bluetooth adapter {adapter} not found")]
+    AdapterNotFound { adapter: String },
 }
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -53,6 +55,36 @@ impl Error {
     }
 }
 
+/// How a device should try to reestablish its connection after it is lost. While reconnecting, the device's
+/// connection status will be [`ConnectionStatus::Reconnecting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnection attempt. Each following attempt waits twice as long as the previous one.
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Gives up and stays disconnected after this many failed attempts, or keeps trying forever if `None`.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: Some(10),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the reconnection attempt at index `attempt`, starting from 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
 #[async_trait]
 pub trait OpenSCQ30DeviceRegistry {
     /// Lists a superset of the connectable devices. For example, this may list all bluetooth devices rather than only
@@ -68,7 +100,8 @@ pub trait OpenSCQ30DeviceRegistry {
 
 #[async_trait]
 pub trait OpenSCQ30Device {
-    /// Returns a tokio::sync::watch::Receiver for tracking when the connection disconnects.
+    /// Returns a tokio::sync::watch::Receiver for tracking when the connection disconnects, or is being reestablished
+    /// if a [`ReconnectPolicy`] is in use.
     fn connection_status(&self) -> watch::Receiver<ConnectionStatus>;
 
     /// Returns the model of the device.
@@ -129,3 +162,24 @@ pub trait OpenSCQ30Device {
     /// single time rather than once for each change.
     async fn set_setting_values(&self, setting_values: Vec<(SettingId, Value)>) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_delay_backs_off_exponentially() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            max_attempts: None,
+        };
+        assert_eq!(
+            vec![1, 2, 4, 8, 10, 10],
+            (0..6)
+                .map(|attempt| policy.delay(attempt).as_secs())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(Duration::from_secs(10), policy.delay(u32::MAX));
+    }
+}
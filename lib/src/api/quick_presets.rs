@@ -1,8 +1,21 @@
+mod export;
+
 use std::{collections::HashSet, sync::Arc};
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
-use crate::storage::{self, OpenSCQ30Database, QuickPreset, QuickPresetField};
+pub(crate) use export::validate_fields;
+pub use export::{
+    ExportFormatError, ImportConflictResolution, ImportedQuickPreset, InvalidFieldReason,
+    InvalidQuickPresetField, QUICK_PRESETS_EXPORT_VERSION, QuickPresetsExport,
+    QuickPresetsImportReport,
+};
+
+use crate::{
+    devices::DeviceModel,
+    storage::{self, OpenSCQ30Database, QuickPreset, QuickPresetField},
+};
 
 use super::{
     device::{self, OpenSCQ30Device},
@@ -52,7 +65,11 @@ impl QuickPresetsHandler {
                 !matches!(
                     setting_id,
                     SettingId::ImportCustomEqualizerProfiles
-                        | SettingId::ExportCustomEqualizerProfiles,
+                        | SettingId::ExportCustomEqualizerProfiles
+                        | SettingId::ImportEqualizerApoProfile
+                        | SettingId::CopyCustomEqualizerProfilesFrom
+                        | SettingId::ShareCustomEqualizerProfile
+                        | SettingId::ImportSharedEqualizerProfile,
                 )
             })
             .filter_map(|setting_id| {
@@ -114,4 +131,84 @@ impl QuickPresetsHandler {
             .delete_quick_preset(device.model(), name)
             .await
     }
+
+    /// Exports all quick presets for each of the specified device models.
+    pub async fn export(
+        &self,
+        device_models: impl IntoIterator<Item = DeviceModel>,
+    ) -> storage::Result<QuickPresetsExport> {
+        let mut quick_presets = IndexMap::new();
+        for device_model in device_models {
+            let presets = self.database.fetch_all_quick_presets(device_model).await?;
+            if !presets.is_empty() {
+                quick_presets.insert(device_model, presets);
+            }
+        }
+        Ok(QuickPresetsExport::new(quick_presets))
+    }
+
+    /// Imports the quick presets for the device's model, ignoring those for other models. Each field is validated
+    /// against the device's current settings, and fields that the device would not accept are left out and listed in
+    /// the report.
+    pub async fn import(
+        &self,
+        device: &(dyn OpenSCQ30Device + Send + Sync),
+        export: QuickPresetsExport,
+        conflict_resolution: ImportConflictResolution,
+    ) -> storage::Result<QuickPresetsImportReport> {
+        let mut taken_names = self
+            .database
+            .fetch_all_quick_presets(device.model())
+            .await?
+            .into_iter()
+            .map(|quick_preset| quick_preset.name)
+            .collect::<HashSet<_>>();
+
+        let mut report = QuickPresetsImportReport::default();
+        let quick_presets = export
+            .quick_presets
+            .into_iter()
+            .filter(|(device_model, _)| *device_model == device.model())
+            .flat_map(|(_, quick_presets)| quick_presets);
+        for quick_preset in quick_presets {
+            let mut original_name = None;
+            let name = if !taken_names.contains(&quick_preset.name) {
+                quick_preset.name
+            } else {
+                match conflict_resolution {
+                    ImportConflictResolution::Skip => {
+                        report.skipped.push(quick_preset.name);
+                        continue;
+                    }
+                    ImportConflictResolution::Overwrite => quick_preset.name,
+                    ImportConflictResolution::Rename => {
+                        let name = export::unique_name(&quick_preset.name, &taken_names);
+                        original_name = Some(quick_preset.name);
+                        name
+                    }
+                }
+            };
+
+            let (fields, invalid_fields) =
+                export::validate_fields(quick_preset.fields, |setting_id| {
+                    device.setting(setting_id)
+                });
+            self.database
+                .replace_quick_preset(
+                    device.model(),
+                    QuickPreset {
+                        name: name.clone(),
+                        fields,
+                    },
+                )
+                .await?;
+            taken_names.insert(name.clone());
+            report.imported.push(ImportedQuickPreset {
+                name,
+                original_name,
+                invalid_fields,
+            });
+        }
+        Ok(report)
+    }
 }
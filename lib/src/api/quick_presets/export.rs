@@ -0,0 +1,443 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, IntoStaticStr, VariantArray};
+
+use crate::{
+    api::settings::{Setting, SettingId, Value, ValueDiscriminants, ValueError},
+    devices::DeviceModel,
+    serialization::versioned,
+    storage::{QuickPreset, QuickPresetField},
+};
+
+/// See [`versioned::UnsupportedVersionError`] for when this changes.
+pub const QUICK_PRESETS_EXPORT_VERSION: u32 = 1;
+
+/// Quick presets in a format that can be moved between machines, grouped by the device model they belong to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickPresetsExport {
+    pub version: u32,
+    pub quick_presets: IndexMap<DeviceModel, Vec<QuickPreset>>,
+}
+
+pub type ExportFormatError = versioned::JsonFormatError;
+
+impl QuickPresetsExport {
+    pub fn new(quick_presets: IndexMap<DeviceModel, Vec<QuickPreset>>) -> Self {
+        Self {
+            version: QUICK_PRESETS_EXPORT_VERSION,
+            quick_presets,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        versioned::to_json(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ExportFormatError> {
+        versioned::from_json("export", QUICK_PRESETS_EXPORT_VERSION, json)
+    }
+}
+
+/// What to do when an imported quick preset has the same name as an existing one.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    IntoStaticStr,
+    VariantArray,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ImportConflictResolution {
+    /// Keep the existing quick preset
+    #[default]
+    Skip,
+    /// Replace the existing quick preset
+    Overwrite,
+    /// Import with a number appended to the name, such as "Name (2)"
+    Rename,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuickPresetsImportReport {
+    pub imported: Vec<ImportedQuickPreset>,
+    /// Names of quick presets that were not imported due to a conflict
+    pub skipped: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedQuickPreset {
+    /// The name the quick preset was saved with
+    pub name: String,
+    /// Set if the quick preset was renamed to avoid a conflict
+    pub original_name: Option<String>,
+    /// Fields that were left out of the quick preset since the device wouldn't accept them
+    pub invalid_fields: Vec<InvalidQuickPresetField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidQuickPresetField {
+    pub setting_id: SettingId,
+    pub value: Value,
+    pub reason: InvalidFieldReason,
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum InvalidFieldReason {
+    #[error("setting is not available")]
+    Unavailable,
+    #[error("setting is read only")]
+    ReadOnly,
+    #[error("expected value of type {expected}, got {actual}")]
+    WrongType { expected: String, actual: String },
+    #[error("{value} is not one of the available options")]
+    InvalidOption { value: String },
+    #[error("{value} is out of range")]
+    OutOfRange { value: String },
+}
+
+impl From<ValueError> for InvalidFieldReason {
+    fn from(err: ValueError) -> Self {
+        match err {
+            ValueError::WrongType { expected, actual } => Self::WrongType {
+                expected: expected.to_string(),
+                actual: ValueDiscriminants::from(&actual).to_string(),
+            },
+            ValueError::InvalidEnumVariant { actual, .. } => Self::InvalidOption {
+                value: actual.to_string(),
+            },
+        }
+    }
+}
+
+/// Splits a quick preset's fields into the ones `setting` accepts and the ones it does not.
+pub fn validate_fields(
+    fields: Vec<QuickPresetField>,
+    setting: impl Fn(&SettingId) -> Option<Setting>,
+) -> (Vec<QuickPresetField>, Vec<InvalidQuickPresetField>) {
+    let mut valid_fields = Vec::new();
+    let mut invalid_fields = Vec::new();
+    for field in fields {
+        match validate_value(setting(&field.setting_id), &field.value) {
+            Ok(()) => valid_fields.push(field),
+            Err(reason) => invalid_fields.push(InvalidQuickPresetField {
+                setting_id: field.setting_id,
+                value: field.value,
+                reason,
+            }),
+        }
+    }
+    (valid_fields, invalid_fields)
+}
+
+fn validate_value(setting: Option<Setting>, value: &Value) -> Result<(), InvalidFieldReason> {
+    let is_option = |options: &[Cow<'static, str>], value: &str| {
+        if options.iter().any(|option| option == value) {
+            Ok(())
+        } else {
+            Err(InvalidFieldReason::InvalidOption {
+                value: value.to_owned(),
+            })
+        }
+    };
+
+    match setting.ok_or(InvalidFieldReason::Unavailable)? {
+        Setting::Toggle { .. } => {
+            value.try_as_bool()?;
+        }
+        Setting::I32Range { setting, .. } => {
+            let number = value.try_as_i32()?;
+            if !setting.range.contains(&number)
+                || (setting.step != 0 && (number - setting.range.start()) % setting.step != 0)
+            {
+                return Err(InvalidFieldReason::OutOfRange {
+                    value: number.to_string(),
+                });
+            }
+        }
+        Setting::Select { setting, .. } => is_option(&setting.options, value.try_as_str()?)?,
+        Setting::OptionalSelect { setting, .. } | Setting::ModifiableSelect { setting, .. } => {
+            if let Some(selection) = value.try_as_optional_str()? {
+                is_option(&setting.options, selection)?;
+            }
+        }
+        Setting::MultiSelect { setting, .. } | Setting::MultiSelectWithRemove { setting, .. } => {
+            for selection in value.clone().try_into_string_vec()? {
+                is_option(&setting.options, &selection)?;
+            }
+        }
+        Setting::Equalizer { setting, .. } => {
+            let volume_adjustments = value.try_as_i16_slice()?;
+            if volume_adjustments.len() != setting.band_hz.len()
+                || volume_adjustments
+                    .iter()
+                    .any(|volume| !(setting.min..=setting.max).contains(volume))
+            {
+                return Err(InvalidFieldReason::OutOfRange {
+                    value: value.to_string(),
+                });
+            }
+        }
+        Setting::Information { .. } => return Err(InvalidFieldReason::ReadOnly),
+        Setting::ImportString { .. } | Setting::Action => (),
+    }
+    Ok(())
+}
+
+/// Picks a name that is not in `taken_names` by appending a number, starting at 2.
+pub fn unique_name(name: &str, taken_names: &HashSet<String>) -> String {
+    (2..)
+        .map(|number| format!("{name} ({number})"))
+        .find(|candidate| !taken_names.contains(candidate))
+        .expect("there are more numbers than taken names")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        OpenSCQ30Session,
+        api::{device::OpenSCQ30Device, quick_presets::QuickPresetsHandler},
+        storage::PairedDevice,
+    };
+
+    use super::*;
+
+    async fn handler_and_device() -> (QuickPresetsHandler, Arc<dyn OpenSCQ30Device + Send + Sync>) {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        let mac_address = DeviceModel::SoundcoreA3028.demo_mac_address();
+        session
+            .pair(PairedDevice {
+                mac_address,
+                model: DeviceModel::SoundcoreA3028,
+                is_demo: true,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        let device = session.connect(mac_address).await.unwrap();
+        (session.quick_preset_handler(), device)
+    }
+
+    fn field(setting_id: SettingId, value: impl Into<Value>) -> QuickPresetField {
+        QuickPresetField {
+            setting_id,
+            value: value.into(),
+            is_enabled: true,
+        }
+    }
+
+    fn export(quick_presets: Vec<QuickPreset>) -> QuickPresetsExport {
+        QuickPresetsExport::new(IndexMap::from([(
+            DeviceModel::SoundcoreA3028,
+            quick_presets,
+        )]))
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let err = QuickPresetsExport::from_json(r#"{"version":2,"somethingNew":[]}"#).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ExportFormatError::UnsupportedVersion(versioned::UnsupportedVersionError {
+                    version: 2,
+                    ..
+                })
+            ),
+            "wanted unsupported version, got {err:?}",
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn round_trips_between_databases() {
+        let (source_handler, source_device) = handler_and_device().await;
+        source_handler
+            .save(source_device.as_ref(), "Commute".into())
+            .await
+            .unwrap();
+        source_handler
+            .toggle_field(
+                source_device.as_ref(),
+                "Commute".into(),
+                SettingId::AmbientSoundMode,
+                true,
+            )
+            .await
+            .unwrap();
+        let json = source_handler
+            .export([DeviceModel::SoundcoreA3028, DeviceModel::SoundcoreA3027])
+            .await
+            .unwrap()
+            .to_json();
+        let export = QuickPresetsExport::from_json(&json).unwrap();
+        assert_eq!(
+            vec![DeviceModel::SoundcoreA3028],
+            export.quick_presets.keys().copied().collect::<Vec<_>>(),
+            "models without quick presets should be left out",
+        );
+
+        let (handler, device) = handler_and_device().await;
+        let report = handler
+            .import(device.as_ref(), export, ImportConflictResolution::Skip)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![ImportedQuickPreset {
+                name: "Commute".into(),
+                original_name: None,
+                invalid_fields: Vec::new(),
+            }],
+            report.imported,
+        );
+        assert_eq!(
+            source_handler
+                .quick_presets(source_device.as_ref())
+                .await
+                .unwrap(),
+            handler.quick_presets(device.as_ref()).await.unwrap(),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resolves_conflicts() {
+        let (handler, device) = handler_and_device().await;
+        let existing = QuickPreset {
+            name: "Commute".into(),
+            fields: vec![field(SettingId::AmbientSoundMode, "Normal")],
+        };
+        let imported = QuickPreset {
+            name: "Commute".into(),
+            fields: vec![field(SettingId::AmbientSoundMode, "Transparency")],
+        };
+        handler
+            .import(
+                device.as_ref(),
+                export(vec![existing.clone()]),
+                ImportConflictResolution::Skip,
+            )
+            .await
+            .unwrap();
+
+        let report = handler
+            .import(
+                device.as_ref(),
+                export(vec![imported.clone()]),
+                ImportConflictResolution::Skip,
+            )
+            .await
+            .unwrap();
+        assert_eq!(vec!["Commute".to_owned()], report.skipped);
+        assert_eq!(
+            vec![existing.clone()],
+            handler.quick_presets(device.as_ref()).await.unwrap(),
+        );
+
+        let report = handler
+            .import(
+                device.as_ref(),
+                export(vec![imported.clone(), imported.clone()]),
+                ImportConflictResolution::Rename,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![
+                ("Commute (2)".to_owned(), Some("Commute".to_owned())),
+                ("Commute (3)".to_owned(), Some("Commute".to_owned())),
+            ],
+            report
+                .imported
+                .into_iter()
+                .map(|imported| (imported.name, imported.original_name))
+                .collect::<Vec<_>>(),
+        );
+
+        handler
+            .import(
+                device.as_ref(),
+                export(vec![imported.clone()]),
+                ImportConflictResolution::Overwrite,
+            )
+            .await
+            .unwrap();
+        let quick_presets = handler.quick_presets(device.as_ref()).await.unwrap();
+        assert_eq!(3, quick_presets.len());
+        assert!(quick_presets.contains(&imported));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_invalid_fields() {
+        let (handler, device) = handler_and_device().await;
+        let report = handler
+            .import(
+                device.as_ref(),
+                export(vec![QuickPreset {
+                    name: "Broken".into(),
+                    fields: vec![
+                        field(SettingId::AmbientSoundMode, "Transparency"),
+                        field(SettingId::NoiseCancelingMode, "Underwater"),
+                        field(SettingId::VolumeAdjustments, vec![0i16; 3]),
+                        field(SettingId::LeftSinglePress, Some("VolumeUp")),
+                        field(SettingId::BatteryLevel, "5/5"),
+                        field(SettingId::AutoPowerOff, 5),
+                    ],
+                }]),
+                ImportConflictResolution::Skip,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                (
+                    SettingId::NoiseCancelingMode,
+                    InvalidFieldReason::InvalidOption {
+                        value: "Underwater".into()
+                    }
+                ),
+                (
+                    SettingId::VolumeAdjustments,
+                    InvalidFieldReason::OutOfRange {
+                        value: "[0, 0, 0]".into()
+                    }
+                ),
+                (SettingId::LeftSinglePress, InvalidFieldReason::Unavailable),
+                (SettingId::BatteryLevel, InvalidFieldReason::ReadOnly),
+                (
+                    SettingId::AutoPowerOff,
+                    InvalidFieldReason::WrongType {
+                        expected: "String".into(),
+                        actual: "I32".into(),
+                    }
+                ),
+            ],
+            report.imported[0]
+                .invalid_fields
+                .iter()
+                .map(|field| (field.setting_id, field.reason.clone()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![field(SettingId::AmbientSoundMode, "Transparency")],
+            handler
+                .quick_presets(device.as_ref())
+                .await
+                .unwrap()
+                .remove(0)
+                .fields,
+        );
+    }
+}
@@ -1,21 +1,48 @@
-use std::{path::PathBuf, sync::Arc};
+mod connection_manager;
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use macaddr::MacAddr6;
+use tokio::sync::broadcast;
 
 use crate::{
-    connection_backend::{self, ConnectionBackends},
-    devices::DeviceModel,
-    storage::{OpenSCQ30Database, PairedDevice},
+    connection_backend::{
+        self, ConnectionBackends,
+        device_definition::{DeviceDefinition, DeviceDefinitionConnectionBackends},
+    },
+    devices::{DeviceModel, soundcore, soundcore::common::demo::DemoEvents},
+    storage::{Backup, BackupImportMode, OpenSCQ30Database, PairedDevice},
 };
 
 use super::{
-    connection::ConnectionDescriptor,
-    device::{self, OpenSCQ30Device},
-    quick_presets::QuickPresetsHandler,
+    connection::{BluetoothAdapter, ConnectionDescriptor},
+    device::{self, OpenSCQ30Device, ReconnectPolicy},
+    quick_presets::{self, InvalidQuickPresetField, QuickPresetsHandler},
 };
 
+pub use connection_manager::ConnectionEvent;
+use connection_manager::ConnectionManager;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackupImportReport {
+    /// Quick presets that had fields left out since their device model wouldn't accept them
+    pub invalid_quick_presets: Vec<InvalidBackupQuickPreset>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidBackupQuickPreset {
+    pub device_model: DeviceModel,
+    pub name: String,
+    pub invalid_fields: Vec<InvalidQuickPresetField>,
+}
+
 pub struct OpenSCQ30Session {
     database: Arc<OpenSCQ30Database>,
+    reconnect_policy: Option<ReconnectPolicy>,
+    device_definition: Option<Arc<DeviceDefinition>>,
+    adapter: Option<String>,
+    demo_events: DemoEvents,
+    connections: ConnectionManager,
 }
 
 impl OpenSCQ30Session {
@@ -24,6 +51,11 @@ impl OpenSCQ30Session {
     pub async fn new(db_path: PathBuf) -> device::Result<Self> {
         Ok(Self {
             database: Arc::new(OpenSCQ30Database::new_file(db_path).await?),
+            reconnect_policy: None,
+            device_definition: None,
+            adapter: None,
+            demo_events: DemoEvents::default(),
+            connections: ConnectionManager::new(),
         })
     }
 
@@ -32,12 +64,71 @@ impl OpenSCQ30Session {
     pub async fn new_with_in_memory_db() -> device::Result<Self> {
         Ok(Self {
             database: Arc::new(OpenSCQ30Database::new_in_memory().await?),
+            reconnect_policy: None,
+            device_definition: None,
+            adapter: None,
+            demo_events: DemoEvents::default(),
+            connections: ConnectionManager::new(),
         })
     }
 
+    /// Devices connected to with this session will attempt to reconnect according to `reconnect_policy` when their
+    /// connection is lost, rather than staying disconnected. Demo devices never lose their connection, so this does
+    /// not affect them.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
+    /// Listing and connecting to devices will go through a fake device served from `device_definition` rather than the
+    /// platform's bluetooth stack. Pairings are not affected, so the device definition's mac address still needs to be
+    /// paired with a model before it can be connected to.
+    pub fn with_device_definition(mut self, device_definition: DeviceDefinition) -> Self {
+        self.device_definition = Some(Arc::new(device_definition));
+        self
+    }
+
+    /// Only devices connected through the bluetooth adapter with this name (such as hci0) or address will be listed and
+    /// connected to, unless a paired device remembers a different adapter. Choosing an adapter is only supported on
+    /// Linux.
+    pub fn with_adapter(mut self, adapter: impl Into<String>) -> Self {
+        self.adapter = Some(adapter.into());
+        self
+    }
+
+    /// Demo devices connected to with this session will send `demo_events` on their own, as if the state of a real
+    /// device were changing. Real devices are not affected.
+    pub fn with_demo_events(mut self, demo_events: DemoEvents) -> Self {
+        self.demo_events = demo_events;
+        self
+    }
+
+    /// Lists the bluetooth adapters that can be chosen with `with_adapter`. Empty on platforms that don't support
+    /// choosing an adapter.
+    pub async fn adapters(&self) -> device::Result<Vec<BluetoothAdapter>> {
+        if let Some(device_definition) = &self.device_definition {
+            return DeviceDefinitionConnectionBackends::new(device_definition.clone())
+                .adapters()
+                .await
+                .map_err(Into::into);
+        }
+        connection_backend::default_backends(None)
+            .expect("no default backends available")
+            .adapters()
+            .await
+            .map_err(Into::into)
+    }
+
     /// Not to be confused with pairing in the bluetooth sense, this associates a `DeviceModel` with a particular mac
-    /// address.
-    pub async fn pair(&self, paired_device: PairedDevice) -> device::Result<()> {
+    /// address. If an adapter was chosen with `with_adapter` and `paired_device` doesn't already specify one, the
+    /// device will remember that adapter. Fails if no adapter matches the chosen one.
+    pub async fn pair(&self, mut paired_device: PairedDevice) -> device::Result<()> {
+        if !paired_device.is_demo
+            && paired_device.adapter_address.is_none()
+            && let Some(selected) = &self.adapter
+        {
+            paired_device.adapter_address = find_adapter(&self.adapters().await?, selected)?;
+        }
         self.database
             .upsert_paired_device(paired_device)
             .await
@@ -61,13 +152,66 @@ impl OpenSCQ30Session {
             .map_err(Into::into)
     }
 
+    /// Returns paired devices, quick presets, and custom equalizer profiles for all device models.
+    pub async fn export_backup(&self) -> device::Result<Backup> {
+        self.database.export_backup().await.map_err(Into::into)
+    }
+
+    /// Restores a backup created with `export_backup`. If anything fails to be restored, the database is left
+    /// unchanged.
+    ///
+    /// Quick preset fields are validated the same way as when importing quick presets, but against a demo device of
+    /// each model, since there may not be a real one connected. Fields that would not be accepted are left out and
+    /// listed in the report.
+    pub async fn import_backup(
+        &self,
+        mut backup: Backup,
+        mode: BackupImportMode,
+    ) -> device::Result<BackupImportReport> {
+        let mut report = BackupImportReport::default();
+        // The demo devices are only needed for their settings, so keep them away from the real database
+        let demo_database = Arc::new(OpenSCQ30Database::new_in_memory().await?);
+        for (device_model, quick_presets) in &mut backup.quick_presets {
+            let device = device_model
+                .demo_device_registry(demo_database.clone())
+                .await?
+                .connect(device_model.demo_mac_address())
+                .await?;
+            for quick_preset in quick_presets {
+                let (fields, invalid_fields) = quick_presets::validate_fields(
+                    std::mem::take(&mut quick_preset.fields),
+                    |setting_id| device.setting(setting_id),
+                );
+                quick_preset.fields = fields;
+                if !invalid_fields.is_empty() {
+                    report.invalid_quick_presets.push(InvalidBackupQuickPreset {
+                        device_model: *device_model,
+                        name: quick_preset.name.clone(),
+                        invalid_fields,
+                    });
+                }
+            }
+        }
+        self.database.import_backup(backup, mode).await?;
+        Ok(report)
+    }
+
     /// Lists all potential devices that could be paired with.
     pub async fn list_devices(
         &self,
         model: DeviceModel,
     ) -> device::Result<Vec<ConnectionDescriptor>> {
+        if let Some(device_definition) = &self.device_definition {
+            return self
+                .list_devices_with_backends(
+                    &DeviceDefinitionConnectionBackends::new(device_definition.clone()),
+                    model,
+                )
+                .await;
+        }
         self.list_devices_with_backends(
-            &connection_backend::default_backends().expect("no default backends available"),
+            &connection_backend::default_backends(self.adapter.clone())
+                .expect("no default backends available"),
             model,
         )
         .await
@@ -80,7 +224,7 @@ impl OpenSCQ30Session {
         model: DeviceModel,
     ) -> device::Result<Vec<ConnectionDescriptor>> {
         model
-            .device_registry(backends, self.database.clone())
+            .device_registry(backends, self.database.clone(), None)
             .await?
             .devices()
             .await
@@ -98,34 +242,99 @@ impl OpenSCQ30Session {
             .await
     }
 
-    /// Connects to a paired device.
+    /// Figures out which `DeviceModel` a device is, so that it can be paired without the user having to know. The
+    /// device's RFCOMM services are checked first, and if those don't say, the device is connected to and asked for
+    /// its serial number. Returns `None` if the model could not be determined.
+    pub async fn detect_model(&self, mac_address: MacAddr6) -> device::Result<Option<DeviceModel>> {
+        if let Some(device_definition) = &self.device_definition {
+            return self
+                .detect_model_with_backends(
+                    &DeviceDefinitionConnectionBackends::new(device_definition.clone()),
+                    mac_address,
+                )
+                .await;
+        }
+        self.detect_model_with_backends(
+            &connection_backend::default_backends(self.adapter.clone())
+                .expect("no default backends available"),
+            mac_address,
+        )
+        .await
+    }
+
+    /// Figures out which `DeviceModel` a device is using the specified backends.
+    pub async fn detect_model_with_backends(
+        &self,
+        backends: &(impl ConnectionBackends + 'static),
+        mac_address: MacAddr6,
+    ) -> device::Result<Option<DeviceModel>> {
+        let rfcomm = backends.rfcomm().await?;
+        soundcore::detection::detect_model(&rfcomm, mac_address).await
+    }
+
+    /// Connects to a paired device. If the device is already connected, the existing connection is shared rather than
+    /// establishing a second one. The connection is closed once all handles to it are dropped.
     pub async fn connect(
         &self,
         mac_address: MacAddr6,
     ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        if let Some(device_definition) = &self.device_definition {
+            return self
+                .connect_with_backends(
+                    &DeviceDefinitionConnectionBackends::new(device_definition.clone()),
+                    mac_address,
+                )
+                .await;
+        }
+        // A device that remembers its adapter is connected through that one even if the session chose another
+        let adapter = match self
+            .database
+            .fetch_paired_device(mac_address)
+            .await?
+            .and_then(|paired_device| paired_device.adapter_address)
+        {
+            Some(remembered) => {
+                remembered_or_session_adapter(&self.adapters().await?, remembered, &self.adapter)
+            }
+            None => self.adapter.clone(),
+        };
         self.connect_with_backends(
-            &connection_backend::default_backends().expect("no default backends available"),
+            &connection_backend::default_backends(adapter).expect("no default backends available"),
             mac_address,
         )
         .await
     }
 
-    /// Connects to a paired device using the specified backends.
+    /// Connects to a paired device using the specified backends. `backends` is not used if the device is already
+    /// connected.
     pub async fn connect_with_backends(
         &self,
         backends: &(impl ConnectionBackends + 'static),
         mac_address: MacAddr6,
+    ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        self.connections
+            .connect(
+                mac_address,
+                self.connect_without_sharing(backends, mac_address),
+            )
+            .await
+    }
+
+    async fn connect_without_sharing(
+        &self,
+        backends: &(impl ConnectionBackends + 'static),
+        mac_address: MacAddr6,
     ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
         if let Some(paired_device) = self.database.fetch_paired_device(mac_address).await? {
             let registry = if paired_device.is_demo {
                 paired_device
                     .model
-                    .demo_device_registry(self.database.clone())
+                    .scripted_demo_device_registry(self.database.clone(), self.demo_events.clone())
                     .await?
             } else {
                 paired_device
                     .model
-                    .device_registry(backends, self.database.clone())
+                    .device_registry(backends, self.database.clone(), self.reconnect_policy)
                     .await?
             };
             registry.connect(mac_address).await
@@ -134,7 +343,185 @@ impl OpenSCQ30Session {
         }
     }
 
+    /// Returns all devices that are currently connected through this session, along with their mac addresses.
+    pub fn connected_devices(&self) -> HashMap<MacAddr6, Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        self.connections.connected_devices()
+    }
+
+    /// Returns the existing connection to a device without connecting if there is none.
+    pub fn connected_device(
+        &self,
+        mac_address: MacAddr6,
+    ) -> Option<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        self.connections.device(mac_address)
+    }
+
+    /// Receives an event whenever a device connects or disconnects through this session.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.connections.subscribe()
+    }
+
     pub fn quick_preset_handler(&self) -> QuickPresetsHandler {
         QuickPresetsHandler::new(self.database.clone())
     }
 }
+
+/// The address of the adapter matching `selected`. `None` if `adapters` is empty, since that means choosing an adapter
+/// isn't supported.
+fn find_adapter(adapters: &[BluetoothAdapter], selected: &str) -> device::Result<Option<MacAddr6>> {
+    if adapters.is_empty() {
+        return Ok(None);
+    }
+    adapters
+        .iter()
+        .find(|adapter| adapter.matches(selected))
+        .map(|adapter| Some(adapter.address))
+        .ok_or_else(|| device::Error::AdapterNotFound {
+            adapter: selected.to_owned(),
+        })
+}
+
+/// The remembered adapter if it still exists, otherwise the session's adapter, or any adapter if the session didn't
+/// choose one.
+fn remembered_or_session_adapter(
+    adapters: &[BluetoothAdapter],
+    remembered: MacAddr6,
+    session_adapter: &Option<String>,
+) -> Option<String> {
+    if adapters.iter().any(|adapter| adapter.address == remembered) {
+        return Some(remembered.to_string());
+    }
+    match session_adapter {
+        Some(session_adapter) => tracing::warn!(
+            "remembered bluetooth adapter {remembered} not found, using {session_adapter} instead"
+        ),
+        None => tracing::warn!(
+            "remembered bluetooth adapter {remembered} not found, using any adapter instead"
+        ),
+    }
+    session_adapter.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use indexmap::IndexMap;
+
+    use crate::{
+        api::{quick_presets::InvalidFieldReason, settings::SettingId},
+        storage::{BACKUP_VERSION, QuickPreset, QuickPresetField},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn import_backup_leaves_out_invalid_quick_preset_fields() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        let field = |setting_id, value: &'static str| QuickPresetField {
+            setting_id,
+            value: Cow::from(value).into(),
+            is_enabled: true,
+        };
+        let report = session
+            .import_backup(
+                Backup {
+                    version: BACKUP_VERSION,
+                    paired_devices: Vec::new(),
+                    quick_presets: IndexMap::from([(
+                        DeviceModel::SoundcoreA3028,
+                        vec![QuickPreset {
+                            name: "Commute".to_owned(),
+                            fields: vec![
+                                field(SettingId::AmbientSoundMode, "NoiseCanceling"),
+                                field(SettingId::NoiseCancelingMode, "Bogus"),
+                            ],
+                        }],
+                    )]),
+                    equalizer_profiles: IndexMap::new(),
+                },
+                BackupImportMode::Merge,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![InvalidBackupQuickPreset {
+                device_model: DeviceModel::SoundcoreA3028,
+                name: "Commute".to_owned(),
+                invalid_fields: vec![InvalidQuickPresetField {
+                    setting_id: SettingId::NoiseCancelingMode,
+                    value: Cow::from("Bogus").into(),
+                    reason: InvalidFieldReason::InvalidOption {
+                        value: "Bogus".to_owned(),
+                    },
+                }],
+            }],
+            report.invalid_quick_presets,
+        );
+        assert_eq!(
+            vec![field(SettingId::AmbientSoundMode, "NoiseCanceling")],
+            session.export_backup().await.unwrap().quick_presets[&DeviceModel::SoundcoreA3028][0]
+                .fields,
+        );
+    }
+
+    fn adapters() -> Vec<BluetoothAdapter> {
+        vec![
+            BluetoothAdapter {
+                name: "hci0".to_owned(),
+                address: MacAddr6::new(0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x13),
+            },
+            BluetoothAdapter {
+                name: "hci1".to_owned(),
+                address: MacAddr6::new(0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x14),
+            },
+        ]
+    }
+
+    #[test]
+    fn finds_adapter_by_name() {
+        assert_eq!(
+            Some(MacAddr6::new(0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x14)),
+            find_adapter(&adapters(), "hci1").unwrap(),
+        );
+    }
+
+    #[test]
+    fn missing_adapter_is_an_error() {
+        assert!(matches!(
+            find_adapter(&adapters(), "hci2"),
+            Err(device::Error::AdapterNotFound { adapter }) if adapter == "hci2",
+        ));
+    }
+
+    #[test]
+    fn adapter_is_not_chosen_when_unsupported() {
+        assert_eq!(None, find_adapter(&[], "hci0").unwrap());
+    }
+
+    #[test]
+    fn uses_remembered_adapter_if_it_exists() {
+        assert_eq!(
+            Some("00:1A:7D:DA:71:13".to_owned()),
+            remembered_or_session_adapter(
+                &adapters(),
+                MacAddr6::new(0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x13),
+                &Some("hci1".to_owned()),
+            ),
+        );
+    }
+
+    #[test]
+    fn falls_back_to_session_adapter_when_remembered_adapter_is_gone() {
+        let remembered = MacAddr6::new(0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x15);
+        assert_eq!(
+            Some("hci1".to_owned()),
+            remembered_or_session_adapter(&adapters(), remembered, &Some("hci1".to_owned())),
+        );
+        assert_eq!(
+            None,
+            remembered_or_session_adapter(&adapters(), remembered, &None),
+        );
+    }
+}
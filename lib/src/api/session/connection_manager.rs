@@ -0,0 +1,463 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+use tokio::sync::{broadcast, watch};
+
+use crate::{
+    api::{
+        connection::ConnectionStatus,
+        device::{self, OpenSCQ30Device},
+        settings::{CategoryId, Setting, SettingId, Value},
+    },
+    devices::DeviceModel,
+    util::AbortOnDropHandle,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A new connection was established. Further calls to connect to the same device will share this connection.
+    Connected {
+        mac_address: MacAddr6,
+        model: DeviceModel,
+    },
+    /// The device lost its connection (after giving up on reconnecting, if it was trying to), all handles to it were
+    /// dropped, or it was replaced by a new connection.
+    Disconnected { mac_address: MacAddr6 },
+}
+
+/// Shares a single connection per device between everything that connects to it. Connections are reference counted
+/// by their handles, so a device is disconnected once all of its handles are dropped. A device that loses its
+/// connection is forgotten right away, even if there are still handles to it.
+pub(super) struct ConnectionManager {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    devices: Mutex<HashMap<MacAddr6, Weak<ManagedDevice>>>,
+    // Per device so that connecting to one device doesn't have to wait for another. Removed once nobody is connecting.
+    connect_locks: Mutex<HashMap<MacAddr6, Arc<tokio::sync::Mutex<()>>>>,
+    events: broadcast::Sender<ConnectionEvent>,
+}
+
+impl ConnectionManager {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                devices: Mutex::default(),
+                connect_locks: Mutex::default(),
+                events: broadcast::channel(100).0,
+            }),
+        }
+    }
+
+    /// Returns the existing connection to the device if there is one that is still usable, or otherwise establishes a
+    /// new connection with `connect`.
+    pub async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        connect: impl Future<Output = device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>>>,
+    ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        let connect_lock = self
+            .inner
+            .connect_locks
+            .lock()
+            .unwrap()
+            .entry(mac_address)
+            .or_default()
+            .clone();
+        let result = {
+            let _guard = connect_lock.lock().await;
+            self.connect_locked(mac_address, connect).await
+        };
+
+        let mut connect_locks = self.inner.connect_locks.lock().unwrap();
+        // One reference is held by the map and the other by us, so nobody else is waiting on this lock. Since the map
+        // is locked, nobody can start waiting on it either.
+        if Arc::strong_count(&connect_lock) == 2 {
+            connect_locks.remove(&mac_address);
+        }
+        result
+    }
+
+    async fn connect_locked(
+        &self,
+        mac_address: MacAddr6,
+        connect: impl Future<Output = device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>>>,
+    ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        let existing = self.managed_device(mac_address);
+        if let Some(device) = &existing
+            && *device.connection_status().borrow() != ConnectionStatus::Disconnected
+        {
+            return Ok(device.clone());
+        }
+
+        let inner = connect.await?;
+        let connection_status = inner.connection_status();
+        let device = Arc::new_cyclic(|device| ManagedDevice {
+            inner,
+            mac_address,
+            manager: Arc::downgrade(&self.inner),
+            _disconnect_watcher_handle: AbortOnDropHandle::new(tokio::spawn(watch_for_disconnect(
+                Arc::downgrade(&self.inner),
+                mac_address,
+                device.clone(),
+                connection_status,
+            ))),
+        });
+        let model = device.model();
+        let replaced = self
+            .inner
+            .devices
+            .lock()
+            .unwrap()
+            .insert(mac_address, Arc::downgrade(&device));
+        // If the old connection was already forgotten when it lost its connection, the event has already been sent
+        if replaced.is_some() {
+            // The old connection's handles no longer count towards this device now that it has been replaced
+            self.inner
+                .send_event(ConnectionEvent::Disconnected { mac_address });
+        }
+        self.inner
+            .send_event(ConnectionEvent::Connected { mac_address, model });
+        Ok(device)
+    }
+
+    fn managed_device(&self, mac_address: MacAddr6) -> Option<Arc<ManagedDevice>> {
+        self.inner
+            .devices
+            .lock()
+            .unwrap()
+            .get(&mac_address)
+            .and_then(Weak::upgrade)
+    }
+
+    /// Returns the connection to the device if it is currently connected.
+    pub fn device(&self, mac_address: MacAddr6) -> Option<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        self.managed_device(mac_address)
+            .filter(|device| device.is_connected())
+            .map(|device| device as Arc<dyn OpenSCQ30Device + Send + Sync>)
+    }
+
+    pub fn connected_devices(&self) -> HashMap<MacAddr6, Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        self.inner
+            .devices
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(mac_address, device)| {
+                device
+                    .upgrade()
+                    .filter(|device| device.is_connected())
+                    .map(|device| {
+                        (
+                            *mac_address,
+                            device as Arc<dyn OpenSCQ30Device + Send + Sync>,
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.inner.events.subscribe()
+    }
+}
+
+impl Inner {
+    fn send_event(&self, event: ConnectionEvent) {
+        tracing::debug!("connection event: {event:?}");
+        // An error only means that nobody is subscribed
+        _ = self.events.send(event);
+    }
+
+    /// Forgets `device` and sends a [`ConnectionEvent::Disconnected`], unless it was already replaced by a new
+    /// connection, in which case the new one must stay.
+    fn remove_device(&self, mac_address: MacAddr6, device: *const ManagedDevice) {
+        let mut devices = self.devices.lock().unwrap();
+        if devices
+            .get(&mac_address)
+            .is_some_and(|existing| std::ptr::eq(existing.as_ptr(), device))
+        {
+            devices.remove(&mac_address);
+            drop(devices);
+            self.send_event(ConnectionEvent::Disconnected { mac_address });
+        }
+    }
+}
+
+/// Forgets the device as soon as it loses its connection, rather than waiting for all of its handles to be dropped.
+async fn watch_for_disconnect(
+    manager: Weak<Inner>,
+    mac_address: MacAddr6,
+    device: Weak<ManagedDevice>,
+    mut connection_status: watch::Receiver<ConnectionStatus>,
+) {
+    // An error means the device was dropped, which already removes it
+    if connection_status
+        .wait_for(|status| *status == ConnectionStatus::Disconnected)
+        .await
+        .is_ok()
+        && let Some(manager) = manager.upgrade()
+    {
+        manager.remove_device(mac_address, device.as_ptr());
+    }
+}
+
+/// A device handed out by the [`ConnectionManager`]. Dropping the last handle removes it from the manager.
+struct ManagedDevice {
+    inner: Arc<dyn OpenSCQ30Device + Send + Sync>,
+    mac_address: MacAddr6,
+    manager: Weak<Inner>,
+    _disconnect_watcher_handle: AbortOnDropHandle<()>,
+}
+
+impl ManagedDevice {
+    fn is_connected(&self) -> bool {
+        *self.inner.connection_status().borrow() != ConnectionStatus::Disconnected
+    }
+}
+
+impl Drop for ManagedDevice {
+    fn drop(&mut self) {
+        if let Some(manager) = self.manager.upgrade() {
+            manager.remove_device(self.mac_address, self);
+        }
+    }
+}
+
+#[async_trait]
+impl OpenSCQ30Device for ManagedDevice {
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn model(&self) -> DeviceModel {
+        self.inner.model()
+    }
+
+    fn categories(&self) -> Vec<CategoryId> {
+        self.inner.categories()
+    }
+
+    fn settings_in_category(&self, category_id: &CategoryId) -> Vec<SettingId> {
+        self.inner.settings_in_category(category_id)
+    }
+
+    fn setting(&self, setting_id: &SettingId) -> Option<Setting> {
+        self.inner.setting(setting_id)
+    }
+
+    fn watch_for_changes(&self) -> watch::Receiver<()> {
+        self.inner.watch_for_changes()
+    }
+
+    async fn set_setting_values(
+        &self,
+        setting_values: Vec<(SettingId, Value)>,
+    ) -> device::Result<()> {
+        self.inner.set_setting_values(setting_values).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use crate::{api::OpenSCQ30Session, storage::PairedDevice};
+
+    use super::*;
+
+    async fn session_with_demo_devices(models: &[DeviceModel]) -> OpenSCQ30Session {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        for model in models {
+            session
+                .pair(PairedDevice {
+                    mac_address: model.demo_mac_address(),
+                    model: *model,
+                    is_demo: true,
+                    adapter_address: None,
+                })
+                .await
+                .unwrap();
+        }
+        session
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shares_connection_to_the_same_device() {
+        let model = DeviceModel::SoundcoreA3028;
+        let session = session_with_demo_devices(&[model]).await;
+        let mut events = session.connection_events();
+
+        let first = session.connect(model.demo_mac_address()).await.unwrap();
+        let second = session.connect(model.demo_mac_address()).await.unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            ConnectionEvent::Connected {
+                mac_address: model.demo_mac_address(),
+                model,
+            },
+            events.recv().await.unwrap(),
+        );
+        assert_eq!(
+            Err(TryRecvError::Empty),
+            events.try_recv(),
+            "should only connect once"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnects_when_last_handle_is_dropped() {
+        let model = DeviceModel::SoundcoreA3028;
+        let mac_address = model.demo_mac_address();
+        let session = session_with_demo_devices(&[model]).await;
+        let mut events = session.connection_events();
+
+        let first = session.connect(mac_address).await.unwrap();
+        let second = session.connect(mac_address).await.unwrap();
+        assert_eq!(
+            ConnectionEvent::Connected { mac_address, model },
+            events.recv().await.unwrap(),
+        );
+
+        drop(first);
+        assert!(session.connected_device(mac_address).is_some());
+        assert_eq!(Err(TryRecvError::Empty), events.try_recv());
+
+        drop(second);
+        assert!(session.connected_device(mac_address).is_none());
+        assert_eq!(
+            ConnectionEvent::Disconnected { mac_address },
+            events.recv().await.unwrap(),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_multiple_devices_connected_at_once() {
+        let models = [DeviceModel::SoundcoreA3028, DeviceModel::SoundcoreA3959];
+        let session = session_with_demo_devices(&models).await;
+
+        let (earbuds, headphones) = tokio::join!(
+            session.connect(models[0].demo_mac_address()),
+            session.connect(models[1].demo_mac_address()),
+        );
+        let (earbuds, headphones) = (earbuds.unwrap(), headphones.unwrap());
+
+        let connected_devices = session.connected_devices();
+        assert_eq!(2, connected_devices.len());
+        assert!(Arc::ptr_eq(
+            &earbuds,
+            &connected_devices[&models[0].demo_mac_address()]
+        ));
+        assert!(Arc::ptr_eq(
+            &headphones,
+            &connected_devices[&models[1].demo_mac_address()]
+        ));
+    }
+
+    struct FakeDevice {
+        connection_status: watch::Sender<ConnectionStatus>,
+    }
+
+    #[async_trait]
+    impl OpenSCQ30Device for FakeDevice {
+        fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+            self.connection_status.subscribe()
+        }
+
+        fn model(&self) -> DeviceModel {
+            DeviceModel::SoundcoreA3028
+        }
+
+        fn categories(&self) -> Vec<CategoryId> {
+            Vec::new()
+        }
+
+        fn settings_in_category(&self, _category_id: &CategoryId) -> Vec<SettingId> {
+            Vec::new()
+        }
+
+        fn setting(&self, _setting_id: &SettingId) -> Option<Setting> {
+            None
+        }
+
+        fn watch_for_changes(&self) -> watch::Receiver<()> {
+            watch::channel(()).1
+        }
+
+        async fn set_setting_values(
+            &self,
+            _setting_values: Vec<(SettingId, Value)>,
+        ) -> device::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn connect_fake_device(
+        manager: &ConnectionManager,
+        mac_address: MacAddr6,
+    ) -> (
+        watch::Sender<ConnectionStatus>,
+        Arc<dyn OpenSCQ30Device + Send + Sync>,
+    ) {
+        let connection_status = watch::channel(ConnectionStatus::Connected).0;
+        let device = manager
+            .connect(mac_address, async {
+                Ok(Arc::new(FakeDevice {
+                    connection_status: connection_status.clone(),
+                }) as Arc<dyn OpenSCQ30Device + Send + Sync>)
+            })
+            .await
+            .unwrap();
+        (connection_status, device)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnects_when_link_is_lost_while_handles_are_alive() {
+        let manager = ConnectionManager::new();
+        let mut events = manager.subscribe();
+        let mac_address = MacAddr6::nil();
+        let model = DeviceModel::SoundcoreA3028;
+
+        let (connection_status, device) = connect_fake_device(&manager, mac_address).await;
+        assert_eq!(
+            ConnectionEvent::Connected { mac_address, model },
+            events.recv().await.unwrap(),
+        );
+
+        connection_status.send_replace(ConnectionStatus::Disconnected);
+        assert_eq!(
+            ConnectionEvent::Disconnected { mac_address },
+            events.recv().await.unwrap(),
+        );
+        assert!(manager.device(mac_address).is_none());
+        assert!(manager.connected_devices().is_empty());
+
+        let (_connection_status, new_device) = connect_fake_device(&manager, mac_address).await;
+        assert!(!Arc::ptr_eq(&device, &new_device));
+        assert_eq!(
+            ConnectionEvent::Connected { mac_address, model },
+            events.recv().await.unwrap(),
+        );
+        drop(device);
+        assert_eq!(
+            Err(TryRecvError::Empty),
+            events.try_recv(),
+            "the lost connection should only be disconnected once"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn forgets_connect_locks_once_connected() {
+        let manager = ConnectionManager::new();
+
+        let (_connection_status, _device) = connect_fake_device(&manager, MacAddr6::nil()).await;
+
+        assert!(manager.inner.connect_locks.lock().unwrap().is_empty());
+    }
+}
@@ -44,6 +44,7 @@ pub enum CategoryId {
     LimitHighVolume,
     DualConnections,
     Case,
+    PerChannelEqualizer,
 }
 
 #[derive(
@@ -160,6 +161,16 @@ pub enum SettingId {
     AirPressure,
     EasyChat,
     EasyChatWaitTime,
+    ImportEqualizerApoProfile,
+    ImportEqualizerApoProfileResult,
+    CopyCustomEqualizerProfilesFrom,
+    ExportEqualizerApoProfile,
+    VolumeAdjustmentsLeft,
+    VolumeAdjustmentsRight,
+    ShareCustomEqualizerProfile,
+    ShareCustomEqualizerProfileOutput,
+    ImportSharedEqualizerProfile,
+    ImportSharedEqualizerProfileResult,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -2,6 +2,11 @@ use std::borrow::Cow;
 
 use serde::{Deserialize, Serialize};
 
+use equalizer_apo::{Filter, FilterKind, GraphicEq};
+
+pub mod equalizer_apo;
+pub mod share_string;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Equalizer {
@@ -10,3 +15,318 @@ pub struct Equalizer {
     pub min: i16,
     pub max: i16,
 }
+
+impl Equalizer {
+    // Enough to not miss narrow peaks without being slow
+    const FIT_SAMPLES_PER_BAND: u32 = 32;
+    // The audible range, with the same number of points as AutoEQ's GraphicEQ output
+    const RESPONSE_MIN_HZ: f64 = 20.0;
+    const RESPONSE_MAX_HZ: f64 = 20000.0;
+    const RESPONSE_POINTS: u16 = 127;
+    // Each iteration roughly halves the remaining error
+    const RESPONSE_CORRECTION_ITERATIONS: usize = 16;
+
+    /// Finds the band values that most closely match a frequency response, where `gain_db_at` gives the gain in dB at
+    /// a frequency in Hz. Each band is set to the average gain over the frequencies closer to it than to its
+    /// neighbours on a logarithmic scale, since that's how the bands are spaced. Values outside of `min..=max` are
+    /// clamped and reported in [`EqualizerFit::clipped_bands`].
+    pub fn fit(&self, gain_db_at: impl Fn(f64) -> f64) -> EqualizerFit {
+        self.quantize((0..self.band_hz.len()).map(|index| {
+            let (low, high) = self.band_edges(index);
+            (0..Self::FIT_SAMPLES_PER_BAND)
+                .map(|sample| {
+                    let position =
+                        (f64::from(sample) + 0.5) / f64::from(Self::FIT_SAMPLES_PER_BAND);
+                    gain_db_at(low * (high / low).powf(position))
+                })
+                .sum::<f64>()
+                / f64::from(Self::FIT_SAMPLES_PER_BAND)
+        }))
+    }
+
+    /// Converts `volume_adjustments` from `source`'s band layout to this one, such as when moving custom profiles to a
+    /// different device model. Unlike [`Self::fit`], each band takes the source's gain at exactly its frequency,
+    /// interpolating between the source's bands on a logarithmic scale, so that converting to an identical layout
+    /// changes nothing.
+    pub fn resample(&self, source: &Self, volume_adjustments: &[i16]) -> EqualizerFit {
+        let source_scale = 10f64.powi(source.fraction_digits.into());
+        let mut points = source
+            .band_hz
+            .iter()
+            .zip(volume_adjustments)
+            .map(|(hz, value)| (f64::from(*hz), f64::from(*value) / source_scale))
+            .collect::<Vec<_>>();
+        points.sort_by(|(left, _), (right, _)| left.total_cmp(right));
+        let source_response = GraphicEq { points };
+        self.quantize(
+            self.band_hz
+                .iter()
+                .map(|hz| source_response.gain_db_at(f64::from(*hz))),
+        )
+    }
+
+    /// Approximates the frequency response of `volume_adjustments` from 20hz to 20khz, evenly spaced on a logarithmic
+    /// scale. Each band is treated as a peaking filter as wide as the gap to its neighbours. Since neighbouring
+    /// filters overlap, their gains are corrected so that the curve still passes through each band's value.
+    ///
+    /// The result can be exported with [`GraphicEq::to_csv`], or as an Equalizer APO config with its `Display` impl.
+    pub fn frequency_response(&self, volume_adjustments: &[i16]) -> GraphicEq {
+        let scale = 10f64.powi(self.fraction_digits.into());
+        let targets = self
+            .band_hz
+            .iter()
+            .zip(volume_adjustments)
+            .map(|(hz, value)| (f64::from(*hz), f64::from(*value) / scale))
+            .collect::<Vec<_>>();
+        let mut filters = targets
+            .iter()
+            .enumerate()
+            .map(|(index, (hz, db))| Filter {
+                kind: FilterKind::Peak,
+                frequency_hz: *hz,
+                gain_db: *db,
+                q: self.band_q(index),
+            })
+            .collect::<Vec<_>>();
+        let gain_db_at = |filters: &[Filter], hz: f64| -> f64 {
+            filters.iter().map(|filter| filter.gain_db_at(hz)).sum()
+        };
+
+        for _ in 0..Self::RESPONSE_CORRECTION_ITERATIONS {
+            let errors = targets
+                .iter()
+                .map(|(hz, db)| db - gain_db_at(&filters, *hz))
+                .collect::<Vec<_>>();
+            for (filter, error) in filters.iter_mut().zip(errors) {
+                filter.gain_db += error;
+            }
+        }
+
+        GraphicEq {
+            points: (0..Self::RESPONSE_POINTS)
+                .map(|point| {
+                    let position = f64::from(point) / f64::from(Self::RESPONSE_POINTS - 1);
+                    let hz = Self::RESPONSE_MIN_HZ
+                        * (Self::RESPONSE_MAX_HZ / Self::RESPONSE_MIN_HZ).powf(position);
+                    (hz, gain_db_at(&filters, hz))
+                })
+                .collect(),
+        }
+    }
+
+    /// Q of a peaking filter that reaches the nearest neighbouring band. Reaching further makes uneven layouts overlap
+    /// too much for the gain corrections in [`Self::frequency_response`] to settle.
+    fn band_q(&self, index: usize) -> f64 {
+        let hz = |index: usize| f64::from(self.band_hz[index]);
+        let ratio = [
+            index
+                .checked_sub(1)
+                .map(|previous| hz(index) / hz(previous)),
+            (index + 1 < self.band_hz.len()).then(|| hz(index + 1) / hz(index)),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(f64::INFINITY, f64::min);
+        // Bands without neighbours get the most common width of one octave
+        let ratio = if ratio.is_finite() && ratio > 1.0 {
+            ratio
+        } else {
+            2.0
+        };
+        ratio.sqrt() / (ratio - 1.0)
+    }
+
+    /// Rounds gains in dB, one per band, to this equalizer's precision and range.
+    fn quantize(&self, gains_db: impl Iterator<Item = f64>) -> EqualizerFit {
+        let scale = 10f64.powi(self.fraction_digits.into());
+        let mut clipped_bands = Vec::new();
+        let volume_adjustments = self
+            .band_hz
+            .iter()
+            .zip(gains_db)
+            .map(|(hz, gain_db)| {
+                let value = (gain_db * scale).round();
+                let clamped = value.clamp(self.min.into(), self.max.into());
+                if clamped != value {
+                    clipped_bands.push(ClippedBand {
+                        band_hz: *hz,
+                        target_db: value / scale,
+                        clipped_db: clamped / scale,
+                    });
+                }
+                clamped as i16
+            })
+            .collect();
+        EqualizerFit {
+            volume_adjustments,
+            clipped_bands,
+        }
+    }
+
+    /// Geometric midpoints between a band and its neighbours. The outer bands extend as far out as they do in.
+    fn band_edges(&self, index: usize) -> (f64, f64) {
+        let hz = |index: usize| f64::from(self.band_hz[index]);
+        let center = hz(index);
+        let low = index
+            .checked_sub(1)
+            .map(|previous| (hz(previous) * center).sqrt());
+        let high = self
+            .band_hz
+            .get(index + 1)
+            .map(|next| (f64::from(*next) * center).sqrt());
+        match (low, high) {
+            (Some(low), Some(high)) => (low, high),
+            (Some(low), None) => (low, center * center / low),
+            (None, Some(high)) => (center * center / high, high),
+            (None, None) => (center, center),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EqualizerFit {
+    /// Ready to be used as the value of [`super::Setting::Equalizer`]
+    pub volume_adjustments: Vec<i16>,
+    pub clipped_bands: Vec<ClippedBand>,
+}
+
+/// A band that needed more gain or cut than the device supports.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClippedBand {
+    pub band_hz: u16,
+    pub target_db: f64,
+    pub clipped_db: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn common_equalizer() -> Equalizer {
+        Equalizer {
+            band_hz: Cow::Borrowed(&[100, 200, 400, 800, 1600, 3200, 6400, 12800]),
+            fraction_digits: 1,
+            min: -120,
+            max: 134,
+        }
+    }
+
+    #[test]
+    fn fits_flat_response() {
+        let fit = common_equalizer().fit(|_| 3.25);
+        assert_eq!(vec![33; 8], fit.volume_adjustments);
+        assert_eq!(Vec::<ClippedBand>::new(), fit.clipped_bands);
+    }
+
+    #[test]
+    fn averages_over_band() {
+        // A step on the edge between the 100hz and 200hz bands only affects the 100hz band
+        let fit = common_equalizer().fit(|hz| if hz < 2f64.sqrt() * 100.0 { 6.0 } else { 0.0 });
+        assert_eq!(vec![60, 0, 0, 0, 0, 0, 0, 0], fit.volume_adjustments);
+    }
+
+    #[test]
+    fn clips_values_out_of_range() {
+        // Split between the 800hz and 1600hz bands
+        let fit = common_equalizer().fit(|hz| {
+            if hz < (800f64 * 1600.0).sqrt() {
+                20.0
+            } else {
+                -20.0
+            }
+        });
+        assert_eq!(
+            vec![134, 134, 134, 134, -120, -120, -120, -120],
+            fit.volume_adjustments
+        );
+        assert_eq!(8, fit.clipped_bands.len());
+        assert_eq!(
+            ClippedBand {
+                band_hz: 100,
+                target_db: 20.0,
+                clipped_db: 13.4,
+            },
+            fit.clipped_bands[0],
+        );
+    }
+
+    #[test]
+    fn frequency_response_passes_through_band_values() {
+        let equalizer = common_equalizer();
+        let volume_adjustments = [60, 60, 60, 60, 60, 60, 60, 60];
+        let response = equalizer.frequency_response(&volume_adjustments);
+
+        assert_eq!(127, response.points.len());
+        assert!((response.points[0].0 - 20.0).abs() < 0.01);
+        assert!((response.points[126].0 - 20000.0).abs() < 0.01);
+        for hz in equalizer.band_hz.iter() {
+            let db = response.gain_db_at(f64::from(*hz));
+            assert!((db - 6.0).abs() < 0.1, "{hz}hz should be 6db, got {db}");
+        }
+        // Between bands, the response dips a bit since each band only reaches so far
+        let db = response.gain_db_at(566.0);
+        assert!(
+            (4.5..6.0).contains(&db),
+            "566hz should be a bit under 6db, got {db}"
+        );
+    }
+
+    #[test]
+    fn frequency_response_handles_uneven_bands() {
+        let equalizer = Equalizer {
+            band_hz: Cow::Borrowed(&[80, 150, 300, 500, 700, 1000, 5000, 8000, 12000]),
+            fraction_digits: 0,
+            min: -6,
+            max: 6,
+        };
+        let volume_adjustments = [6, -6, 6, -6, 6, -6, 6, -6, 6];
+        let response = equalizer.frequency_response(&volume_adjustments);
+        for (hz, value) in equalizer.band_hz.iter().zip(volume_adjustments) {
+            let db = response.gain_db_at(f64::from(*hz));
+            assert!(
+                (db - f64::from(value)).abs() < 0.5,
+                "{hz}hz should be {value}db, got {db}",
+            );
+        }
+    }
+
+    #[test]
+    fn frequency_response_ignores_values_without_bands() {
+        let equalizer = common_equalizer();
+        assert_eq!(
+            equalizer.frequency_response(&[10, 20, 30, 40, 50, 60, 70, 80]),
+            equalizer.frequency_response(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]),
+        );
+    }
+
+    #[test]
+    fn resamples_identical_layout_without_changes() {
+        let equalizer = common_equalizer();
+        let volume_adjustments = [-120, -60, 0, 15, 27, 60, 100, 134];
+        let fit = equalizer.resample(&equalizer, &volume_adjustments);
+        assert_eq!(volume_adjustments.to_vec(), fit.volume_adjustments);
+        assert_eq!(Vec::<ClippedBand>::new(), fit.clipped_bands);
+    }
+
+    #[test]
+    fn resamples_to_different_layout() {
+        let target = Equalizer {
+            band_hz: Cow::Borrowed(&[80, 150, 300, 500, 700, 1000, 5000, 8000, 12000]),
+            fraction_digits: 0,
+            min: -6,
+            max: 6,
+        };
+        let fit = target.resample(&common_equalizer(), &[40, 20, 0, 0, -20, -40, 0, 120]);
+        // 80hz is below the lowest source band so it keeps 100hz's gain, and 12000hz is most of the way to 12800hz
+        assert_eq!(vec![4, 3, 1, 0, 0, -1, -1, 4, 6], fit.volume_adjustments);
+        assert_eq!(
+            vec![ClippedBand {
+                band_hz: 12000,
+                target_db: 11.0,
+                clipped_db: 6.0,
+            }],
+            fit.clipped_bands,
+        );
+    }
+}
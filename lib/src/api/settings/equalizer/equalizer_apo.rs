@@ -0,0 +1,478 @@
+//! Reads [Equalizer APO](https://sourceforge.net/p/equalizerapo/wiki/Configuration%20reference/) configs, such as the
+//! `ParametricEQ.txt` and `GraphicEQ.txt` files that [AutoEQ](https://github.com/jaakkopasanen/AutoEq) generates, so
+//! that they can be fit to a device's equalizer with [`super::Equalizer::fit`]. [`GraphicEq`] can also be written back
+//! out, which is how [`super::Equalizer::frequency_response`] is exported.
+//!
+//! Only the commands that describe a frequency response are understood. Others, such as `Channel` or `Include`, are
+//! ignored.
+use std::{
+    f64::consts::PI,
+    fmt::{self, Display, Write},
+    str::FromStr,
+};
+
+use tracing::debug;
+
+/// AutoEQ designs its filters for 48khz
+const SAMPLE_RATE: f64 = 48000.0;
+/// Used by Equalizer APO for shelf filters that don't specify Q
+const DEFAULT_SHELF_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct EqualizerApoProfile {
+    /// Taken from the first comment, since the files AutoEQ generates don't otherwise include a name
+    pub name: Option<String>,
+    /// Headroom to avoid clipping on PCs. Devices manage their own headroom, so this isn't included in the frequency
+    /// response.
+    pub preamp_db: f64,
+    pub filters: Vec<Filter>,
+    pub graphic_eqs: Vec<GraphicEq>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub frequency_hz: f64,
+    pub gain_db: f64,
+    pub q: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterKind {
+    Peak,
+    LowShelf,
+    HighShelf,
+}
+
+/// Gains at arbitrary frequencies, interpolated between on a logarithmic scale.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct GraphicEq {
+    /// Sorted by frequency
+    pub points: Vec<(f64, f64)>,
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("line {line}: {reason}")]
+    InvalidLine { line: usize, reason: String },
+    #[error("line {line}: unsupported filter type {filter_type}")]
+    UnsupportedFilterType { line: usize, filter_type: String },
+    #[error("no filters or GraphicEQ found")]
+    Empty,
+}
+
+impl EqualizerApoProfile {
+    /// Gain in dB at `hz` from all filters and GraphicEQs combined, not including the preamp.
+    pub fn gain_db_at(&self, hz: f64) -> f64 {
+        self.filters
+            .iter()
+            .map(|filter| filter.gain_db_at(hz))
+            .chain(
+                self.graphic_eqs
+                    .iter()
+                    .map(|graphic_eq| graphic_eq.gain_db_at(hz)),
+            )
+            .sum()
+    }
+}
+
+impl FromStr for EqualizerApoProfile {
+    type Err = ParseError;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let mut profile = Self::default();
+        for (index, line) in config.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                if profile.name.is_none() && !comment.trim().is_empty() {
+                    profile.name = Some(comment.trim().to_owned());
+                }
+                continue;
+            }
+
+            let invalid_line = |reason: &str| ParseError::InvalidLine {
+                line: line_number,
+                reason: reason.to_owned(),
+            };
+            let (command, arguments) = line
+                .split_once(':')
+                .ok_or_else(|| invalid_line("expected a command followed by :"))?;
+            // Filters may be numbered, such as "Filter 1"
+            match command.split_whitespace().next().unwrap_or_default() {
+                "Preamp" => {
+                    profile.preamp_db = parse_number(arguments.split_whitespace().next())
+                        .ok_or_else(|| invalid_line("expected preamp gain"))?;
+                }
+                "Filter" => {
+                    if let Some(filter) = Filter::parse(arguments, line_number)? {
+                        profile.filters.push(filter);
+                    }
+                }
+                "GraphicEQ" => profile
+                    .graphic_eqs
+                    .push(GraphicEq::parse(arguments, line_number)?),
+                command => debug!("ignoring unsupported command {command} on line {line_number}"),
+            }
+        }
+
+        if profile.filters.is_empty() && profile.graphic_eqs.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        Ok(profile)
+    }
+}
+
+impl Filter {
+    /// Returns `None` for filters that are turned off.
+    fn parse(arguments: &str, line: usize) -> Result<Option<Self>, ParseError> {
+        let invalid_line = |reason: &str| ParseError::InvalidLine {
+            line,
+            reason: reason.to_owned(),
+        };
+        let mut tokens = arguments.split_whitespace();
+        match tokens.next() {
+            Some("ON") => (),
+            Some("OFF") => return Ok(None),
+            _ => return Err(invalid_line("expected ON or OFF")),
+        }
+        let filter_type = tokens
+            .next()
+            .ok_or_else(|| invalid_line("expected filter type"))?;
+        let kind = match filter_type {
+            "PK" | "PEQ" => FilterKind::Peak,
+            "LS" | "LSC" => FilterKind::LowShelf,
+            "HS" | "HSC" => FilterKind::HighShelf,
+            _ => {
+                return Err(ParseError::UnsupportedFilterType {
+                    line,
+                    filter_type: filter_type.to_owned(),
+                });
+            }
+        };
+
+        let (mut frequency_hz, mut gain_db, mut q) = (None, None, None);
+        while let Some(token) = tokens.next() {
+            // Units following the numbers are skipped over along with anything else that isn't recognized
+            match token {
+                "Fc" => frequency_hz = parse_number(tokens.next()),
+                "Gain" => gain_db = parse_number(tokens.next()),
+                "Q" => q = parse_number(tokens.next()),
+                _ => (),
+            }
+        }
+        let q = match (q, kind) {
+            (Some(q), _) => q,
+            (None, FilterKind::LowShelf | FilterKind::HighShelf) => DEFAULT_SHELF_Q,
+            (None, FilterKind::Peak) => return Err(invalid_line("expected Q")),
+        };
+        if q <= 0.0 {
+            return Err(invalid_line("Q must be positive"));
+        }
+        Ok(Some(Self {
+            kind,
+            frequency_hz: frequency_hz
+                .filter(|hz| *hz > 0.0)
+                .ok_or_else(|| invalid_line("expected positive Fc"))?,
+            gain_db: gain_db.ok_or_else(|| invalid_line("expected Gain"))?,
+            q,
+        }))
+    }
+
+    /// Magnitude response of the biquad from the Audio EQ Cookbook that Equalizer APO uses for this filter.
+    pub fn gain_db_at(&self, hz: f64) -> f64 {
+        let a = 10f64.powf(self.gain_db / 40.0);
+        let w0 = 2.0 * PI * self.frequency_hz / SAMPLE_RATE;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * self.q);
+        let shelf_alpha = 2.0 * a.sqrt() * alpha;
+        let (b, a) = match self.kind {
+            FilterKind::Peak => (
+                [1.0 + alpha * a, -2.0 * cos_w0, 1.0 - alpha * a],
+                [1.0 + alpha / a, -2.0 * cos_w0, 1.0 - alpha / a],
+            ),
+            FilterKind::LowShelf => (
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 + shelf_alpha),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 - shelf_alpha),
+                ],
+                [
+                    (a + 1.0) + (a - 1.0) * cos_w0 + shelf_alpha,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    (a + 1.0) + (a - 1.0) * cos_w0 - shelf_alpha,
+                ],
+            ),
+            FilterKind::HighShelf => (
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 + shelf_alpha),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 - shelf_alpha),
+                ],
+                [
+                    (a + 1.0) - (a - 1.0) * cos_w0 + shelf_alpha,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    (a + 1.0) - (a - 1.0) * cos_w0 - shelf_alpha,
+                ],
+            ),
+        };
+
+        let w = 2.0 * PI * hz / SAMPLE_RATE;
+        let squared_magnitude = |coefficients: [f64; 3]| {
+            let real =
+                coefficients[0] + coefficients[1] * w.cos() + coefficients[2] * (2.0 * w).cos();
+            let imaginary = coefficients[1] * w.sin() + coefficients[2] * (2.0 * w).sin();
+            real * real + imaginary * imaginary
+        };
+        10.0 * (squared_magnitude(b) / squared_magnitude(a)).log10()
+    }
+}
+
+impl GraphicEq {
+    fn parse(arguments: &str, line: usize) -> Result<Self, ParseError> {
+        let invalid_line = |reason: &str| ParseError::InvalidLine {
+            line,
+            reason: reason.to_owned(),
+        };
+        let mut points = arguments
+            .split(';')
+            .filter(|point| !point.trim().is_empty())
+            .map(|point| {
+                let mut numbers = point.split_whitespace();
+                let hz = parse_number(numbers.next()).filter(|hz| *hz > 0.0);
+                let gain_db = parse_number(numbers.next());
+                hz.zip(gain_db)
+                    .ok_or_else(|| invalid_line("expected pairs of frequency and gain"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if points.is_empty() {
+            return Err(invalid_line("expected at least one frequency and gain"));
+        }
+        points.sort_by(|(left, _), (right, _)| left.total_cmp(right));
+        Ok(Self { points })
+    }
+
+    /// Frequencies outside of the outermost points use the gain of the nearest one.
+    pub fn gain_db_at(&self, hz: f64) -> f64 {
+        let next_index = self.points.partition_point(|(point_hz, _)| *point_hz < hz);
+        match (
+            next_index.checked_sub(1).map(|index| self.points[index]),
+            self.points.get(next_index),
+        ) {
+            (Some((low_hz, low_db)), Some((high_hz, high_db))) => {
+                let position = (hz / low_hz).ln() / (high_hz / low_hz).ln();
+                low_db + (high_db - low_db) * position
+            }
+            (Some((_, db)), None) | (None, Some(&(_, db))) => db,
+            (None, None) => 0.0,
+        }
+    }
+
+    /// Formats the points as CSV with the `frequency,raw` header that AutoEQ and Squiglink use for frequency responses.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("frequency,raw\n");
+        for (hz, db) in &self.points {
+            writeln!(csv, "{hz:.2},{db:.2}").expect("writing to a String can't fail");
+        }
+        csv
+    }
+}
+
+/// Formats as a `GraphicEQ` line, which Equalizer APO, AutoEQ, and Wavelet can read.
+impl Display for GraphicEq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GraphicEQ: ")?;
+        for (index, (hz, db)) in self.points.iter().enumerate() {
+            if index != 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{hz:.0} {db:.1}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_number(token: Option<&str>) -> Option<f64> {
+    token?
+        .parse()
+        .ok()
+        .filter(|number: &f64| number.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::api::settings::Equalizer;
+
+    use super::*;
+
+    fn assert_db_eq(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 0.01,
+            "expected {expected} dB, got {actual} dB"
+        );
+    }
+
+    #[test]
+    fn parses_parametric_eq() {
+        let profile: EqualizerApoProfile = "# Sennheiser HD 600
+Preamp: -6.4 dB
+Filter 1: ON LSC Fc 105 Hz Gain 5.5 dB Q 0.70
+Filter 2: ON PK Fc 2200 Hz Gain -3.2 dB Q 1.41
+Filter 3: OFF PK Fc 4000 Hz Gain 2.0 dB Q 2.00
+Filter 4: ON HSC Fc 10000 Hz Gain -2.0 dB Q 0.70
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(Some("Sennheiser HD 600"), profile.name.as_deref());
+        assert_eq!(-6.4, profile.preamp_db);
+        assert_eq!(
+            vec![
+                Filter {
+                    kind: FilterKind::LowShelf,
+                    frequency_hz: 105.0,
+                    gain_db: 5.5,
+                    q: 0.7,
+                },
+                Filter {
+                    kind: FilterKind::Peak,
+                    frequency_hz: 2200.0,
+                    gain_db: -3.2,
+                    q: 1.41,
+                },
+                Filter {
+                    kind: FilterKind::HighShelf,
+                    frequency_hz: 10000.0,
+                    gain_db: -2.0,
+                    q: 0.7,
+                },
+            ],
+            profile.filters,
+        );
+    }
+
+    #[test]
+    fn parses_graphic_eq() {
+        let profile: EqualizerApoProfile =
+            "GraphicEQ: 20 -2.1; 10000 3.0; 1000 0.5".parse().unwrap();
+        assert_eq!(
+            vec![GraphicEq {
+                points: vec![(20.0, -2.1), (1000.0, 0.5), (10000.0, 3.0)],
+            }],
+            profile.graphic_eqs,
+        );
+    }
+
+    #[test]
+    fn graphic_eq_round_trips_through_string() {
+        let graphic_eq = GraphicEq {
+            points: vec![(20.0, -2.1), (1000.0, 0.5), (10000.0, 3.0)],
+        };
+        let config = graphic_eq.to_string();
+        assert_eq!("GraphicEQ: 20 -2.1; 1000 0.5; 10000 3.0", config);
+        assert_eq!(
+            vec![graphic_eq],
+            config.parse::<EqualizerApoProfile>().unwrap().graphic_eqs,
+        );
+    }
+
+    #[test]
+    fn graphic_eq_to_csv() {
+        let graphic_eq = GraphicEq {
+            points: vec![(20.0, -2.1), (1000.0, 0.5)],
+        };
+        assert_eq!(
+            "frequency,raw\n20.00,-2.10\n1000.00,0.50\n",
+            graphic_eq.to_csv()
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_filter_types() {
+        assert_eq!(
+            Err(ParseError::UnsupportedFilterType {
+                line: 2,
+                filter_type: "LP".to_owned(),
+            }),
+            "Preamp: -1 dB\nFilter 1: ON LP Fc 1000 Hz".parse::<EqualizerApoProfile>(),
+        );
+    }
+
+    #[test]
+    fn rejects_configs_without_a_frequency_response() {
+        assert_eq!(
+            Err(ParseError::Empty),
+            "Preamp: -1 dB".parse::<EqualizerApoProfile>(),
+        );
+    }
+
+    #[test]
+    fn peak_filter_has_full_gain_at_center_frequency() {
+        let filter = Filter {
+            kind: FilterKind::Peak,
+            frequency_hz: 1000.0,
+            gain_db: 6.0,
+            q: 1.0,
+        };
+        assert_db_eq(6.0, filter.gain_db_at(1000.0));
+        assert_db_eq(0.0, filter.gain_db_at(20.0));
+    }
+
+    #[test]
+    fn shelf_filters_have_full_gain_past_their_frequency() {
+        let low_shelf = Filter {
+            kind: FilterKind::LowShelf,
+            frequency_hz: 1000.0,
+            gain_db: 6.0,
+            q: DEFAULT_SHELF_Q,
+        };
+        assert_db_eq(6.0, low_shelf.gain_db_at(20.0));
+        assert_db_eq(3.0, low_shelf.gain_db_at(1000.0));
+        assert_db_eq(0.0, low_shelf.gain_db_at(20000.0));
+
+        let high_shelf = Filter {
+            kind: FilterKind::HighShelf,
+            ..low_shelf
+        };
+        assert_db_eq(0.0, high_shelf.gain_db_at(20.0));
+        assert_db_eq(6.0, high_shelf.gain_db_at(20000.0));
+    }
+
+    #[test]
+    fn graphic_eq_interpolates_logarithmically() {
+        let graphic_eq = GraphicEq {
+            points: vec![(100.0, 0.0), (10000.0, 10.0)],
+        };
+        assert_db_eq(0.0, graphic_eq.gain_db_at(50.0));
+        assert_db_eq(5.0, graphic_eq.gain_db_at(1000.0));
+        assert_db_eq(10.0, graphic_eq.gain_db_at(20000.0));
+    }
+
+    #[test]
+    fn fits_to_device_equalizer() {
+        let profile: EqualizerApoProfile =
+            "Filter: ON PK Fc 400 Hz Gain 20 dB Q 1".parse().unwrap();
+        let fit = Equalizer {
+            band_hz: Cow::Borrowed(&[100, 200, 400, 800, 1600, 3200, 6400, 12800]),
+            fraction_digits: 1,
+            min: -120,
+            max: 134,
+        }
+        .fit(|hz| profile.gain_db_at(hz));
+
+        assert_eq!(
+            vec![400],
+            fit.clipped_bands
+                .iter()
+                .map(|band| band.band_hz)
+                .collect::<Vec<_>>()
+        );
+        // Falls off symmetrically around 400hz on a logarithmic scale
+        assert_eq!(vec![24, 75, 134, 75, 24, 7, 2, 0], fit.volume_adjustments);
+    }
+}
@@ -0,0 +1,279 @@
+//! Compact strings for sharing a single custom equalizer profile, short enough to paste into a chat or to show as a QR
+//! code. Unlike the JSON export, the device model and band layout the profile was made for are included, so that it
+//! can be converted when imported on a device with different bands.
+//!
+//! The format is [`PREFIX`] followed by base64url (without padding) of:
+//! - version: u8
+//! - device model: u8 length followed by that many bytes of UTF-8
+//! - name: u8 length followed by that many bytes of UTF-8
+//! - fraction digits: u8
+//! - band frequencies in Hz: u8 count followed by that many big endian u16s
+//! - channels: u8 count (1, or 2 for separate left and right), each of which is a u8 count followed by that many big
+//!   endian i16 volume adjustments
+//! - CRC-32 (IEEE) of everything before it: big endian u32
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use nom::{
+    Parser,
+    combinator::{all_consuming, map_res},
+    multi::length_count,
+    number::complete::{be_i16, be_u8, be_u16},
+};
+
+use crate::{
+    devices::DeviceModel,
+    serialization::versioned::{self, UnsupportedVersionError},
+    util::{base64url, crc32},
+};
+
+use super::Equalizer;
+
+pub const PREFIX: &str = "openscq30-eq:";
+/// See [`UnsupportedVersionError`] for when this changes.
+pub const VERSION: u8 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedEqualizerProfile {
+    pub device_model: DeviceModel,
+    /// Truncated to 255 bytes when encoded
+    pub name: String,
+    pub band_hz: Vec<u16>,
+    pub fraction_digits: u8,
+    pub volume_adjustments: Vec<i16>,
+    /// Only set when the right channel differs from the left, in which case `volume_adjustments` is the left channel.
+    pub right_volume_adjustments: Option<Vec<i16>>,
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("not an OpenSCQ30 equalizer profile, expected it to start with {PREFIX}")]
+    MissingPrefix,
+    #[error("invalid character {0:?}")]
+    InvalidCharacter(char),
+    #[error("checksum mismatch, the profile may not have been copied completely")]
+    ChecksumMismatch,
+    #[error(transparent)]
+    UnsupportedVersion(#[from] UnsupportedVersionError),
+    #[error("unknown device model {0}")]
+    UnknownDeviceModel(String),
+    #[error("malformed profile data")]
+    Malformed,
+}
+
+impl SharedEqualizerProfile {
+    /// The layout the profile was made for, for use with [`Equalizer::resample`]. The range isn't shared, so it is
+    /// left unbounded.
+    pub fn equalizer(&self) -> Equalizer {
+        Equalizer {
+            band_hz: Cow::Owned(self.band_hz.clone()),
+            fraction_digits: self.fraction_digits.into(),
+            min: i16::MIN,
+            max: i16::MAX,
+        }
+    }
+
+    /// Whether the volume adjustments can be used as is on a device with `equalizer`'s layout, rather than needing to
+    /// be resampled.
+    pub fn fits(&self, equalizer: &Equalizer) -> bool {
+        self.band_hz == *equalizer.band_hz
+            && i16::from(self.fraction_digits) == equalizer.fraction_digits
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        fn push_len_prefixed(bytes: &mut Vec<u8>, data: &[u8]) {
+            bytes.push(data.len() as u8);
+            bytes.extend_from_slice(data);
+        }
+        fn push_volume_adjustments(bytes: &mut Vec<u8>, volume_adjustments: &[i16]) {
+            let volume_adjustments = &volume_adjustments[..volume_adjustments.len().min(255)];
+            bytes.push(volume_adjustments.len() as u8);
+            bytes.extend(volume_adjustments.iter().flat_map(|v| v.to_be_bytes()));
+        }
+
+        let mut name_len = self.name.len().min(255);
+        while !self.name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+        let band_hz = &self.band_hz[..self.band_hz.len().min(255)];
+
+        let mut bytes = vec![VERSION];
+        push_len_prefixed(&mut bytes, self.device_model.as_ref().as_bytes());
+        push_len_prefixed(&mut bytes, &self.name.as_bytes()[..name_len]);
+        bytes.push(self.fraction_digits);
+        bytes.push(band_hz.len() as u8);
+        bytes.extend(band_hz.iter().flat_map(|hz| hz.to_be_bytes()));
+        match &self.right_volume_adjustments {
+            Some(right_volume_adjustments) => {
+                bytes.push(2);
+                push_volume_adjustments(&mut bytes, &self.volume_adjustments);
+                push_volume_adjustments(&mut bytes, right_volume_adjustments);
+            }
+            None => {
+                bytes.push(1);
+                push_volume_adjustments(&mut bytes, &self.volume_adjustments);
+            }
+        }
+        bytes.extend(crc32(&bytes).to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let Some(checksum_start) = bytes.len().checked_sub(4) else {
+            return Err(ParseError::Malformed);
+        };
+        let (payload, checksum) = bytes.split_at(checksum_start);
+        if crc32(payload).to_be_bytes() != checksum {
+            return Err(ParseError::ChecksumMismatch);
+        }
+        let Some((&version, rest)) = payload.split_first() else {
+            return Err(ParseError::Malformed);
+        };
+        versioned::check_version("equalizer profile", version.into(), VERSION.into())?;
+
+        let length_prefixed_str = || map_res(length_count(be_u8, be_u8), String::from_utf8);
+        let (_, (device_model, name, fraction_digits, band_hz, channels)) = all_consuming((
+            length_prefixed_str(),
+            length_prefixed_str(),
+            be_u8,
+            length_count(be_u8, be_u16),
+            length_count(be_u8, length_count(be_u8, be_i16)),
+        ))
+        .parse(rest)
+        .map_err(|_: nom::Err<nom::error::Error<&[u8]>>| ParseError::Malformed)?;
+
+        let device_model = device_model
+            .parse()
+            .map_err(|_| ParseError::UnknownDeviceModel(device_model))?;
+        let mut channels = channels.into_iter();
+        let (Some(volume_adjustments), right_volume_adjustments, None) =
+            (channels.next(), channels.next(), channels.next())
+        else {
+            return Err(ParseError::Malformed);
+        };
+        Ok(Self {
+            device_model,
+            name,
+            band_hz,
+            fraction_digits,
+            volume_adjustments,
+            right_volume_adjustments,
+        })
+    }
+}
+
+impl fmt::Display for SharedEqualizerProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(PREFIX)?;
+        f.write_str(&base64url::encode(&self.to_bytes()))
+    }
+}
+
+impl FromStr for SharedEqualizerProfile {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Whitespace tends to sneak in when copying from chat messages
+        let encoded = s
+            .trim()
+            .strip_prefix(PREFIX)
+            .ok_or(ParseError::MissingPrefix)?;
+        Self::from_bytes(&base64url::decode(encoded)?)
+    }
+}
+
+impl From<base64url::DecodeError> for ParseError {
+    fn from(err: base64url::DecodeError) -> Self {
+        match err {
+            base64url::DecodeError::InvalidCharacter(c) => Self::InvalidCharacter(c),
+            base64url::DecodeError::InvalidLength(_) => Self::Malformed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> SharedEqualizerProfile {
+        SharedEqualizerProfile {
+            device_model: DeviceModel::SoundcoreA3933,
+            name: "Bass ♪".to_owned(),
+            band_hz: vec![100, 200, 400, 800, 1600, 3200, 6400, 12800],
+            fraction_digits: 1,
+            volume_adjustments: vec![40, 30, 10, 0, 0, 0, 0, 0, -5, 7],
+            right_volume_adjustments: None,
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let profile = profile();
+        let share_string = profile.to_string();
+        assert!(share_string.starts_with(PREFIX), "{share_string}");
+        assert_eq!(Ok(profile), share_string.parse());
+    }
+
+    #[test]
+    fn round_trips_with_right_channel() {
+        let profile = SharedEqualizerProfile {
+            right_volume_adjustments: Some(vec![-40, -30, -10, 0, 0, 0, 0, 0, 5, -7]),
+            ..profile()
+        };
+        assert_eq!(Ok(profile.clone()), profile.to_string().parse());
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        let share_string = format!("  {}\n", profile());
+        assert_eq!(Ok(profile()), share_string.parse());
+    }
+
+    #[test]
+    fn truncates_long_names_on_char_boundaries() {
+        let profile = SharedEqualizerProfile {
+            name: "♪".repeat(100),
+            ..profile()
+        };
+        let parsed: SharedEqualizerProfile = profile.to_string().parse().unwrap();
+        assert_eq!("♪".repeat(85), parsed.name);
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let share_string = profile().to_string();
+        let mut corrupted = share_string.into_bytes();
+        let last = corrupted.len() - 10;
+        corrupted[last] = if corrupted[last] == b'A' { b'B' } else { b'A' };
+        assert_eq!(
+            Err(ParseError::ChecksumMismatch),
+            String::from_utf8(corrupted)
+                .unwrap()
+                .parse::<SharedEqualizerProfile>(),
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut bytes = profile().to_bytes();
+        bytes.truncate(bytes.len() - 4);
+        bytes[0] = VERSION + 1;
+        bytes.extend(crc32(&bytes).to_be_bytes());
+        let share_string = format!("{PREFIX}{}", base64url::encode(&bytes));
+        assert_eq!(
+            Err(ParseError::UnsupportedVersion(UnsupportedVersionError {
+                format: "equalizer profile",
+                version: (VERSION + 1).into(),
+                newest_supported_version: VERSION.into(),
+            })),
+            share_string.parse::<SharedEqualizerProfile>(),
+        );
+    }
+
+    #[test]
+    fn rejects_missing_prefix() {
+        assert_eq!(
+            Err(ParseError::MissingPrefix),
+            r#"[{"name":"Test","volumeAdjustments":[]}]"#.parse::<SharedEqualizerProfile>(),
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use crate::api::connection::{self, RfcommBackend};
+use std::sync::Arc;
+
+use crate::api::connection::{
+    self, BluetoothAdapter, GattBackend, RfcommBackend, Transport, TransportBackend,
+};
 
 cfg_select! {
     target_os = "linux" => {
@@ -12,25 +16,55 @@ cfg_select! {
         mod none;
     }
 }
+pub mod btsnoop;
+pub mod capture;
+pub mod device_definition;
 #[cfg(test)]
 pub(crate) mod mock;
+pub mod unsupported;
 
 /// Groups together platform specific implementations of various means of connecting to devices.
 pub trait ConnectionBackends {
     type Rfcomm: RfcommBackend + Send + Sync;
 
+    type Gatt: GattBackend + Send + Sync;
+
     fn rfcomm(&self) -> impl Future<Output = connection::Result<Self::Rfcomm>> + Send;
+    fn gatt(&self) -> impl Future<Output = connection::Result<Self::Gatt>> + Send;
+
+    /// Lists the bluetooth adapters that devices can be connected through. Backends that don't support choosing an
+    /// adapter return an empty list.
+    fn adapters(&self) -> impl Future<Output = connection::Result<Vec<BluetoothAdapter>>> + Send {
+        async { Ok(Vec::new()) }
+    }
+}
+
+/// Creates only the backend needed for `transport`.
+pub async fn transport_backend(
+    backends: &(impl ConnectionBackends + 'static),
+    transport: Transport,
+) -> connection::Result<TransportBackend> {
+    Ok(match transport {
+        Transport::Rfcomm(_) => TransportBackend::Rfcomm(Arc::new(backends.rfcomm().await?)),
+        Transport::Gatt(_) => TransportBackend::Gatt(Arc::new(backends.gatt().await?)),
+    })
 }
 
-pub fn default_backends() -> Option<impl ConnectionBackends> {
+/// `adapter` is the name or address of the bluetooth adapter to use, or `None` to use any adapter. Choosing an adapter
+/// is only supported on Linux.
+pub fn default_backends(adapter: Option<String>) -> Option<impl ConnectionBackends> {
     cfg_select! {
         target_os = "linux" => {
-            Some(linux::PlatformConnectionBackends)
+            Some(linux::PlatformConnectionBackends::new(adapter))
         }
         target_os = "windows" => {
+            if let Some(adapter) = adapter {
+                tracing::warn!("choosing a bluetooth adapter is not supported on Windows, ignoring {adapter}");
+            }
             Some(windows::PlatformConnectionBackends)
         }
         _ => {
+            _ = adapter;
             None::<none::NoneConnectionBackends>
         }
     }
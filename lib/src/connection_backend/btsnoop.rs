@@ -0,0 +1,749 @@
+//! Extraction of Soundcore traffic from btsnoop HCI logs, such as the `btsnoop_hci.log` found in Android bug reports.
+//!
+//! ACL data is reassembled into L2CAP frames, L2CAP frames on RFCOMM channels are split into RFCOMM frames, and the
+//! payloads of those frames are reassembled into Soundcore packets. Any RFCOMM channel that doesn't carry Soundcore
+//! packets is ignored.
+use std::{
+    collections::{HashMap, HashSet},
+    panic::Location,
+    path::Path,
+};
+
+use macaddr::MacAddr6;
+use nom_language::error::VerboseError;
+
+use crate::{
+    connection_backend::capture::{
+        CAPTURE_VERSION, Capture, CaptureDirection, CaptureEntry, CaptureHeader,
+    },
+    devices::soundcore::common::packet::{self, ChecksumKind, Direction},
+    macros::impl_from_source_error_with_location,
+};
+
+const MAGIC: &[u8; 8] = b"btsnoop\0";
+const FILE_HEADER_LENGTH: usize = 16;
+const RECORD_HEADER_LENGTH: usize = 24;
+/// Microseconds from midnight January 1st, 0 AD to the unix epoch
+const UNIX_EPOCH_OFFSET_MICROS: i64 = 0x00dc_ddb3_0f2f_8000;
+
+const DATALINK_HCI_UNENCAPSULATED: u32 = 1001;
+const DATALINK_HCI_UART: u32 = 1002;
+
+const H4_ACL: u8 = 0x02;
+const H4_EVENT: u8 = 0x04;
+const EVENT_CONNECTION_COMPLETE: u8 = 0x03;
+
+const L2CAP_SIGNALING_CID: u16 = 0x0001;
+const L2CAP_FIRST_DYNAMIC_CID: u16 = 0x0040;
+const L2CAP_CONNECTION_REQUEST: u8 = 0x02;
+const L2CAP_CONNECTION_RESPONSE: u8 = 0x03;
+const RFCOMM_PSM: u16 = 0x0003;
+
+const RFCOMM_UIH: u8 = 0xEF;
+const RFCOMM_POLL_FINAL: u8 = 0x10;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("io error")]
+    IOError {
+        source: std::io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("not a btsnoop log")]
+    InvalidHeader,
+    #[error("unsupported btsnoop version {version}")]
+    UnsupportedVersion { version: u32 },
+    #[error("unsupported btsnoop datalink type {datalink}")]
+    UnsupportedDatalink { datalink: u32 },
+    #[error("invalid btsnoop record timestamp {timestamp}")]
+    InvalidTimestamp { timestamp: i64 },
+}
+impl_from_source_error_with_location!(Error::IOError(std::io::Error));
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A single RFCOMM frame's payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RfcommFrame {
+    /// Microseconds since the unix epoch
+    pub timestamp_micros: i64,
+    pub direction: CaptureDirection,
+    pub connection_handle: u16,
+    pub dlci: u8,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimestampedPacket {
+    /// Microseconds since the unix epoch, taken from the record containing the end of the packet
+    pub timestamp_micros: i64,
+    pub connection_handle: u16,
+    pub dlci: u8,
+    pub packet: SoundcorePacket,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoundcorePacket {
+    Inbound(packet::Inbound),
+    Outbound(packet::Outbound),
+}
+
+impl SoundcorePacket {
+    pub fn direction(&self) -> Direction {
+        match self {
+            Self::Inbound(_) => Direction::Inbound,
+            Self::Outbound(_) => Direction::Outbound,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BtsnoopLog {
+    /// Mac addresses of devices that connected while the log was being recorded
+    pub mac_addresses: HashMap<u16, MacAddr6>,
+    pub rfcomm_frames: Vec<RfcommFrame>,
+}
+
+impl BtsnoopLog {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < FILE_HEADER_LENGTH || !bytes.starts_with(MAGIC) {
+            return Err(Error::InvalidHeader);
+        }
+        let version = be_u32(&bytes[8..12]);
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let datalink = be_u32(&bytes[12..16]);
+        if datalink != DATALINK_HCI_UNENCAPSULATED && datalink != DATALINK_HCI_UART {
+            return Err(Error::UnsupportedDatalink { datalink });
+        }
+
+        let mut parser = Parser::default();
+        let mut remaining = &bytes[FILE_HEADER_LENGTH..];
+        while remaining.len() >= RECORD_HEADER_LENGTH {
+            let original_length = be_u32(&remaining[0..4]) as usize;
+            let included_length = be_u32(&remaining[4..8]) as usize;
+            let flags = be_u32(&remaining[8..12]);
+            let timestamp = i64::from_be_bytes(remaining[16..24].try_into().unwrap());
+            let Some(data) =
+                remaining.get(RECORD_HEADER_LENGTH..RECORD_HEADER_LENGTH + included_length)
+            else {
+                // Logs pulled from a device that is still recording often end part way through a record
+                tracing::warn!("btsnoop: ignoring incomplete record at end of log");
+                break;
+            };
+            remaining = &remaining[RECORD_HEADER_LENGTH + included_length..];
+            if included_length != original_length {
+                tracing::warn!("btsnoop: skipping record that was cut short when recording");
+                continue;
+            }
+
+            let direction = if flags & 1 == 0 {
+                CaptureDirection::Outbound
+            } else {
+                CaptureDirection::Inbound
+            };
+            let is_command_or_event = flags & 2 != 0;
+            let (packet_type, data) = if datalink == DATALINK_HCI_UART {
+                match data.split_first() {
+                    Some((packet_type, data)) => (*packet_type, data),
+                    None => continue,
+                }
+            } else if is_command_or_event {
+                // Commands are only sent and events are only received
+                match direction {
+                    CaptureDirection::Outbound => continue,
+                    CaptureDirection::Inbound => (H4_EVENT, data),
+                }
+            } else {
+                (H4_ACL, data)
+            };
+
+            let timestamp_micros = timestamp
+                .checked_sub(UNIX_EPOCH_OFFSET_MICROS)
+                .ok_or(Error::InvalidTimestamp { timestamp })?;
+            match packet_type {
+                H4_ACL => parser.acl(timestamp_micros, direction, data),
+                H4_EVENT => parser.event(data),
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            mac_addresses: parser.mac_addresses,
+            rfcomm_frames: parser.rfcomm_frames,
+        })
+    }
+
+    /// Reassembles Soundcore packets from the payloads of RFCOMM frames. Each channel and direction is reassembled
+    /// separately, and bytes that aren't part of a Soundcore packet are skipped.
+    pub fn soundcore_packets(&self, checksum_kind: ChecksumKind) -> Vec<TimestampedPacket> {
+        let mut buffers = HashMap::<(u16, u8, CaptureDirection), Vec<u8>>::new();
+        let mut packets = Vec::new();
+        for frame in &self.rfcomm_frames {
+            let buffer = buffers
+                .entry((frame.connection_handle, frame.dlci, frame.direction))
+                .or_default();
+            buffer.extend_from_slice(&frame.data);
+            loop {
+                let result = match frame.direction {
+                    CaptureDirection::Inbound => {
+                        take_packet::<packet::InboundMarker>(buffer, checksum_kind)
+                            .map(SoundcorePacket::Inbound)
+                    }
+                    CaptureDirection::Outbound => {
+                        take_packet::<packet::OutboundMarker>(buffer, checksum_kind)
+                            .map(SoundcorePacket::Outbound)
+                    }
+                };
+                match result {
+                    TakeResult::Packet(length, packet) => {
+                        buffer.drain(..length);
+                        packets.push(TimestampedPacket {
+                            timestamp_micros: frame.timestamp_micros,
+                            connection_handle: frame.connection_handle,
+                            dlci: frame.dlci,
+                            packet,
+                        });
+                    }
+                    TakeResult::Skip(length) => {
+                        buffer.drain(..length);
+                    }
+                    TakeResult::Incomplete => break,
+                }
+            }
+        }
+        packets
+    }
+
+    /// Converts the RFCOMM channel carrying Soundcore traffic into a [`Capture`] so that it can be replayed. If
+    /// multiple channels carry Soundcore traffic, the first one is used. Returns None if there is no Soundcore
+    /// traffic.
+    pub fn to_capture(&self, checksum_kind: ChecksumKind) -> Option<Capture> {
+        let packets = self.soundcore_packets(checksum_kind);
+        let connection_handle = packets.first()?.connection_handle;
+        let dlcis = packets
+            .iter()
+            .filter(|packet| packet.connection_handle == connection_handle)
+            .map(|packet| packet.dlci)
+            .collect::<HashSet<_>>();
+        let frames = self
+            .rfcomm_frames
+            .iter()
+            .filter(|frame| {
+                frame.connection_handle == connection_handle && dlcis.contains(&frame.dlci)
+            })
+            .collect::<Vec<_>>();
+        let started_at = frames.first()?.timestamp_micros;
+
+        Some(Capture {
+            header: CaptureHeader {
+                version: CAPTURE_VERSION,
+                mac_address: self
+                    .mac_addresses
+                    .get(&connection_handle)
+                    .copied()
+                    .unwrap_or_default(),
+                started_at: (started_at / 1000).try_into().unwrap_or_default(),
+            },
+            entries: frames
+                .into_iter()
+                .map(|frame| CaptureEntry {
+                    elapsed_micros: (frame.timestamp_micros - started_at)
+                        .try_into()
+                        .unwrap_or_default(),
+                    direction: frame.direction,
+                    data: frame.data.clone(),
+                })
+                .collect(),
+        })
+    }
+}
+
+enum TakeResult<T> {
+    Packet(usize, T),
+    /// Not the start of a packet, so this many bytes should be thrown away
+    Skip(usize),
+    Incomplete,
+}
+
+impl<T> TakeResult<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> TakeResult<U> {
+        match self {
+            Self::Packet(length, packet) => TakeResult::Packet(length, f(packet)),
+            Self::Skip(length) => TakeResult::Skip(length),
+            Self::Incomplete => TakeResult::Incomplete,
+        }
+    }
+}
+
+fn take_packet<D: packet::HasDirection>(
+    buffer: &[u8],
+    checksum_kind: ChecksumKind,
+) -> TakeResult<packet::Packet<D>> {
+    let prefix = D::DIRECTION.bytes();
+    let Some(start) = buffer
+        .windows(prefix.len())
+        .position(|window| window == prefix)
+    else {
+        // The end of the buffer could be the start of a prefix
+        let keep = (1..prefix.len())
+            .rev()
+            .find(|length| buffer.ends_with(&prefix[..*length]))
+            .unwrap_or(0);
+        return match buffer.len() - keep {
+            0 => TakeResult::Incomplete,
+            skip => TakeResult::Skip(skip),
+        };
+    };
+    if start != 0 {
+        return TakeResult::Skip(start);
+    }
+    match packet::Packet::<D>::take::<VerboseError<_>>(checksum_kind)(buffer) {
+        Ok((remaining, packet)) => TakeResult::Packet(buffer.len() - remaining.len(), packet),
+        Err(nom::Err::Incomplete(_)) => TakeResult::Incomplete,
+        Err(_) => {
+            tracing::debug!("btsnoop: skipping invalid packet");
+            TakeResult::Skip(prefix.len())
+        }
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    mac_addresses: HashMap<u16, MacAddr6>,
+    rfcomm_frames: Vec<RfcommFrame>,
+    /// Incomplete L2CAP frames
+    l2cap_buffers: HashMap<(u16, CaptureDirection), Vec<u8>>,
+    /// L2CAP connection requests for RFCOMM that haven't been responded to yet, keyed by identifier
+    pending_rfcomm_connections: HashSet<(u16, u8)>,
+    /// Channels known to be RFCOMM. The cid is the one in the L2CAP header, so it is the receiver's cid.
+    rfcomm_channels: HashSet<(u16, CaptureDirection, u16)>,
+}
+
+impl Parser {
+    fn event(&mut self, data: &[u8]) {
+        // event code, parameter length, status, connection handle, mac address
+        if data.len() >= 11 && data[0] == EVENT_CONNECTION_COMPLETE && data[2] == 0 {
+            let connection_handle = le_u16(&data[3..5]) & 0x0FFF;
+            let mut mac_address: [u8; 6] = data[5..11].try_into().unwrap();
+            mac_address.reverse();
+            self.mac_addresses
+                .insert(connection_handle, MacAddr6::from(mac_address));
+        }
+    }
+
+    fn acl(&mut self, timestamp_micros: i64, direction: CaptureDirection, data: &[u8]) {
+        if data.len() < 4 {
+            return;
+        }
+        let handle_and_flags = le_u16(&data[0..2]);
+        let connection_handle = handle_and_flags & 0x0FFF;
+        let is_continuation = (handle_and_flags >> 12) & 0b11 == 0b01;
+        let data = &data[4..];
+
+        let buffer = self
+            .l2cap_buffers
+            .entry((connection_handle, direction))
+            .or_default();
+        if is_continuation {
+            if buffer.is_empty() {
+                // We missed the start of this frame
+                return;
+            }
+        } else {
+            buffer.clear();
+        }
+        buffer.extend_from_slice(data);
+
+        if buffer.len() < 4 {
+            return;
+        }
+        let length = le_u16(&buffer[0..2]) as usize;
+        if buffer.len() < 4 + length {
+            return;
+        }
+        let frame = std::mem::take(buffer);
+        let cid = le_u16(&frame[2..4]);
+        let payload = &frame[4..4 + length];
+        if cid == L2CAP_SIGNALING_CID {
+            self.signaling(connection_handle, direction, payload);
+        } else if self.is_rfcomm_channel(connection_handle, direction, cid)
+            && let Some((dlci, data)) = rfcomm_uih_payload(payload)
+        {
+            self.rfcomm_frames.push(RfcommFrame {
+                timestamp_micros,
+                direction,
+                connection_handle,
+                dlci,
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    fn is_rfcomm_channel(
+        &self,
+        connection_handle: u16,
+        direction: CaptureDirection,
+        cid: u16,
+    ) -> bool {
+        if self
+            .rfcomm_channels
+            .iter()
+            .any(|(handle, _, _)| *handle == connection_handle)
+        {
+            self.rfcomm_channels
+                .contains(&(connection_handle, direction, cid))
+        } else {
+            // The connection was established before the log started, so we have to guess
+            cid >= L2CAP_FIRST_DYNAMIC_CID
+        }
+    }
+
+    fn signaling(
+        &mut self,
+        connection_handle: u16,
+        direction: CaptureDirection,
+        mut payload: &[u8],
+    ) {
+        // A single signaling frame can contain multiple commands
+        while payload.len() >= 4 {
+            let code = payload[0];
+            let identifier = payload[1];
+            let length = le_u16(&payload[2..4]) as usize;
+            let Some(data) = payload.get(4..4 + length) else {
+                return;
+            };
+            payload = &payload[4 + length..];
+
+            let opposite_direction = match direction {
+                CaptureDirection::Inbound => CaptureDirection::Outbound,
+                CaptureDirection::Outbound => CaptureDirection::Inbound,
+            };
+            match code {
+                // psm, source cid
+                L2CAP_CONNECTION_REQUEST
+                    if data.len() >= 4 && le_u16(&data[0..2]) == RFCOMM_PSM =>
+                {
+                    self.pending_rfcomm_connections
+                        .insert((connection_handle, identifier));
+                    self.rfcomm_channels.insert((
+                        connection_handle,
+                        opposite_direction,
+                        le_u16(&data[2..4]),
+                    ));
+                }
+                // destination cid, source cid, result, status
+                L2CAP_CONNECTION_RESPONSE
+                    if data.len() >= 8
+                        && le_u16(&data[4..6]) == 0
+                        && self
+                            .pending_rfcomm_connections
+                            .remove(&(connection_handle, identifier)) =>
+                {
+                    self.rfcomm_channels.insert((
+                        connection_handle,
+                        opposite_direction,
+                        le_u16(&data[0..2]),
+                    ));
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Returns the dlci and payload of UIH frames on data channels. Everything else is control traffic.
+fn rfcomm_uih_payload(frame: &[u8]) -> Option<(u8, &[u8])> {
+    let [address, control, rest @ ..] = frame else {
+        return None;
+    };
+    let dlci = address >> 2;
+    if dlci == 0 || control & !RFCOMM_POLL_FINAL != RFCOMM_UIH {
+        return None;
+    }
+    let (length, rest) = match rest {
+        [length, rest @ ..] if length & 1 == 1 => ((length >> 1) as usize, rest),
+        [low, high, rest @ ..] => (((low >> 1) as usize) | ((*high as usize) << 7), rest),
+        _ => return None,
+    };
+    // With credit based flow control, the poll/final bit indicates that a credit count comes before the payload
+    let rest = if control & RFCOMM_POLL_FINAL != 0 {
+        rest.get(1..)?
+    } else {
+        rest
+    };
+    // The frame check sequence after the payload isn't verified, since only the address and control fields are
+    // covered by it
+    Some((dlci, rest.get(..length)?))
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes(bytes.try_into().unwrap())
+}
+
+fn le_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes(bytes.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::devices::soundcore::common::packet::Command;
+
+    use super::*;
+
+    const HANDLE: u16 = 0x0042;
+    const MAC_ADDRESS: MacAddr6 = MacAddr6::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+    const SOUNDCORE_DLCI: u8 = 2;
+    /// Our L2CAP cid, so the one used by inbound frames
+    const LOCAL_CID: u16 = 0x0040;
+    /// The device's L2CAP cid, so the one used by outbound frames
+    const REMOTE_CID: u16 = 0x0041;
+
+    struct LogBuilder {
+        bytes: Vec<u8>,
+        timestamp_micros: i64,
+    }
+
+    impl LogBuilder {
+        fn new() -> Self {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(1u32.to_be_bytes());
+            bytes.extend(DATALINK_HCI_UART.to_be_bytes());
+            Self {
+                bytes,
+                timestamp_micros: 1_700_000_000_000_000,
+            }
+        }
+
+        fn record(&mut self, direction: CaptureDirection, is_event: bool, data: &[u8]) {
+            let flags = match direction {
+                CaptureDirection::Outbound => 0u32,
+                CaptureDirection::Inbound => 1,
+            } | if is_event { 2 } else { 0 };
+            self.bytes.extend((data.len() as u32).to_be_bytes());
+            self.bytes.extend((data.len() as u32).to_be_bytes());
+            self.bytes.extend(flags.to_be_bytes());
+            self.bytes.extend(0u32.to_be_bytes());
+            self.bytes
+                .extend((self.timestamp_micros + UNIX_EPOCH_OFFSET_MICROS).to_be_bytes());
+            self.bytes.extend(data);
+            self.timestamp_micros += 1000;
+        }
+
+        fn connection_complete(&mut self) {
+            let mut data = vec![H4_EVENT, EVENT_CONNECTION_COMPLETE, 11, 0];
+            data.extend(HANDLE.to_le_bytes());
+            data.extend(MAC_ADDRESS.as_bytes().iter().rev());
+            data.extend([0x01, 0x00]);
+            self.record(CaptureDirection::Inbound, true, &data);
+        }
+
+        /// Sends an L2CAP frame split into ACL fragments of at most `fragment_size` bytes
+        fn l2cap(
+            &mut self,
+            direction: CaptureDirection,
+            cid: u16,
+            payload: &[u8],
+            fragment_size: usize,
+        ) {
+            let mut frame = (payload.len() as u16).to_le_bytes().to_vec();
+            frame.extend(cid.to_le_bytes());
+            frame.extend(payload);
+            for (i, fragment) in frame.chunks(fragment_size).enumerate() {
+                let packet_boundary: u16 = if i == 0 { 0b10 } else { 0b01 };
+                let mut data = vec![H4_ACL];
+                data.extend((HANDLE | (packet_boundary << 12)).to_le_bytes());
+                data.extend((fragment.len() as u16).to_le_bytes());
+                data.extend(fragment);
+                self.record(direction, false, &data);
+            }
+        }
+
+        fn open_rfcomm_channel(&mut self) {
+            // Connection request: psm 3, source cid is ours
+            let mut request = vec![L2CAP_CONNECTION_REQUEST, 1, 4, 0];
+            request.extend(RFCOMM_PSM.to_le_bytes());
+            request.extend(LOCAL_CID.to_le_bytes());
+            self.l2cap(
+                CaptureDirection::Outbound,
+                L2CAP_SIGNALING_CID,
+                &request,
+                64,
+            );
+            // Connection response: destination cid is the device's, source cid is ours
+            let mut response = vec![L2CAP_CONNECTION_RESPONSE, 1, 8, 0];
+            response.extend(REMOTE_CID.to_le_bytes());
+            response.extend(LOCAL_CID.to_le_bytes());
+            response.extend([0, 0, 0, 0]);
+            self.l2cap(
+                CaptureDirection::Inbound,
+                L2CAP_SIGNALING_CID,
+                &response,
+                64,
+            );
+        }
+
+        fn rfcomm(
+            &mut self,
+            direction: CaptureDirection,
+            dlci: u8,
+            data: &[u8],
+            with_credits: bool,
+        ) {
+            let cid = match direction {
+                CaptureDirection::Outbound => REMOTE_CID,
+                CaptureDirection::Inbound => LOCAL_CID,
+            };
+            let mut frame = vec![(dlci << 2) | 0b11];
+            if with_credits {
+                frame.push(RFCOMM_UIH | RFCOMM_POLL_FINAL);
+            } else {
+                frame.push(RFCOMM_UIH);
+            }
+            if data.len() < 128 {
+                frame.push(((data.len() as u8) << 1) | 1);
+            } else {
+                frame.push((data.len() as u8) << 1);
+                frame.push((data.len() >> 7) as u8);
+            }
+            if with_credits {
+                frame.push(5);
+            }
+            frame.extend(data);
+            frame.push(0); // frame check sequence
+            self.l2cap(direction, cid, &frame, 27);
+        }
+    }
+
+    fn request_state() -> packet::Outbound {
+        packet::Outbound::new(Command([0x01, 0x01]), Vec::new())
+    }
+
+    fn state_update() -> packet::Inbound {
+        packet::Inbound::new(Command([0x01, 0x01]), (0..150).collect())
+    }
+
+    fn build_log(checksum_kind: ChecksumKind) -> Vec<u8> {
+        let mut log = LogBuilder::new();
+        log.connection_complete();
+        log.open_rfcomm_channel();
+        // Something other than soundcore packets on another channel
+        log.rfcomm(CaptureDirection::Outbound, 4, &[0x08, 0xee, 1, 2, 3], false);
+        log.rfcomm(
+            CaptureDirection::Outbound,
+            SOUNDCORE_DLCI,
+            &request_state().bytes(checksum_kind),
+            true,
+        );
+        // Large enough to be split into multiple ACL fragments, and also split across RFCOMM frames
+        let state_update = state_update().bytes(checksum_kind);
+        let (first, second) = state_update.split_at(100);
+        log.rfcomm(CaptureDirection::Inbound, SOUNDCORE_DLCI, first, false);
+        log.rfcomm(CaptureDirection::Inbound, SOUNDCORE_DLCI, second, true);
+        log.bytes
+    }
+
+    #[test]
+    fn extracts_soundcore_packets() {
+        let log = BtsnoopLog::from_bytes(&build_log(ChecksumKind::Suffix)).unwrap();
+        assert_eq!(Some(&MAC_ADDRESS), log.mac_addresses.get(&HANDLE));
+
+        let packets = log.soundcore_packets(ChecksumKind::Suffix);
+        assert_eq!(
+            vec![
+                SoundcorePacket::Outbound(request_state()),
+                SoundcorePacket::Inbound(state_update()),
+            ],
+            packets
+                .iter()
+                .map(|packet| packet.packet.clone())
+                .collect::<Vec<_>>(),
+        );
+        assert!(
+            packets[0].timestamp_micros < packets[1].timestamp_micros,
+            "timestamps should increase: {packets:?}",
+        );
+        assert!(packets[0].timestamp_micros >= 1_700_000_000_000_000);
+    }
+
+    #[test]
+    fn extracts_soundcore_packets_without_checksum() {
+        let log = BtsnoopLog::from_bytes(&build_log(ChecksumKind::None)).unwrap();
+        assert_eq!(
+            vec![
+                SoundcorePacket::Outbound(request_state()),
+                SoundcorePacket::Inbound(state_update()),
+            ],
+            log.soundcore_packets(ChecksumKind::None)
+                .into_iter()
+                .map(|packet| packet.packet)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn converts_to_capture() {
+        let log = BtsnoopLog::from_bytes(&build_log(ChecksumKind::Suffix)).unwrap();
+        let capture = log.to_capture(ChecksumKind::Suffix).unwrap();
+        assert_eq!(MAC_ADDRESS, capture.header.mac_address);
+        assert_eq!(
+            vec![
+                CaptureDirection::Outbound,
+                CaptureDirection::Inbound,
+                CaptureDirection::Inbound,
+            ],
+            capture
+                .entries
+                .iter()
+                .map(|entry| entry.direction)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(0, capture.entries[0].elapsed_micros);
+        assert_eq!(
+            state_update().bytes(ChecksumKind::Suffix),
+            capture.entries[1..]
+                .iter()
+                .flat_map(|entry| entry.data.iter().copied())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn guesses_channels_when_connection_was_not_logged() {
+        let mut log = LogBuilder::new();
+        log.rfcomm(
+            CaptureDirection::Outbound,
+            SOUNDCORE_DLCI,
+            &request_state().bytes(ChecksumKind::Suffix),
+            false,
+        );
+        let log = BtsnoopLog::from_bytes(&log.bytes).unwrap();
+        assert_eq!(1, log.soundcore_packets(ChecksumKind::Suffix).len());
+    }
+
+    #[test]
+    fn rejects_timestamp_before_year_zero() {
+        let mut log = LogBuilder::new();
+        log.connection_complete();
+        let timestamp_start = FILE_HEADER_LENGTH + 16;
+        log.bytes[timestamp_start..timestamp_start + 8].copy_from_slice(&i64::MIN.to_be_bytes());
+        assert!(matches!(
+            BtsnoopLog::from_bytes(&log.bytes),
+            Err(Error::InvalidTimestamp {
+                timestamp: i64::MIN
+            }),
+        ));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(matches!(
+            BtsnoopLog::from_bytes(b"not a btsnoop log"),
+            Err(Error::InvalidHeader),
+        ));
+    }
+}
@@ -0,0 +1,333 @@
+//! Recording and replaying of raw RFCOMM traffic.
+//!
+//! A capture is stored as JSON lines. The first line is a [`CaptureHeader`], and every following line is a
+//! [`CaptureEntry`] containing a single chunk of bytes as it was written to or read from the connection.
+mod recording;
+mod replay;
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    panic::Location,
+    path::Path,
+};
+
+use macaddr::MacAddr6;
+use serde::{Deserialize, Serialize};
+
+use crate::macros::impl_from_source_error_with_location;
+
+pub use recording::*;
+pub use replay::*;
+
+pub const CAPTURE_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("io error")]
+    IOError {
+        source: std::io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("failed to parse line {line_number}")]
+    JsonError {
+        line_number: usize,
+        source: serde_json::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("capture is empty")]
+    MissingHeader,
+    #[error("unsupported capture version {version}")]
+    UnsupportedVersion { version: u32 },
+}
+impl_from_source_error_with_location!(Error::IOError(std::io::Error));
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureHeader {
+    pub version: u32,
+    #[serde(with = "crate::serialization::mac_addr")]
+    pub mac_address: MacAddr6,
+    /// Milliseconds since the unix epoch at the time the connection was established
+    pub started_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureEntry {
+    /// Microseconds since the connection was established
+    pub elapsed_micros: u64,
+    pub direction: CaptureDirection,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureDirection {
+    /// Device to host
+    Inbound,
+    /// Host to device
+    Outbound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    pub header: CaptureHeader,
+    pub entries: Vec<CaptureEntry>,
+}
+
+impl Capture {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()));
+
+        let (line_number, header_line) = lines.next().ok_or(Error::MissingHeader)?;
+        let header: CaptureHeader = parse_line(line_number, &header_line?)?;
+        if header.version != CAPTURE_VERSION {
+            return Err(Error::UnsupportedVersion {
+                version: header.version,
+            });
+        }
+
+        let entries = lines
+            .map(|(line_number, line)| parse_line(line_number, &line?))
+            .collect::<Result<Vec<CaptureEntry>>>()?;
+        Ok(Self { header, entries })
+    }
+}
+
+#[track_caller]
+fn parse_line<T: for<'de> Deserialize<'de>>(line_number: usize, line: &str) -> Result<T> {
+    serde_json::from_str(line).map_err(|source| Error::JsonError {
+        line_number,
+        source,
+        location: Location::caller(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use macaddr::MacAddr6;
+    use tokio::time::Instant;
+
+    use crate::{
+        api::{
+            OpenSCQ30Session,
+            connection::{
+                RfcommBackend, RfcommConnection, RfcommServiceSelectionStrategy,
+                test_stub::StubRfcommConnection,
+            },
+            settings::{SettingId, Value},
+        },
+        devices::DeviceModel,
+        storage::PairedDevice,
+    };
+
+    use super::*;
+
+    const MAC_ADDRESS: MacAddr6 = MacAddr6::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55);
+
+    const REQUEST_STATE_PACKET: &[u8] =
+        &[0x08, 0xee, 0x00, 0x00, 0x00, 0x01, 0x01, 0x0a, 0x00, 0x02];
+
+    fn a3028_state_update_packet() -> Vec<u8> {
+        vec![
+            0x09, 0xff, 0x00, 0x00, 0x01, 0x01, 0x01, 0x46, 0x00, 0x05, 0x00, 0xfe, 0xfe, 0x3c,
+            0xb4, 0x8f, 0xa0, 0x8e, 0xb4, 0x74, 0x88, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x30, 0x32, 0x2e, 0x33, 0x30, 0x33, 0x30, 0x32,
+            0x39, 0x30, 0x38, 0x36, 0x45, 0x43, 0x38, 0x32, 0x46, 0x31, 0x32, 0x41, 0x43, 0x30,
+        ]
+    }
+
+    fn a3028_capture() -> Capture {
+        Capture {
+            header: CaptureHeader {
+                version: CAPTURE_VERSION,
+                mac_address: MAC_ADDRESS,
+                started_at: 0,
+            },
+            entries: vec![
+                CaptureEntry {
+                    elapsed_micros: 1000,
+                    direction: CaptureDirection::Outbound,
+                    data: REQUEST_STATE_PACKET.to_vec(),
+                },
+                CaptureEntry {
+                    elapsed_micros: 51000,
+                    direction: CaptureDirection::Inbound,
+                    data: a3028_state_update_packet(),
+                },
+                CaptureEntry {
+                    elapsed_micros: 2000000,
+                    direction: CaptureDirection::Inbound,
+                    // sound mode changed to noise canceling
+                    data: vec![
+                        0x09, 0xff, 0x00, 0x00, 0x01, 0x06, 0x01, 0x0e, 0x00, 0x00, 0x01, 0x01,
+                        0x00, 0x20,
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parse_capture() {
+        let capture = Capture::from_reader(
+            concat!(
+                r#"{"version":1,"macAddress":"00:11:22:33:44:55","startedAt":5}"#,
+                "\n",
+                r#"{"elapsedMicros":10,"direction":"outbound","data":[1,2,3]}"#,
+                "\n\n",
+                r#"{"elapsedMicros":20,"direction":"inbound","data":[4,5]}"#,
+                "\n",
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            Capture {
+                header: CaptureHeader {
+                    version: 1,
+                    mac_address: MAC_ADDRESS,
+                    started_at: 5,
+                },
+                entries: vec![
+                    CaptureEntry {
+                        elapsed_micros: 10,
+                        direction: CaptureDirection::Outbound,
+                        data: vec![1, 2, 3],
+                    },
+                    CaptureEntry {
+                        elapsed_micros: 20,
+                        direction: CaptureDirection::Inbound,
+                        data: vec![4, 5],
+                    },
+                ],
+            },
+            capture,
+        );
+    }
+
+    #[test]
+    fn parse_capture_errors() {
+        assert!(matches!(
+            Capture::from_reader("".as_bytes()),
+            Err(Error::MissingHeader),
+        ));
+        assert!(matches!(
+            Capture::from_reader(
+                r#"{"version":2,"macAddress":"00:11:22:33:44:55","startedAt":5}"#.as_bytes()
+            ),
+            Err(Error::UnsupportedVersion { version: 2 }),
+        ));
+        assert!(matches!(
+            Capture::from_reader(
+                concat!(
+                    r#"{"version":1,"macAddress":"00:11:22:33:44:55","startedAt":5}"#,
+                    "\n",
+                    "not json",
+                )
+                .as_bytes()
+            ),
+            Err(Error::JsonError { line_number: 2, .. }),
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recording_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("capture.jsonl");
+
+        let (stub, inbound_sender, mut outbound_receiver) = StubRfcommConnection::new();
+        let connection =
+            RecordingRfcommConnection::new(Arc::new(stub), &path, MAC_ADDRESS).unwrap();
+        let mut read_channel = connection.read_channel();
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        connection.write(&[1, 2, 3]).await.unwrap();
+        assert_eq!(Some(vec![1, 2, 3]), outbound_receiver.recv().await);
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        inbound_sender.send(vec![4, 5]).await.unwrap();
+        assert_eq!(Some(vec![4, 5]), read_channel.recv().await);
+        drop(connection);
+
+        let capture = Capture::load(&path).unwrap();
+        assert_eq!(MAC_ADDRESS, capture.header.mac_address);
+        assert_eq!(
+            vec![
+                CaptureEntry {
+                    elapsed_micros: 1000,
+                    direction: CaptureDirection::Outbound,
+                    data: vec![1, 2, 3],
+                },
+                CaptureEntry {
+                    elapsed_micros: 2000,
+                    direction: CaptureDirection::Inbound,
+                    data: vec![4, 5],
+                },
+            ],
+            capture.entries,
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_only_responds_to_recorded_packets() {
+        let backend = ReplayRfcommBackend::new(Arc::new(a3028_capture()));
+        let connection = backend
+            .connect(
+                MAC_ADDRESS,
+                RfcommServiceSelectionStrategy::Constant(uuid::Uuid::nil()),
+            )
+            .await
+            .unwrap();
+        let mut read_channel = connection.read_channel();
+
+        connection.write(&[1, 2, 3]).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        read_channel.try_recv().unwrap_err();
+
+        let start = Instant::now();
+        connection.write(REQUEST_STATE_PACKET).await.unwrap();
+        assert_eq!(Some(a3028_state_update_packet()), read_channel.recv().await);
+        assert_eq!(Duration::from_millis(50), start.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_with_session() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        session
+            .pair(PairedDevice {
+                mac_address: MAC_ADDRESS,
+                model: DeviceModel::SoundcoreA3028,
+                is_demo: false,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        let device = session
+            .connect_with_backends(&ReplayConnectionBackends::new(a3028_capture()), MAC_ADDRESS)
+            .await
+            .unwrap();
+
+        // the state update packet says normal, but that is followed by a sound mode change
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert_eq!(
+            "NoiseCanceling",
+            Value::from(device.setting(&SettingId::AmbientSoundMode).unwrap())
+                .try_as_str()
+                .unwrap()
+        );
+    }
+}
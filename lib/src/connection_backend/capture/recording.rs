@@ -0,0 +1,221 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{LineWriter, Write},
+    panic::Location,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+use tokio::{
+    sync::{mpsc, watch},
+    time::Instant,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::connection::{
+        self, BluetoothAdapter, ConnectionDescriptor, ConnectionStatus, RfcommBackend,
+        RfcommConnection, RfcommServiceSelectionStrategy,
+    },
+    connection_backend::ConnectionBackends,
+};
+
+use super::{CAPTURE_VERSION, CaptureDirection, CaptureEntry, CaptureHeader};
+
+/// Wraps another set of backends, recording all RFCOMM traffic to the file at `path`. Since a capture only covers a
+/// single connection, each connection after the first, such as when reconnecting, is recorded to a new numbered file
+/// next to `path` instead. For example, `capture.jsonl` is followed by `capture.1.jsonl`, `capture.2.jsonl`, etc.
+pub struct RecordingConnectionBackends<B> {
+    inner: B,
+    path: PathBuf,
+    connections: Arc<AtomicUsize>,
+}
+
+impl<B> RecordingConnectionBackends<B> {
+    pub fn new(inner: B, path: PathBuf) -> Self {
+        Self {
+            inner,
+            path,
+            connections: Arc::default(),
+        }
+    }
+}
+
+impl<B: ConnectionBackends + Sync> ConnectionBackends for RecordingConnectionBackends<B> {
+    type Rfcomm = RecordingRfcommBackend<B::Rfcomm>;
+    // Captures only hold RFCOMM traffic
+    type Gatt = B::Gatt;
+
+    async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
+        Ok(RecordingRfcommBackend {
+            inner: self.inner.rfcomm().await?,
+            path: self.path.clone(),
+            connections: self.connections.clone(),
+        })
+    }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        self.inner.gatt().await
+    }
+
+    async fn adapters(&self) -> connection::Result<Vec<BluetoothAdapter>> {
+        self.inner.adapters().await
+    }
+}
+
+pub struct RecordingRfcommBackend<R> {
+    inner: R,
+    path: PathBuf,
+    connections: Arc<AtomicUsize>,
+}
+
+impl<R> RecordingRfcommBackend<R> {
+    pub fn new(inner: R, path: PathBuf) -> Self {
+        Self {
+            inner,
+            path,
+            connections: Arc::default(),
+        }
+    }
+
+    /// `path` for the first connection, and `path` with the connection number inserted before the extension for
+    /// following connections.
+    fn next_path(&self) -> PathBuf {
+        let connection_number = self.connections.fetch_add(1, Ordering::Relaxed);
+        if connection_number == 0 {
+            return self.path.clone();
+        }
+        let mut file_name = self.path.file_stem().unwrap_or_default().to_owned();
+        file_name.push(format!(".{connection_number}"));
+        if let Some(extension) = self.path.extension() {
+            file_name.push(".");
+            file_name.push(extension);
+        }
+        self.path.with_file_name(file_name)
+    }
+}
+
+#[async_trait]
+impl<R: RfcommBackend + Send + Sync> RfcommBackend for RecordingRfcommBackend<R> {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        self.inner.devices().await
+    }
+
+    async fn service_uuids(&self, mac_address: MacAddr6) -> connection::Result<HashSet<Uuid>> {
+        self.inner.service_uuids(mac_address).await
+    }
+
+    async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        service_selection_strategy: RfcommServiceSelectionStrategy,
+    ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
+        let connection = self
+            .inner
+            .connect(mac_address, service_selection_strategy)
+            .await?;
+        let recording_connection =
+            RecordingRfcommConnection::new(connection, &self.next_path(), mac_address).map_err(
+                |err| connection::Error::Other {
+                    source: Box::new(err),
+                    location: Location::caller(),
+                },
+            )?;
+        Ok(Arc::new(recording_connection))
+    }
+}
+
+/// Passes everything through to the inner connection, writing a [`CaptureEntry`] for every chunk of data sent or
+/// received.
+pub struct RecordingRfcommConnection {
+    inner: Arc<dyn RfcommConnection + Send + Sync>,
+    writer: Arc<CaptureWriter>,
+}
+
+impl RecordingRfcommConnection {
+    pub fn new(
+        inner: Arc<dyn RfcommConnection + Send + Sync>,
+        path: &Path,
+        mac_address: MacAddr6,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            inner,
+            writer: Arc::new(CaptureWriter::new(path, mac_address)?),
+        })
+    }
+}
+
+#[async_trait]
+impl RfcommConnection for RecordingRfcommConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        self.inner.write(data).await?;
+        self.writer.record(CaptureDirection::Outbound, data);
+        Ok(())
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        let (sender, receiver) = mpsc::channel(100);
+        let mut inner_receiver = self.inner.read_channel();
+        let writer = self.writer.clone();
+        tokio::spawn(async move {
+            while let Some(data) = inner_receiver.recv().await {
+                writer.record(CaptureDirection::Inbound, &data);
+                if sender.send(data).await.is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+}
+
+struct CaptureWriter {
+    file: Mutex<LineWriter<File>>,
+    started_at: Instant,
+}
+
+impl CaptureWriter {
+    fn new(path: &Path, mac_address: MacAddr6) -> std::io::Result<Self> {
+        let mut file = LineWriter::new(File::create(path)?);
+        let header = CaptureHeader {
+            version: CAPTURE_VERSION,
+            mac_address,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        };
+        serde_json::to_writer(&mut file, &header)?;
+        file.write_all(b"\n")?;
+        Ok(Self {
+            file: Mutex::new(file),
+            started_at: Instant::now(),
+        })
+    }
+
+    fn record(&self, direction: CaptureDirection, data: &[u8]) {
+        let entry = CaptureEntry {
+            elapsed_micros: self.started_at.elapsed().as_micros() as u64,
+            direction,
+            data: data.to_owned(),
+        };
+        let mut file = self.file.lock().unwrap();
+        let result = serde_json::to_writer(&mut *file, &entry)
+            .map_err(std::io::Error::from)
+            .and_then(|()| file.write_all(b"\n"));
+        if let Err(err) = result {
+            tracing::warn!("failed to write capture entry: {err:?}");
+        }
+    }
+}
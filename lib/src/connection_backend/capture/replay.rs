@@ -0,0 +1,177 @@
+use std::{
+    collections::HashSet,
+    panic::Location,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+use tokio::sync::{mpsc, watch};
+
+use crate::{
+    api::connection::{
+        self, ConnectionDescriptor, ConnectionStatus, RfcommBackend, RfcommConnection,
+        RfcommServiceSelectionStrategy,
+    },
+    connection_backend::{ConnectionBackends, unsupported::UnsupportedGattBackend},
+    util::AbortOnDropHandle,
+};
+
+use super::{Capture, CaptureDirection};
+
+/// Serves a single recorded [`Capture`] in place of a real device.
+pub struct ReplayConnectionBackends {
+    capture: Arc<Capture>,
+}
+
+impl ReplayConnectionBackends {
+    pub fn new(capture: Capture) -> Self {
+        Self {
+            capture: Arc::new(capture),
+        }
+    }
+}
+
+impl ConnectionBackends for ReplayConnectionBackends {
+    type Rfcomm = ReplayRfcommBackend;
+    type Gatt = UnsupportedGattBackend;
+
+    async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
+        Ok(ReplayRfcommBackend::new(self.capture.clone()))
+    }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        Ok(UnsupportedGattBackend)
+    }
+}
+
+pub struct ReplayRfcommBackend {
+    capture: Arc<Capture>,
+}
+
+impl ReplayRfcommBackend {
+    pub fn new(capture: Arc<Capture>) -> Self {
+        Self { capture }
+    }
+}
+
+#[async_trait]
+impl RfcommBackend for ReplayRfcommBackend {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        Ok(HashSet::from([ConnectionDescriptor {
+            name: "Replay".to_owned(),
+            mac_address: self.capture.header.mac_address,
+        }]))
+    }
+
+    async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        _service_selection_strategy: RfcommServiceSelectionStrategy,
+    ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
+        if mac_address != self.capture.header.mac_address {
+            return Err(connection::Error::DeviceNotFound {
+                source: None,
+                location: Location::caller(),
+            });
+        }
+        Ok(Arc::new(ReplayRfcommConnection::new(self.capture.clone())))
+    }
+}
+
+/// Inbound packets are played back with their original timing relative to the outbound packet that preceded them in
+/// the capture. Playback of each group of inbound packets only begins once the matching outbound packet is written.
+pub struct ReplayRfcommConnection {
+    capture: Arc<Capture>,
+    position: Mutex<usize>,
+    playback_sender: mpsc::UnboundedSender<(Duration, Vec<u8>)>,
+    packet_receiver: Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
+    connection_status_sender: watch::Sender<ConnectionStatus>,
+    _playback_handle: AbortOnDropHandle<()>,
+}
+
+impl ReplayRfcommConnection {
+    pub fn new(capture: Arc<Capture>) -> Self {
+        let (playback_sender, mut playback_receiver) =
+            mpsc::unbounded_channel::<(Duration, Vec<u8>)>();
+        let (packet_sender, packet_receiver) = mpsc::channel(100);
+        let playback_handle = AbortOnDropHandle::new(tokio::spawn(async move {
+            while let Some((delay, data)) = playback_receiver.recv().await {
+                tokio::time::sleep(delay).await;
+                if packet_sender.send(data).await.is_err() {
+                    break;
+                }
+            }
+        }));
+
+        let connection = Self {
+            capture,
+            position: Mutex::new(0),
+            playback_sender,
+            packet_receiver: Mutex::new(Some(packet_receiver)),
+            connection_status_sender: watch::channel(ConnectionStatus::Connected).0,
+            _playback_handle: playback_handle,
+        };
+        // Anything received before the first outbound packet doesn't need to wait for us
+        *connection.position.lock().unwrap() = connection.queue_inbound(0, 0);
+        connection
+    }
+
+    /// Queues all inbound entries starting at `start_index` up until the next outbound entry, returning the index of
+    /// that outbound entry.
+    fn queue_inbound(&self, start_index: usize, mut previous_elapsed_micros: u64) -> usize {
+        let entries = &self.capture.entries;
+        let mut index = start_index;
+        while let Some(entry) = entries
+            .get(index)
+            .filter(|entry| entry.direction == CaptureDirection::Inbound)
+        {
+            let delay =
+                Duration::from_micros(entry.elapsed_micros.saturating_sub(previous_elapsed_micros));
+            previous_elapsed_micros = entry.elapsed_micros;
+            // The receiver only goes away when we are dropped
+            let _ = self.playback_sender.send((delay, entry.data.clone()));
+            index += 1;
+        }
+        index
+    }
+}
+
+#[async_trait]
+impl RfcommConnection for ReplayRfcommConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        let mut position = self.position.lock().unwrap();
+        let entries = &self.capture.entries;
+        let Some(outbound_index) = entries
+            .iter()
+            .enumerate()
+            .skip(*position)
+            .find(|(_, entry)| entry.direction == CaptureDirection::Outbound && entry.data == data)
+            .map(|(index, _)| index)
+        else {
+            tracing::warn!("replay: outbound packet not found in remainder of capture: {data:?}");
+            return Ok(());
+        };
+        if outbound_index != *position {
+            tracing::warn!(
+                "replay: skipping {} entries that did not match outbound packet {data:?}",
+                outbound_index - *position,
+            );
+        }
+        *position = self.queue_inbound(outbound_index + 1, entries[outbound_index].elapsed_micros);
+        Ok(())
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        self.packet_receiver
+            .lock()
+            .unwrap()
+            .take()
+            .expect("read_channel should only be called once")
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status_sender.subscribe()
+    }
+}
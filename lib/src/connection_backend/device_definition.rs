@@ -0,0 +1,328 @@
+//! Fake devices described by the TOML files in `tools/soundcore-device-faker/devices`.
+//!
+//! Each file contains a name, mac address, RFCOMM service uuid, and a list of canned responses keyed by command. Any
+//! command without a response is acked, the same as the Python device faker does.
+use std::{
+    collections::{HashMap, HashSet},
+    panic::Location,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+use nom_language::error::VerboseError;
+use serde::Deserialize;
+use tokio::sync::{mpsc, watch};
+use uuid::Uuid;
+
+use crate::{
+    api::connection::{
+        self, ConnectionDescriptor, ConnectionStatus, RfcommBackend, RfcommConnection,
+        RfcommServiceSelectionStrategy,
+    },
+    connection_backend::{ConnectionBackends, unsupported::UnsupportedGattBackend},
+    devices::soundcore::common::packet::{self, ChecksumKind, Command},
+    macros::impl_from_source_error_with_location,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("io error")]
+    IOError {
+        source: std::io::Error,
+        location: &'static Location<'static>,
+    },
+    #[error("failed to parse device definition")]
+    TomlError {
+        source: toml::de::Error,
+        location: &'static Location<'static>,
+    },
+}
+impl_from_source_error_with_location!(Error::IOError(std::io::Error));
+impl_from_source_error_with_location!(Error::TomlError(toml::de::Error));
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceDefinition {
+    pub name: String,
+    #[serde(with = "crate::serialization::mac_addr")]
+    pub mac_address: MacAddr6,
+    pub rfcomm_uuid: Uuid,
+    #[serde(default = "default_has_checksum")]
+    pub has_checksum: bool,
+    #[serde(default)]
+    pub responses: Vec<DeviceDefinitionResponse>,
+}
+
+fn default_has_checksum() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceDefinitionResponse {
+    pub command: [u8; 2],
+    /// Packet body, excluding the header, command, length, and checksum
+    pub response: Vec<u8>,
+}
+
+impl DeviceDefinition {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    fn checksum_kind(&self) -> ChecksumKind {
+        if self.has_checksum {
+            ChecksumKind::Suffix
+        } else {
+            ChecksumKind::None
+        }
+    }
+}
+
+/// Serves a single [`DeviceDefinition`] in place of a real device.
+pub struct DeviceDefinitionConnectionBackends {
+    definition: Arc<DeviceDefinition>,
+}
+
+impl DeviceDefinitionConnectionBackends {
+    pub fn new(definition: Arc<DeviceDefinition>) -> Self {
+        Self { definition }
+    }
+}
+
+impl ConnectionBackends for DeviceDefinitionConnectionBackends {
+    type Rfcomm = DeviceDefinitionRfcommBackend;
+    type Gatt = UnsupportedGattBackend;
+
+    async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
+        Ok(DeviceDefinitionRfcommBackend::new(self.definition.clone()))
+    }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        Ok(UnsupportedGattBackend)
+    }
+}
+
+pub struct DeviceDefinitionRfcommBackend {
+    definition: Arc<DeviceDefinition>,
+}
+
+impl DeviceDefinitionRfcommBackend {
+    pub fn new(definition: Arc<DeviceDefinition>) -> Self {
+        Self { definition }
+    }
+}
+
+#[async_trait]
+impl RfcommBackend for DeviceDefinitionRfcommBackend {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        Ok(HashSet::from([ConnectionDescriptor {
+            name: self.definition.name.clone(),
+            mac_address: self.definition.mac_address,
+        }]))
+    }
+
+    async fn service_uuids(&self, mac_address: MacAddr6) -> connection::Result<HashSet<Uuid>> {
+        if mac_address != self.definition.mac_address {
+            return Err(connection::Error::DeviceNotFound {
+                source: None,
+                location: Location::caller(),
+            });
+        }
+        Ok(HashSet::from([self.definition.rfcomm_uuid]))
+    }
+
+    async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        service_selection_strategy: RfcommServiceSelectionStrategy,
+    ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
+        if mac_address != self.definition.mac_address {
+            return Err(connection::Error::DeviceNotFound {
+                source: None,
+                location: Location::caller(),
+            });
+        }
+        // A real device only offers the one service, so don't let a mismatch go unnoticed
+        let uuid = match service_selection_strategy {
+            RfcommServiceSelectionStrategy::Constant(uuid) => uuid,
+            RfcommServiceSelectionStrategy::Dynamic(select_uuid) => {
+                select_uuid(HashSet::from([self.definition.rfcomm_uuid]))
+            }
+        };
+        if uuid != self.definition.rfcomm_uuid {
+            tracing::warn!(
+                "requested RFCOMM service {uuid}, but the device definition only has {}",
+                self.definition.rfcomm_uuid,
+            );
+            return Err(connection::Error::DeviceNotFound {
+                source: None,
+                location: Location::caller(),
+            });
+        }
+        Ok(Arc::new(DeviceDefinitionRfcommConnection::new(
+            &self.definition,
+        )))
+    }
+}
+
+pub struct DeviceDefinitionRfcommConnection {
+    responses: HashMap<Command, Vec<u8>>,
+    checksum_kind: ChecksumKind,
+    packet_sender: mpsc::Sender<Vec<u8>>,
+    packet_receiver: Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
+    connection_status_sender: watch::Sender<ConnectionStatus>,
+}
+
+impl DeviceDefinitionRfcommConnection {
+    pub fn new(definition: &DeviceDefinition) -> Self {
+        let (packet_sender, packet_receiver) = mpsc::channel(100);
+        Self {
+            responses: definition
+                .responses
+                .iter()
+                .map(|entry| (Command(entry.command), entry.response.clone()))
+                .collect(),
+            checksum_kind: definition.checksum_kind(),
+            packet_sender,
+            packet_receiver: Mutex::new(Some(packet_receiver)),
+            connection_status_sender: watch::channel(ConnectionStatus::Connected).0,
+        }
+    }
+}
+
+#[async_trait]
+impl RfcommConnection for DeviceDefinitionRfcommConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        let Ok((_remainder, packet)) =
+            packet::Outbound::take::<VerboseError<_>>(self.checksum_kind)(data)
+        else {
+            tracing::warn!("device definition: ignoring unparsable packet {data:?}");
+            return Ok(());
+        };
+        let response = match self.responses.get(&packet.command) {
+            Some(body) => packet::Inbound::new(packet.command, body.clone()),
+            None => {
+                tracing::debug!(
+                    "device definition: no response for {:?}, sending ack",
+                    packet.command,
+                );
+                packet.ack()
+            }
+        };
+        // The receiver only goes away when we are dropped
+        let _ = self
+            .packet_sender
+            .send(response.bytes(self.checksum_kind))
+            .await;
+        Ok(())
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        self.packet_receiver
+            .lock()
+            .unwrap()
+            .take()
+            .expect("read_channel should only be called once")
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status_sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        api::{
+            OpenSCQ30Session,
+            settings::{SettingId, Value},
+        },
+        devices::DeviceModel,
+        storage::PairedDevice,
+    };
+
+    use super::*;
+
+    fn devices_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tools/soundcore-device-faker/devices")
+    }
+
+    #[test]
+    fn parses_all_device_definitions() {
+        let mut count = 0;
+        for entry in std::fs::read_dir(devices_dir()).unwrap() {
+            let path = entry.unwrap().path();
+            DeviceDefinition::load(&path)
+                .unwrap_or_else(|err| panic!("{}: {err:?}", path.display()));
+            count += 1;
+        }
+        assert_ne!(0, count);
+    }
+
+    #[tokio::test]
+    async fn serves_responses() {
+        let definition = DeviceDefinition::load(&devices_dir().join("a3028.toml")).unwrap();
+        let mac_address = definition.mac_address;
+        let session = OpenSCQ30Session::new_with_in_memory_db()
+            .await
+            .unwrap()
+            .with_device_definition(definition);
+        session
+            .pair(PairedDevice {
+                mac_address,
+                model: DeviceModel::SoundcoreA3028,
+                is_demo: false,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![ConnectionDescriptor {
+                name: "Soundcore Life Q30".to_owned(),
+                mac_address,
+            }],
+            session
+                .list_devices(DeviceModel::SoundcoreA3028)
+                .await
+                .unwrap(),
+        );
+
+        let device = session.connect(mac_address).await.unwrap();
+        assert_eq!(
+            Value::from(vec![0i16, 30, 30, 20, 40, 50, 30, 40]),
+            Value::from(device.setting(&SettingId::VolumeAdjustments).unwrap()),
+        );
+        // unknown commands are acked, so setting values works too
+        device
+            .set_setting_values(vec![(
+                SettingId::AmbientSoundMode,
+                Value::from("Transparency"),
+            )])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_other_mac_addresses() {
+        let definition = DeviceDefinition::load(&devices_dir().join("a3028.toml")).unwrap();
+        let backend = DeviceDefinitionRfcommBackend::new(Arc::new(definition));
+        let result = backend
+            .connect(
+                MacAddr6::new(0, 1, 2, 3, 4, 5),
+                RfcommServiceSelectionStrategy::Dynamic(|uuids| uuids.into_iter().next().unwrap()),
+            )
+            .await;
+        assert!(
+            matches!(result, Err(connection::Error::DeviceNotFound { .. })),
+            "should not find device",
+        );
+    }
+}
@@ -1,14 +1,45 @@
+mod gatt;
 mod rfcomm;
+mod utils;
 
-use crate::{api::connection, connection_backend::ConnectionBackends};
+use bluer::Session;
+
+use crate::{
+    api::connection::{self, BluetoothAdapter},
+    connection_backend::ConnectionBackends,
+};
 
 #[derive(Default)]
-pub struct PlatformConnectionBackends;
+pub struct PlatformConnectionBackends {
+    adapter: Option<String>,
+}
+
+impl PlatformConnectionBackends {
+    /// `adapter` is the name (such as hci0) or address of the bluetooth adapter to use. If `None`, devices connected
+    /// through any adapter are available.
+    pub fn new(adapter: Option<String>) -> Self {
+        Self { adapter }
+    }
+}
 
 impl ConnectionBackends for PlatformConnectionBackends {
     type Rfcomm = rfcomm::BluerRfcommBackend;
+    type Gatt = gatt::BluerGattBackend;
 
     async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
-        rfcomm::BluerRfcommBackend::new().await
+        rfcomm::BluerRfcommBackend::new(self.adapter.clone()).await
+    }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        gatt::BluerGattBackend::new(self.adapter.clone()).await
+    }
+
+    async fn adapters(&self) -> connection::Result<Vec<BluetoothAdapter>> {
+        let session = Session::new().await?;
+        let mut adapters = Vec::new();
+        for adapter in utils::adapters(&session, None).await? {
+            adapters.push(utils::describe_adapter(&adapter).await?);
+        }
+        Ok(adapters)
     }
 }
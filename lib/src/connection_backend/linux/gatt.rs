@@ -0,0 +1,180 @@
+use std::{collections::HashSet, panic::Location, sync::Arc};
+
+use async_trait::async_trait;
+use bluer::{
+    Device, Session,
+    gatt::remote::{Characteristic, Service},
+};
+use futures::{Stream, StreamExt};
+use macaddr::MacAddr6;
+use tokio::sync::{mpsc, watch};
+use tracing::{Instrument, debug, instrument, trace, trace_span};
+use uuid::Uuid;
+
+use crate::{
+    api::connection::{
+        self, ConnectionDescriptor, ConnectionStatus, GattBackend, GattConnection, GattService,
+    },
+    util::AbortOnDropHandle,
+};
+
+use super::utils;
+
+pub struct BluerGattBackend {
+    session: Session,
+    adapter: Option<String>,
+}
+
+impl BluerGattBackend {
+    /// `adapter` limits devices to those connected through the adapter with that name or address.
+    pub async fn new(adapter: Option<String>) -> connection::Result<Self> {
+        Ok(Self {
+            session: Session::new().await?,
+            adapter,
+        })
+    }
+
+    async fn find_service(device: &Device, uuid: Uuid) -> connection::Result<Service> {
+        for service in device.services().await? {
+            if service.uuid().await? == uuid {
+                return Ok(service);
+            }
+        }
+        debug!("GATT service {uuid} not found");
+        Err(connection::Error::DeviceNotFound {
+            source: None,
+            location: Location::caller(),
+        })
+    }
+
+    async fn find_characteristic(
+        service: &Service,
+        uuid: Uuid,
+    ) -> connection::Result<Characteristic> {
+        for characteristic in service.characteristics().await? {
+            if characteristic.uuid().await? == uuid {
+                return Ok(characteristic);
+            }
+        }
+        debug!("GATT characteristic {uuid} not found");
+        Err(connection::Error::DeviceNotFound {
+            source: None,
+            location: Location::caller(),
+        })
+    }
+}
+
+#[async_trait]
+impl GattBackend for BluerGattBackend {
+    #[instrument(skip(self))]
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        utils::connected_devices(&self.session, self.adapter.as_deref()).await
+    }
+
+    #[instrument(skip(self))]
+    async fn connect(
+        &self,
+        mac_address: MacAddr6,
+        service: GattService,
+    ) -> connection::Result<Arc<dyn GattConnection + Send + Sync>> {
+        let device = utils::device(&self.session, self.adapter.as_deref(), mac_address).await?;
+        debug!("connecting to device");
+        if let Err(err) = device.connect().await {
+            // Connect can fail with br-connection-busy even when already connected
+            debug!("connect failed, continuing anyway: {err:?}");
+        };
+
+        let gatt_service = Self::find_service(&device, service.service).await?;
+        let write_characteristic =
+            Self::find_characteristic(&gatt_service, service.write_characteristic).await?;
+        let notify_characteristic =
+            Self::find_characteristic(&gatt_service, service.notify_characteristic).await?;
+        let notifications = notify_characteristic.notify().await?;
+        debug!("connected");
+
+        let connection =
+            BluerGattConnection::new(device, write_characteristic, notifications).await?;
+        Ok(Arc::new(connection))
+    }
+}
+
+pub struct BluerGattConnection {
+    write_characteristic: Characteristic,
+    inbound_packet_stream: std::sync::Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
+    _inbound_packet_handle: AbortOnDropHandle<()>,
+    connection_status_receiver: watch::Receiver<ConnectionStatus>,
+    _connection_status_handle: AbortOnDropHandle<()>,
+    // We need to not drop device in order for the device.events() stream in spawn_connection_status to not terminate
+    _device: Device,
+}
+
+impl BluerGattConnection {
+    pub async fn new(
+        device: Device,
+        write_characteristic: Characteristic,
+        notifications: impl Stream<Item = Vec<u8>> + Send + 'static,
+    ) -> connection::Result<Self> {
+        // AbortOnDropHandle used for all join handles to ensure cancel safety
+        let (connection_status_receiver, connection_status_handle) =
+            utils::spawn_connection_status(device.to_owned()).await?;
+        let (inbound_packet_stream, inbound_packet_handle) =
+            Self::spawn_inbound_packet_channel(notifications);
+
+        Ok(Self {
+            write_characteristic,
+            inbound_packet_stream: std::sync::Mutex::new(Some(inbound_packet_stream)),
+            _inbound_packet_handle: inbound_packet_handle,
+            connection_status_receiver,
+            _connection_status_handle: connection_status_handle,
+            _device: device,
+        })
+    }
+
+    fn spawn_inbound_packet_channel(
+        notifications: impl Stream<Item = Vec<u8>> + Send + 'static,
+    ) -> (mpsc::Receiver<Vec<u8>>, AbortOnDropHandle<()>) {
+        // This queue should always be really small unless something is malfunctioning
+        let (sender, receiver) = mpsc::channel(100);
+        let abort_handle = AbortOnDropHandle::new(tokio::spawn(
+            async move {
+                let mut notifications = std::pin::pin!(notifications);
+                while let Some(bytes) = notifications.next().await {
+                    trace!(event = "gatt notification", ?bytes);
+                    if !bytes.is_empty() && sender.send(bytes).await.is_err() {
+                        // receiver closed
+                        break;
+                    }
+                }
+                debug!("notification stream ended");
+            }
+            .instrument(trace_span!("bluer_gatt_connection inbound_packets_channel reader")),
+        ));
+
+        (receiver, abort_handle)
+    }
+}
+
+#[async_trait]
+impl GattConnection for BluerGattConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        self.write_characteristic
+            .write(data)
+            .await
+            .map_err(|err| connection::Error::WriteError {
+                source: Some(Box::new(err)),
+                location: Location::caller(),
+            })
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        self.inbound_packet_stream
+            .lock()
+            .unwrap()
+            .take()
+            .expect("inbound_packets_channel should only be called once")
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status_receiver.clone()
+    }
+}
@@ -7,7 +7,7 @@ use std::{
 
 use async_trait::async_trait;
 use bluer::{
-    Adapter, Device, DeviceProperty, Session,
+    Device, Session,
     rfcomm::{
         Profile, ReqError, Role, Stream,
         stream::{OwnedReadHalf, OwnedWriteHalf},
@@ -24,7 +24,8 @@ use tokio::{
         watch,
     },
 };
-use tracing::{Instrument, debug, debug_span, instrument, trace, trace_span, warn};
+use tracing::{Instrument, debug, instrument, trace, trace_span, warn};
+use uuid::Uuid;
 
 use crate::{
     api::connection::{
@@ -34,106 +35,34 @@ use crate::{
     util::AbortOnDropHandle,
 };
 
+use super::utils;
+
 pub struct BluerRfcommBackend {
     session: Session,
+    adapter: Option<String>,
 }
 
 impl BluerRfcommBackend {
-    pub async fn new() -> connection::Result<Self> {
+    /// `adapter` limits devices to those connected through the adapter with that name or address.
+    pub async fn new(adapter: Option<String>) -> connection::Result<Self> {
         Ok(Self {
             session: Session::new().await?,
+            adapter,
         })
     }
-
-    async fn adapters(&self) -> connection::Result<Vec<Adapter>> {
-        let adapter_names = self.session.adapter_names().await.map_err(|err| {
-            warn!("failed to get bluetooth adapter names");
-            connection::Error::BluetoothAdapterUnavailable {
-                source: Some(Box::new(err)),
-                location: Location::caller(),
-            }
-        })?;
-
-        let adapters = adapter_names
-            .into_iter()
-            .filter_map(|adapter_name| match self.session.adapter(&adapter_name) {
-                Ok(adapter) => Some(adapter),
-                Err(err) => {
-                    warn!("bluetooth adapter {adapter_name} unavailable: {err:?}");
-                    None
-                }
-            })
-            .collect::<Vec<Adapter>>();
-
-        if adapters.is_empty() {
-            tracing::warn!("No bluetooth adapters found");
-            return Err(connection::Error::BluetoothAdapterUnavailable {
-                source: None,
-                location: Location::caller(),
-            });
-        }
-
-        Ok(adapters)
-    }
-
-    async fn device(&self, mac_address: MacAddr6) -> connection::Result<Device> {
-        for adapter in self.adapters().await? {
-            match Self::device_from_adapter(&adapter, mac_address).await {
-                Ok(Some(device)) => return Ok(device),
-                Ok(None) => (),
-                // Keep going on error in case another adapter has the device
-                Err(err) => tracing::error!(
-                    "failed to find device {mac_address} on bluetoooth adapter {adapter:?}: {err:?}"
-                ),
-            }
-        }
-        Err(connection::Error::DeviceNotFound {
-            source: None,
-            location: Location::caller(),
-        })
-    }
-
-    async fn device_from_adapter(
-        adapter: &Adapter,
-        mac_address: MacAddr6,
-    ) -> bluer::Result<Option<Device>> {
-        match adapter.device(mac_address.into()) {
-            Ok(device) => device
-                .is_connected()
-                .await
-                .map(|is_connected| is_connected.then_some(device)),
-            Err(err) => Err(err),
-        }
-    }
-
-    async fn add_devices_from_adapter(
-        adapter: &Adapter,
-        connection_descriptors: &mut HashSet<ConnectionDescriptor>,
-    ) -> connection::Result<()> {
-        let device_addresses = adapter.device_addresses().await?;
-        for address in device_addresses {
-            let device = adapter.device(address)?;
-            if device.is_connected().await? {
-                connection_descriptors.insert(ConnectionDescriptor {
-                    name: device.name().await?.unwrap_or_default(),
-                    mac_address: address.into(),
-                });
-            }
-        }
-        Ok(())
-    }
 }
 
 #[async_trait]
 impl RfcommBackend for BluerRfcommBackend {
     #[instrument(skip(self))]
     async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
-        let mut connection_descriptors = HashSet::new();
-        for adapter in self.adapters().await? {
-            Self::add_devices_from_adapter(&adapter, &mut connection_descriptors).await?;
-        }
+        utils::connected_devices(&self.session, self.adapter.as_deref()).await
+    }
 
-        Ok(connection_descriptors)
+    #[instrument(skip(self))]
+    async fn service_uuids(&self, mac_address: MacAddr6) -> connection::Result<HashSet<Uuid>> {
+        let device = utils::device(&self.session, self.adapter.as_deref(), mac_address).await?;
+        Ok(device.uuids().await?.unwrap_or_default())
     }
 
     #[instrument(skip(self, service_selection_strategy))]
@@ -142,7 +71,7 @@ impl RfcommBackend for BluerRfcommBackend {
         mac_address: MacAddr6,
         service_selection_strategy: RfcommServiceSelectionStrategy,
     ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
-        let device = self.device(mac_address).await?;
+        let device = utils::device(&self.session, self.adapter.as_deref(), mac_address).await?;
         debug!("connecting to device");
         if let Err(err) = device.connect().await {
             // Connect can fail with br-connection-busy even when already connected
@@ -217,7 +146,7 @@ impl BluerRfcommConnection {
     pub async fn new(device: Device, stream: Stream) -> connection::Result<Self> {
         // AbortOnDropHandle used for all join handles to ensure cancel safety
         let (connection_status_receiver, connection_status_handle) =
-            Self::spawn_connection_status(device.to_owned()).await?;
+            utils::spawn_connection_status(device.to_owned()).await?;
         let (read_stream, write_stream) = stream.into_split();
         let (inbound_packet_stream, inbound_packet_handle) =
             Self::spawn_inbound_packet_channel(read_stream).await;
@@ -233,35 +162,6 @@ impl BluerRfcommConnection {
         Ok(connection)
     }
 
-    async fn spawn_connection_status(
-        device: Device,
-    ) -> connection::Result<(watch::Receiver<ConnectionStatus>, AbortOnDropHandle<()>)> {
-        let (connection_status_sender, connection_status_receiver) =
-            watch::channel(ConnectionStatus::Connected);
-
-        let mut events = device.events().await?;
-        let connection_status_handle = AbortOnDropHandle::new(tokio::spawn(
-            async move {
-                while let Some(event) = events.next().await {
-                    tracing::debug!("got event {event:?}");
-                    if let bluer::DeviceEvent::PropertyChanged(DeviceProperty::Connected(
-                        is_connected,
-                    )) = event
-                    {
-                        connection_status_sender.send_replace(match is_connected {
-                            true => ConnectionStatus::Connected,
-                            false => ConnectionStatus::Disconnected,
-                        });
-                    }
-                }
-                tracing::debug!("event stream ended");
-            }
-            .instrument(debug_span!("spawn_connection_status")),
-        ));
-
-        Ok((connection_status_receiver, connection_status_handle))
-    }
-
     async fn spawn_inbound_packet_channel(
         mut read_stream: OwnedReadHalf,
     ) -> (mpsc::Receiver<Vec<u8>>, AbortOnDropHandle<()>) {
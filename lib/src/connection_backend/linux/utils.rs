@@ -0,0 +1,164 @@
+use std::{collections::HashSet, panic::Location};
+
+use bluer::{Adapter, Device, DeviceProperty, Session};
+use futures::StreamExt;
+use macaddr::MacAddr6;
+use tokio::sync::watch;
+use tracing::{Instrument, debug_span, warn};
+
+use crate::{
+    api::connection::{self, BluetoothAdapter, ConnectionDescriptor, ConnectionStatus},
+    util::AbortOnDropHandle,
+};
+
+/// Lists all adapters, or only the one matching `selected` by name or address if set.
+pub async fn adapters(
+    session: &Session,
+    selected: Option<&str>,
+) -> connection::Result<Vec<Adapter>> {
+    let adapter_names = session.adapter_names().await.map_err(|err| {
+        warn!("failed to get bluetooth adapter names");
+        connection::Error::BluetoothAdapterUnavailable {
+            source: Some(Box::new(err)),
+            location: Location::caller(),
+        }
+    })?;
+
+    let adapters = adapter_names
+        .into_iter()
+        .filter_map(|adapter_name| match session.adapter(&adapter_name) {
+            Ok(adapter) => Some(adapter),
+            Err(err) => {
+                warn!("bluetooth adapter {adapter_name} unavailable: {err:?}");
+                None
+            }
+        })
+        .collect::<Vec<Adapter>>();
+
+    let adapters = match selected {
+        Some(selected) => {
+            let mut matching = Vec::new();
+            for adapter in adapters {
+                if describe_adapter(&adapter).await?.matches(selected) {
+                    matching.push(adapter);
+                }
+            }
+            matching
+        }
+        None => adapters,
+    };
+
+    if adapters.is_empty() {
+        match selected {
+            Some(selected) => tracing::warn!("No bluetooth adapter matching {selected} found"),
+            None => tracing::warn!("No bluetooth adapters found"),
+        }
+        return Err(connection::Error::BluetoothAdapterUnavailable {
+            source: None,
+            location: Location::caller(),
+        });
+    }
+
+    Ok(adapters)
+}
+
+pub async fn describe_adapter(adapter: &Adapter) -> connection::Result<BluetoothAdapter> {
+    Ok(BluetoothAdapter {
+        name: adapter.name().to_owned(),
+        address: adapter.address().await?.into(),
+    })
+}
+
+/// Finds a connected device with the specified mac address on any of the selected adapters.
+pub async fn device(
+    session: &Session,
+    adapter: Option<&str>,
+    mac_address: MacAddr6,
+) -> connection::Result<Device> {
+    for adapter in adapters(session, adapter).await? {
+        match device_from_adapter(&adapter, mac_address).await {
+            Ok(Some(device)) => return Ok(device),
+            Ok(None) => (),
+            // Keep going on error in case another adapter has the device
+            Err(err) => tracing::error!(
+                "failed to find device {mac_address} on bluetoooth adapter {adapter:?}: {err:?}"
+            ),
+        }
+    }
+    Err(connection::Error::DeviceNotFound {
+        source: None,
+        location: Location::caller(),
+    })
+}
+
+async fn device_from_adapter(
+    adapter: &Adapter,
+    mac_address: MacAddr6,
+) -> bluer::Result<Option<Device>> {
+    match adapter.device(mac_address.into()) {
+        Ok(device) => device
+            .is_connected()
+            .await
+            .map(|is_connected| is_connected.then_some(device)),
+        Err(err) => Err(err),
+    }
+}
+
+/// Lists connected devices on all of the selected adapters.
+pub async fn connected_devices(
+    session: &Session,
+    adapter: Option<&str>,
+) -> connection::Result<HashSet<ConnectionDescriptor>> {
+    let mut connection_descriptors = HashSet::new();
+    for adapter in adapters(session, adapter).await? {
+        add_devices_from_adapter(&adapter, &mut connection_descriptors).await?;
+    }
+    Ok(connection_descriptors)
+}
+
+async fn add_devices_from_adapter(
+    adapter: &Adapter,
+    connection_descriptors: &mut HashSet<ConnectionDescriptor>,
+) -> connection::Result<()> {
+    let device_addresses = adapter.device_addresses().await?;
+    for address in device_addresses {
+        let device = adapter.device(address)?;
+        if device.is_connected().await? {
+            connection_descriptors.insert(ConnectionDescriptor {
+                name: device.name().await?.unwrap_or_default(),
+                mac_address: address.into(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `device` must not be dropped while the connection status is still needed, or else the event stream will end.
+pub async fn spawn_connection_status(
+    device: Device,
+) -> connection::Result<(watch::Receiver<ConnectionStatus>, AbortOnDropHandle<()>)> {
+    let (connection_status_sender, connection_status_receiver) =
+        watch::channel(ConnectionStatus::Connected);
+
+    let mut events = device.events().await?;
+    let connection_status_handle = AbortOnDropHandle::new(tokio::spawn(
+        async move {
+            while let Some(event) = events.next().await {
+                tracing::debug!("got event {event:?}");
+                if let bluer::DeviceEvent::PropertyChanged(DeviceProperty::Connected(
+                    is_connected,
+                )) = event
+                {
+                    connection_status_sender.send_replace(match is_connected {
+                        true => ConnectionStatus::Connected,
+                        false => ConnectionStatus::Disconnected,
+                    });
+                }
+            }
+            tracing::debug!("event stream ended");
+        }
+        .instrument(debug_span!("spawn_connection_status")),
+    ));
+
+    Ok((connection_status_receiver, connection_status_handle))
+}
@@ -1 +1,2 @@
+pub mod gatt;
 pub mod rfcomm;
@@ -0,0 +1,93 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+use tokio::sync::{mpsc, watch};
+
+use crate::api::connection::{
+    self, ConnectionDescriptor, ConnectionStatus, GattBackend, GattConnection, GattService,
+};
+
+pub struct MockGattBackend {
+    inbound: Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
+    outbound: Mutex<Option<mpsc::Sender<Vec<u8>>>>,
+}
+
+impl MockGattBackend {
+    pub fn new(inbound: mpsc::Receiver<Vec<u8>>, outbound: mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            inbound: Mutex::new(Some(inbound)),
+            outbound: Mutex::new(Some(outbound)),
+        }
+    }
+}
+
+#[async_trait]
+impl GattBackend for MockGattBackend {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        Ok([ConnectionDescriptor {
+            name: "Mock Device".to_owned(),
+            mac_address: MacAddr6::nil(),
+        }]
+        .into_iter()
+        .collect())
+    }
+
+    async fn connect(
+        &self,
+        _mac_address: MacAddr6,
+        _service: GattService,
+    ) -> connection::Result<Arc<dyn GattConnection + Send + Sync>> {
+        Ok(Arc::new(MockGattConnection::new(
+            self.inbound
+                .lock()
+                .unwrap()
+                .take()
+                .expect("connect should only be called once"),
+            self.outbound
+                .lock()
+                .unwrap()
+                .take()
+                .expect("connect should only be called once"),
+        )))
+    }
+}
+
+pub struct MockGattConnection {
+    inbound: Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
+    outbound: mpsc::Sender<Vec<u8>>,
+    connection_status: watch::Sender<ConnectionStatus>,
+}
+
+impl MockGattConnection {
+    pub fn new(inbound: mpsc::Receiver<Vec<u8>>, outbound: mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            inbound: Mutex::new(Some(inbound)),
+            outbound,
+            connection_status: watch::channel(ConnectionStatus::Connected).0,
+        }
+    }
+}
+
+#[async_trait]
+impl GattConnection for MockGattConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        self.outbound.send(data.to_vec()).await.unwrap();
+        Ok(())
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        self.inbound
+            .lock()
+            .unwrap()
+            .take()
+            .expect("read_channel should only be called once")
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status.subscribe()
+    }
+}
@@ -2,12 +2,16 @@ mod rfcomm;
 
 use crate::api::connection;
 
-use super::ConnectionBackends;
+use super::{ConnectionBackends, unsupported::UnsupportedGattBackend};
 
 pub struct NoneConnectionBackends;
 impl ConnectionBackends for NoneConnectionBackends {
     type Rfcomm = rfcomm::NoneRfcommBackend;
+    type Gatt = UnsupportedGattBackend;
     async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
         unimplemented!()
     }
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        Ok(UnsupportedGattBackend)
+    }
 }
@@ -0,0 +1,27 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+use macaddr::MacAddr6;
+
+use crate::api::connection::{
+    self, ConnectionDescriptor, GattBackend, GattConnection, GattService,
+};
+
+/// For platforms and backends that have no GATT support. No devices are listed, and connecting always fails.
+#[derive(Default)]
+pub struct UnsupportedGattBackend;
+
+#[async_trait]
+impl GattBackend for UnsupportedGattBackend {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        Ok(HashSet::new())
+    }
+
+    async fn connect(
+        &self,
+        _mac_address: MacAddr6,
+        _service: GattService,
+    ) -> connection::Result<Arc<dyn GattConnection + Send + Sync>> {
+        Err(connection::Error::UnsupportedTransport { transport: "GATT" })
+    }
+}
@@ -1,15 +1,23 @@
 mod rfcomm;
 mod utils;
 
-use crate::{api::connection, connection_backend::ConnectionBackends};
+use crate::{
+    api::connection,
+    connection_backend::{ConnectionBackends, unsupported::UnsupportedGattBackend},
+};
 
 #[derive(Default)]
 pub struct PlatformConnectionBackends;
 
 impl ConnectionBackends for PlatformConnectionBackends {
     type Rfcomm = rfcomm::WindowsRfcommBackend;
+    type Gatt = UnsupportedGattBackend;
 
     async fn rfcomm(&self) -> connection::Result<Self::Rfcomm> {
         Ok(rfcomm::WindowsRfcommBackend)
     }
+
+    async fn gatt(&self) -> connection::Result<Self::Gatt> {
+        Ok(UnsupportedGattBackend)
+    }
 }
@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use macaddr::MacAddr6;
 use tokio::sync::{mpsc, watch};
 use tracing::{debug, debug_span, error, instrument, trace, warn};
+use uuid::Uuid;
 use windows::{
     Devices::{
         Bluetooth::{
@@ -62,6 +63,21 @@ impl RfcommBackend for WindowsRfcommBackend {
         .unwrap()
     }
 
+    async fn service_uuids(&self, mac_address: MacAddr6) -> connection::Result<HashSet<Uuid>> {
+        tokio::task::spawn_blocking(move || -> connection::Result<HashSet<Uuid>> {
+            let device = Self::get_bluetooth_device_from_mac_address(mac_address)?;
+            let services = device.GetRfcommServicesAsync()?.join()?.Services()?;
+            let uuids = services
+                .into_iter()
+                .map(|service| Ok(service.ServiceId()?.Uuid()?.as_uuid()))
+                .collect::<windows::core::Result<HashSet<_>>>()?;
+            debug!("found RFCOMM services: {uuids:?}");
+            Ok(uuids)
+        })
+        .await
+        .unwrap()
+    }
+
     async fn connect(
         &self,
         mac_address: MacAddr6,
@@ -1,4 +1,4 @@
-mod soundcore;
+pub mod soundcore;
 
 mod device_model;
 pub use device_model::*;
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use macaddr::MacAddr6;
 use openscq30_i18n_macros::Translate;
@@ -6,9 +6,12 @@ use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumIter, EnumString, IntoStaticStr, VariantArray};
 
 use crate::{
-    api::device::{self, OpenSCQ30DeviceRegistry},
-    connection_backend::ConnectionBackends,
-    devices::soundcore,
+    api::{
+        device::{self, OpenSCQ30DeviceRegistry, ReconnectPolicy},
+        settings::Equalizer,
+    },
+    connection_backend::{self, ConnectionBackends},
+    devices::soundcore::{self, common::demo::DemoEvents},
     storage::OpenSCQ30Database,
 };
 
@@ -60,6 +63,10 @@ pub enum DeviceModel {
     SoundcoreA3947,
     SoundcoreA3948,
     SoundcoreA3949,
+    SoundcoreA6611,
+    SoundcoreA3330,
+    SoundcoreA3958,
+    SoundcoreA3130,
     SoundcoreDevelopment,
 }
 
@@ -68,14 +75,19 @@ impl DeviceModel {
         &self,
         backends: &B,
         database: Arc<OpenSCQ30Database>,
+        reconnect_policy: Option<ReconnectPolicy>,
     ) -> device::Result<Arc<dyn OpenSCQ30DeviceRegistry + Send + Sync>> {
         macro_rules! new_soundcore_device {
             ($($module:tt)*) => {
-                Ok(Arc::new($($module)*::device_registry(
-                    Arc::new(backends.rfcomm().await?),
-                    database,
-                    *self,
-                )))
+                Ok(Arc::new(
+                    $($module)*::device_registry(
+                        connection_backend::transport_backend(backends, $($module)*::transport())
+                            .await?,
+                        database,
+                        *self,
+                    )
+                    .with_reconnect_policy(reconnect_policy),
+                ))
             };
         }
         match self {
@@ -111,19 +123,39 @@ impl DeviceModel {
             Self::SoundcoreA3954 => new_soundcore_device!(soundcore::a3954),
             Self::SoundcoreA3955 => new_soundcore_device!(soundcore::a3955),
             Self::SoundcoreA3957 => new_soundcore_device!(soundcore::a3957),
-            Self::SoundcoreA3959 => new_soundcore_device!(soundcore::a3959),
+            Self::SoundcoreA3958 | Self::SoundcoreA3959 => {
+                new_soundcore_device!(soundcore::a3959)
+            }
             Self::SoundcoreA3968 => new_soundcore_device!(soundcore::a3968),
-            Self::SoundcoreDevelopment => new_soundcore_device!(soundcore::development),
+            Self::SoundcoreA6611 => new_soundcore_device!(soundcore::a6611),
+            Self::SoundcoreA3330 => new_soundcore_device!(soundcore::a3330),
+            Self::SoundcoreA3130 => new_soundcore_device!(soundcore::a3130),
+            // The development device doesn't have any state worth restoring, so it doesn't reconnect
+            Self::SoundcoreDevelopment => Ok(Arc::new(soundcore::development::device_registry(
+                Arc::new(backends.rfcomm().await?),
+                database,
+                *self,
+            ))),
         }
     }
 
     pub async fn demo_device_registry(
         &self,
         database: Arc<OpenSCQ30Database>,
+    ) -> device::Result<Arc<dyn OpenSCQ30DeviceRegistry + Send + Sync>> {
+        self.scripted_demo_device_registry(database, DemoEvents::default())
+            .await
+    }
+
+    /// Like `demo_device_registry`, but the device also sends `events` on its own after connecting.
+    pub async fn scripted_demo_device_registry(
+        &self,
+        database: Arc<OpenSCQ30Database>,
+        events: DemoEvents,
     ) -> device::Result<Arc<dyn OpenSCQ30DeviceRegistry + Send + Sync>> {
         macro_rules! new_soundcore_device {
             ($($module:tt)*) => {
-                Ok(Arc::new($($module)*::demo_device_registry(database, *self)))
+                Ok(Arc::new($($module)*::demo_device_registry(database, *self, events)))
             };
         }
         match self {
@@ -160,12 +192,128 @@ impl DeviceModel {
             Self::SoundcoreA3954 => new_soundcore_device!(soundcore::a3954),
             Self::SoundcoreA3955 => new_soundcore_device!(soundcore::a3955),
             Self::SoundcoreA3957 => new_soundcore_device!(soundcore::a3957),
-            Self::SoundcoreA3959 => new_soundcore_device!(soundcore::a3959),
+            Self::SoundcoreA3958 | Self::SoundcoreA3959 => {
+                new_soundcore_device!(soundcore::a3959)
+            }
             Self::SoundcoreA3968 => new_soundcore_device!(soundcore::a3968),
+            Self::SoundcoreA6611 => new_soundcore_device!(soundcore::a6611),
+            Self::SoundcoreA3330 => new_soundcore_device!(soundcore::a3330),
+            Self::SoundcoreA3130 => new_soundcore_device!(soundcore::a3130),
             Self::SoundcoreDevelopment => new_soundcore_device!(soundcore::development),
         }
     }
 
+    /// `DemoEvents::wind_noise_detected` with this model's sound modes, which are otherwise left at their defaults.
+    /// `None` if this model doesn't report wind noise being detected.
+    pub fn demo_wind_noise_detected_events(
+        &self,
+        delay: Duration,
+        duration: Duration,
+    ) -> Option<DemoEvents> {
+        macro_rules! wind_noise_detected {
+            ($structures:path, $sound_modes:ident) => {{
+                use $structures as structures;
+                Some(DemoEvents::wind_noise_detected(
+                    delay,
+                    duration,
+                    |is_detected| structures::$sound_modes {
+                        wind_noise: structures::WindNoise {
+                            is_detected,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                ))
+            }};
+        }
+        match self {
+            Self::SoundcoreA3936 => {
+                wind_noise_detected!(soundcore::a3936::structures, A3936SoundModes)
+            }
+            Self::SoundcoreA3954 => wind_noise_detected!(soundcore::a3954::structures, SoundModes),
+            Self::SoundcoreA3955 => wind_noise_detected!(soundcore::a3955::structures, SoundModes),
+            Self::SoundcoreA3957 => wind_noise_detected!(soundcore::a3957::structures, SoundModes),
+            Self::SoundcoreA3958 | Self::SoundcoreA3959 => {
+                wind_noise_detected!(soundcore::a3959::structures, SoundModes)
+            }
+            Self::SoundcoreA3968 => wind_noise_detected!(soundcore::a3968::structures, SoundModes),
+            Self::SoundcoreA3004
+            | Self::SoundcoreA3027
+            | Self::SoundcoreA3028
+            | Self::SoundcoreA3029
+            | Self::SoundcoreA3030
+            | Self::SoundcoreA3031
+            | Self::SoundcoreA3033
+            | Self::SoundcoreA3035
+            | Self::SoundcoreA3040
+            | Self::SoundcoreA3062
+            | Self::SoundcoreA3116
+            | Self::SoundcoreA3909
+            | Self::SoundcoreA3926
+            | Self::SoundcoreA3930
+            | Self::SoundcoreA3931
+            | Self::SoundcoreA3933
+            | Self::SoundcoreA3935
+            | Self::SoundcoreA3939
+            | Self::SoundcoreA3945
+            | Self::SoundcoreA3947
+            | Self::SoundcoreA3948
+            | Self::SoundcoreA3949
+            | Self::SoundcoreA3951
+            | Self::SoundcoreA3952
+            | Self::SoundcoreA6611
+            | Self::SoundcoreA3330
+            | Self::SoundcoreA3130
+            | Self::SoundcoreDevelopment => None,
+        }
+    }
+
+    /// Describes this model's equalizer, or `None` if it doesn't have one.
+    pub fn equalizer(&self) -> Option<Equalizer> {
+        match self {
+            Self::SoundcoreA3116 => {
+                Some(soundcore::a3116::modules::equalizer::module_settings().equalizer())
+            }
+            Self::SoundcoreA3909 => {
+                Some(soundcore::a3909::modules::equalizer::module_settings().equalizer())
+            }
+            Self::SoundcoreA3004
+            | Self::SoundcoreA3027
+            | Self::SoundcoreA3028
+            | Self::SoundcoreA3029
+            | Self::SoundcoreA3030
+            | Self::SoundcoreA3031
+            | Self::SoundcoreA3033
+            | Self::SoundcoreA3035
+            | Self::SoundcoreA3040
+            | Self::SoundcoreA3062
+            | Self::SoundcoreA3926
+            | Self::SoundcoreA3930
+            | Self::SoundcoreA3931
+            | Self::SoundcoreA3933
+            | Self::SoundcoreA3936
+            | Self::SoundcoreA3945
+            | Self::SoundcoreA3951
+            | Self::SoundcoreA3952
+            | Self::SoundcoreA3939
+            | Self::SoundcoreA3935
+            | Self::SoundcoreA3954
+            | Self::SoundcoreA3955
+            | Self::SoundcoreA3957
+            | Self::SoundcoreA3959
+            | Self::SoundcoreA3968
+            | Self::SoundcoreA3947
+            | Self::SoundcoreA3948
+            | Self::SoundcoreA3949
+            | Self::SoundcoreA6611
+            | Self::SoundcoreA3330
+            | Self::SoundcoreA3958 => {
+                Some(soundcore::common::modules::equalizer::common_settings().equalizer())
+            }
+            Self::SoundcoreA3130 | Self::SoundcoreDevelopment => None,
+        }
+    }
+
     pub fn demo_mac_address(&self) -> MacAddr6 {
         let index = Self::VARIANTS
             .iter()
@@ -180,10 +328,29 @@ impl DeviceModel {
 mod tests {
     use strum::IntoEnumIterator;
 
-    use crate::api::settings::{Setting, Value};
+    use crate::api::settings::{Setting, SettingId, Value};
 
     use super::*;
 
+    #[tokio::test(start_paused = true)]
+    async fn equalizer_matches_demo_device() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        for model in DeviceModel::iter() {
+            let device = model
+                .demo_device_registry(database.clone())
+                .await
+                .unwrap()
+                .connect(model.demo_mac_address())
+                .await
+                .unwrap();
+            let demo_equalizer = match device.setting(&SettingId::VolumeAdjustments) {
+                Some(Setting::Equalizer { setting, .. }) => Some(setting),
+                _ => None,
+            };
+            assert_eq!(demo_equalizer, model.equalizer(), "{model}");
+        }
+    }
+
     #[tokio::test(start_paused = true)]
     async fn test_set_all_settings() {
         let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
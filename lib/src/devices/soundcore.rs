@@ -7,6 +7,8 @@ pub mod a3035;
 pub mod a3040;
 pub mod a3062;
 pub mod a3116;
+pub mod a3130;
+pub mod a3330;
 pub mod a3909;
 pub mod a3926;
 pub mod a3930;
@@ -24,7 +26,10 @@ pub mod a3955;
 pub mod a3957;
 pub mod a3959;
 pub mod a3968;
+pub mod a6611;
 pub mod common;
+pub mod decode;
+pub mod detection;
 pub mod development;
 
 use uuid::{Uuid, uuid};
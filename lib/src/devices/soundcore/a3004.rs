@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::devices::soundcore::{
     a3004::{packets::A3004StateUpdatePacket, state::A3004State},
     common::{
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{equalizer, sound_modes::AvailableSoundModes},
@@ -11,7 +12,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -37,9 +38,13 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3004StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3004StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+        })
     },
 );
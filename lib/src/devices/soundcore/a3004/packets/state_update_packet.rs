@@ -4,6 +4,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -26,7 +27,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
 pub struct A3004StateUpdatePacket {
     pub battery: SingleBattery,
     pub equalizer_configuration: CommonEqualizerConfiguration<1, 10>,
@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3028::{packets::A3028StateUpdatePacket, state::A3028State},
     common::{
         self,
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{equalizer, sound_modes::AvailableSoundModes},
@@ -12,7 +13,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -43,10 +44,15 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3028StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3028StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.auto_power_off();
+        })
     },
 );
 
@@ -59,6 +65,7 @@ mod tests {
 
     use crate::{
         api::{
+            connection::TransportBackend,
             device::OpenSCQ30DeviceRegistry,
             settings::{SettingId, Value},
         },
@@ -92,7 +99,10 @@ mod tests {
         let (outbound_sender, outbound_receiver) = mpsc::channel(10);
         let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
         let registry = super::device_registry(
-            Arc::new(MockRfcommBackend::new(inbound_receiver, outbound_sender)),
+            TransportBackend::Rfcomm(Arc::new(MockRfcommBackend::new(
+                inbound_receiver,
+                outbound_sender,
+            ))),
             database,
             DeviceModel::SoundcoreA3028,
         );
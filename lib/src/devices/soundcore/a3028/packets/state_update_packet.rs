@@ -5,6 +5,8 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u8,
 };
+use openscq30_lib_has::MaybeHas;
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -28,7 +30,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3028StateUpdatePacket {
     pub battery: SingleBattery,
     pub equalizer_configuration: CommonEqualizerConfiguration<1, 8>,
@@ -57,6 +59,33 @@ impl Default for A3028StateUpdatePacket {
     }
 }
 
+impl MaybeHas<AutoPowerOff> for A3028StateUpdatePacket {
+    fn maybe_get(&self) -> Option<&AutoPowerOff> {
+        self.extra_fields
+            .as_ref()
+            .map(|extra_fields| &extra_fields.auto_power_off)
+    }
+
+    fn maybe_get_mut(&mut self) -> Option<&mut AutoPowerOff> {
+        self.extra_fields
+            .as_mut()
+            .map(|extra_fields| &mut extra_fields.auto_power_off)
+    }
+
+    fn set_maybe(&mut self, maybe_value: Option<AutoPowerOff>) {
+        let Some(auto_power_off) = maybe_value else {
+            return;
+        };
+        match self.maybe_get_mut() {
+            Some(current) => *current = auto_power_off,
+            // Auto power off can't be stored without the rest of the extra fields, which we don't have values for
+            None => tracing::warn!(
+                "a3028 state update packet has no extra fields, ignoring auto power off {auto_power_off:?}"
+            ),
+        }
+    }
+}
+
 impl FromPacketBody for A3028StateUpdatePacket {
     type DirectionMarker = packet::InboundMarker;
 
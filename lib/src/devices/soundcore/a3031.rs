@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3031::{packets::A3031StateUpdatePacket, state::A3031State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         modules::{
             button_configuration::COMMON_SETTINGS as BUTTON_CONFIGURATION_SETTINGS, equalizer,
@@ -17,7 +18,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -58,7 +59,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3031StateUpdatePacket::default().to_packet(),
@@ -67,6 +68,11 @@ soundcore_device!(
                 RequestSerialNumberAndFirmwareVersion::COMMAND,
                 SerialNumberAndFirmwareVersion::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3031StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.auto_power_off();
+        })
     },
 );
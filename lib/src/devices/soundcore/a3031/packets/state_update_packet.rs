@@ -4,6 +4,7 @@ use nom::{
     combinator::{all_consuming, map},
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::devices::soundcore::{
@@ -24,7 +25,7 @@ use crate::devices::soundcore::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3031StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::devices::soundcore::{
     a3033::{packets::A3033StateUpdatePacket, state::A3033State},
     common::{
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::equalizer,
@@ -10,7 +11,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -26,9 +27,12 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3033StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3033StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+        })
     },
 );
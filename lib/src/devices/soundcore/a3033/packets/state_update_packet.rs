@@ -4,6 +4,7 @@ use nom::{
     combinator::{all_consuming, map},
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -27,7 +28,7 @@ use crate::{
 };
 
 // A3033 and A3033EU
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
 pub struct A3033StateUpdatePacket {
     pub battery: SingleBattery,
     pub equalizer_configuration: CommonEqualizerConfiguration<1, 8>,
@@ -1,21 +1,28 @@
 use std::collections::HashMap;
 
+use nom_language::error::VerboseError;
+
 use crate::devices::soundcore::{
     a3035::{packets::inbound::A3035StateUpdatePacket, state::A3035State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         packet::{
             inbound::TryToPacket,
-            outbound::{RequestState, ToPacket},
+            outbound::{
+                RequestState, SET_AUTO_PLAY_PAUSE_COMMAND, SET_BUTTON_CONFIGURATION_COMMAND,
+                SET_LDAC_COMMAND, ToPacket,
+            },
         },
+        structures::{AutoPlayPause, Ldac},
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3035State,
@@ -60,10 +67,32 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3035StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3035StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.on(SET_BUTTON_CONFIGURATION_COMMAND, |body, state_update| {
+                // side and button id are always 0, followed by the double press action
+                let (_, button_configuration) =
+                    structures::ButtonConfiguration::take::<VerboseError<_>>(body.get(2..)?)
+                        .ok()?;
+                state_update.button_configuration = button_configuration;
+                Some(())
+            });
+            simulator.ambient_sound_mode_cycle();
+            simulator.limit_high_volume();
+            simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+            simulator.auto_power_off();
+            simulator.flag::<AutoPlayPause>(SET_AUTO_PLAY_PAUSE_COMMAND);
+            simulator
+                .flag::<structures::BatteryAlert>(packets::outbound::SET_BATTERY_ALERT_COMMAND);
+            simulator.flag::<structures::AmbientSoundModeVoicePrompt>(
+                packets::outbound::SET_AMBIENT_SOUND_MODE_VOICE_PROMPT_COMMAND,
+            );
+        })
     },
 );
 
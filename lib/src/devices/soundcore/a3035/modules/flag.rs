@@ -7,7 +7,7 @@ use crate::{
         a3035,
         common::{
             modules::{ModuleCollection, flag::FlagConfiguration},
-            packet::{self, PacketIOController},
+            packet::PacketIOController,
         },
     },
     settings::SettingId,
@@ -22,7 +22,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::LowBatteryPrompt,
-                set_command: packet::Command([1, 175]),
+                set_command: a3035::packets::outbound::SET_BATTERY_ALERT_COMMAND,
                 update_command: None,
             },
         );
@@ -41,7 +41,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::VoicePrompt,
-                set_command: packet::Command([1, 174]),
+                set_command: a3035::packets::outbound::SET_AMBIENT_SOUND_MODE_VOICE_PROMPT_COMMAND,
                 update_command: None,
             },
         );
@@ -8,6 +8,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::be_u32,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -33,7 +34,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
 pub struct A3035StateUpdatePacket {
     pub battery_level: BatteryLevel,
     pub firmware_version: FirmwareVersion,
@@ -49,6 +50,7 @@ pub struct A3035StateUpdatePacket {
     pub ambient_sound_mode_voice_prompt: a3035::structures::AmbientSoundModeVoicePrompt,
     pub battery_alert: a3035::structures::BatteryAlert,
     pub ldac: Ldac,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
 }
 
@@ -8,6 +8,9 @@ use crate::devices::soundcore::{
     },
 };
 
+pub const SET_AMBIENT_SOUND_MODE_VOICE_PROMPT_COMMAND: packet::Command = packet::Command([1, 174]);
+pub const SET_BATTERY_ALERT_COMMAND: packet::Command = packet::Command([1, 175]);
+
 pub fn set_button_double_press_action(
     maybe_action: Option<a3035::structures::ButtonAction>,
 ) -> packet::Outbound {
@@ -1,21 +1,27 @@
 use std::collections::HashMap;
 
+use nom_language::error::VerboseError;
+
 use crate::devices::soundcore::{
     a3040::{packets::A3040StateUpdatePacket, state::A3040State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         packet::{
             inbound::TryToPacket,
-            outbound::{RequestState, ToPacket},
+            outbound::{
+                RequestState, SET_BUTTON_CONFIGURATION_COMMAND, SET_LDAC_COMMAND, ToPacket,
+            },
         },
+        structures::Ldac,
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3040State,
@@ -59,10 +65,28 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3040StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3040StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.on(SET_BUTTON_CONFIGURATION_COMMAND, |body, state_update| {
+                // side and button id are always 0, followed by the double press action
+                let (_, button_configuration) =
+                    structures::ButtonConfiguration::take::<VerboseError<_>>(body.get(2..)?)
+                        .ok()?;
+                state_update.button_configuration = button_configuration;
+                Some(())
+            });
+            simulator.ambient_sound_mode_cycle();
+            simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+            simulator.flag::<structures::VoicePrompt>(packets::SET_VOICE_PROMPT_COMMAND);
+            simulator.flag::<structures::LowBatteryPrompt>(packets::SET_LOW_BATTERY_PROMPT_COMMAND);
+            simulator.auto_power_off();
+            simulator.limit_high_volume();
+        })
     },
 );
 
@@ -7,7 +7,7 @@ use crate::{
         a3040,
         common::{
             modules::{ModuleCollection, flag::FlagConfiguration},
-            packet::{self, PacketIOController},
+            packet::PacketIOController,
         },
     },
     settings::SettingId,
@@ -22,7 +22,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::LowBatteryPrompt,
-                set_command: packet::Command([1, 175]),
+                set_command: a3040::packets::SET_LOW_BATTERY_PROMPT_COMMAND,
                 update_command: None,
             },
         );
@@ -38,7 +38,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::VoicePrompt,
-                set_command: packet::Command([1, 174]),
+                set_command: a3040::packets::SET_VOICE_PROMPT_COMMAND,
                 update_command: None,
             },
         );
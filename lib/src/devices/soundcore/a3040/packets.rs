@@ -5,3 +5,8 @@ mod state_update;
 pub use set_button_action::*;
 pub use set_equalizer::*;
 pub use state_update::*;
+
+use crate::devices::soundcore::common::packet;
+
+pub const SET_VOICE_PROMPT_COMMAND: packet::Command = packet::Command([1, 174]);
+pub const SET_LOW_BATTERY_PROMPT_COMMAND: packet::Command = packet::Command([1, 175]);
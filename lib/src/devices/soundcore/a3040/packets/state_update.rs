@@ -9,6 +9,7 @@ use nom::{
     multi::count,
     number::complete::be_u32,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -34,7 +35,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Has)]
 pub struct A3040StateUpdatePacket {
     pub battery_level: BatteryLevel,
     pub firmware_version: FirmwareVersion,
@@ -49,6 +50,7 @@ pub struct A3040StateUpdatePacket {
     pub low_battery_prompt: a3040::structures::LowBatteryPrompt,
     pub hear_id: CustomHearId<2, 10>,
     pub ldac: Ldac,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
 }
 
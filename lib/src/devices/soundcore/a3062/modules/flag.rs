@@ -7,7 +7,7 @@ use crate::{
         a3062,
         common::{
             modules::{ModuleCollection, flag::FlagConfiguration},
-            packet::{self, PacketIOController},
+            packet::PacketIOController,
         },
     },
     settings::SettingId,
@@ -22,7 +22,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::DolbyAudio,
-                set_command: packet::Command([2, 134]),
+                set_command: a3062::packets::outbound::SET_DOLBY_AUDIO_COMMAND,
                 update_command: None,
             },
         );
@@ -38,7 +38,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::SideTone,
-                set_command: packet::Command([1, 132]),
+                set_command: a3062::packets::outbound::SET_SIDE_TONE_COMMAND,
                 update_command: None,
             },
         );
@@ -57,7 +57,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::VoicePrompt,
-                set_command: packet::Command([1, 174]),
+                set_command: a3062::packets::outbound::SET_AMBIENT_SOUND_MODE_VOICE_PROMPT_COMMAND,
                 update_command: None,
             },
         );
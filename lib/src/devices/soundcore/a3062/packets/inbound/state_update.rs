@@ -5,6 +5,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -30,7 +31,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
 pub struct A3062StateUpdatePacket {
     pub battery: SingleBattery,
     pub firmware_version: FirmwareVersion,
@@ -43,6 +44,7 @@ pub struct A3062StateUpdatePacket {
     pub low_battery_prompt: LowBatteryPrompt,
     pub dolby_audio: a3062::structures::DolbyAudio,
     pub ldac: Ldac,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
     pub auto_power_off: AutoPowerOff,
     pub limit_high_volume: LimitHighVolume,
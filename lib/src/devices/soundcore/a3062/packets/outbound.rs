@@ -8,6 +8,10 @@ use crate::devices::soundcore::{
     },
 };
 
+pub const SET_SIDE_TONE_COMMAND: packet::Command = packet::Command([1, 132]);
+pub const SET_AMBIENT_SOUND_MODE_VOICE_PROMPT_COMMAND: packet::Command = packet::Command([1, 174]);
+pub const SET_DOLBY_AUDIO_COMMAND: packet::Command = packet::Command([2, 134]);
+
 pub fn set_button_double_press_action(
     maybe_action: Option<a3062::structures::ButtonAction>,
 ) -> packet::Outbound {
@@ -1,19 +1,27 @@
 use std::collections::HashMap;
 
+use nom_language::error::VerboseError;
 use uuid::uuid;
 
-use crate::connection::RfcommServiceSelectionStrategy;
+use crate::connection::{RfcommServiceSelectionStrategy, Transport};
 use crate::devices::soundcore::a3116::packets::inbound::{
     A3116StateUpdatePacket, VoicePromptUpdatePacket,
 };
 use crate::devices::soundcore::a3116::state::A3116State;
+use crate::devices::soundcore::common::demo::DemoState;
 use crate::devices::soundcore::common::device::SoundcoreDeviceConfig;
-use crate::devices::soundcore::common::packet::{self, inbound::TryToPacket, outbound::ToPacket};
-use crate::devices::soundcore::common::structures::FirmwareVersion;
+use crate::devices::soundcore::common::packet::{
+    self,
+    inbound::TryToPacket,
+    outbound::{SET_VOICE_PROMPT_COMMAND, ToPacket},
+};
+use crate::devices::soundcore::common::structures::{
+    EqualizerConfiguration, FirmwareVersion, VoicePrompt, VolumeAdjustments,
+};
 use crate::devices::soundcore::common::{macros::soundcore_device, packet::outbound::RequestState};
 
-mod modules;
-mod packets;
+pub mod modules;
+pub mod packets;
 mod state;
 mod structures;
 
@@ -49,7 +57,7 @@ soundcore_device!(
         builder.serial_number_and_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3116StateUpdatePacket::default().to_packet(),
@@ -58,16 +66,66 @@ soundcore_device!(
                 packets::outbound::REQUEST_VOICE_PROMPT_COMMAND,
                 packets::inbound::VoicePromptUpdatePacket::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3116StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.on(
+                packets::outbound::SET_VOLUME_COMMAND,
+                |body, state_update| {
+                    let (_, volume) = structures::Volume::take::<VerboseError<_>>(body).ok()?;
+                    state_update.volume = volume;
+                    Some(())
+                },
+            );
+            simulator.on(
+                packets::outbound::SET_AUTO_POWER_OFF_COMMAND,
+                |body, state_update| {
+                    let (_, duration) =
+                        structures::AutoPowerOffDuration::take::<VerboseError<_>>(body).ok()?;
+                    state_update.auto_power_off_duration = duration;
+                    Some(())
+                },
+            );
+            simulator.on(
+                packets::outbound::SET_EQUALIZER_PRESET_COMMAND,
+                |body, state_update| {
+                    let [preset_id] = *body else {
+                        return None;
+                    };
+                    state_update.equalizer_configuration = EqualizerConfiguration::new(
+                        preset_id.into(),
+                        *state_update.equalizer_configuration.volume_adjustments(),
+                    );
+                    Some(())
+                },
+            );
+            simulator.on(
+                packets::outbound::SET_EQUALIZER_VOLUME_ADJUSTMENTS_COMMAND,
+                |body, state_update| {
+                    let (_, volume_adjustments) =
+                        VolumeAdjustments::take::<VerboseError<_>>(body).ok()?;
+                    state_update.equalizer_configuration = EqualizerConfiguration::new(
+                        state_update.equalizer_configuration.preset_id(),
+                        [volume_adjustments],
+                    );
+                    Some(())
+                },
+            );
+        })
+        .simulate::<VoicePromptUpdatePacket>(
+            packets::outbound::REQUEST_VOICE_PROMPT_COMMAND,
+            |simulator| {
+                simulator.flag::<VoicePrompt>(SET_VOICE_PROMPT_COMMAND);
+            },
+        )
     },
     CONFIG,
 );
 
 const CONFIG: SoundcoreDeviceConfig = SoundcoreDeviceConfig {
     checksum_kind: packet::ChecksumKind::None,
-    rfcomm_service_selection_strategy: RfcommServiceSelectionStrategy::Constant(uuid!(
+    transport: Transport::Rfcomm(RfcommServiceSelectionStrategy::Constant(uuid!(
         "0cf12d31-fac3-4553-bd80-d6832e7b3116"
-    )),
+    ))),
 };
 
 #[cfg(test)]
@@ -75,12 +133,18 @@ mod tests {
     use std::borrow::Cow;
 
     use crate::{
-        DeviceModel,
+        DeviceModel, OpenSCQ30Session,
+        device::OpenSCQ30Device,
         devices::soundcore::{
             a3116::packets::outbound::REQUEST_VOICE_PROMPT_COMMAND,
             common::device::test_utils::TestSoundcoreDevice,
         },
-        settings::{self, SettingId},
+        settings::{
+            self, SettingId,
+            equalizer_apo::EqualizerApoProfile,
+            share_string::{self, SharedEqualizerProfile},
+        },
+        storage::PairedDevice,
     };
 
     use super::*;
@@ -242,4 +306,357 @@ mod tests {
             settings::Value::I16Vec(vec![4, 3, 2, 1, 0, -1, -2, -3, -4]),
         )]);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn equalizer_apo_import_is_clipped_to_device_range() {
+        let mut device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3116,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                packets::inbound::A3116StateUpdatePacket::default().to_packet(),
+            )]),
+            CONFIG,
+        )
+        .await;
+
+        device
+            .assert_set_settings_response_unordered(
+                vec![
+                    (
+                        SettingId::ImportEqualizerApoProfile,
+                        settings::Value::String(
+                            "# Test\nPreamp: -9 dB\nGraphicEQ: 20 3; 1000 3; 1001 -9; 20000 -9"
+                                .into(),
+                        ),
+                    ),
+                    (
+                        SettingId::CustomEqualizerProfile,
+                        settings::Value::String("Test".into()),
+                    ),
+                ],
+                vec![
+                    packet::Outbound::new(packet::Command([0x02, 0x81]), vec![0xF]),
+                    packet::Outbound::new(
+                        packet::Command([0x02, 0x83]),
+                        vec![9, 9, 9, 9, 9, 0, 0, 0, 0],
+                    ),
+                ],
+            )
+            .await;
+
+        device.assert_setting_values([
+            (
+                SettingId::VolumeAdjustments,
+                settings::Value::I16Vec(vec![3, 3, 3, 3, 3, -6, -6, -6, -6]),
+            ),
+            (
+                SettingId::ImportEqualizerApoProfileResult,
+                settings::Value::String(
+                    concat!(
+                        r#"{"name":"Test","clippedBands":["#,
+                        r#"{"bandHz":1000,"targetDb":-7.0,"clippedDb":-6.0},"#,
+                        r#"{"bandHz":5000,"targetDb":-9.0,"clippedDb":-6.0},"#,
+                        r#"{"bandHz":8000,"targetDb":-9.0,"clippedDb":-6.0},"#,
+                        r#"{"bandHz":12000,"targetDb":-9.0,"clippedDb":-6.0}]}"#,
+                    )
+                    .into(),
+                ),
+            ),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn copies_custom_equalizer_profiles_from_other_models() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        for model in [DeviceModel::SoundcoreA3027, DeviceModel::SoundcoreA3116] {
+            session
+                .pair(PairedDevice {
+                    mac_address: model.demo_mac_address(),
+                    model,
+                    is_demo: true,
+                    adapter_address: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let source = session
+            .connect(DeviceModel::SoundcoreA3027.demo_mac_address())
+            .await
+            .unwrap();
+        source
+            .set_setting_values(vec![(
+                SettingId::ImportCustomEqualizerProfiles,
+                settings::Value::String(
+                    r#"[{"name":"Test","volumeAdjustments":[4,2,0,0,-2,-4,0,12]}]"#.into(),
+                ),
+            )])
+            .await
+            .unwrap();
+
+        let device = session
+            .connect(DeviceModel::SoundcoreA3116.demo_mac_address())
+            .await
+            .unwrap();
+        let Some(settings::Setting::OptionalSelect { setting, .. }) =
+            device.setting(&SettingId::CopyCustomEqualizerProfilesFrom)
+        else {
+            panic!("copy custom equalizer profiles from should be an optional select");
+        };
+        assert_eq!(vec![Cow::from("SoundcoreA3027")], setting.options);
+
+        device
+            .set_setting_values(vec![
+                (
+                    SettingId::CopyCustomEqualizerProfilesFrom,
+                    settings::Value::OptionalString(Some("SoundcoreA3027".into())),
+                ),
+                (
+                    SettingId::CustomEqualizerProfile,
+                    settings::Value::String("Test".into()),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(settings::Value::I16Vec(vec![4, 3, 1, 0, 0, -1, -1, 4, 6])),
+            device
+                .setting(&SettingId::VolumeAdjustments)
+                .map(settings::Value::from),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn copyable_models_are_refreshed_when_profiles_change() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        for model in [DeviceModel::SoundcoreA3027, DeviceModel::SoundcoreA3116] {
+            session
+                .pair(PairedDevice {
+                    mac_address: model.demo_mac_address(),
+                    model,
+                    is_demo: true,
+                    adapter_address: None,
+                })
+                .await
+                .unwrap();
+        }
+        let copyable_models = |device: &dyn OpenSCQ30Device| {
+            let Some(settings::Setting::OptionalSelect { setting, .. }) =
+                device.setting(&SettingId::CopyCustomEqualizerProfilesFrom)
+            else {
+                panic!("copy custom equalizer profiles from should be an optional select");
+            };
+            setting.options
+        };
+
+        let device = session
+            .connect(DeviceModel::SoundcoreA3116.demo_mac_address())
+            .await
+            .unwrap();
+        assert_eq!(Vec::<Cow<str>>::new(), copyable_models(device.as_ref()));
+
+        let source = session
+            .connect(DeviceModel::SoundcoreA3027.demo_mac_address())
+            .await
+            .unwrap();
+        source
+            .set_setting_values(vec![(
+                SettingId::ImportCustomEqualizerProfiles,
+                settings::Value::String(
+                    r#"[{"name":"Test","volumeAdjustments":[0,0,0,0,0,0,0,0]}]"#.into(),
+                ),
+            )])
+            .await
+            .unwrap();
+        let mut changes = device.watch_for_changes();
+        device
+            .set_setting_values(vec![(
+                SettingId::ImportCustomEqualizerProfiles,
+                settings::Value::String(
+                    r#"[{"name":"Local","volumeAdjustments":[0,0,0,0,0,0,0,0,0]}]"#.into(),
+                ),
+            )])
+            .await
+            .unwrap();
+        while copyable_models(device.as_ref()).is_empty() {
+            changes.changed().await.unwrap();
+        }
+        assert_eq!(
+            vec![Cow::from("SoundcoreA3027")],
+            copyable_models(device.as_ref())
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shared_equalizer_profile_is_converted_for_other_models() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        for model in [DeviceModel::SoundcoreA3027, DeviceModel::SoundcoreA3116] {
+            session
+                .pair(PairedDevice {
+                    mac_address: model.demo_mac_address(),
+                    model,
+                    is_demo: true,
+                    adapter_address: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let source = session
+            .connect(DeviceModel::SoundcoreA3027.demo_mac_address())
+            .await
+            .unwrap();
+        source
+            .set_setting_values(vec![
+                (
+                    SettingId::ImportCustomEqualizerProfiles,
+                    settings::Value::String(
+                        r#"[{"name":"Test","volumeAdjustments":[4,2,0,0,-2,-4,0,12]}]"#.into(),
+                    ),
+                ),
+                (
+                    SettingId::ShareCustomEqualizerProfile,
+                    settings::Value::OptionalString(Some("Test".into())),
+                ),
+            ])
+            .await
+            .unwrap();
+        let Some(settings::Setting::Information {
+            value: share_string,
+            ..
+        }) = source.setting(&SettingId::ShareCustomEqualizerProfileOutput)
+        else {
+            panic!("share custom equalizer profile output should be information");
+        };
+        assert!(share_string.starts_with(share_string::PREFIX));
+
+        let device = session
+            .connect(DeviceModel::SoundcoreA3116.demo_mac_address())
+            .await
+            .unwrap();
+        device
+            .set_setting_values(vec![
+                (
+                    SettingId::ImportSharedEqualizerProfile,
+                    settings::Value::String(share_string.into()),
+                ),
+                (
+                    SettingId::CustomEqualizerProfile,
+                    settings::Value::String("Test".into()),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(settings::Value::I16Vec(vec![4, 3, 1, 0, 0, -1, -1, 4, 6])),
+            device
+                .setting(&SettingId::VolumeAdjustments)
+                .map(settings::Value::from),
+        );
+        assert_eq!(
+            Some(settings::Value::String(
+                concat!(
+                    r#"{"name":"Test","convertedFrom":"SoundcoreA3027","clippedBands":["#,
+                    r#"{"bandHz":12000,"targetDb":11.0,"clippedDb":6.0}]}"#,
+                )
+                .into()
+            )),
+            device
+                .setting(&SettingId::ImportSharedEqualizerProfileResult)
+                .map(settings::Value::from),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shared_equalizer_profile_round_trips_on_same_model() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        let mac_address = DeviceModel::SoundcoreA3116.demo_mac_address();
+        session
+            .pair(PairedDevice {
+                mac_address,
+                model: DeviceModel::SoundcoreA3116,
+                is_demo: true,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        let device = session.connect(mac_address).await.unwrap();
+        let shared = SharedEqualizerProfile {
+            device_model: DeviceModel::SoundcoreA3116,
+            name: "Test".to_owned(),
+            band_hz: vec![80, 150, 300, 500, 700, 1000, 5000, 8000, 12000],
+            fraction_digits: 0,
+            volume_adjustments: vec![6, 3, 0, -3, -6, 0, 2, 4, 6],
+            right_volume_adjustments: None,
+        };
+        device
+            .set_setting_values(vec![
+                (
+                    SettingId::ImportSharedEqualizerProfile,
+                    settings::Value::String(shared.to_string().into()),
+                ),
+                (
+                    SettingId::ShareCustomEqualizerProfile,
+                    settings::Value::OptionalString(Some("Test".into())),
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(settings::Value::String(
+                r#"{"name":"Test","convertedFrom":null,"clippedBands":[]}"#.into()
+            )),
+            device
+                .setting(&SettingId::ImportSharedEqualizerProfileResult)
+                .map(settings::Value::from),
+        );
+        assert_eq!(
+            Some(settings::Value::String(shared.to_string().into())),
+            device
+                .setting(&SettingId::ShareCustomEqualizerProfileOutput)
+                .map(settings::Value::from),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn equalizer_apo_export_matches_volume_adjustments() {
+        let session = OpenSCQ30Session::new_with_in_memory_db().await.unwrap();
+        let mac_address = DeviceModel::SoundcoreA3116.demo_mac_address();
+        session
+            .pair(PairedDevice {
+                mac_address,
+                model: DeviceModel::SoundcoreA3116,
+                is_demo: true,
+                adapter_address: None,
+            })
+            .await
+            .unwrap();
+        let device = session.connect(mac_address).await.unwrap();
+        let volume_adjustments = vec![6, 3, 0, -3, -6, 0, 2, 4, 6];
+        device
+            .set_setting_values(vec![(
+                SettingId::VolumeAdjustments,
+                settings::Value::I16Vec(volume_adjustments.clone()),
+            )])
+            .await
+            .unwrap();
+
+        let Some(settings::Setting::Information { value, .. }) =
+            device.setting(&SettingId::ExportEqualizerApoProfile)
+        else {
+            panic!("export equalizer apo profile should be information");
+        };
+        let profile: EqualizerApoProfile = value.parse().unwrap();
+        for (hz, value) in [80, 150, 300, 500, 700, 1000, 5000, 8000, 12000]
+            .into_iter()
+            .zip(volume_adjustments)
+        {
+            let db = profile.gain_db_at(hz.into());
+            assert!(
+                (db - f64::from(value)).abs() < 0.5,
+                "{hz}hz should be {value}db, got {db}",
+            );
+        }
+    }
 }
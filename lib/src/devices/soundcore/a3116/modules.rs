@@ -6,7 +6,7 @@ use crate::devices::soundcore::{
 };
 
 mod auto_power_off;
-mod equalizer;
+pub mod equalizer;
 mod power_off;
 mod volume;
 
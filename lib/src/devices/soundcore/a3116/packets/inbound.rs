@@ -7,6 +7,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u8,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -30,7 +31,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
 pub struct A3116StateUpdatePacket {
     pub battery: SingleBattery,
     pub volume: a3116::structures::Volume,
@@ -153,7 +154,7 @@ impl ModuleCollection<A3116State> {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Has)]
 pub struct VoicePromptUpdatePacket {
     pub voice_prompt: VoicePrompt,
 }
@@ -3,23 +3,28 @@ use crate::devices::soundcore::{
     common::{packet, structures::VolumeAdjustments},
 };
 
+pub const SET_VOLUME_COMMAND: packet::Command = packet::Command([0x01, 0x81]);
+pub const SET_AUTO_POWER_OFF_COMMAND: packet::Command = packet::Command([0x01, 0x83]);
+pub const SET_EQUALIZER_PRESET_COMMAND: packet::Command = packet::Command([0x02, 0x81]);
+pub const SET_EQUALIZER_VOLUME_ADJUSTMENTS_COMMAND: packet::Command = packet::Command([0x02, 0x83]);
+
 pub fn set_auto_power_off(duration: &a3116::structures::AutoPowerOffDuration) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([0x01, 0x83]), duration.bytes().collect())
+    packet::Outbound::new(SET_AUTO_POWER_OFF_COMMAND, duration.bytes().collect())
 }
 
 pub fn set_volume(volume: &a3116::structures::Volume) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([0x01, 0x81]), volume.bytes().collect())
+    packet::Outbound::new(SET_VOLUME_COMMAND, volume.bytes().collect())
 }
 
 pub fn set_equalizer_preset(preset_id: u8) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([0x02, 0x81]), vec![preset_id])
+    packet::Outbound::new(SET_EQUALIZER_PRESET_COMMAND, vec![preset_id])
 }
 
 pub fn set_equalizer_volume_adjustments(
     volume_adjustments: VolumeAdjustments<9, -6, 6, 0>,
 ) -> packet::Outbound {
     packet::Outbound::new(
-        packet::Command([0x02, 0x83]),
+        SET_EQUALIZER_VOLUME_ADJUSTMENTS_COMMAND,
         volume_adjustments.bytes().to_vec(),
     )
 }
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use crate::devices::soundcore::{
+    a3130::{packets::inbound::A3130StateUpdatePacket, state::A3130State},
+    common::{
+        demo::DemoState,
+        device::fetch_state_from_state_update_packet,
+        macros::soundcore_device,
+        modules::auto_power_off::AutoPowerOffDuration,
+        packet::outbound::{RequestState, SET_VOICE_PROMPT_COMMAND, ToPacket},
+        structures::VoicePrompt,
+    },
+};
+
+mod modules;
+pub mod packets;
+mod state;
+pub mod structures;
+
+// The equalizer is not exposed as a setting yet. Its bands have adjustable center frequencies, and the
+// format of the packet for setting it is unknown.
+soundcore_device!(
+    A3130State,
+    async |packet_io| {
+        fetch_state_from_state_update_packet::<A3130State, A3130StateUpdatePacket>(packet_io).await
+    },
+    async |builder| {
+        builder.module_collection().add_state_update();
+        builder.voice_prompt();
+        builder.auto_power_off(AutoPowerOffDuration::five_ten_twenty_sixty());
+        builder.single_battery(5);
+        builder.a3130_serial_number_and_firmware_version();
+    },
+    {
+        DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            A3130StateUpdatePacket::default().to_packet(),
+        )]))
+        .simulate::<A3130StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.flag::<VoicePrompt>(SET_VOICE_PROMPT_COMMAND);
+            simulator.auto_power_off();
+        })
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        DeviceModel,
+        devices::soundcore::common::{
+            device::{SoundcoreDeviceConfig, test_utils::TestSoundcoreDevice},
+            packet,
+        },
+        settings::SettingId,
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn new_with_faker_state_update_packet() {
+        let device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3130,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a3130.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        device.assert_setting_values([
+            (SettingId::BatteryLevel, "5/5".into()),
+            (SettingId::IsCharging, "No".into()),
+            (SettingId::VoicePrompt, true.into()),
+            (SettingId::AutoPowerOff, "disabled".into()),
+            (SettingId::FirmwareVersion, "7.2.4".into()),
+            (SettingId::SerialNumber, "AFN4FZ1F18100661".into()),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn set_auto_power_off() {
+        let mut device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3130,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a3130.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        device
+            .assert_set_settings_response(
+                vec![(SettingId::AutoPowerOff, "60m".into())],
+                vec![packet::Outbound::new(
+                    packet::Command([1, 0x86]),
+                    vec![1, 3],
+                )],
+            )
+            .await;
+    }
+}
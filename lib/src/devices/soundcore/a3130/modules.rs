@@ -0,0 +1,19 @@
+use openscq30_lib_has::Has;
+
+use crate::devices::soundcore::{
+    a3130,
+    common::{device::SoundcoreDeviceBuilder, structures::SerialNumber},
+};
+
+mod serial_number_and_firmware_version;
+
+impl<StateType> SoundcoreDeviceBuilder<StateType>
+where
+    StateType:
+        Has<SerialNumber> + Has<a3130::structures::FirmwareVersion> + Send + Sync + Clone + 'static,
+{
+    pub fn a3130_serial_number_and_firmware_version(&mut self) {
+        self.module_collection()
+            .add_a3130_serial_number_and_firmware_version();
+    }
+}
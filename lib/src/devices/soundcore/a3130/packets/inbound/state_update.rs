@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use nom::{
+    IResult, Parser,
+    bytes::complete::take,
+    combinator::map,
+    error::{ContextError, ParseError, context},
+    number::complete::le_u8,
+};
+use openscq30_lib_macros::Has;
+use tokio::sync::watch;
+
+use crate::{
+    device,
+    devices::soundcore::{
+        a3130::{self, state::A3130State},
+        common::{
+            modules::ModuleCollection,
+            packet::{
+                self, Command,
+                inbound::{FromPacketBody, TryToPacket},
+                outbound::ToPacket,
+                parsing::take_bool,
+            },
+            packet_manager::PacketHandler,
+            structures::{AutoPowerOff, SerialNumber, SingleBattery, VoicePrompt},
+        },
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Has)]
+pub struct A3130StateUpdatePacket {
+    /// 0 to 31
+    #[has(skip)]
+    pub volume: u8,
+    pub battery: SingleBattery,
+    pub voice_prompt: VoicePrompt,
+    pub auto_power_off: AutoPowerOff,
+    pub firmware_version: a3130::structures::FirmwareVersion,
+    pub serial_number: SerialNumber,
+    #[has(skip)]
+    pub bass_up: bool,
+    pub equalizer_configuration: a3130::structures::EqualizerConfiguration,
+}
+
+impl FromPacketBody for A3130StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "a3130 state update packet",
+            map(
+                (
+                    le_u8,
+                    SingleBattery::take,
+                    take(1usize), // unknown
+                    VoicePrompt::take,
+                    AutoPowerOff::take,
+                    a3130::structures::FirmwareVersion::take,
+                    SerialNumber::take,
+                    take_bool,
+                    take(4usize), // unknown
+                    a3130::structures::EqualizerConfiguration::take,
+                    take(3usize), // unknown
+                ),
+                |(
+                    volume,
+                    battery,
+                    _unknown0,
+                    voice_prompt,
+                    auto_power_off,
+                    firmware_version,
+                    serial_number,
+                    bass_up,
+                    _unknown1,
+                    equalizer_configuration,
+                    _unknown2,
+                )| Self {
+                    volume,
+                    battery,
+                    voice_prompt,
+                    auto_power_off,
+                    firmware_version,
+                    serial_number,
+                    bass_up,
+                    equalizer_configuration,
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+}
+
+impl ToPacket for A3130StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        packet::inbound::STATE_COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        [self.volume]
+            .into_iter()
+            .chain(self.battery.bytes())
+            .chain([0]) // unknown
+            .chain(self.voice_prompt.bytes())
+            .chain(self.auto_power_off.bytes())
+            .chain(self.firmware_version.bytes())
+            .chain(self.serial_number.bytes())
+            .chain([self.bass_up.into()])
+            .chain([0; 4]) // unknown
+            .chain(self.equalizer_configuration.bytes())
+            .chain([0; 3]) // unknown
+            .collect()
+    }
+}
+
+struct StateUpdatePacketHandler;
+
+#[async_trait]
+impl PacketHandler<A3130State> for StateUpdatePacketHandler {
+    async fn handle_packet(
+        &self,
+        state: &watch::Sender<A3130State>,
+        packet: &packet::Inbound,
+    ) -> device::Result<()> {
+        let packet: A3130StateUpdatePacket = packet.try_to_packet()?;
+        state.send_modify(|state| *state = packet.into());
+        Ok(())
+    }
+}
+
+impl ModuleCollection<A3130State> {
+    pub fn add_state_update(&mut self) {
+        self.packet_handlers.set_handler(
+            packet::inbound::STATE_COMMAND,
+            Box::new(StateUpdatePacketHandler {}),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom_language::error::VerboseError;
+
+    use crate::devices::soundcore::common::packet::inbound::TryToPacket;
+
+    use super::*;
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let bytes = A3130StateUpdatePacket::default()
+            .to_packet()
+            .bytes_with_checksum();
+        let (_, packet) = packet::Inbound::take_with_checksum::<VerboseError<_>>(&bytes).unwrap();
+        let _: A3130StateUpdatePacket = packet.try_to_packet().unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+use openscq30_lib_macros::Has;
+
+use crate::devices::soundcore::{
+    a3130::{self, packets::inbound::A3130StateUpdatePacket},
+    common::structures::{AutoPowerOff, SerialNumber, SingleBattery, VoicePrompt},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
+pub struct A3130State {
+    battery: SingleBattery,
+    voice_prompt: VoicePrompt,
+    auto_power_off: AutoPowerOff,
+    firmware_version: a3130::structures::FirmwareVersion,
+    serial_number: SerialNumber,
+}
+
+impl From<A3130StateUpdatePacket> for A3130State {
+    fn from(value: A3130StateUpdatePacket) -> Self {
+        Self {
+            battery: value.battery,
+            voice_prompt: value.voice_prompt,
+            auto_power_off: value.auto_power_off,
+            firmware_version: value.firmware_version,
+            serial_number: value.serial_number,
+        }
+    }
+}
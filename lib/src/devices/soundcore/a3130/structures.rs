@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+use nom::{
+    IResult, Parser,
+    bytes::complete::{tag, take},
+    combinator::{all_consuming, map, map_parser},
+    error::{ContextError, ParseError, context},
+    multi::count,
+    number::complete::le_u8,
+};
+
+/// Unlike most devices, which use the xx.xx format, the firmware version is formatted as x.x.x
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FirmwareVersion {
+    major: u8,
+    minor: u8,
+    patch: u8,
+}
+
+impl FirmwareVersion {
+    pub const fn new(major: u8, minor: u8, patch: u8) -> Self {
+        debug_assert!(major < 10, "major version must fit within one digit");
+        debug_assert!(minor < 10, "minor version must fit within one digit");
+        debug_assert!(patch < 10, "patch version must fit within one digit");
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        let digit = || map_parser(take(1usize), all_consuming(nom::character::complete::u8));
+        context(
+            "x.x.x firmware version",
+            map(
+                (digit(), tag("."), digit(), tag("."), digit()),
+                |(major, _, minor, _, patch)| Self::new(major, minor, patch),
+            ),
+        )
+        .parse_complete(input)
+    }
+
+    pub fn bytes(&self) -> [u8; 5] {
+        [
+            b'0' + self.major,
+            b'.',
+            b'0' + self.minor,
+            b'.',
+            b'0' + self.patch,
+        ]
+    }
+}
+
+impl Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Each band has an adjustable center frequency, so this can't be represented as a
+/// [`crate::devices::soundcore::common::structures::EqualizerConfiguration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EqualizerConfiguration {
+    /// 0=soundcore signature, 1=xtra bass, 2=voice, 3=treble boost, 4=balanced, 254=custom
+    pub preset_id: u8,
+    pub bands: [EqualizerBand; 9],
+}
+
+impl EqualizerConfiguration {
+    pub fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "a3130 equalizer configuration",
+            map(
+                (le_u8, count(EqualizerBand::take, 9)),
+                |(preset_id, bands)| Self {
+                    preset_id,
+                    bands: bands
+                        .try_into()
+                        .expect("count is guaranteed to return a vec with the desired length"),
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+
+    pub fn bytes(&self) -> impl Iterator<Item = u8> {
+        std::iter::once(self.preset_id).chain(self.bands.iter().flat_map(EqualizerBand::bytes))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EqualizerBand {
+    /// 120 is 0db, with each step being 0.1db
+    pub volume: u8,
+    /// Index into the list of frequencies that this band can be centered on
+    pub frequency_index: u8,
+    /// Always 20 as far as we know
+    pub unknown: u8,
+}
+
+impl Default for EqualizerBand {
+    fn default() -> Self {
+        Self {
+            volume: 120,
+            frequency_index: 0,
+            unknown: 20,
+        }
+    }
+}
+
+impl EqualizerBand {
+    pub fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "a3130 equalizer band",
+            map(
+                (le_u8, le_u8, le_u8),
+                |(volume, frequency_index, unknown)| Self {
+                    volume,
+                    frequency_index,
+                    unknown,
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+
+    pub fn bytes(&self) -> [u8; 3] {
+        [self.volume, self.frequency_index, self.unknown]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom_language::error::VerboseError;
+
+    use super::*;
+
+    #[test]
+    fn parses_firmware_version() {
+        let (_, firmware_version) = FirmwareVersion::take::<VerboseError<_>>(b"7.2.4").unwrap();
+        assert_eq!(FirmwareVersion::new(7, 2, 4), firmware_version);
+        assert_eq!("7.2.4", firmware_version.to_string());
+        assert_eq!(*b"7.2.4", firmware_version.bytes());
+    }
+
+    #[test]
+    fn firmware_version_parsing_fails_with_xx_xx_format() {
+        FirmwareVersion::take::<VerboseError<_>>(b"01.23").unwrap_err();
+    }
+}
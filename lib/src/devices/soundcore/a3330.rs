@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use crate::devices::soundcore::{
+    a3330::{packets::inbound::A3330StateUpdatePacket, state::A3330State},
+    common::{
+        demo::DemoState,
+        macros::soundcore_device,
+        modules::{
+            button_configuration::{
+                ButtonConfigurationSettings, ButtonDisableMode, ButtonSettings,
+                COMMON_ACTIONS_WITHOUT_SOUND_MODES,
+            },
+            dual_connections::take_dual_connection_devices,
+            equalizer,
+        },
+        packet::{
+            inbound::TryToPacket,
+            outbound::{RequestState, ToPacket},
+        },
+        structures::button_configuration::{
+            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        },
+    },
+};
+
+pub mod packets;
+mod state;
+
+soundcore_device!(
+    A3330State,
+    async |packet_io| {
+        let state_update_packet: A3330StateUpdatePacket = packet_io
+            .send_with_response(&RequestState.to_packet())
+            .await?
+            .try_to_packet()?;
+        let dual_connections_devices = if state_update_packet.dual_connections_enabled {
+            take_dual_connection_devices(&packet_io).await?
+        } else {
+            Vec::new()
+        };
+        Ok(A3330State::new(
+            state_update_packet,
+            dual_connections_devices,
+        ))
+    },
+    async |builder| {
+        builder.module_collection().add_state_update();
+        builder.equalizer_tws(equalizer::common_settings()).await;
+
+        builder.button_configuration(&BUTTON_CONFIGURATION_SETTINGS);
+        builder.reset_button_configuration::<A3330StateUpdatePacket>(RequestState.to_packet());
+
+        builder.surround_sound();
+        builder.dual_connections();
+        builder.touch_tone();
+        builder.low_battery_prompt();
+
+        builder.serial_number_and_dual_firmware_version();
+        builder.tws_status();
+        builder.dual_battery(5);
+        builder.case_battery_level(5);
+    },
+    {
+        DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            A3330StateUpdatePacket::default().to_packet(),
+        )]))
+        .simulate::<A3330StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
+    },
+);
+
+pub const BUTTON_CONFIGURATION_SETTINGS: ButtonConfigurationSettings<6, 3> =
+    ButtonConfigurationSettings {
+        supports_set_all_packet: false,
+        ignore_enabled_flag: true,
+        order: [
+            Button::LeftDoublePress,
+            Button::RightDoublePress,
+            Button::LeftTriplePress,
+            Button::RightTriplePress,
+            Button::LeftLongPress,
+            Button::RightLongPress,
+        ],
+        settings: [
+            ButtonSettings {
+                parse_settings: ButtonParseSettings {
+                    enabled_flag_kind: EnabledFlagKind::None,
+                    action_kind: ActionKind::TwsLowBits,
+                },
+                button_id: 0,
+                press_kind: ButtonPressKind::Double,
+                available_actions: COMMON_ACTIONS_WITHOUT_SOUND_MODES,
+                disable_mode: ButtonDisableMode::IndividualDisable,
+            },
+            ButtonSettings {
+                parse_settings: ButtonParseSettings {
+                    enabled_flag_kind: EnabledFlagKind::None,
+                    action_kind: ActionKind::TwsLowBits,
+                },
+                button_id: 5,
+                press_kind: ButtonPressKind::Triple,
+                available_actions: COMMON_ACTIONS_WITHOUT_SOUND_MODES,
+                disable_mode: ButtonDisableMode::IndividualDisable,
+            },
+            ButtonSettings {
+                parse_settings: ButtonParseSettings {
+                    enabled_flag_kind: EnabledFlagKind::None,
+                    action_kind: ActionKind::TwsLowBits,
+                },
+                button_id: 1,
+                press_kind: ButtonPressKind::Long,
+                available_actions: COMMON_ACTIONS_WITHOUT_SOUND_MODES,
+                disable_mode: ButtonDisableMode::IndividualDisable,
+            },
+        ],
+    };
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        DeviceModel,
+        devices::soundcore::{
+            a3330::packets::inbound::A3330StateUpdatePacket,
+            common::{
+                device::{SoundcoreDeviceConfig, test_utils::TestSoundcoreDevice},
+                packet::{self, inbound::TryToPacket},
+            },
+        },
+        settings::{CategoryId, SettingId, Value},
+    };
+
+    #[test]
+    fn parses_faker_state_update_packet() {
+        let packet: A3330StateUpdatePacket = TestSoundcoreDevice::faker_state_update("a3330.toml")
+            .try_to_packet()
+            .unwrap();
+        insta::assert_debug_snapshot!(packet, @r#"
+        A3330StateUpdatePacket {
+            tws_status: TwsStatus {
+                is_connected: true,
+                host_device: Right,
+            },
+            battery: DualBattery {
+                left: SingleBattery {
+                    is_charging: No,
+                    level: BatteryLevel(
+                        5,
+                    ),
+                },
+                right: SingleBattery {
+                    is_charging: No,
+                    level: BatteryLevel(
+                        5,
+                    ),
+                },
+            },
+            firmware_version: Both {
+                left: FirmwareVersion {
+                    major: 4,
+                    minor: 27,
+                },
+                right: FirmwareVersion {
+                    major: 4,
+                    minor: 27,
+                },
+            },
+            serial_number: SerialNumber(
+                "333098474479f629",
+            ),
+            case_battery: CaseBatteryLevel(
+                BatteryLevel(
+                    2,
+                ),
+            ),
+            button_configuration: ButtonStatusCollection(
+                [
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 1,
+                            disconnected: 6,
+                        },
+                    },
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 0,
+                            disconnected: 6,
+                        },
+                    },
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 3,
+                            disconnected: 3,
+                        },
+                    },
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 3,
+                            disconnected: 3,
+                        },
+                    },
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 6,
+                            disconnected: 15,
+                        },
+                    },
+                    ButtonStatus {
+                        enabled: None,
+                        action: Tws {
+                            connected: 6,
+                            disconnected: 15,
+                        },
+                    },
+                ],
+            ),
+            surround_sound: SurroundSound(
+                false,
+            ),
+            touch_tone: TouchTone(
+                true,
+            ),
+            low_battery_prompt: LowBatteryPrompt(
+                true,
+            ),
+            dual_connections_enabled: false,
+            equalizer_configuration: EqualizerConfiguration {
+                preset_id: 65278,
+                volume_adjustments: [
+                    VolumeAdjustments {
+                        inner: [
+                            11,
+                            15,
+                            16,
+                            11,
+                            4,
+                            0,
+                            0,
+                            0,
+                            0,
+                            0,
+                        ],
+                    },
+                ],
+            },
+        }
+        "#);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_with_faker_state_update_packet() {
+        let device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3330,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a3330.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        let device = device.inner();
+        let settings = device
+            .categories()
+            .iter()
+            // import/export settings are derived from the equalizer rather than decoded from the packet
+            .filter(|category_id| **category_id != CategoryId::EqualizerImportExport)
+            .flat_map(|category_id| device.settings_in_category(category_id))
+            .filter_map(|setting_id| {
+                let setting = device.setting(&setting_id)?;
+                setting
+                    .mode()
+                    .is_readable()
+                    .then(|| format!("{setting_id}: {}", Value::from(setting)))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(settings, @r"
+        presetEqualizerProfile: None
+        customEqualizerProfile: None
+        volumeAdjustments: [11, 15, 16, 11, 4, 0, 0, 0, 0, 0]
+        leftDoublePress: VolumeDown
+        rightDoublePress: VolumeUp
+        leftTriplePress: NextSong
+        rightTriplePress: NextSong
+        leftLongPress: PlayPause
+        rightLongPress: PlayPause
+        surroundSound: false
+        touchTone: true
+        lowBatteryPrompt: true
+        dualConnections: false
+        dualConnectionsDevices: 
+        serialNumber: 333098474479f629
+        firmwareVersionLeft: 04.27
+        firmwareVersionRight: 04.27
+        twsStatus: Connected
+        hostDevice: Right
+        isChargingLeft: No
+        isChargingRight: No
+        batteryLevelLeft: 5/5
+        batteryLevelRight: 5/5
+        caseBatteryLevel: 2/5
+        ");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn set_left_double_press() {
+        let mut device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3330,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a3330.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        device
+            .assert_set_settings_response(
+                vec![(SettingId::LeftDoublePress, "PreviousSong".into())],
+                vec![packet::Outbound::new(
+                    packet::Command([0x04, 0x81]),
+                    vec![0, 0, 0x62],
+                )],
+            )
+            .await;
+    }
+}
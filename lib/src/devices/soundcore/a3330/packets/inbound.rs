@@ -0,0 +1,3 @@
+mod state_update;
+
+pub use state_update::*;
@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use nom::{
+    IResult, Parser,
+    bytes::complete::take,
+    combinator::map,
+    error::{ContextError, ParseError, context},
+};
+use openscq30_lib_macros::Has;
+use tokio::sync::watch;
+
+use crate::{
+    device,
+    devices::soundcore::{
+        a3330::{self, state::A3330State},
+        common::{
+            modules::ModuleCollection,
+            packet::{
+                self, Command,
+                inbound::{FromPacketBody, TryToPacket},
+                outbound::ToPacket,
+                parsing::take_bool,
+            },
+            packet_manager::PacketHandler,
+            state::Update,
+            structures::{
+                BatteryLevel, CaseBatteryLevel, CommonEqualizerConfiguration, DualBattery,
+                DualFirmwareVersion, LowBatteryPrompt, SerialNumber, SingleBattery, SurroundSound,
+                TouchTone, TwsStatus, button_configuration::ButtonStatusCollection,
+            },
+        },
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
+pub struct A3330StateUpdatePacket {
+    pub tws_status: TwsStatus,
+    pub battery: DualBattery,
+    pub firmware_version: DualFirmwareVersion,
+    pub serial_number: SerialNumber,
+    pub case_battery: CaseBatteryLevel,
+    pub button_configuration: ButtonStatusCollection<6>,
+    pub surround_sound: SurroundSound,
+    pub touch_tone: TouchTone,
+    pub low_battery_prompt: LowBatteryPrompt,
+    pub dual_connections_enabled: bool,
+    pub equalizer_configuration: CommonEqualizerConfiguration<1, 10>,
+}
+
+impl Default for A3330StateUpdatePacket {
+    fn default() -> Self {
+        Self {
+            tws_status: Default::default(),
+            battery: Default::default(),
+            firmware_version: Default::default(),
+            serial_number: Default::default(),
+            case_battery: Default::default(),
+            button_configuration: a3330::BUTTON_CONFIGURATION_SETTINGS.default_status_collection(),
+            surround_sound: Default::default(),
+            touch_tone: Default::default(),
+            low_battery_prompt: Default::default(),
+            dual_connections_enabled: Default::default(),
+            equalizer_configuration: Default::default(),
+        }
+    }
+}
+
+impl FromPacketBody for A3330StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "a3330 state update packet",
+            map(
+                (
+                    TwsStatus::take,
+                    // Charging status is not included
+                    BatteryLevel::take,
+                    BatteryLevel::take,
+                    DualFirmwareVersion::take,
+                    SerialNumber::take,
+                    take(5usize), // unknown, always an ascii version string such as 0.1.3
+                    CaseBatteryLevel::take,
+                    take(1usize), // unknown
+                    ButtonStatusCollection::take(
+                        a3330::BUTTON_CONFIGURATION_SETTINGS.parse_settings(),
+                    ),
+                    take(1usize), // unknown
+                    SurroundSound::take,
+                    TouchTone::take,
+                    LowBatteryPrompt::take,
+                    take_bool,
+                    take(2usize), // unknown: control lock, bass mode
+                    CommonEqualizerConfiguration::take,
+                    take(5usize), // unknown: includes button configuration during calls
+                    take(3usize), // padding
+                ),
+                |(
+                    tws_status,
+                    left_battery_level,
+                    right_battery_level,
+                    firmware_version,
+                    serial_number,
+                    _unknown0,
+                    case_battery,
+                    _unknown1,
+                    button_configuration,
+                    _unknown2,
+                    surround_sound,
+                    touch_tone,
+                    low_battery_prompt,
+                    dual_connections_enabled,
+                    _unknown3,
+                    equalizer_configuration,
+                    _unknown4,
+                    _padding,
+                )| Self {
+                    tws_status,
+                    battery: DualBattery {
+                        left: SingleBattery {
+                            level: left_battery_level,
+                            is_charging: Default::default(),
+                        },
+                        right: SingleBattery {
+                            level: right_battery_level,
+                            is_charging: Default::default(),
+                        },
+                    },
+                    firmware_version,
+                    serial_number,
+                    case_battery,
+                    button_configuration,
+                    surround_sound,
+                    touch_tone,
+                    low_battery_prompt,
+                    dual_connections_enabled,
+                    equalizer_configuration,
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+}
+
+impl ToPacket for A3330StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        packet::inbound::STATE_COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        self.tws_status
+            .bytes()
+            .into_iter()
+            .chain([self.battery.left.level.0, self.battery.right.level.0])
+            .chain(self.firmware_version.bytes())
+            .chain(self.serial_number.bytes())
+            .chain([0; 5]) // unknown
+            .chain(self.case_battery.bytes())
+            .chain([0]) // unknown
+            .chain(
+                self.button_configuration
+                    .bytes(a3330::BUTTON_CONFIGURATION_SETTINGS.parse_settings()),
+            )
+            .chain([0]) // unknown
+            .chain(self.surround_sound.bytes())
+            .chain(self.touch_tone.bytes())
+            .chain(self.low_battery_prompt.bytes())
+            .chain([self.dual_connections_enabled.into()])
+            .chain([0; 2]) // unknown
+            .chain(self.equalizer_configuration.bytes())
+            .chain([0; 5]) // unknown
+            .chain([255; 3]) // padding
+            .collect()
+    }
+}
+
+struct StateUpdatePacketHandler;
+
+#[async_trait]
+impl PacketHandler<A3330State> for StateUpdatePacketHandler {
+    async fn handle_packet(
+        &self,
+        state: &watch::Sender<A3330State>,
+        packet: &packet::Inbound,
+    ) -> device::Result<()> {
+        let packet: A3330StateUpdatePacket = packet.try_to_packet()?;
+        state.send_modify(|state| state.update(packet));
+        Ok(())
+    }
+}
+
+impl ModuleCollection<A3330State> {
+    pub fn add_state_update(&mut self) {
+        self.packet_handlers.set_handler(
+            packet::inbound::STATE_COMMAND,
+            Box::new(StateUpdatePacketHandler {}),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom_language::error::VerboseError;
+
+    use crate::devices::soundcore::common::packet::inbound::TryToPacket;
+
+    use super::*;
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let bytes = A3330StateUpdatePacket::default()
+            .to_packet()
+            .bytes_with_checksum();
+        let (_, packet) = packet::Inbound::take_with_checksum::<VerboseError<_>>(&bytes).unwrap();
+        let _: A3330StateUpdatePacket = packet.try_to_packet().unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+use openscq30_lib_macros::Has;
+
+use crate::devices::soundcore::{
+    a3330::packets::inbound::A3330StateUpdatePacket,
+    common::{
+        modules::reset_button_configuration::ResetButtonConfigurationPending,
+        state::Update,
+        structures::{
+            CaseBatteryLevel, CommonEqualizerConfiguration, DualBattery, DualConnections,
+            DualConnectionsDevice, DualFirmwareVersion, LowBatteryPrompt, SerialNumber,
+            SurroundSound, TouchTone, TwsStatus, button_configuration::ButtonStatusCollection,
+        },
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
+pub struct A3330State {
+    tws_status: TwsStatus,
+    battery: DualBattery,
+    case_battery: CaseBatteryLevel,
+    firmware_version: DualFirmwareVersion,
+    serial_number: SerialNumber,
+    equalizer_configuration: CommonEqualizerConfiguration<1, 10>,
+    button_configuration: ButtonStatusCollection<6>,
+    surround_sound: SurroundSound,
+    touch_tone: TouchTone,
+    low_battery_prompt: LowBatteryPrompt,
+    dual_connections: DualConnections,
+    button_reset_pending: ResetButtonConfigurationPending,
+}
+
+impl A3330State {
+    pub fn new(
+        packet: A3330StateUpdatePacket,
+        dual_connections_devices: Vec<DualConnectionsDevice>,
+    ) -> Self {
+        Self {
+            tws_status: packet.tws_status,
+            battery: packet.battery,
+            case_battery: packet.case_battery,
+            firmware_version: packet.firmware_version,
+            serial_number: packet.serial_number,
+            equalizer_configuration: packet.equalizer_configuration,
+            button_configuration: packet.button_configuration,
+            surround_sound: packet.surround_sound,
+            touch_tone: packet.touch_tone,
+            low_battery_prompt: packet.low_battery_prompt,
+            dual_connections: DualConnections {
+                is_enabled: packet.dual_connections_enabled,
+                devices: dual_connections_devices,
+            },
+            button_reset_pending: ResetButtonConfigurationPending::default(),
+        }
+    }
+}
+
+impl Update<A3330StateUpdatePacket> for A3330State {
+    fn update(&mut self, partial: A3330StateUpdatePacket) {
+        let A3330StateUpdatePacket {
+            tws_status,
+            battery,
+            firmware_version,
+            serial_number,
+            case_battery,
+            button_configuration,
+            surround_sound,
+            touch_tone,
+            low_battery_prompt,
+            dual_connections_enabled,
+            equalizer_configuration,
+        } = partial;
+        self.tws_status = tws_status;
+        self.battery = battery;
+        self.firmware_version = firmware_version;
+        self.serial_number = serial_number;
+        self.case_battery = case_battery;
+        self.button_configuration = button_configuration;
+        self.surround_sound = surround_sound;
+        self.touch_tone = touch_tone;
+        self.low_battery_prompt = low_battery_prompt;
+        self.dual_connections.is_enabled = dual_connections_enabled;
+        self.equalizer_configuration = equalizer_configuration;
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     devices::soundcore::{
         a3909::{packets::inbound::A3909StateUpdatePacket, state::A3909State},
         common::{
+            demo::DemoState,
             macros::soundcore_device,
             modules::button_configuration::{
                 ButtonAction, ButtonConfigurationSettings, ButtonDisableMode, ButtonSettings,
@@ -20,8 +21,8 @@ use crate::{
     i18n::fl,
 };
 
-mod modules;
-mod packets;
+pub mod modules;
+pub mod packets;
 mod state;
 mod structures;
 
@@ -49,7 +50,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3909StateUpdatePacket::default().to_packet(),
@@ -58,7 +59,11 @@ soundcore_device!(
                 RequestSerialNumberAndFirmwareVersion::COMMAND,
                 SerialNumberAndFirmwareVersion::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3909StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
 );
 
@@ -37,16 +37,20 @@ where
             database,
             device_model,
             change_notify,
-            EqualizerModuleSettings {
-                custom_preset_id: 0xfefe,
-                band_hz: [100, 200, 400, 800, 1600, 3200, 6400, 12800],
-                presets: PRESETS.clone(),
-            },
+            module_settings(),
         )
         .await;
     }
 }
 
+pub fn module_settings() -> EqualizerModuleSettings<8, 8, -12, 12, 0> {
+    EqualizerModuleSettings {
+        custom_preset_id: 0xfefe,
+        band_hz: [100, 200, 400, 800, 1600, 3200, 6400, 12800],
+        presets: PRESETS.clone(),
+    }
+}
+
 pub static PRESETS: LazyLock<Vec<EqualizerPreset<8, -12, 12, 0>>> = LazyLock::new(|| {
     let common_settings = common::modules::equalizer::common_settings();
     common_settings
@@ -5,6 +5,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u16,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -28,7 +29,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3909StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
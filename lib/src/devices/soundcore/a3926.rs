@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::devices::soundcore::{
     a3926::{packets::A3926StateUpdatePacket, state::A3926State},
     common::{
+        demo::DemoState,
         macros::soundcore_device,
         modules::equalizer,
         packet::{
@@ -14,7 +15,7 @@ use crate::devices::soundcore::{
 
 pub use crate::devices::soundcore::common::modules::button_configuration::COMMON_SETTINGS as BUTTON_CONFIGURATION_SETTINGS;
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -56,7 +57,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3926StateUpdatePacket::default().to_packet(),
@@ -65,7 +66,11 @@ soundcore_device!(
                 RequestSerialNumberAndFirmwareVersion::COMMAND,
                 SerialNumberAndFirmwareVersion::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3926StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
 );
 
@@ -4,6 +4,7 @@ use nom::{
     combinator::{all_consuming, map},
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -28,7 +29,7 @@ use crate::{
 };
 
 // A3926 and A3926Z11
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3926StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
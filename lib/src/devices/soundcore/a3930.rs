@@ -3,11 +3,12 @@ use std::collections::HashMap;
 use uuid::uuid;
 
 use crate::{
-    connection::RfcommServiceSelectionStrategy,
+    connection::{RfcommServiceSelectionStrategy, Transport},
     devices::soundcore::{
         a3930::{packets::A3930StateUpdatePacket, state::A3930State},
         common::{
             self,
+            demo::DemoState,
             device::SoundcoreDeviceConfig,
             macros::soundcore_device,
             modules::{
@@ -33,7 +34,7 @@ use crate::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -74,7 +75,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3930StateUpdatePacket::default().to_packet(),
@@ -87,16 +88,21 @@ soundcore_device!(
                 common::packet::outbound::REQUEST_LDAC_STATE_COMMAND,
                 common::packet::inbound::LdacState::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3930StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
     CONFIG,
 );
 
 const CONFIG: SoundcoreDeviceConfig = SoundcoreDeviceConfig {
     checksum_kind: ChecksumKind::Suffix,
-    rfcomm_service_selection_strategy: RfcommServiceSelectionStrategy::Constant(uuid!(
+    transport: Transport::Rfcomm(RfcommServiceSelectionStrategy::Constant(uuid!(
         "00001101-0000-1000-8000-00805f9b34fb"
-    )),
+    ))),
 };
 
 const BUTTON_CONFIGURATION_SETTINGS: ButtonConfigurationSettings<6, 3> =
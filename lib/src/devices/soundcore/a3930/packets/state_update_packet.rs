@@ -5,6 +5,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u16,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -30,7 +31,7 @@ use crate::{
 };
 
 // A3930
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3930StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
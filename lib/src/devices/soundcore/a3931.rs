@@ -7,6 +7,7 @@ use crate::{
     devices::soundcore::{
         a3931::{packets::A3931StateUpdatePacket, state::A3931State},
         common::{
+            demo::DemoState,
             macros::soundcore_device,
             modules::{
                 button_configuration::{
@@ -30,7 +31,7 @@ use crate::{
     i18n::fl,
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -76,7 +77,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3931StateUpdatePacket::default().to_packet(),
@@ -85,7 +86,13 @@ soundcore_device!(
                 RequestSerialNumberAndFirmwareVersion::COMMAND,
                 SerialNumberAndFirmwareVersion::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3931StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.auto_power_off();
+        })
     },
 );
 
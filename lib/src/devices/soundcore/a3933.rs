@@ -4,6 +4,7 @@ pub use crate::devices::soundcore::common::modules::button_configuration::COMMON
 use crate::devices::soundcore::{
     a3933::{packets::inbound::A3933StateUpdatePacket, state::A3933State},
     common::{
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{equalizer, sound_modes::AvailableSoundModes},
@@ -12,7 +13,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -51,10 +52,15 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3933StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3933StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
 );
 
@@ -63,7 +69,7 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        api::settings::{SettingId, Value},
+        api::settings::{ModifiableSelectCommand, SettingId, Value},
         devices::{
             DeviceModel,
             soundcore::common::{
@@ -76,9 +82,8 @@ mod tests {
 
     use super::*;
 
-    #[tokio::test(start_paused = true)]
-    async fn it_remembers_eq_band_9_and_10_values() {
-        let state_update_packet = packet::Inbound::new(
+    fn state_update_packet() -> packet::Inbound {
+        packet::Inbound::new(
             packet::inbound::STATE_COMMAND,
             vec![
                 0x00, // host device
@@ -117,15 +122,22 @@ mod tests {
                 0x00, // wind noise detection
                 0xFF, 0xFF, 0xFF, // three unknown bytes
             ],
-        );
+        )
+    }
 
-        let mut device = TestSoundcoreDevice::new(
+    async fn test_device() -> TestSoundcoreDevice {
+        TestSoundcoreDevice::new(
             super::device_registry,
             DeviceModel::SoundcoreA3933,
-            HashMap::from([(RequestState::COMMAND, state_update_packet)]),
+            HashMap::from([(RequestState::COMMAND, state_update_packet())]),
             SoundcoreDeviceConfig::default(),
         )
-        .await;
+        .await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_remembers_eq_band_9_and_10_values() {
+        let mut device = test_device().await;
         device
             .assert_set_settings_response(
                 vec![(
@@ -144,4 +156,84 @@ mod tests {
             )
             .await;
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn it_sets_left_and_right_volume_adjustments_separately() {
+        let mut device = test_device().await;
+        device
+            .assert_set_settings_response(
+                vec![(
+                    SettingId::VolumeAdjustmentsRight,
+                    Value::I16Vec(vec![10, 20, 30, 40, 50, 60, 70, 80]),
+                )],
+                vec![packet::outbound::set_equalizer(
+                    &CommonEqualizerConfiguration::<2, 10>::new(
+                        0xfefe,
+                        [
+                            CommonVolumeAdjustments::new([0, 0, 0, 0, 0, 0, 0, 0, 1, 2]),
+                            CommonVolumeAdjustments::new([10, 20, 30, 40, 50, 60, 70, 80, 3, 4]),
+                        ],
+                    ),
+                )],
+            )
+            .await;
+        device.assert_setting_values([
+            (
+                SettingId::VolumeAdjustmentsLeft,
+                Value::I16Vec(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 2]),
+            ),
+            (
+                SettingId::VolumeAdjustmentsRight,
+                Value::I16Vec(vec![10, 20, 30, 40, 50, 60, 70, 80, 3, 4]),
+            ),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn custom_equalizer_profiles_keep_both_channels() {
+        let mut device = test_device().await;
+        device
+            .set_settings(vec![
+                (
+                    SettingId::VolumeAdjustmentsRight,
+                    Value::I16Vec(vec![10, 20, 30, 40, 50, 60, 70, 80]),
+                ),
+                (
+                    SettingId::CustomEqualizerProfile,
+                    Value::ModifiableSelectCommand(ModifiableSelectCommand::Add("Split".into())),
+                ),
+            ])
+            .await;
+        device
+            .set_settings(vec![(
+                SettingId::VolumeAdjustments,
+                Value::I16Vec(vec![0, 0, 0, 0, 0, 0, 0, 0]),
+            )])
+            .await;
+        device.assert_setting_values([(
+            SettingId::CustomEqualizerProfile,
+            Value::OptionalString(None),
+        )]);
+
+        device
+            .set_settings(vec![(
+                SettingId::CustomEqualizerProfile,
+                Value::String("Split".into()),
+            )])
+            .await;
+        device.assert_setting_values([
+            (
+                SettingId::CustomEqualizerProfile,
+                Value::OptionalString(Some("Split".into())),
+            ),
+            (
+                SettingId::VolumeAdjustmentsLeft,
+                Value::I16Vec(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 2]),
+            ),
+            (
+                SettingId::VolumeAdjustmentsRight,
+                Value::I16Vec(vec![10, 20, 30, 40, 50, 60, 70, 80, 3, 4]),
+            ),
+        ]);
+    }
 }
@@ -7,6 +7,7 @@ use nom::{
     number::complete::le_u8,
     sequence::pair,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -34,7 +35,7 @@ use crate::{
 
 // A3933 and A3939
 // Despite EQ being 10 bands, only the first 8 seem to be used?
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3933StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3936::{packets::A3936StateUpdatePacket, state::A3936State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         modules::{
             button_configuration::{
@@ -15,18 +16,24 @@ use crate::devices::soundcore::{
         packet::{
             self,
             inbound::{DualConnectionsDevicePacket, TryToPacket},
-            outbound::{RequestState, ToPacket},
+            outbound::{
+                RequestState, SET_GAMING_MODE_COMMAND, SET_LDAC_COMMAND, SET_TOUCH_TONE_COMMAND,
+                ToPacket,
+            },
         },
-        structures::button_configuration::{
-            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        structures::{
+            GamingMode, Ldac, TouchTone,
+            button_configuration::{
+                ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+            },
         },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3936State,
@@ -67,7 +74,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3936StateUpdatePacket::default().to_packet(),
@@ -76,7 +83,17 @@ soundcore_device!(
                 packet::Command([0x0b, 0x01]),
                 DualConnectionsDevicePacket::demo().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3936StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::A3936SoundModes>();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.ambient_sound_mode_cycle();
+            simulator.auto_power_off();
+            simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+            simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+            simulator.flag::<GamingMode>(SET_GAMING_MODE_COMMAND);
+        })
     },
 );
 
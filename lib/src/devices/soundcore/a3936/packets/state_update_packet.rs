@@ -4,6 +4,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u8,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -31,7 +32,7 @@ use crate::{
 };
 
 // A3936
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3936StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
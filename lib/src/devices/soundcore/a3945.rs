@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::devices::soundcore::{
     a3945::{packets::A3945StateUpdatePacket, state::A3945State},
     common::{
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{
@@ -19,7 +20,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -41,10 +42,14 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3945StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3945StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
 );
 
@@ -5,6 +5,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::le_u8,
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -31,7 +32,7 @@ use crate::{
 
 // A3945 only
 // Despite EQ being 10 bands, only the first 8 seem to be used?
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3945StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -4,22 +4,31 @@ use crate::devices::soundcore::{
     a3947::{packets::A3947StateUpdatePacket, state::A3947State},
     common::{
         self,
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::button_configuration::{
             ButtonConfigurationSettings, ButtonDisableMode, ButtonSettings, COMMON_ACTIONS,
         },
-        packet::outbound::{RequestState, ToPacket},
-        structures::button_configuration::{
-            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        packet::outbound::{
+            RequestState, SET_AUTO_PLAY_PAUSE_COMMAND, SET_LOW_BATTERY_PROMPT_COMMAND,
+            SET_SOUND_LEAK_COMPENSATION_COMMAND, SET_SURROUND_SOUND_COMMAND,
+            SET_TOUCH_LOCK_COMMAND, SET_TOUCH_TONE_COMMAND, SET_WEARING_TONE_COMMAND, ToPacket,
+        },
+        structures::{
+            AutoPlayPause, GamingMode, LowBatteryPrompt, SoundLeakCompensation, SurroundSound,
+            TouchLock, TouchTone, WearingTone,
+            button_configuration::{
+                ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+            },
         },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3947State,
@@ -56,10 +65,25 @@ soundcore_device!(
         builder.case_battery_level(5);
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3947StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3947StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.limit_high_volume();
+            simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+            simulator.flag::<GamingMode>(packets::SET_GAMING_MODE_COMMAND);
+            simulator.flag::<SoundLeakCompensation>(SET_SOUND_LEAK_COMPENSATION_COMMAND);
+            simulator.flag::<SurroundSound>(SET_SURROUND_SOUND_COMMAND);
+            simulator.flag::<AutoPlayPause>(SET_AUTO_PLAY_PAUSE_COMMAND);
+            simulator.flag::<WearingTone>(SET_WEARING_TONE_COMMAND);
+            simulator.flag::<TouchLock>(SET_TOUCH_LOCK_COMMAND);
+            simulator.flag::<LowBatteryPrompt>(SET_LOW_BATTERY_PROMPT_COMMAND);
+            simulator.auto_power_off();
+        })
     },
 );
 
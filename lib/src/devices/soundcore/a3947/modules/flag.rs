@@ -3,10 +3,13 @@ use std::sync::Arc;
 use openscq30_lib_has::Has;
 
 use crate::{
-    devices::soundcore::common::{
-        modules::{ModuleCollection, flag::FlagConfiguration},
-        packet::{self, PacketIOController},
-        structures::GamingMode,
+    devices::soundcore::{
+        a3947,
+        common::{
+            modules::{ModuleCollection, flag::FlagConfiguration},
+            packet::PacketIOController,
+            structures::GamingMode,
+        },
     },
     settings::SettingId,
 };
@@ -20,7 +23,7 @@ where
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::GamingMode,
-                set_command: packet::Command([0x10, 0x85]),
+                set_command: a3947::packets::SET_GAMING_MODE_COMMAND,
                 update_command: None,
             },
         );
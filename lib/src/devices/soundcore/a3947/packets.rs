@@ -3,3 +3,7 @@ mod state_update;
 
 pub use set_equalizer_configuration::*;
 pub use state_update::*;
+
+use crate::devices::soundcore::common::packet;
+
+pub const SET_GAMING_MODE_COMMAND: packet::Command = packet::Command([0x10, 0x85]);
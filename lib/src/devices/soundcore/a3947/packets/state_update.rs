@@ -7,6 +7,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -32,7 +33,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3947StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
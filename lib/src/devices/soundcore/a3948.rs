@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::devices::soundcore::{
     a3948::{packets::inbound::A3948StateUpdatePacket, state::A3948State},
     common::{
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{
@@ -19,7 +20,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -43,10 +44,14 @@ soundcore_device!(
         builder.dual_battery(5);
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3948StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3948StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+        })
     },
 );
 
@@ -5,6 +5,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -27,7 +28,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3949StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3951::{packets::A3951StateUpdatePacket, state::A3951State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         modules::{
             button_configuration::COMMON_SETTINGS as BUTTON_SETTINGS, equalizer,
@@ -17,7 +18,7 @@ use crate::devices::soundcore::{
     },
 };
 
-mod packets;
+pub mod packets;
 mod state;
 
 soundcore_device!(
@@ -73,7 +74,7 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([
+        DemoState::new(HashMap::from([
             (
                 RequestState::COMMAND,
                 A3951StateUpdatePacket::default().to_packet(),
@@ -86,6 +87,11 @@ soundcore_device!(
                 common::packet::outbound::REQUEST_LDAC_STATE_COMMAND,
                 common::packet::inbound::LdacState::default().to_packet(),
             ),
-        ])
+        ]))
+        .simulate::<A3951StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_SETTINGS);
+        })
     },
 );
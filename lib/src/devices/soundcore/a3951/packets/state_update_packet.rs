@@ -7,6 +7,7 @@ use nom::{
     error::{ContextError, ParseError, context},
     number::complete::{le_u8, le_u16},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -33,7 +34,7 @@ use crate::{
 };
 
 // A3951
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3951StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -43,13 +44,17 @@ pub struct A3951StateUpdatePacket {
     pub custom_hear_id: CustomHearId<2, 8>,
     pub button_configuration: ButtonStatusCollection<6>,
     pub sound_modes: SoundModes,
+    #[has(skip)]
     pub side_tone: bool,
     pub wearing_detection: WearingDetection,
     pub touch_tone: TouchTone,
     pub hear_id_eq_preset: Option<u16>,
+    #[has(skip)]
     pub supports_new_battery: bool, // yes if packet is >98, don't parse
-    pub left_new_battery: u8,       // 0 to 9
-    pub right_new_battery: u8,      // 0 to 9
+    #[has(skip)]
+    pub left_new_battery: u8, // 0 to 9
+    #[has(skip)]
+    pub right_new_battery: u8, // 0 to 9
 }
 
 impl Default for A3951StateUpdatePacket {
@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3952::{packets::inbound::A3952StateUpdatePacket, state::A3952State},
     common::{
         self,
+        demo::DemoState,
         device::fetch_state_from_state_update_packet,
         macros::soundcore_device,
         modules::{
@@ -12,17 +13,23 @@ use crate::devices::soundcore::{
             },
             equalizer,
         },
-        packet::outbound::{RequestState, ToPacket},
-        structures::button_configuration::{
-            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        packet::outbound::{
+            RequestState, SET_LDAC_COMMAND, SET_TOUCH_TONE_COMMAND, SET_WEARING_DETECTION_COMMAND,
+            SET_WEARING_TONE_COMMAND, ToPacket,
+        },
+        structures::{
+            Ldac, TouchTone, WearingDetection, WearingTone,
+            button_configuration::{
+                ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+            },
         },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3952State,
@@ -59,10 +66,21 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3952StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3952StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.ambient_sound_mode_cycle();
+            simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+            simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+            simulator.flag::<WearingDetection>(SET_WEARING_DETECTION_COMMAND);
+            simulator.flag::<WearingTone>(SET_WEARING_TONE_COMMAND);
+            simulator.auto_power_off();
+        })
     },
 );
 
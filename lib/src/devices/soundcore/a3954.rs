@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
+use nom_language::error::VerboseError;
+
 use crate::{
     devices::soundcore::{
         a3954::{packets::inbound::A3954StateUpdatePacket, state::A3954State},
         common::{
             self,
+            demo::DemoState,
             macros::soundcore_device,
             modules::{
                 button_configuration::{
@@ -16,10 +19,16 @@ use crate::{
             },
             packet::{
                 inbound::TryToPacket,
-                outbound::{RequestState, ToPacket},
+                outbound::{
+                    RequestState, SET_LDAC_COMMAND, SET_LOW_BATTERY_PROMPT_COMMAND,
+                    SET_SOUND_LEAK_COMPENSATION_COMMAND, SET_WEARING_DETECTION_COMMAND, ToPacket,
+                },
             },
-            structures::button_configuration::{
-                ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+            structures::{
+                Ldac, LowBatteryPrompt, SoundLeakCompensation, WearingDetection,
+                button_configuration::{
+                    ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+                },
             },
         },
     },
@@ -27,9 +36,9 @@ use crate::{
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3954State,
@@ -88,10 +97,58 @@ soundcore_device!(
         builder.a3954_case_serial_number_and_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3954StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3954StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.ambient_sound_mode_cycle();
+            simulator.limit_high_volume();
+            simulator.on(
+                packets::outbound::SET_CASE_FEATURES_COMMAND,
+                |body, state_update| {
+                    let (_, case_features) =
+                        structures::CaseFeatures::take::<VerboseError<_>>(body).ok()?;
+                    state_update.case_features = case_features;
+                    Some(())
+                },
+            );
+            simulator.on(
+                packets::outbound::SET_CASE_LANGUAGE_COMMAND,
+                |body, state_update| {
+                    let (_, case_language) =
+                        structures::CaseLanguage::take::<VerboseError<_>>(body).ok()?;
+                    state_update.case_language = case_language;
+                    Some(())
+                },
+            );
+            simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+            simulator.auto_power_off();
+            simulator.flag::<LowBatteryPrompt>(SET_LOW_BATTERY_PROMPT_COMMAND);
+            simulator.on(
+                packets::outbound::SET_SPATIAL_AUDIO_COMMAND,
+                |body, state_update| {
+                    let (_, spatial_audio) =
+                        structures::SpatialAudio::take::<VerboseError<_>>(body).ok()?;
+                    state_update.spatial_audio = spatial_audio;
+                    Some(())
+                },
+            );
+            simulator.on(
+                packets::outbound::SET_EASY_CHAT_COMMAND,
+                |body, state_update| {
+                    let (_, easy_chat) =
+                        structures::EasyChat::take::<VerboseError<_>>(body).ok()?;
+                    state_update.easy_chat = easy_chat;
+                    Some(())
+                },
+            );
+            simulator.flag::<SoundLeakCompensation>(SET_SOUND_LEAK_COMPENSATION_COMMAND);
+            simulator.flag::<WearingDetection>(SET_WEARING_DETECTION_COMMAND);
+        })
     },
 );
 
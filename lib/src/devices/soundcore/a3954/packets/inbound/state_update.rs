@@ -8,6 +8,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -34,6 +35,7 @@ use crate::{
     },
 };
 
+#[derive(Debug, Has)]
 pub struct A3954StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub battery: DualBattery,
@@ -51,6 +53,7 @@ pub struct A3954StateUpdatePacket {
     pub air_pressure: a3954::structures::AirPressure,
     pub low_battery_prompt: LowBatteryPrompt,
     pub ldac: Ldac,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
     pub auto_power_off: AutoPowerOff,
     pub limit_high_volume: LimitHighVolume,
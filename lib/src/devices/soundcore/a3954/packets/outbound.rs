@@ -5,20 +5,25 @@ use crate::devices::soundcore::{
     common::{self, packet},
 };
 
+pub const SET_CASE_FEATURES_COMMAND: packet::Command = packet::Command([7, 135]);
+pub const SET_CASE_LANGUAGE_COMMAND: packet::Command = packet::Command([7, 138]);
+pub const SET_EASY_CHAT_COMMAND: packet::Command = packet::Command([16, 157]);
+pub const SET_SPATIAL_AUDIO_COMMAND: packet::Command = packet::Command([16, 129]);
+
 pub fn set_case_features(case_features: &a3954::structures::CaseFeatures) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([7, 135]), case_features.bytes().collect())
+    packet::Outbound::new(SET_CASE_FEATURES_COMMAND, case_features.bytes().collect())
 }
 
 pub fn set_case_language(case_language: &a3954::structures::CaseLanguage) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([7, 138]), case_language.bytes().collect())
+    packet::Outbound::new(SET_CASE_LANGUAGE_COMMAND, case_language.bytes().collect())
 }
 
 pub fn set_easy_chat(easy_chat: &a3954::structures::EasyChat) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([16, 157]), easy_chat.bytes().collect())
+    packet::Outbound::new(SET_EASY_CHAT_COMMAND, easy_chat.bytes().collect())
 }
 
 pub fn set_spatial_audio(spatial_audio: &a3954::structures::SpatialAudio) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([16, 129]), spatial_audio.bytes().collect())
+    packet::Outbound::new(SET_SPATIAL_AUDIO_COMMAND, spatial_audio.bytes().collect())
 }
 
 pub fn set_equalizer_configuration<
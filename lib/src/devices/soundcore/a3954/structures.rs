@@ -389,6 +389,22 @@ pub struct EasyChat {
 }
 
 impl EasyChat {
+    pub fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "easy chat",
+            map(
+                (take_bool, EasyChatWaitTime::take),
+                |(is_enabled, wait_time)| Self {
+                    is_enabled,
+                    wait_time,
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+
     pub fn bytes(&self) -> impl Iterator<Item = u8> {
         std::iter::once(u8::from(self.is_enabled)).chain(self.wait_time.bytes())
     }
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::devices::soundcore::common::{
     self,
+    demo::DemoState,
     macros::soundcore_device,
     modules::{
         button_configuration::{
@@ -11,17 +12,22 @@ use crate::devices::soundcore::common::{
     },
     packet::{
         inbound::TryToPacket,
-        outbound::{RequestState, ToPacket},
+        outbound::{
+            RequestState, SET_LOW_BATTERY_PROMPT_COMMAND, SET_TOUCH_TONE_COMMAND, ToPacket,
+        },
     },
-    structures::button_configuration::{
-        ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+    structures::{
+        LowBatteryPrompt, TouchTone,
+        button_configuration::{
+            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     state::A3955State,
@@ -69,10 +75,37 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             packets::inbound::A3955StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<packets::inbound::A3955StateUpdatePacket>(
+            RequestState::COMMAND,
+            |simulator| {
+                simulator.sound_modes_v2::<structures::SoundModes>();
+                simulator.flag::<structures::AncPersonalizedToEarCanal>(
+                    packets::outbound::SET_ANC_PERSONALIZED_TO_EAR_CANAL_COMMAND,
+                );
+                simulator.equalizer();
+                simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+                simulator.ambient_sound_mode_cycle();
+                simulator.limit_high_volume();
+                simulator.on(
+                    packets::outbound::SET_IMMERSIVE_EXPERIENCE_COMMAND,
+                    |body, state_update| {
+                        let [immersive_experience] = *body else {
+                            return None;
+                        };
+                        state_update.immersive_experience =
+                            structures::ImmersiveExperience::from_repr(immersive_experience)?;
+                        Some(())
+                    },
+                );
+                simulator.auto_power_off();
+                simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+                simulator.flag::<LowBatteryPrompt>(SET_LOW_BATTERY_PROMPT_COMMAND);
+            },
+        )
     },
 );
 
@@ -3,15 +3,21 @@ use crate::devices::soundcore::{
     common::packet,
 };
 
+pub const SET_ANC_PERSONALIZED_TO_EAR_CANAL_COMMAND: packet::Command = packet::Command([3, 144]);
+pub const SET_IMMERSIVE_EXPERIENCE_COMMAND: packet::Command = packet::Command([18, 129]);
+
 pub fn set_anc_personalized_to_hear_canal(
     anc_personalized_to_ear_canal: &AncPersonalizedToEarCanal,
 ) -> packet::Outbound {
     packet::Outbound::new(
-        packet::Command([3, 144]),
+        SET_ANC_PERSONALIZED_TO_EAR_CANAL_COMMAND,
         anc_personalized_to_ear_canal.bytes().to_vec(),
     )
 }
 
 pub fn set_immersive_experience(immersive_experience: ImmersiveExperience) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([18, 129]), vec![immersive_experience as u8])
+    packet::Outbound::new(
+        SET_IMMERSIVE_EXPERIENCE_COMMAND,
+        vec![immersive_experience as u8],
+    )
 }
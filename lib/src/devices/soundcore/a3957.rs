@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::devices::soundcore::common::{
     self,
+    demo::DemoState,
     macros::soundcore_device,
     modules::{
         button_configuration::{
@@ -11,17 +12,25 @@ use crate::devices::soundcore::common::{
     },
     packet::{
         inbound::TryToPacket,
-        outbound::{RequestState, ToPacket},
+        outbound::{
+            RequestState, SET_LDAC_COMMAND, SET_LOW_BATTERY_PROMPT_COMMAND,
+            SET_SOUND_LEAK_COMPENSATION_COMMAND, SET_TOUCH_TONE_COMMAND,
+            SET_WEARING_DETECTION_COMMAND, SET_WEARING_TONE_COMMAND, ToPacket,
+        },
     },
-    structures::button_configuration::{
-        ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+    structures::{
+        GamingMode, Ldac, LowBatteryPrompt, SoundLeakCompensation, TouchTone, WearingDetection,
+        WearingTone,
+        button_configuration::{
+            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     state::A3957State,
@@ -81,10 +90,28 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             packets::inbound::A3957StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<packets::inbound::A3957StateUpdatePacket>(
+            RequestState::COMMAND,
+            |simulator| {
+                simulator.sound_modes_v2::<structures::SoundModes>();
+                simulator.equalizer();
+                simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+                simulator.ambient_sound_mode_cycle();
+                simulator.limit_high_volume();
+                simulator.flag::<Ldac>(SET_LDAC_COMMAND);
+                simulator.auto_power_off();
+                simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+                simulator.flag::<LowBatteryPrompt>(SET_LOW_BATTERY_PROMPT_COMMAND);
+                simulator.flag::<WearingTone>(SET_WEARING_TONE_COMMAND);
+                simulator.flag::<WearingDetection>(SET_WEARING_DETECTION_COMMAND);
+                simulator.flag::<SoundLeakCompensation>(SET_SOUND_LEAK_COMPENSATION_COMMAND);
+                simulator.flag::<GamingMode>(packets::outbound::SET_GAMING_MODE_COMMAND);
+            },
+        )
     },
 );
 
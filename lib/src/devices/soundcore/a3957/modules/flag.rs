@@ -2,10 +2,10 @@ use std::sync::Arc;
 
 use crate::{
     devices::soundcore::{
-        a3957::state::A3957State,
+        a3957::{self, state::A3957State},
         common::{
             modules::{ModuleCollection, flag::FlagConfiguration},
-            packet::{self, PacketIOController},
+            packet::PacketIOController,
             structures::GamingMode,
         },
     },
@@ -18,7 +18,7 @@ impl ModuleCollection<A3957State> {
             packet_io,
             FlagConfiguration {
                 setting_id: SettingId::GamingMode,
-                set_command: packet::Command([16, 133]),
+                set_command: a3957::packets::outbound::SET_GAMING_MODE_COMMAND,
                 update_command: None,
             },
         );
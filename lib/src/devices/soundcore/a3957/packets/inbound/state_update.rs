@@ -7,6 +7,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -29,7 +30,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3957StateUpdatePacket {
     pub tws_status: common::structures::TwsStatus,
     pub dual_battery: common::structures::DualBattery,
@@ -47,6 +48,7 @@ pub struct A3957StateUpdatePacket {
     pub wearing_tone: common::structures::WearingTone,
     pub low_battery_prompt: common::structures::LowBatteryPrompt,
     pub ldac: Ldac,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
     pub auto_power_off: common::structures::AutoPowerOff,
     pub limit_high_volume: common::structures::LimitHighVolume,
@@ -0,0 +1,3 @@
+use crate::devices::soundcore::common::packet;
+
+pub const SET_GAMING_MODE_COMMAND: packet::Command = packet::Command([16, 133]);
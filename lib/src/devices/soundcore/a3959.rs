@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::devices::soundcore::common::{
     self,
+    demo::DemoState,
     macros::soundcore_device,
     modules::{
         button_configuration::{
@@ -11,17 +12,23 @@ use crate::devices::soundcore::common::{
     },
     packet::{
         inbound::TryToPacket,
-        outbound::{RequestState, ToPacket},
+        outbound::{
+            RequestState, SET_GAMING_MODE_COMMAND, SET_LOW_BATTERY_PROMPT_COMMAND,
+            SET_TOUCH_TONE_COMMAND, ToPacket,
+        },
     },
-    structures::button_configuration::{
-        ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+    structures::{
+        GamingMode, LowBatteryPrompt, TouchTone,
+        button_configuration::{
+            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     state::A3959State,
@@ -64,10 +71,23 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             packets::inbound::A3959StateUpdate::default().to_packet(),
-        )])
+        )]))
+        .simulate::<packets::inbound::A3959StateUpdate>(
+            RequestState::COMMAND,
+            |simulator| {
+                simulator.sound_modes_v2::<structures::SoundModes>();
+                simulator.equalizer();
+                simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+                simulator.ambient_sound_mode_cycle();
+                simulator.auto_power_off();
+                simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+                simulator.flag::<LowBatteryPrompt>(SET_LOW_BATTERY_PROMPT_COMMAND);
+                simulator.flag::<GamingMode>(SET_GAMING_MODE_COMMAND);
+            },
+        )
     },
 );
 
@@ -385,6 +405,32 @@ mod tests {
             .await;
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn a3958_with_faker_state_update_packet() {
+        let device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA3958,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a3958.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+
+        device.assert_setting_values([
+            (SettingId::BatteryLevelLeft, "7/10".into()),
+            (SettingId::BatteryLevelRight, "6/10".into()),
+            (SettingId::FirmwareVersionLeft, "01.49".into()),
+            (SettingId::FirmwareVersionRight, "01.47".into()),
+            (SettingId::SerialNumber, "395858FD6C5B1820".into()),
+            (SettingId::AmbientSoundMode, "NoiseCanceling".into()),
+            (SettingId::TouchTone, true.into()),
+            (SettingId::DualConnections, true.into()),
+        ]);
+        assert_eq!(device.inner().setting(&SettingId::GamingMode), None);
+    }
+
     #[tokio::test(start_paused = true)]
     async fn has_no_gaming_mode_on_old_firmware() {
         let device = TestSoundcoreDevice::new(
@@ -5,6 +5,7 @@ use nom::{
     combinator::{all_consuming, map},
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -30,7 +31,7 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3959StateUpdate {
     pub tws_status: common::structures::TwsStatus,
     pub dual_battery: common::structures::DualBattery,
@@ -43,7 +44,9 @@ pub struct A3959StateUpdate {
     pub touch_tone: common::structures::TouchTone,
     pub auto_power_off: common::structures::AutoPowerOff,
     pub low_battery_prompt: LowBatteryPrompt,
+    #[has(maybe)]
     pub gaming_mode: Option<GamingMode>,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
 }
 
@@ -4,6 +4,7 @@ use crate::devices::soundcore::{
     a3968::{packets::inbound::A3968StateUpdatePacket, state::A3968State},
     common::{
         self,
+        demo::DemoState,
         macros::soundcore_device,
         modules::{
             auto_power_off::AutoPowerOffDuration,
@@ -14,18 +15,23 @@ use crate::devices::soundcore::{
         },
         packet::{
             inbound::TryToPacket,
-            outbound::{RequestState, ToPacket},
+            outbound::{
+                RequestState, SET_SURROUND_SOUND_COMMAND, SET_TOUCH_TONE_COMMAND, ToPacket,
+            },
         },
-        structures::button_configuration::{
-            ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+        structures::{
+            SurroundSound, TouchTone,
+            button_configuration::{
+                ActionKind, Button, ButtonParseSettings, ButtonPressKind, EnabledFlagKind,
+            },
         },
     },
 };
 
 mod modules;
-mod packets;
+pub mod packets;
 mod state;
-mod structures;
+pub mod structures;
 
 soundcore_device!(
     A3968State,
@@ -62,10 +68,19 @@ soundcore_device!(
         builder.serial_number_and_dual_firmware_version();
     },
     {
-        HashMap::from([(
+        DemoState::new(HashMap::from([(
             RequestState::COMMAND,
             A3968StateUpdatePacket::default().to_packet(),
-        )])
+        )]))
+        .simulate::<A3968StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes_v2::<structures::SoundModes>();
+            simulator.equalizer();
+            simulator.button_configuration(BUTTON_CONFIGURATION_SETTINGS);
+            simulator.ambient_sound_mode_cycle_tws();
+            simulator.auto_power_off();
+            simulator.flag::<SurroundSound>(SET_SURROUND_SOUND_COMMAND);
+            simulator.flag::<TouchTone>(SET_TOUCH_TONE_COMMAND);
+        })
     },
 );
 
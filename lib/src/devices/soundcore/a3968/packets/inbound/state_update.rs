@@ -5,6 +5,7 @@ use nom::{
     combinator::map,
     error::{ContextError, ParseError, context},
 };
+use openscq30_lib_macros::Has;
 use tokio::sync::watch;
 
 use crate::{
@@ -43,7 +44,7 @@ use crate::{
 ///            EQ "set" command, so EQ is deferred to a follow-up once that's reverse-engineered.
 ///   117..124 sound-mode block (A3968 format: ambient sound mode at offset 117)
 ///   124..143 reserved
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
 pub struct A3968StateUpdatePacket {
     pub tws_status: TwsStatus,
     pub dual_battery: DualBattery,
@@ -55,6 +56,7 @@ pub struct A3968StateUpdatePacket {
     pub button_configuration: ButtonStatusCollection<6>,
     pub ambient_sound_mode_cycle: AmbientSoundModeCycleTws,
     pub sound_modes: a3968::structures::SoundModes,
+    #[has(skip)]
     pub dual_connections_enabled: bool,
     pub touch_tone: TouchTone,
     pub auto_power_off: AutoPowerOff,
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::devices::soundcore::{
+    a6611::{packets::inbound::A6611StateUpdatePacket, state::A6611State},
+    common::{
+        demo::DemoState,
+        device::fetch_state_from_state_update_packet,
+        macros::soundcore_device,
+        modules::{
+            case_battery_level::CaseBatteryLevelConfiguration,
+            dual_battery::DualBatteryConfiguration, equalizer,
+        },
+        packet::outbound::{RequestState, ToPacket},
+    },
+};
+
+pub mod packets;
+mod state;
+
+soundcore_device!(
+    A6611State,
+    async |packet_io| {
+        fetch_state_from_state_update_packet::<A6611State, A6611StateUpdatePacket>(packet_io).await
+    },
+    async |builder| {
+        builder.module_collection().add_state_update();
+        builder
+            .equalizer_with_drc_tws(equalizer::common_settings())
+            .await;
+
+        builder.touch_tone();
+        builder.low_battery_prompt();
+
+        builder.serial_number_and_dual_firmware_version();
+        builder.tws_status();
+        builder.dual_battery_custom(DualBatteryConfiguration {
+            max_level: 10,
+            level_offset: 1,
+        });
+        builder.case_battery_level_custom(CaseBatteryLevelConfiguration {
+            max_level: 10,
+            level_offset: 1,
+        });
+    },
+    {
+        DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            A6611StateUpdatePacket::default().to_packet(),
+        )]))
+        .simulate::<A6611StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.equalizer();
+        })
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        DeviceModel,
+        devices::soundcore::common::{
+            device::{SoundcoreDeviceConfig, test_utils::TestSoundcoreDevice},
+            packet,
+        },
+        settings::{SettingId, Value},
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn new_with_faker_state_update_packet() {
+        let device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA6611,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a6611.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        device.assert_setting_values([
+            (SettingId::BatteryLevelLeft, "8/10".into()),
+            (SettingId::BatteryLevelRight, "9/10".into()),
+            (SettingId::CaseBatteryLevel, "9/10".into()),
+            (SettingId::FirmwareVersionLeft, "01.50".into()),
+            (SettingId::FirmwareVersionRight, "01.50".into()),
+            (SettingId::SerialNumber, "6611F49D8A27565C".into()),
+            (SettingId::TouchTone, true.into()),
+            (SettingId::LowBatteryPrompt, false.into()),
+            (
+                SettingId::VolumeAdjustments,
+                Value::from(vec![30i16, 30, -20, -20, 0, 20, 30, 40, 0, -120]),
+            ),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn set_touch_tone() {
+        let mut device = TestSoundcoreDevice::new(
+            super::device_registry,
+            DeviceModel::SoundcoreA6611,
+            HashMap::from([(
+                packet::Command([1, 1]),
+                TestSoundcoreDevice::faker_state_update("a6611.toml"),
+            )]),
+            SoundcoreDeviceConfig::default(),
+        )
+        .await;
+        device
+            .assert_set_settings_response(
+                vec![(SettingId::TouchTone, false.into())],
+                vec![packet::Outbound::new(packet::Command([1, 0x83]), vec![0])],
+            )
+            .await;
+    }
+}
@@ -0,0 +1,252 @@
+use async_trait::async_trait;
+use nom::{
+    IResult, Parser,
+    bytes::complete::take,
+    combinator::map,
+    error::{ContextError, ParseError, context},
+};
+use openscq30_lib_macros::Has;
+use tokio::sync::watch;
+
+use crate::{
+    device,
+    devices::soundcore::{
+        a6611::state::A6611State,
+        common::{
+            modules::ModuleCollection,
+            packet::{
+                self, Command,
+                inbound::{FromPacketBody, TryToPacket},
+                outbound::ToPacket,
+            },
+            packet_manager::PacketHandler,
+            structures::{
+                BatteryLevel, CaseBatteryLevel, CommonEqualizerConfiguration, DualBattery,
+                DualFirmwareVersion, LowBatteryPrompt, SerialNumber, SingleBattery, TouchTone,
+                TwsStatus,
+                button_configuration::{
+                    ActionKind, ActionStatus, ButtonParseSettings, ButtonStatus,
+                    ButtonStatusCollection, EnabledFlagKind,
+                },
+            },
+        },
+    },
+};
+
+// Left double tap, right double tap, left triple tap, right triple tap. The action ids (0xd for the
+// faker's left double tap) don't match the actions of any other device, so they are carried through
+// without being exposed as button settings until they're mapped.
+const BUTTON_PARSE_SETTINGS: [ButtonParseSettings; 4] = [ButtonParseSettings {
+    enabled_flag_kind: EnabledFlagKind::None,
+    action_kind: ActionKind::TwsLowBits,
+}; 4];
+
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
+pub struct A6611StateUpdatePacket {
+    pub tws_status: TwsStatus,
+    pub battery: DualBattery,
+    pub firmware_version: DualFirmwareVersion,
+    pub serial_number: SerialNumber,
+    pub equalizer_configuration: CommonEqualizerConfiguration<1, 10>,
+    pub touch_tone: TouchTone,
+    pub low_battery_prompt: LowBatteryPrompt,
+    pub case_battery: CaseBatteryLevel,
+    pub buttons: ButtonStatusCollection<4>,
+}
+
+impl Default for A6611StateUpdatePacket {
+    fn default() -> Self {
+        Self {
+            tws_status: Default::default(),
+            battery: Default::default(),
+            firmware_version: Default::default(),
+            serial_number: Default::default(),
+            equalizer_configuration: Default::default(),
+            touch_tone: Default::default(),
+            low_battery_prompt: Default::default(),
+            case_battery: Default::default(),
+            buttons: ButtonStatusCollection::new(
+                [ButtonStatus {
+                    enabled: None,
+                    action: ActionStatus::Tws {
+                        connected: 0,
+                        disconnected: 0,
+                    },
+                }; 4],
+            ),
+        }
+    }
+}
+
+impl FromPacketBody for A6611StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn take<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        input: &'a [u8],
+    ) -> IResult<&'a [u8], Self, E> {
+        context(
+            "a6611 state update packet",
+            map(
+                (
+                    TwsStatus::take,
+                    // Unlike most devices, charging status is not included
+                    BatteryLevel::take,
+                    BatteryLevel::take,
+                    DualFirmwareVersion::take,
+                    SerialNumber::take,
+                    CommonEqualizerConfiguration::take,
+                    take(10usize), // right channel volume adjustments, always 255
+                    take(1usize),  // unknown
+                    ButtonStatusCollection::take(BUTTON_PARSE_SETTINGS),
+                    // unknown: next alarm, sleep mode. The faker's notes on these are guesses, and
+                    // alarms are read with their own command anyway.
+                    take(18usize),
+                    TouchTone::take,
+                    LowBatteryPrompt::take,
+                    take(49usize), // unknown: 77-125, includes notification settings
+                    CaseBatteryLevel::take,
+                    take(4usize), // padding
+                ),
+                |(
+                    tws_status,
+                    left_battery_level,
+                    right_battery_level,
+                    firmware_version,
+                    serial_number,
+                    equalizer_configuration,
+                    _right_volume_adjustments,
+                    _unknown0,
+                    buttons,
+                    _unknown1,
+                    touch_tone,
+                    low_battery_prompt,
+                    _unknown2,
+                    case_battery,
+                    _padding,
+                )| Self {
+                    tws_status,
+                    battery: DualBattery {
+                        left: SingleBattery {
+                            level: left_battery_level,
+                            is_charging: Default::default(),
+                        },
+                        right: SingleBattery {
+                            level: right_battery_level,
+                            is_charging: Default::default(),
+                        },
+                    },
+                    firmware_version,
+                    serial_number,
+                    equalizer_configuration,
+                    touch_tone,
+                    low_battery_prompt,
+                    case_battery,
+                    buttons,
+                },
+            ),
+        )
+        .parse_complete(input)
+    }
+}
+
+impl ToPacket for A6611StateUpdatePacket {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        packet::inbound::STATE_COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        self.tws_status
+            .bytes()
+            .into_iter()
+            .chain([self.battery.left.level.0, self.battery.right.level.0])
+            .chain(self.firmware_version.bytes())
+            .chain(self.serial_number.bytes())
+            .chain(self.equalizer_configuration.bytes())
+            .chain([255; 10]) // right channel volume adjustments
+            .chain([0]) // unknown
+            .chain(self.buttons.bytes(BUTTON_PARSE_SETTINGS))
+            .chain([0; 18]) // unknown
+            .chain(self.touch_tone.bytes())
+            .chain(self.low_battery_prompt.bytes())
+            .chain([0; 49]) // unknown
+            .chain(self.case_battery.bytes())
+            .chain([0; 4]) // padding
+            .collect()
+    }
+}
+
+struct StateUpdatePacketHandler;
+
+#[async_trait]
+impl PacketHandler<A6611State> for StateUpdatePacketHandler {
+    async fn handle_packet(
+        &self,
+        state: &watch::Sender<A6611State>,
+        packet: &packet::Inbound,
+    ) -> device::Result<()> {
+        let packet: A6611StateUpdatePacket = packet.try_to_packet()?;
+        state.send_modify(|state| *state = packet.into());
+        Ok(())
+    }
+}
+
+impl ModuleCollection<A6611State> {
+    pub fn add_state_update(&mut self) {
+        self.packet_handlers.set_handler(
+            packet::inbound::STATE_COMMAND,
+            Box::new(StateUpdatePacketHandler {}),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom_language::error::VerboseError;
+
+    use crate::devices::soundcore::common::{
+        device::test_utils::TestSoundcoreDevice, packet::inbound::TryToPacket,
+    };
+
+    use super::*;
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let bytes = A6611StateUpdatePacket::default()
+            .to_packet()
+            .bytes_with_checksum();
+        let (_, packet) = packet::Inbound::take_with_checksum::<VerboseError<_>>(&bytes).unwrap();
+        let _: A6611StateUpdatePacket = packet.try_to_packet().unwrap();
+    }
+
+    #[test]
+    fn parses_faker_buttons() {
+        let packet: A6611StateUpdatePacket = TestSoundcoreDevice::faker_state_update("a6611.toml")
+            .try_to_packet()
+            .unwrap();
+        let actions = packet.buttons.0.map(|status| status.action);
+        assert_eq!(
+            actions,
+            [
+                ActionStatus::Tws {
+                    connected: 0xd,
+                    disconnected: 0xd,
+                },
+                ActionStatus::Tws {
+                    connected: 0x3,
+                    disconnected: 0x3,
+                },
+                ActionStatus::Tws {
+                    connected: 0x1,
+                    disconnected: 0x1,
+                },
+                ActionStatus::Tws {
+                    connected: 0,
+                    disconnected: 0,
+                },
+            ],
+        );
+        assert_eq!(packet.to_packet().body[53..57], [0xdd, 0x33, 0x11, 0x00]);
+    }
+}
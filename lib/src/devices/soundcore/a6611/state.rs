@@ -0,0 +1,36 @@
+use openscq30_lib_macros::Has;
+
+use crate::devices::soundcore::{
+    a6611::packets::inbound::A6611StateUpdatePacket,
+    common::structures::{
+        CaseBatteryLevel, CommonEqualizerConfiguration, DualBattery, DualFirmwareVersion,
+        LowBatteryPrompt, SerialNumber, TouchTone, TwsStatus,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Has)]
+pub struct A6611State {
+    tws_status: TwsStatus,
+    battery: DualBattery,
+    case_battery: CaseBatteryLevel,
+    firmware_version: DualFirmwareVersion,
+    serial_number: SerialNumber,
+    equalizer_configuration: CommonEqualizerConfiguration<1, 10>,
+    touch_tone: TouchTone,
+    low_battery_prompt: LowBatteryPrompt,
+}
+
+impl From<A6611StateUpdatePacket> for A6611State {
+    fn from(value: A6611StateUpdatePacket) -> Self {
+        Self {
+            tws_status: value.tws_status,
+            battery: value.battery,
+            case_battery: value.case_battery,
+            firmware_version: value.firmware_version,
+            serial_number: value.serial_number,
+            equalizer_configuration: value.equalizer_configuration,
+            touch_tone: value.touch_tone,
+            low_battery_prompt: value.low_battery_prompt,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashSet,
     panic::Location,
     sync::{Arc, Mutex},
 };
@@ -12,44 +12,63 @@ use tokio::sync::{mpsc, watch};
 
 use crate::{
     api::connection::{
-        self, ConnectionDescriptor, ConnectionStatus, RfcommBackend, RfcommConnection,
+        self, ConnectionDescriptor, ConnectionStatus, GattBackend, GattConnection, GattService,
+        RfcommBackend, RfcommConnection, Transport, TransportBackend,
     },
     connection::RfcommServiceSelectionStrategy,
     devices::{
         DeviceModel,
         soundcore::common::{device::SoundcoreDeviceConfig, packet},
     },
+    util::AbortOnDropHandle,
 };
 
-use super::packet::Command;
+mod events;
+mod simulation;
 
+pub use events::*;
+pub use simulation::*;
+
+/// Connections made through the same registry share state, so changes persist across reconnects.
 pub struct DemoConnectionRegistry {
     model: DeviceModel,
-    packet_responses: HashMap<Command, packet::Inbound>,
+    state: Arc<Mutex<DemoState>>,
     config: SoundcoreDeviceConfig,
 }
 
 impl DemoConnectionRegistry {
     pub fn new(
         model: DeviceModel,
-        packet_responses: HashMap<Command, packet::Inbound>,
+        state: impl Into<DemoState>,
         config: SoundcoreDeviceConfig,
     ) -> Self {
         Self {
             model,
-            packet_responses,
+            state: Arc::new(Mutex::new(state.into())),
             config,
         }
     }
+
+    /// Serves the demo device over whichever transport the real device uses.
+    pub fn into_transport_backend(self) -> TransportBackend {
+        match self.config.transport {
+            Transport::Rfcomm(_) => TransportBackend::Rfcomm(Arc::new(self)),
+            Transport::Gatt(_) => TransportBackend::Gatt(Arc::new(self)),
+        }
+    }
+
+    fn descriptors(&self) -> HashSet<ConnectionDescriptor> {
+        HashSet::from([ConnectionDescriptor {
+            name: self.model.translate().clone(),
+            mac_address: self.model.demo_mac_address(),
+        }])
+    }
 }
 
 #[async_trait]
 impl RfcommBackend for DemoConnectionRegistry {
     async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
-        Ok(HashSet::from([ConnectionDescriptor {
-            name: self.model.translate().clone(),
-            mac_address: self.model.demo_mac_address(),
-        }]))
+        Ok(self.descriptors())
     }
 
     async fn connect(
@@ -58,36 +77,54 @@ impl RfcommBackend for DemoConnectionRegistry {
         _select_uuid: RfcommServiceSelectionStrategy,
     ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
         Ok(Arc::new(DemoConnection::new(
-            self.packet_responses.to_owned(),
+            self.state.clone(),
+            self.config,
+        )))
+    }
+}
+
+#[async_trait]
+impl GattBackend for DemoConnectionRegistry {
+    async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+        Ok(self.descriptors())
+    }
+
+    async fn connect(
+        &self,
+        _mac_address: MacAddr6,
+        _service: GattService,
+    ) -> connection::Result<Arc<dyn GattConnection + Send + Sync>> {
+        Ok(Arc::new(DemoConnection::new(
+            self.state.clone(),
             self.config,
         )))
     }
 }
 
 pub struct DemoConnection {
-    _connection_status_sender: watch::Sender<ConnectionStatus>,
-    connection_status_receiver: Mutex<Option<watch::Receiver<ConnectionStatus>>>,
+    connection_status_sender: watch::Sender<ConnectionStatus>,
     packet_sender: mpsc::Sender<Vec<u8>>,
     packet_receiver: Mutex<Option<mpsc::Receiver<Vec<u8>>>>,
-    packet_responses: HashMap<Command, packet::Inbound>,
+    state: Arc<Mutex<DemoState>>,
     config: SoundcoreDeviceConfig,
+    _events_handle: AbortOnDropHandle<()>,
 }
 
 impl DemoConnection {
-    pub fn new(
-        packet_responses: HashMap<Command, packet::Inbound>,
-        config: SoundcoreDeviceConfig,
-    ) -> Self {
-        let (connection_status_sender, connection_status_receiver) =
-            watch::channel(ConnectionStatus::Connected);
+    pub fn new(state: Arc<Mutex<DemoState>>, config: SoundcoreDeviceConfig) -> Self {
+        let (connection_status_sender, _) = watch::channel(ConnectionStatus::Connected);
         let (packet_sender, packet_receiver) = mpsc::channel(10);
+        let events = state.lock().unwrap().events().clone();
+        let events_handle = AbortOnDropHandle::new(tokio::spawn(
+            events.play(packet_sender.clone(), config.checksum_kind),
+        ));
         Self {
-            _connection_status_sender: connection_status_sender,
-            connection_status_receiver: Mutex::new(Some(connection_status_receiver)),
+            connection_status_sender,
             packet_sender,
             packet_receiver: Mutex::new(Some(packet_receiver)),
-            packet_responses,
+            state,
             config,
+            _events_handle: events_handle,
         }
     }
 }
@@ -108,17 +145,12 @@ impl RfcommConnection for DemoConnection {
             packet::Outbound::take::<VerboseError<_>>(self.config.checksum_kind)(data)
                 .expect("we should never send invalid packets");
 
-        if let Some(response) = self.packet_responses.get(&packet.command) {
+        let responses = self.state.lock().unwrap().handle(&packet);
+        for response in responses {
             self.packet_sender
                 .send(response.bytes(self.config.checksum_kind))
                 .await
                 .unwrap();
-        } else {
-            // ACK
-            self.packet_sender
-                .send(packet.ack().bytes(self.config.checksum_kind))
-                .await
-                .unwrap();
         }
         Ok(())
     }
@@ -138,10 +170,22 @@ impl RfcommConnection for DemoConnection {
     }
 
     fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
-        self.connection_status_receiver
-            .lock()
-            .unwrap()
-            .take()
-            .unwrap()
+        self.connection_status_sender.subscribe()
+    }
+}
+
+// Packets are the same regardless of transport, so GATT is handled the same as RFCOMM
+#[async_trait]
+impl GattConnection for DemoConnection {
+    async fn write(&self, data: &[u8]) -> connection::Result<()> {
+        RfcommConnection::write(self, data).await
+    }
+
+    fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+        RfcommConnection::read_channel(self)
+    }
+
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        RfcommConnection::connection_status(self)
     }
 }
@@ -0,0 +1,290 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::devices::soundcore::common::{
+    modules::sound_modes_v2::ToPacketBody,
+    packet::{self, ChecksumKind, outbound::ToPacket},
+    structures::{self, BatteryLevel, HostDevice, IsBatteryCharging},
+};
+
+/// Inbound packets that a demo device sends without being asked, such as battery level changes or earbuds being put
+/// in their case.
+#[derive(Debug, Clone, Default)]
+pub struct DemoEvents {
+    events: Vec<(Duration, packet::Inbound)>,
+    repeat: bool,
+}
+
+impl DemoEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends `packet` after waiting for `delay` since the previous event was sent, or since connecting if this is the
+    /// first event.
+    pub fn then(mut self, delay: Duration, packet: packet::Inbound) -> Self {
+        self.events.push((delay, packet));
+        self
+    }
+
+    pub fn chain(mut self, other: Self) -> Self {
+        self.events.extend(other.events);
+        self
+    }
+
+    /// Starts over from the first event once the last event is sent.
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Battery level starting at `max_level` immediately, decreasing by one every `interval` until it reaches 0.
+    pub fn single_battery_drain(max_level: u8, interval: Duration) -> Self {
+        Self::battery_drain(max_level, interval, |level| {
+            packet::inbound::SingleBatteryLevel { level }.to_packet()
+        })
+    }
+
+    /// Battery level of both earbuds starting at `max_level` immediately, decreasing by one every `interval` until
+    /// it reaches 0.
+    pub fn dual_battery_drain(max_level: u8, interval: Duration) -> Self {
+        Self::battery_drain(max_level, interval, |level| {
+            packet::inbound::DualBatteryLevel {
+                left: level,
+                right: level,
+            }
+            .to_packet()
+        })
+    }
+
+    fn battery_drain(
+        max_level: u8,
+        interval: Duration,
+        to_packet: impl Fn(BatteryLevel) -> packet::Inbound,
+    ) -> Self {
+        (0..=max_level)
+            .rev()
+            .enumerate()
+            .fold(Self::new(), |events, (i, level)| {
+                let delay = if i == 0 { Duration::ZERO } else { interval };
+                events.then(delay, to_packet(BatteryLevel(level)))
+            })
+    }
+
+    /// Both earbuds being put in the case after `delay`, charging for `duration`, and then being taken back out.
+    pub fn case_cycle(delay: Duration, duration: Duration) -> Self {
+        let charging = |is_charging: IsBatteryCharging| {
+            packet::inbound::DualBatteryCharging {
+                left: is_charging,
+                right: is_charging,
+            }
+            .to_packet()
+        };
+        Self::new()
+            .then(delay, charging(IsBatteryCharging::Yes))
+            .then(duration, charging(IsBatteryCharging::No))
+    }
+
+    /// The earbuds losing their connection to each other after `delay`, and reconnecting after `duration`.
+    pub fn tws_disconnect(delay: Duration, duration: Duration) -> Self {
+        let tws_status = |is_connected: bool| {
+            packet::inbound::TwsStatus(structures::TwsStatus {
+                host_device: HostDevice::Left,
+                is_connected,
+            })
+            .to_packet()
+        };
+        Self::new()
+            .then(delay, tws_status(false))
+            .then(duration, tws_status(true))
+    }
+
+    /// Wind noise being detected after `delay`, and no longer being detected after `duration`. Wind noise is reported
+    /// along with the rest of the sound modes, so `sound_modes` builds the model's sound modes with wind noise
+    /// detected or not.
+    pub fn wind_noise_detected<SoundModesT: ToPacketBody>(
+        delay: Duration,
+        duration: Duration,
+        sound_modes: impl Fn(bool) -> SoundModesT,
+    ) -> Self {
+        let sound_modes_update = |is_detected: bool| {
+            packet::Inbound::new(
+                packet::inbound::SoundModes::COMMAND,
+                sound_modes(is_detected).bytes(),
+            )
+        };
+        Self::new()
+            .then(delay, sound_modes_update(true))
+            .then(duration, sound_modes_update(false))
+    }
+
+    pub(crate) async fn play(self, sender: mpsc::Sender<Vec<u8>>, checksum_kind: ChecksumKind) {
+        if self.events.is_empty() {
+            return;
+        }
+        loop {
+            for (delay, packet) in &self.events {
+                tokio::time::sleep(*delay).await;
+                tracing::debug!("demo: sending scripted packet {packet:?}");
+                if sender.send(packet.bytes(checksum_kind)).await.is_err() {
+                    return;
+                }
+            }
+            if !self.repeat {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use macaddr::MacAddr6;
+    use tokio::time::Instant;
+
+    use crate::{
+        api::{
+            device::{OpenSCQ30Device, OpenSCQ30DeviceRegistry},
+            settings::{SettingId, Value},
+        },
+        devices::{DeviceModel, soundcore},
+        storage::OpenSCQ30Database,
+    };
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn plays_events_with_delays() {
+        let (sender, mut receiver) = mpsc::channel(10);
+        let start = Instant::now();
+        tokio::spawn(
+            DemoEvents::tws_disconnect(Duration::from_secs(5), Duration::from_secs(2))
+                .play(sender, ChecksumKind::None),
+        );
+
+        assert_eq!(
+            Some(vec![
+                0x09, 0xff, 0x00, 0x00, 0x01, 0x01, 0x02, 0x0b, 0x00, 0x00, 0x00
+            ]),
+            receiver.recv().await,
+        );
+        assert_eq!(Duration::from_secs(5), start.elapsed());
+        assert_eq!(
+            Some(vec![
+                0x09, 0xff, 0x00, 0x00, 0x01, 0x01, 0x02, 0x0b, 0x00, 0x00, 0x01
+            ]),
+            receiver.recv().await,
+        );
+        assert_eq!(Duration::from_secs(7), start.elapsed());
+        assert_eq!(None, receiver.recv().await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn repeats_events() {
+        let (sender, mut receiver) = mpsc::channel(10);
+        tokio::spawn(
+            DemoEvents::single_battery_drain(1, Duration::from_secs(1))
+                .repeat()
+                .play(sender, ChecksumKind::None),
+        );
+
+        let mut levels = Vec::new();
+        for _ in 0..5 {
+            levels.push(*receiver.recv().await.unwrap().last().unwrap());
+        }
+        assert_eq!(vec![1, 0, 1, 0, 1], levels);
+    }
+
+    async fn wait_for_setting(device: &dyn OpenSCQ30Device, setting_id: SettingId, value: &str) {
+        let mut changes = device.watch_for_changes();
+        tokio::time::timeout(Duration::from_secs(600), async {
+            while Value::from(device.setting(&setting_id).unwrap())
+                .try_as_str()
+                .unwrap()
+                != value
+            {
+                changes.changed().await.unwrap();
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("{setting_id} should become {value}"));
+    }
+
+    /// Connects without letting paused time auto advance. Connecting waits on the database thread, and the paused clock
+    /// would otherwise skip ahead while the runtime sits idle, playing events before the caller is watching for them.
+    /// An in flight blocking task keeps the clock from auto advancing.
+    async fn connect_holding_clock(
+        registry: &impl OpenSCQ30DeviceRegistry,
+    ) -> Arc<dyn OpenSCQ30Device + Send + Sync> {
+        let (connected_sender, connected_receiver) = std::sync::mpsc::channel::<()>();
+        let hold_clock = tokio::task::spawn_blocking(move || connected_receiver.recv());
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        drop(connected_sender);
+        hold_clock.await.unwrap().unwrap_err();
+        device
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn demo_device_state_changes_with_events() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3951::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3951,
+            DemoEvents::dual_battery_drain(5, Duration::from_secs(60))
+                .chain(DemoEvents::case_cycle(
+                    Duration::from_secs(30),
+                    Duration::from_secs(30),
+                ))
+                .chain(DemoEvents::tws_disconnect(
+                    Duration::from_secs(30),
+                    Duration::from_secs(10),
+                )),
+        );
+        let device = connect_holding_clock(&registry).await;
+
+        wait_for_setting(device.as_ref(), SettingId::BatteryLevelLeft, "4/5").await;
+        wait_for_setting(device.as_ref(), SettingId::BatteryLevelRight, "0/5").await;
+        wait_for_setting(device.as_ref(), SettingId::IsChargingLeft, "Yes").await;
+        wait_for_setting(device.as_ref(), SettingId::IsChargingLeft, "No").await;
+        wait_for_setting(device.as_ref(), SettingId::TwsStatus, "Disconnected").await;
+        wait_for_setting(device.as_ref(), SettingId::TwsStatus, "Connected").await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn demo_device_detects_wind_noise() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3936::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3936,
+            DeviceModel::SoundcoreA3936
+                .demo_wind_noise_detected_events(Duration::from_secs(30), Duration::from_secs(10))
+                .unwrap(),
+        );
+        let device = connect_holding_clock(&registry).await;
+
+        wait_for_setting(device.as_ref(), SettingId::WindNoiseDetected, "true").await;
+        wait_for_setting(device.as_ref(), SettingId::WindNoiseDetected, "false").await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn demo_device_without_events_does_not_change() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3951::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3951,
+            DemoEvents::default(),
+        );
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+
+        let mut changes = device.watch_for_changes();
+        assert!(
+            tokio::time::timeout(Duration::from_secs(3600), changes.changed())
+                .await
+                .is_err(),
+            "demo device should not change on its own",
+        );
+    }
+}
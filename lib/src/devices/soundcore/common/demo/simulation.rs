@@ -0,0 +1,617 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use nom_language::error::VerboseError;
+use openscq30_lib_has::{Has, MaybeHas};
+
+use crate::devices::soundcore::common::{
+    demo::DemoEvents,
+    modules::button_configuration::ButtonConfigurationSettings,
+    packet::{
+        self, Command,
+        inbound::{FromPacketBody, TryToPacket},
+        outbound::{
+            RESET_BUTTON_CONFIGURATIONS_COMMAND, SET_ALL_BUTTON_CONFIGURATIONS_COMMAND,
+            SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND, SET_BUTTON_CONFIGURATION_COMMAND,
+            SET_BUTTON_CONFIGURATION_ENABLED_COMMAND, SET_EQUALIZER_AND_CUSTOM_HEAR_ID_COMMAND,
+            SET_EQUALIZER_AND_CUSTOM_HEAR_ID_WITH_GENRE_COMMAND, SET_EQUALIZER_COMMAND,
+            SET_EQUALIZER_WITH_DRC_COMMAND, SET_LIMIT_HIGH_VOLUME_COMMAND,
+            SET_LIMIT_HIGH_VOLUME_REFRESH_RATE_COMMAND, SetAutoPowerOff, SetSoundModes, ToPacket,
+        },
+    },
+    structures::{
+        AmbientSoundModeCycle, AmbientSoundModeCycleTws, AutoPowerOff, DecibelReadingRefreshRate,
+        EqualizerConfiguration, Flag, LimitHighVolume, SoundModes,
+        button_configuration::{ActionStatus, ButtonStatusCollection, EnabledStatus},
+    },
+};
+
+/// Returns the command of the response that was updated, if any.
+type DemoPacketHandler =
+    Box<dyn Fn(&[u8], &mut HashMap<Command, packet::Inbound>) -> Option<Command> + Send + Sync>;
+
+/// The packets a demo device responds with, along with handlers that modify those responses when the device receives
+/// a packet that would change its state.
+pub struct DemoState {
+    responses: HashMap<Command, packet::Inbound>,
+    handlers: HashMap<Command, Vec<DemoPacketHandler>>,
+    events: DemoEvents,
+}
+
+impl DemoState {
+    pub fn new(responses: HashMap<Command, packet::Inbound>) -> Self {
+        Self {
+            responses,
+            handlers: HashMap::new(),
+            events: DemoEvents::default(),
+        }
+    }
+
+    /// Packets that will be sent unprompted by each connection to the device.
+    pub fn with_events(mut self, events: DemoEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub(crate) fn events(&self) -> &DemoEvents {
+        &self.events
+    }
+
+    /// Registers handlers that update the `StatePacket` response to `request_command` as set packets are received.
+    pub fn simulate<StatePacket>(
+        mut self,
+        request_command: Command,
+        register: impl FnOnce(&mut DemoStateSimulator<'_, StatePacket>),
+    ) -> Self
+    where
+        StatePacket: FromPacketBody + ToPacket<DirectionMarker = packet::InboundMarker> + 'static,
+    {
+        register(&mut DemoStateSimulator {
+            state: &mut self,
+            request_command,
+            _state_packet: PhantomData,
+        });
+        self
+    }
+
+    /// Applies the effects of `packet` and returns the packets the device sends back: the response or an ACK,
+    /// followed by any state update packets that were changed as a result.
+    pub(crate) fn handle(&mut self, packet: &packet::Outbound) -> Vec<packet::Inbound> {
+        let mut updated_commands = Vec::new();
+        if let Some(handlers) = self.handlers.get(&packet.command) {
+            for handler in handlers {
+                if let Some(command) = handler(&packet.body, &mut self.responses)
+                    && !updated_commands.contains(&command)
+                {
+                    updated_commands.push(command);
+                }
+            }
+        }
+        let response = self
+            .responses
+            .get(&packet.command)
+            .cloned()
+            .unwrap_or_else(|| packet.ack());
+        std::iter::once(response)
+            .chain(
+                updated_commands
+                    .iter()
+                    .filter_map(|command| self.responses.get(command).cloned()),
+            )
+            .collect()
+    }
+}
+
+impl From<HashMap<Command, packet::Inbound>> for DemoState {
+    fn from(responses: HashMap<Command, packet::Inbound>) -> Self {
+        Self::new(responses)
+    }
+}
+
+pub struct DemoStateSimulator<'a, StatePacket> {
+    state: &'a mut DemoState,
+    request_command: Command,
+    _state_packet: PhantomData<fn() -> StatePacket>,
+}
+
+impl<StatePacket> DemoStateSimulator<'_, StatePacket>
+where
+    StatePacket: FromPacketBody + ToPacket<DirectionMarker = packet::InboundMarker> + 'static,
+{
+    /// `apply` should return None if the body of the packet could not be understood.
+    pub fn on(
+        &mut self,
+        command: Command,
+        apply: impl Fn(&[u8], &mut StatePacket) -> Option<()> + Send + Sync + 'static,
+    ) {
+        let request_command = self.request_command;
+        self.state
+            .handlers
+            .entry(command)
+            .or_default()
+            .push(Box::new(move |body, responses| {
+                let Some(response) = responses.get_mut(&request_command) else {
+                    tracing::warn!("demo: no response to {request_command:?} to update");
+                    return None;
+                };
+                let mut state_packet: StatePacket = match response.try_to_packet() {
+                    Ok(state_packet) => state_packet,
+                    Err(err) => {
+                        tracing::warn!(
+                            "demo: failed to parse response to {request_command:?}: {err:?}"
+                        );
+                        return None;
+                    }
+                };
+                if apply(body, &mut state_packet).is_none() {
+                    tracing::warn!("demo: failed to apply {command:?} with body {body:?}");
+                    return None;
+                }
+                *response = state_packet.to_packet();
+                Some(request_command)
+            }));
+    }
+
+    pub fn sound_modes(&mut self)
+    where
+        StatePacket: Has<SoundModes>,
+    {
+        self.on(SetSoundModes::COMMAND, |body, state_packet| {
+            let (_, sound_modes) = SoundModes::take::<VerboseError<_>>(body).ok()?;
+            *state_packet.get_mut() = sound_modes;
+            Some(())
+        });
+    }
+
+    /// For models with their own sound modes structure, which is sent in its entirety in the same way as the common
+    /// one.
+    pub fn sound_modes_v2<SoundModesT>(&mut self)
+    where
+        StatePacket: Has<SoundModesT>,
+        SoundModesT: FromPacketBody + 'static,
+    {
+        self.on(SetSoundModes::COMMAND, |body, state_packet| {
+            let (_, sound_modes) = SoundModesT::take::<VerboseError<_>>(body).ok()?;
+            *state_packet.get_mut() = sound_modes;
+            Some(())
+        });
+    }
+
+    pub fn equalizer<
+        const CHANNELS: usize,
+        const BANDS: usize,
+        const MIN_VOLUME: i16,
+        const MAX_VOLUME: i16,
+        const FRACTION_DIGITS: u8,
+    >(
+        &mut self,
+    ) where
+        StatePacket:
+            Has<EqualizerConfiguration<CHANNELS, BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>>,
+    {
+        fn apply<
+            StatePacket,
+            const CHANNELS: usize,
+            const BANDS: usize,
+            const MIN_VOLUME: i16,
+            const MAX_VOLUME: i16,
+            const FRACTION_DIGITS: u8,
+        >(
+            body: &[u8],
+            state_packet: &mut StatePacket,
+        ) -> Option<()>
+        where
+            StatePacket: Has<
+                EqualizerConfiguration<CHANNELS, BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>,
+            >,
+        {
+            // Anything following the equalizer configuration (drc, hear id) is not simulated
+            let (_, equalizer_configuration) = EqualizerConfiguration::<
+                CHANNELS,
+                BANDS,
+                MIN_VOLUME,
+                MAX_VOLUME,
+                FRACTION_DIGITS,
+            >::take::<VerboseError<_>>(body)
+            .ok()?;
+            *state_packet.get_mut() = equalizer_configuration;
+            Some(())
+        }
+
+        self.on(SET_EQUALIZER_COMMAND, apply);
+        self.on(SET_EQUALIZER_WITH_DRC_COMMAND, apply);
+        self.on(SET_EQUALIZER_AND_CUSTOM_HEAR_ID_COMMAND, apply);
+        self.on(
+            SET_EQUALIZER_AND_CUSTOM_HEAR_ID_WITH_GENRE_COMMAND,
+            |body, state_packet| {
+                // preset id, then the hear id music genre, then volume adjustments
+                let preset_id = body.get(0..2)?;
+                let volume_adjustments = body.get(4..)?;
+                apply(&[preset_id, volume_adjustments].concat(), state_packet)
+            },
+        );
+    }
+
+    pub fn button_configuration<const NUM_BUTTONS: usize, const NUM_PRESS_KINDS: usize>(
+        &mut self,
+        settings: ButtonConfigurationSettings<NUM_BUTTONS, NUM_PRESS_KINDS>,
+    ) where
+        StatePacket: Has<ButtonStatusCollection<NUM_BUTTONS>>,
+    {
+        let position = move |side: u8, button_id: u8| {
+            settings.order.iter().position(|button| {
+                u8::from(button.side()) == side
+                    && settings
+                        .button_settings(*button)
+                        .is_some_and(|button_settings| button_settings.button_id == button_id)
+            })
+        };
+
+        self.on(
+            SET_BUTTON_CONFIGURATION_COMMAND,
+            move |body, state_packet| {
+                let [side, button_id, ref action @ ..] = *body else {
+                    return None;
+                };
+                let index = position(side, button_id)?;
+                let parse_settings = settings.parse_settings()[index];
+                let (_, action) =
+                    ActionStatus::take::<VerboseError<_>>(parse_settings.action_kind)(action)
+                        .ok()?;
+                let statuses: &mut ButtonStatusCollection<NUM_BUTTONS> = state_packet.get_mut();
+                statuses.0[index].action = action;
+                Some(())
+            },
+        );
+        self.on(
+            SET_BUTTON_CONFIGURATION_ENABLED_COMMAND,
+            move |body, state_packet| {
+                let [side, button_id, ref enabled @ ..] = *body else {
+                    return None;
+                };
+                let index = position(side, button_id)?;
+                let parse_settings = settings.parse_settings()[index];
+                let (_, enabled) = EnabledStatus::take::<VerboseError<_>>(
+                    parse_settings.enabled_flag_kind,
+                )(enabled)
+                .ok()?;
+                let statuses: &mut ButtonStatusCollection<NUM_BUTTONS> = state_packet.get_mut();
+                statuses.0[index].enabled = enabled;
+                Some(())
+            },
+        );
+        self.on(
+            SET_ALL_BUTTON_CONFIGURATIONS_COMMAND,
+            move |body, state_packet| {
+                let (_, statuses) = ButtonStatusCollection::take::<VerboseError<_>, NUM_BUTTONS>(
+                    settings.parse_settings(),
+                )(body)
+                .ok()?;
+                *state_packet.get_mut() = statuses;
+                Some(())
+            },
+        );
+        self.on(
+            RESET_BUTTON_CONFIGURATIONS_COMMAND,
+            move |_body, state_packet| {
+                *state_packet.get_mut() = settings.default_status_collection();
+                Some(())
+            },
+        );
+    }
+
+    pub fn ambient_sound_mode_cycle(&mut self)
+    where
+        StatePacket: Has<AmbientSoundModeCycle>,
+    {
+        self.on(
+            SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND,
+            |body, state_packet| {
+                let (_, cycle) = AmbientSoundModeCycle::take::<VerboseError<_>>(body).ok()?;
+                *state_packet.get_mut() = cycle;
+                Some(())
+            },
+        );
+    }
+
+    pub fn ambient_sound_mode_cycle_tws(&mut self)
+    where
+        StatePacket: Has<AmbientSoundModeCycleTws>,
+    {
+        self.on(
+            SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND,
+            |body, state_packet| {
+                let (_, cycle) = AmbientSoundModeCycleTws::take::<VerboseError<_>>(body).ok()?;
+                *state_packet.get_mut() = cycle;
+                Some(())
+            },
+        );
+    }
+
+    pub fn limit_high_volume(&mut self)
+    where
+        StatePacket: Has<LimitHighVolume>,
+    {
+        self.on(SET_LIMIT_HIGH_VOLUME_COMMAND, |body, state_packet| {
+            let [enabled, db_limit] = *body else {
+                return None;
+            };
+            let limit_high_volume: &mut LimitHighVolume = state_packet.get_mut();
+            limit_high_volume.enabled = enabled != 0;
+            limit_high_volume.db_limit = db_limit;
+            Some(())
+        });
+        self.on(
+            SET_LIMIT_HIGH_VOLUME_REFRESH_RATE_COMMAND,
+            |body, state_packet| {
+                let (_, refresh_rate) =
+                    DecibelReadingRefreshRate::take::<VerboseError<_>>(body).ok()?;
+                let limit_high_volume: &mut LimitHighVolume = state_packet.get_mut();
+                limit_high_volume.refresh_rate = refresh_rate;
+                Some(())
+            },
+        );
+    }
+
+    /// `command` is the one the flag's module sends to set it.
+    pub fn flag<FlagT>(&mut self, command: Command)
+    where
+        StatePacket: MaybeHas<FlagT>,
+        FlagT: Flag + 'static,
+    {
+        self.on(command, |body, state_packet| {
+            let [value] = *body else {
+                return None;
+            };
+            state_packet.maybe_get_mut()?.set_bool(value != 0);
+            Some(())
+        });
+    }
+
+    pub fn auto_power_off(&mut self)
+    where
+        StatePacket: MaybeHas<AutoPowerOff>,
+    {
+        self.on(SetAutoPowerOff::COMMAND, |body, state_packet| {
+            let (_, auto_power_off) = AutoPowerOff::take::<VerboseError<_>>(body).ok()?;
+            // Rejected rather than dropped if the state update packet has nowhere to store it
+            *state_packet.maybe_get_mut()? = auto_power_off;
+            Some(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use macaddr::MacAddr6;
+
+    use crate::{
+        api::{
+            device::OpenSCQ30DeviceRegistry,
+            settings::{SettingId, Value},
+        },
+        devices::{
+            DeviceModel,
+            soundcore::{
+                self,
+                a3028::packets::A3028StateUpdatePacket,
+                common::{
+                    packet::outbound::RequestState,
+                    structures::{AmbientSoundMode, AutoPowerOffDurationIndex, NoiseCancelingMode},
+                },
+            },
+        },
+        storage::OpenSCQ30Database,
+    };
+
+    use super::*;
+
+    #[test]
+    fn set_packet_is_acked_and_followed_by_state_update() {
+        let mut state = DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            A3028StateUpdatePacket::default().to_packet(),
+        )]))
+        .simulate::<A3028StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+        });
+        let sound_modes = SoundModes {
+            ambient_sound_mode: AmbientSoundMode::NoiseCanceling,
+            noise_canceling_mode: NoiseCancelingMode::Outdoor,
+            ..Default::default()
+        };
+
+        let responses = state.handle(&SetSoundModes(sound_modes).to_packet());
+
+        assert_eq!(2, responses.len());
+        assert_eq!(SetSoundModes::COMMAND, responses[0].command);
+        assert!(responses[0].body.is_empty(), "should be an ACK");
+        let state_update: A3028StateUpdatePacket = responses[1].try_to_packet().unwrap();
+        assert_eq!(sound_modes, state_update.sound_modes);
+    }
+
+    #[test]
+    fn request_packet_is_not_followed_by_state_update() {
+        let mut state = DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            A3028StateUpdatePacket::default().to_packet(),
+        )]))
+        .simulate::<A3028StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.sound_modes();
+        });
+
+        let responses = state.handle(&RequestState.to_packet());
+
+        assert_eq!(
+            vec![A3028StateUpdatePacket::default().to_packet()],
+            responses
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn settings_persist_across_reconnects() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3028::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3028,
+            DemoEvents::default(),
+        );
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        device
+            .set_setting_values(vec![
+                (SettingId::AmbientSoundMode, "NoiseCanceling".into()),
+                (SettingId::NoiseCancelingMode, "Outdoor".into()),
+                (
+                    SettingId::VolumeAdjustments,
+                    vec![-60i16, 60, 23, 40, 22, 60, -4, 16].into(),
+                ),
+                (SettingId::AutoPowerOff, "90m".into()),
+            ])
+            .await
+            .unwrap();
+        drop(device);
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        assert_eq!(
+            "NoiseCanceling",
+            Value::from(device.setting(&SettingId::AmbientSoundMode).unwrap())
+                .try_as_str()
+                .unwrap()
+        );
+        assert_eq!(
+            "Outdoor",
+            Value::from(device.setting(&SettingId::NoiseCancelingMode).unwrap())
+                .try_as_str()
+                .unwrap()
+        );
+        assert_eq!(
+            vec![-60, 60, 23, 40, 22, 60, -4, 16],
+            Value::from(device.setting(&SettingId::VolumeAdjustments).unwrap())
+                .try_into_i16_vec()
+                .unwrap()
+        );
+        assert_eq!(
+            "90m",
+            Value::from(device.setting(&SettingId::AutoPowerOff).unwrap())
+                .try_as_str()
+                .unwrap()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn button_configuration_persists_across_reconnects() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3951::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3951,
+            DemoEvents::default(),
+        );
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        device
+            .set_setting_values(vec![
+                (SettingId::LeftSinglePress, Some("VolumeDown").into()),
+                (
+                    SettingId::VolumeAdjustments,
+                    vec![-60i16, 60, 23, 40, 22, 60, -4, 16].into(),
+                ),
+            ])
+            .await
+            .unwrap();
+        drop(device);
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        assert_eq!(
+            Some("VolumeDown"),
+            Value::from(device.setting(&SettingId::LeftSinglePress).unwrap())
+                .try_as_optional_str()
+                .unwrap()
+        );
+        assert_eq!(
+            vec![-60, 60, 23, 40, 22, 60, -4, 16],
+            Value::from(device.setting(&SettingId::VolumeAdjustments).unwrap())
+                .try_into_i16_vec()
+                .unwrap()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn model_specific_settings_persist_across_reconnects() {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let registry = soundcore::a3954::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3954,
+            DemoEvents::default(),
+        );
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        device
+            .set_setting_values(vec![
+                (SettingId::AmbientSoundMode, "NoiseCanceling".into()),
+                (SettingId::Ldac, true.into()),
+                (SettingId::EasyChat, true.into()),
+                (SettingId::SoundLeakCompensation, true.into()),
+            ])
+            .await
+            .unwrap();
+        drop(device);
+
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        assert_eq!(
+            "NoiseCanceling",
+            Value::from(device.setting(&SettingId::AmbientSoundMode).unwrap())
+                .try_as_str()
+                .unwrap()
+        );
+        for setting_id in [
+            SettingId::Ldac,
+            SettingId::EasyChat,
+            SettingId::SoundLeakCompensation,
+        ] {
+            assert!(
+                Value::from(device.setting(&setting_id).unwrap())
+                    .try_as_bool()
+                    .unwrap(),
+                "{setting_id:?} should be enabled"
+            );
+        }
+    }
+
+    #[test]
+    fn auto_power_off_is_rejected_without_extra_fields() {
+        let state_update = A3028StateUpdatePacket {
+            extra_fields: None,
+            ..Default::default()
+        };
+        let mut state = DemoState::new(HashMap::from([(
+            RequestState::COMMAND,
+            state_update.to_packet(),
+        )]))
+        .simulate::<A3028StateUpdatePacket>(RequestState::COMMAND, |simulator| {
+            simulator.auto_power_off();
+        });
+
+        let responses = state.handle(
+            &SetAutoPowerOff(AutoPowerOff {
+                is_enabled: true,
+                duration: AutoPowerOffDurationIndex(2),
+            })
+            .to_packet(),
+        );
+
+        assert!(
+            responses
+                .iter()
+                .all(|response| response.command != RequestState::COMMAND),
+            "no state update should be sent, got {responses:?}"
+        );
+        assert_eq!(
+            state_update,
+            state.responses[&RequestState::COMMAND]
+                .try_to_packet()
+                .unwrap()
+        );
+    }
+}
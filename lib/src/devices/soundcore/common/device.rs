@@ -1,3 +1,5 @@
+mod reconnect;
+
 use std::{marker::PhantomData, pin::Pin, sync::Arc};
 
 use async_trait::async_trait;
@@ -11,8 +13,11 @@ use tokio::{
 
 use crate::{
     api::{
-        connection::{ConnectionDescriptor, ConnectionStatus, RfcommBackend, RfcommConnection},
-        device::{self, OpenSCQ30Device, OpenSCQ30DeviceRegistry},
+        connection::{
+            ConnectionDescriptor, ConnectionStatus, Transport, TransportBackend,
+            TransportConnection,
+        },
+        device::{self, OpenSCQ30Device, OpenSCQ30DeviceRegistry, ReconnectPolicy},
         settings::{CategoryId, Setting, SettingId, Value},
     },
     connection::RfcommServiceSelectionStrategy,
@@ -40,6 +45,7 @@ use crate::{
     storage::OpenSCQ30Database,
 };
 
+use self::reconnect::ReconnectingDevice;
 use super::{
     modules::{
         ModuleCollection, ModuleCollectionSpawnPacketHandlerExt, sound_modes::AvailableSoundModes,
@@ -82,37 +88,43 @@ pub struct SoundcoreDeviceConfig {
     /// Most devices have a checksum at the end of their packets, but for the ones that don't,
     /// this can be set to ChecksumKind::None to disable checksums.
     pub checksum_kind: packet::ChecksumKind,
-    pub rfcomm_service_selection_strategy: RfcommServiceSelectionStrategy,
+    /// Headphones and earbuds use RFCOMM, but some newer devices only offer their control protocol over BLE GATT.
+    pub transport: Transport,
+}
+
+impl SoundcoreDeviceConfig {
+    /// Prefers the vendor RFCOMM service, falling back to the standard serial port service for older devices.
+    pub const DEFAULT_RFCOMM_SERVICE_SELECTION_STRATEGY: RfcommServiceSelectionStrategy =
+        RfcommServiceSelectionStrategy::Dynamic(|service_uuids| {
+            service_uuids
+                .into_iter()
+                .find(soundcore::is_soundcore_vendor_rfcomm_uuid)
+                .unwrap_or(soundcore::RFCOMM_UUID)
+        });
 }
 
 impl Default for SoundcoreDeviceConfig {
     fn default() -> Self {
         Self {
             checksum_kind: Default::default(),
-            rfcomm_service_selection_strategy: RfcommServiceSelectionStrategy::Dynamic(
-                |service_uuids| {
-                    service_uuids
-                        .into_iter()
-                        .find(soundcore::is_soundcore_vendor_rfcomm_uuid)
-                        .unwrap_or(soundcore::RFCOMM_UUID)
-                },
-            ),
+            transport: Transport::Rfcomm(Self::DEFAULT_RFCOMM_SERVICE_SELECTION_STRATEGY),
         }
     }
 }
 
 pub struct SoundcoreDeviceRegistry<StateType> {
-    backend: Arc<dyn RfcommBackend + Send + Sync>,
+    backend: TransportBackend,
     database: Arc<OpenSCQ30Database>,
     device_model: DeviceModel,
-    fetch_state: FetchStateFn<StateType>,
+    fetch_state: Arc<FetchStateFn<StateType>>,
     config: SoundcoreDeviceConfig,
+    reconnect_policy: Option<ReconnectPolicy>,
     _state: PhantomData<StateType>,
 }
 
 impl<StateType> SoundcoreDeviceRegistry<StateType> {
     pub fn new(
-        backend: Arc<dyn RfcommBackend + Send + Sync>,
+        backend: TransportBackend,
         database: Arc<OpenSCQ30Database>,
         device_model: DeviceModel,
         fetch_state: FetchStateFn<StateType>,
@@ -122,11 +134,41 @@ impl<StateType> SoundcoreDeviceRegistry<StateType> {
             backend,
             device_model,
             database,
-            fetch_state,
+            fetch_state: Arc::new(fetch_state),
             config,
+            reconnect_policy: None,
             _state: PhantomData,
         }
     }
+
+    /// Devices connected through this registry will automatically reconnect according to `reconnect_policy` when
+    /// their connection is lost.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: Option<ReconnectPolicy>) -> Self {
+        self.reconnect_policy = reconnect_policy;
+        self
+    }
+}
+
+impl<StateType> SoundcoreDeviceRegistry<StateType>
+where
+    StateType: Clone + Send + Sync + 'static,
+    Self: BuildDevice<StateType>,
+{
+    async fn connect_device(
+        backend: &TransportBackend,
+        database: Arc<OpenSCQ30Database>,
+        device_model: DeviceModel,
+        fetch_state: &FetchStateFn<StateType>,
+        config: SoundcoreDeviceConfig,
+        mac_address: macaddr::MacAddr6,
+    ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
+        let connection = backend.connect(mac_address, config.transport).await?;
+        let mut builder =
+            SoundcoreDeviceBuilder::new(database, connection, device_model, fetch_state, config)
+                .await?;
+        Self::build_device(&mut builder).await;
+        Ok(Arc::new(builder.build().await))
+    }
 }
 
 #[async_trait]
@@ -155,20 +197,44 @@ where
         &self,
         mac_address: macaddr::MacAddr6,
     ) -> device::Result<Arc<dyn OpenSCQ30Device + Send + Sync>> {
-        let connection = self
-            .backend
-            .connect(mac_address, self.config.rfcomm_service_selection_strategy)
-            .await?;
-        let mut builder = SoundcoreDeviceBuilder::new(
+        let device = Self::connect_device(
+            &self.backend,
             self.database.clone(),
-            connection,
             self.device_model,
             &self.fetch_state,
             self.config,
+            mac_address,
         )
         .await?;
-        Self::build_device(&mut builder).await;
-        Ok(Arc::new(builder.build().await))
+        let Some(reconnect_policy) = self.reconnect_policy else {
+            return Ok(device);
+        };
+
+        let backend = self.backend.clone();
+        let database = self.database.clone();
+        let device_model = self.device_model;
+        let fetch_state = self.fetch_state.clone();
+        let config = self.config;
+        Ok(Arc::new(ReconnectingDevice::new(
+            device,
+            reconnect_policy,
+            Box::new(move || {
+                let backend = backend.clone();
+                let database = database.clone();
+                let fetch_state = fetch_state.clone();
+                Box::pin(async move {
+                    Self::connect_device(
+                        &backend,
+                        database,
+                        device_model,
+                        &fetch_state,
+                        config,
+                        mac_address,
+                    )
+                    .await
+                })
+            }),
+        )))
     }
 }
 
@@ -211,7 +277,7 @@ where
 {
     pub async fn new(
         database: Arc<OpenSCQ30Database>,
-        connection: Arc<dyn RfcommConnection + Send + Sync>,
+        connection: TransportConnection,
         device_model: DeviceModel,
         fetch_state: &FetchStateFn<StateType>,
         config: SoundcoreDeviceConfig,
@@ -793,14 +859,15 @@ where
 
 #[cfg(test)]
 pub mod test_utils {
-    use std::{collections::HashMap, time::Duration};
+    use std::{collections::HashMap, path::Path, time::Duration};
 
     use macaddr::MacAddr6;
     use nom_language::error::VerboseError;
 
     use crate::{
+        connection_backend::device_definition::DeviceDefinition,
         devices::soundcore::common::packet::{self, Command},
-        mock::rfcomm::MockRfcommBackend,
+        mock::{gatt::MockGattBackend, rfcomm::MockRfcommBackend},
     };
 
     use super::*;
@@ -817,7 +884,7 @@ pub mod test_utils {
         // PacketIOController as a parameter
         pub async fn new_with_delayed_responses<StateType>(
             constructor: fn(
-                Arc<dyn RfcommBackend + Send + Sync>,
+                TransportBackend,
                 Arc<OpenSCQ30Database>,
                 DeviceModel,
             ) -> SoundcoreDeviceRegistry<StateType>,
@@ -834,11 +901,17 @@ pub mod test_utils {
             let (outbound_sender, mut outbound_receiver) = mpsc::channel(100);
             let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
 
-            let registry = constructor(
-                Arc::new(MockRfcommBackend::new(inbound_receiver, outbound_sender)),
-                database,
-                device_model,
-            );
+            let backend = match config.transport {
+                Transport::Rfcomm(_) => TransportBackend::Rfcomm(Arc::new(MockRfcommBackend::new(
+                    inbound_receiver,
+                    outbound_sender,
+                ))),
+                Transport::Gatt(_) => TransportBackend::Gatt(Arc::new(MockGattBackend::new(
+                    inbound_receiver,
+                    outbound_sender,
+                ))),
+            };
+            let registry = constructor(backend, database, device_model);
 
             // spawn a future to connect to the device rather than to handle packets so that we don't have to move
             // outbound_receiver into the future and back out when it's done
@@ -886,7 +959,7 @@ pub mod test_utils {
 
         pub async fn new<StateType>(
             constructor: fn(
-                Arc<dyn RfcommBackend + Send + Sync>,
+                TransportBackend,
                 Arc<OpenSCQ30Database>,
                 DeviceModel,
             ) -> SoundcoreDeviceRegistry<StateType>,
@@ -908,6 +981,22 @@ pub mod test_utils {
             .await
         }
 
+        /// The state update packet from a soundcore-device-faker device definition, such as `a3951.toml`.
+        pub fn faker_state_update(file_name: &str) -> packet::Inbound {
+            let definition = DeviceDefinition::load(
+                &Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("../tools/soundcore-device-faker/devices")
+                    .join(file_name),
+            )
+            .unwrap();
+            let response = definition
+                .responses
+                .into_iter()
+                .find(|response| response.command == [1, 1])
+                .expect("faker definition should include a state update");
+            packet::Inbound::new(Command(response.command), response.response)
+        }
+
         pub fn inner(&self) -> &Arc<dyn OpenSCQ30Device + Send + Sync> {
             &self.device
         }
@@ -0,0 +1,421 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use tokio::{select, sync::watch};
+
+use crate::{
+    api::{
+        connection::ConnectionStatus,
+        device::{self, OpenSCQ30Device, ReconnectPolicy},
+        settings::{CategoryId, Setting, SettingId, Value},
+    },
+    devices::DeviceModel,
+    util::AbortOnDropHandle,
+};
+
+type Device = Arc<dyn OpenSCQ30Device + Send + Sync>;
+
+pub type ConnectFn =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = device::Result<Device>> + Send>> + Send + Sync>;
+
+/// Wraps a device, replacing it with a freshly connected one whenever its connection is lost. Since reconnecting goes
+/// through the same path as the initial connection, state is fetched again and packet handlers are set up on the new
+/// connection.
+pub struct ReconnectingDevice {
+    device_model: DeviceModel,
+    inner: Arc<RwLock<Device>>,
+    connection_status: watch::Sender<ConnectionStatus>,
+    change_notify: watch::Sender<()>,
+    _reconnect_handle: AbortOnDropHandle<()>,
+}
+
+impl ReconnectingDevice {
+    pub fn new(device: Device, policy: ReconnectPolicy, connect: ConnectFn) -> Self {
+        let device_model = device.model();
+        let connection_status = watch::channel(*device.connection_status().borrow()).0;
+        let change_notify = watch::channel(()).0;
+        let inner = Arc::new(RwLock::new(device));
+        let reconnect_handle = AbortOnDropHandle::new(tokio::spawn(Self::reconnect_loop(
+            inner.clone(),
+            policy,
+            connect,
+            connection_status.clone(),
+            change_notify.clone(),
+        )));
+        Self {
+            device_model,
+            inner,
+            connection_status,
+            change_notify,
+            _reconnect_handle: reconnect_handle,
+        }
+    }
+
+    fn inner(&self) -> Device {
+        self.inner.read().unwrap().clone()
+    }
+
+    async fn reconnect_loop(
+        inner: Arc<RwLock<Device>>,
+        policy: ReconnectPolicy,
+        connect: ConnectFn,
+        connection_status: watch::Sender<ConnectionStatus>,
+        change_notify: watch::Sender<()>,
+    ) {
+        loop {
+            let device = inner.read().unwrap().clone();
+            Self::forward_changes_until_disconnected(device.as_ref(), &change_notify).await;
+            drop(device);
+
+            tracing::info!("connection lost, reconnecting");
+            connection_status.send_replace(ConnectionStatus::Reconnecting);
+            let mut attempt = 0;
+            let new_device = loop {
+                if policy
+                    .max_attempts
+                    .is_some_and(|max_attempts| attempt >= max_attempts)
+                {
+                    tracing::info!("giving up on reconnecting after {attempt} attempts");
+                    connection_status.send_replace(ConnectionStatus::Disconnected);
+                    return;
+                }
+                tokio::time::sleep(policy.delay(attempt)).await;
+                attempt += 1;
+                match connect().await {
+                    Ok(device) => break device,
+                    Err(err) => tracing::warn!("reconnection attempt {attempt} failed: {err:?}"),
+                }
+            };
+
+            *inner.write().unwrap() = new_device;
+            connection_status.send_replace(ConnectionStatus::Connected);
+            change_notify.send_replace(());
+        }
+    }
+
+    async fn forward_changes_until_disconnected(
+        device: &(dyn OpenSCQ30Device + Send + Sync),
+        change_notify: &watch::Sender<()>,
+    ) {
+        let mut connection_status = device.connection_status();
+        let mut changes = device.watch_for_changes();
+        let mut is_watching_changes = true;
+        loop {
+            select! {
+                // a closed connection status channel means nothing is left to tell us we're connected
+                _ = connection_status.wait_for(|status| *status == ConnectionStatus::Disconnected) => return,
+                result = changes.changed(), if is_watching_changes => match result {
+                    Ok(()) => {
+                        change_notify.send_replace(());
+                    }
+                    Err(_) => is_watching_changes = false,
+                },
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl OpenSCQ30Device for ReconnectingDevice {
+    fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.connection_status.subscribe()
+    }
+
+    fn model(&self) -> DeviceModel {
+        self.device_model
+    }
+
+    fn categories(&self) -> Vec<CategoryId> {
+        self.inner().categories()
+    }
+
+    fn settings_in_category(&self, category_id: &CategoryId) -> Vec<SettingId> {
+        self.inner().settings_in_category(category_id)
+    }
+
+    fn setting(&self, setting_id: &SettingId) -> Option<Setting> {
+        self.inner().setting(setting_id)
+    }
+
+    fn watch_for_changes(&self) -> watch::Receiver<()> {
+        self.change_notify.subscribe()
+    }
+
+    async fn set_setting_values(
+        &self,
+        setting_values: Vec<(SettingId, Value)>,
+    ) -> device::Result<()> {
+        self.inner().set_setting_values(setting_values).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        panic::Location,
+        sync::{
+            Mutex,
+            atomic::{AtomicU32, Ordering},
+        },
+        time::Duration,
+    };
+
+    use macaddr::MacAddr6;
+    use tokio::sync::mpsc;
+
+    use crate::{
+        api::{
+            connection::{
+                self, ConnectionDescriptor, RfcommBackend, RfcommConnection,
+                RfcommServiceSelectionStrategy, TransportBackend,
+            },
+            device::OpenSCQ30DeviceRegistry,
+        },
+        connection_backend::capture::{Capture, CaptureDirection, RecordingRfcommBackend},
+        devices::soundcore::{
+            self,
+            common::{demo::DemoEvents, device::SoundcoreDeviceRegistry},
+        },
+        storage::OpenSCQ30Database,
+    };
+
+    use super::*;
+
+    /// Wraps another backend, allowing its connections to be dropped and new connections to fail on demand.
+    struct FlakyRfcommBackend {
+        inner: Arc<dyn RfcommBackend + Send + Sync>,
+        connection_statuses: Mutex<Vec<watch::Sender<ConnectionStatus>>>,
+        connect_attempts: AtomicU32,
+        failures_remaining: AtomicU32,
+    }
+
+    impl FlakyRfcommBackend {
+        fn new(inner: Arc<dyn RfcommBackend + Send + Sync>) -> Self {
+            Self {
+                inner,
+                connection_statuses: Mutex::new(Vec::new()),
+                connect_attempts: AtomicU32::new(0),
+                failures_remaining: AtomicU32::new(0),
+            }
+        }
+
+        fn disconnect(&self, failed_reconnects: u32) {
+            self.failures_remaining
+                .store(failed_reconnects, Ordering::Relaxed);
+            for status in self.connection_statuses.lock().unwrap().drain(..) {
+                status.send_replace(ConnectionStatus::Disconnected);
+            }
+        }
+    }
+
+    #[async_trait]
+    impl RfcommBackend for FlakyRfcommBackend {
+        async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+            self.inner.devices().await
+        }
+
+        async fn connect(
+            &self,
+            mac_address: MacAddr6,
+            service_selection_strategy: RfcommServiceSelectionStrategy,
+        ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
+            self.connect_attempts.fetch_add(1, Ordering::Relaxed);
+            if self
+                .failures_remaining
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(connection::Error::DeviceNotFound {
+                    source: None,
+                    location: Location::caller(),
+                });
+            }
+            let connection_status = watch::channel(ConnectionStatus::Connected).0;
+            self.connection_statuses
+                .lock()
+                .unwrap()
+                .push(connection_status.clone());
+            Ok(Arc::new(FlakyRfcommConnection {
+                inner: self
+                    .inner
+                    .connect(mac_address, service_selection_strategy)
+                    .await?,
+                connection_status,
+            }))
+        }
+    }
+
+    struct FlakyRfcommConnection {
+        inner: Arc<dyn RfcommConnection + Send + Sync>,
+        connection_status: watch::Sender<ConnectionStatus>,
+    }
+
+    #[async_trait]
+    impl RfcommConnection for FlakyRfcommConnection {
+        async fn write(&self, data: &[u8]) -> connection::Result<()> {
+            self.inner.write(data).await
+        }
+
+        fn read_channel(&self) -> mpsc::Receiver<Vec<u8>> {
+            self.inner.read_channel()
+        }
+
+        fn connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+            self.connection_status.subscribe()
+        }
+    }
+
+    /// Forwards to a shared backend, for wrapping it in backends that take ownership of their inner backend.
+    struct SharedRfcommBackend(Arc<dyn RfcommBackend + Send + Sync>);
+
+    #[async_trait]
+    impl RfcommBackend for SharedRfcommBackend {
+        async fn devices(&self) -> connection::Result<HashSet<ConnectionDescriptor>> {
+            self.0.devices().await
+        }
+
+        async fn connect(
+            &self,
+            mac_address: MacAddr6,
+            service_selection_strategy: RfcommServiceSelectionStrategy,
+        ) -> connection::Result<Arc<dyn RfcommConnection + Send + Sync>> {
+            self.0
+                .connect(mac_address, service_selection_strategy)
+                .await
+        }
+    }
+
+    async fn connect(
+        policy: ReconnectPolicy,
+    ) -> (
+        Arc<FlakyRfcommBackend>,
+        Arc<dyn OpenSCQ30Device + Send + Sync>,
+    ) {
+        connect_wrapped(policy, |backend| backend).await
+    }
+
+    /// Like `connect`, but the flaky backend is wrapped by `wrap` before being used by the device.
+    async fn connect_wrapped(
+        policy: ReconnectPolicy,
+        wrap: impl FnOnce(Arc<dyn RfcommBackend + Send + Sync>) -> Arc<dyn RfcommBackend + Send + Sync>,
+    ) -> (
+        Arc<FlakyRfcommBackend>,
+        Arc<dyn OpenSCQ30Device + Send + Sync>,
+    ) {
+        let database = Arc::new(OpenSCQ30Database::new_in_memory().await.unwrap());
+        let demo_registry = soundcore::a3028::demo_device_registry(
+            database,
+            DeviceModel::SoundcoreA3028,
+            DemoEvents::default(),
+        );
+        let TransportBackend::Rfcomm(demo_backend) = demo_registry.backend.clone() else {
+            panic!("A3028 should use RFCOMM");
+        };
+        let backend = Arc::new(FlakyRfcommBackend::new(demo_backend));
+        let registry = SoundcoreDeviceRegistry {
+            backend: TransportBackend::Rfcomm(wrap(backend.clone())),
+            ..demo_registry
+        }
+        .with_reconnect_policy(Some(policy));
+        let device = registry.connect(MacAddr6::nil()).await.unwrap();
+        (backend, device)
+    }
+
+    // No timeout, since reconnecting waits on the database thread, and paused time would skip straight to the timeout
+    // while that happens.
+    async fn wait_for_status(device: &dyn OpenSCQ30Device, status: ConnectionStatus) {
+        device
+            .connection_status()
+            .wait_for(|current_status| *current_status == status)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnects_and_restores_state() {
+        let (backend, device) = connect(ReconnectPolicy::default()).await;
+        device
+            .set_setting_values(vec![(
+                SettingId::AmbientSoundMode,
+                Value::from("NoiseCanceling"),
+            )])
+            .await
+            .unwrap();
+
+        let mut changes = device.watch_for_changes();
+        backend.disconnect(2);
+        wait_for_status(device.as_ref(), ConnectionStatus::Reconnecting).await;
+        wait_for_status(device.as_ref(), ConnectionStatus::Connected).await;
+        changes.changed().await.unwrap();
+
+        assert_eq!(4, backend.connect_attempts.load(Ordering::Relaxed));
+        assert_eq!(
+            "NoiseCanceling",
+            Value::from(device.setting(&SettingId::AmbientSoundMode).unwrap())
+                .try_as_str()
+                .unwrap(),
+        );
+
+        // the new connection should be just as usable as the original one
+        device
+            .set_setting_values(vec![(SettingId::AmbientSoundMode, Value::from("Normal"))])
+            .await
+            .unwrap();
+        assert_eq!(
+            "Normal",
+            Value::from(device.setting(&SettingId::AmbientSoundMode).unwrap())
+                .try_as_str()
+                .unwrap(),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn records_each_connection_to_its_own_capture() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("capture.jsonl");
+        let (backend, device) = connect_wrapped(ReconnectPolicy::default(), |backend| {
+            Arc::new(RecordingRfcommBackend::new(
+                SharedRfcommBackend(backend),
+                path.clone(),
+            ))
+        })
+        .await;
+
+        backend.disconnect(0);
+        wait_for_status(device.as_ref(), ConnectionStatus::Reconnecting).await;
+        wait_for_status(device.as_ref(), ConnectionStatus::Connected).await;
+        drop(device);
+
+        // both connections requested the device's state, so neither capture should have been cut short by the other
+        for file_name in ["capture.jsonl", "capture.1.jsonl"] {
+            let capture = Capture::load(&directory.path().join(file_name)).unwrap();
+            assert!(
+                capture
+                    .entries
+                    .iter()
+                    .any(|entry| entry.direction == CaptureDirection::Outbound),
+                "{file_name} should contain packets sent to the device",
+            );
+        }
+        assert!(!directory.path().join("capture.2.jsonl").exists());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_attempts() {
+        let (backend, device) = connect(ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(1),
+            max_attempts: Some(3),
+        })
+        .await;
+
+        backend.disconnect(u32::MAX);
+        wait_for_status(device.as_ref(), ConnectionStatus::Reconnecting).await;
+        wait_for_status(device.as_ref(), ConnectionStatus::Disconnected).await;
+        assert_eq!(4, backend.connect_attempts.load(Ordering::Relaxed));
+    }
+}
@@ -4,7 +4,7 @@
 /// - state: type that holds state for the device
 /// - initializer: async function that requests data needed to populate state and returns the current state
 /// - builder: adds modules for the device's features
-/// - demo_packets: HashMap<packet::Command, packet::Inbound> that will be referenced in `demo_device_registry` to respond to outbound packets
+/// - demo_packets: HashMap<packet::Command, packet::Inbound> or DemoState that will be referenced in `demo_device_registry` to respond to outbound packets
 /// - config (optional): SoundcoreDeviceConfig for specifying other options, such as whether packets end with checksums
 ///
 /// For examples, see any file in lib/src/device/soundcore/a*.rs
@@ -33,7 +33,7 @@ macro_rules! soundcore_device {
         $config:expr$(,)?
     ) => {
         pub fn device_registry(
-            backend: std::sync::Arc<dyn $crate::api::connection::RfcommBackend + Send + Sync>,
+            backend: $crate::api::connection::TransportBackend,
             database: std::sync::Arc<$crate::storage::OpenSCQ30Database>,
             device_model: $crate::devices::DeviceModel,
         ) -> $crate::devices::soundcore::common::device::SoundcoreDeviceRegistry<$state> {
@@ -46,18 +46,21 @@ macro_rules! soundcore_device {
             )
         }
 
+        /// Each connection to the demo device plays `events`, so pass `DemoEvents::default()` for a device that
+        /// only changes when settings are set.
         pub fn demo_device_registry(
             database: std::sync::Arc<$crate::storage::OpenSCQ30Database>,
             device_model: $crate::devices::DeviceModel,
+            events: $crate::devices::soundcore::common::demo::DemoEvents,
         ) -> $crate::devices::soundcore::common::device::SoundcoreDeviceRegistry<$state> {
+            let state: $crate::devices::soundcore::common::demo::DemoState = $demo_packets.into();
             $crate::devices::soundcore::common::device::SoundcoreDeviceRegistry::new(
-                std::sync::Arc::new(
-                    $crate::devices::soundcore::common::demo::DemoConnectionRegistry::new(
-                        device_model,
-                        $demo_packets,
-                        $config,
-                    ),
-                ),
+                $crate::devices::soundcore::common::demo::DemoConnectionRegistry::new(
+                    device_model,
+                    state.with_events(events),
+                    $config,
+                )
+                .into_transport_backend(),
                 database,
                 device_model,
                 Box::new(|$packet_io_controller| Box::pin(async move { $fetch_state })),
@@ -65,6 +68,12 @@ macro_rules! soundcore_device {
             )
         }
 
+        /// The transport that `device_registry`'s backend should be for.
+        pub fn transport() -> $crate::api::connection::Transport {
+            let config: $crate::devices::soundcore::common::device::SoundcoreDeviceConfig = $config;
+            config.transport
+        }
+
         impl $crate::devices::soundcore::common::device::BuildDevice<$state>
             for $crate::devices::soundcore::common::device::SoundcoreDeviceRegistry<$state>
         {
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{borrow::Cow, sync::Arc};
 
 use openscq30_lib_has::Has;
 use setting_handler::EqualizerSettingHandler;
@@ -10,11 +10,12 @@ use strum::{EnumIter, EnumString, IntoStaticStr};
 use tokio::sync::watch;
 
 use crate::{
-    api::settings::{CategoryId, SettingId},
+    api::settings::{self, CategoryId, SettingId},
     devices::{
         DeviceModel,
         soundcore::common::{
             modules::equalizer::{
+                channel_setting_handler::ChannelEqualizerSettingHandler,
                 custom_equalizer_profile_store::CustomEqualizerProfileStore,
                 import_export_setting_handler::ImportExportSettingHandler,
                 state_modifier::EqualizerWithCustomHearIdOptions,
@@ -34,6 +35,7 @@ use crate::{
 
 use super::ModuleCollection;
 
+mod channel_setting_handler;
 mod custom_equalizer_profile_store;
 mod import_export_setting_handler;
 mod setting_handler;
@@ -49,6 +51,15 @@ enum_subset!(
     }
 );
 
+enum_subset!(
+    SettingId,
+    #[derive(EnumString, EnumIter, IntoStaticStr)]
+    enum ChannelEqualizerSetting {
+        VolumeAdjustmentsLeft,
+        VolumeAdjustmentsRight,
+    }
+);
+
 enum_subset!(
     SettingId,
     #[derive(EnumString, EnumIter, IntoStaticStr)]
@@ -56,6 +67,14 @@ enum_subset!(
         ImportCustomEqualizerProfiles,
         ExportCustomEqualizerProfiles,
         ExportCustomEqualizerProfilesOutput,
+        ShareCustomEqualizerProfile,
+        ShareCustomEqualizerProfileOutput,
+        ImportSharedEqualizerProfile,
+        ImportSharedEqualizerProfileResult,
+        ImportEqualizerApoProfile,
+        ImportEqualizerApoProfileResult,
+        ExportEqualizerApoProfile,
+        CopyCustomEqualizerProfilesFrom,
     }
 );
 
@@ -77,6 +96,25 @@ pub struct EqualizerModuleSettings<
     pub presets: Vec<EqualizerPreset<PRESET_BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>>,
 }
 
+impl<
+    const VISIBLE_BANDS: usize,
+    const PRESET_BANDS: usize,
+    const MIN_VOLUME: i16,
+    const MAX_VOLUME: i16,
+    const FRACTION_DIGITS: u8,
+> EqualizerModuleSettings<VISIBLE_BANDS, PRESET_BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>
+{
+    /// The band layout and range that the volume adjustments setting will have.
+    pub fn equalizer(&self) -> settings::Equalizer {
+        settings::Equalizer {
+            band_hz: Cow::Owned(self.band_hz.to_vec()),
+            fraction_digits: FRACTION_DIGITS.into(),
+            min: MIN_VOLUME,
+            max: MAX_VOLUME,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct EqualizerPreset<
     const BANDS: usize,
@@ -568,8 +606,12 @@ impl<T: 'static> ModuleCollection<T> {
             + Sync,
     {
         let profile_store = Arc::new(
-            CustomEqualizerProfileStore::new(database, device_model, change_notify.to_owned())
-                .await,
+            CustomEqualizerProfileStore::new(
+                database.to_owned(),
+                device_model,
+                change_notify.to_owned(),
+            )
+            .await,
         );
         self.setting_manager.add_handler(
             CategoryId::Equalizer,
@@ -590,9 +632,31 @@ impl<T: 'static> ModuleCollection<T> {
             )
             .with_tws(),
         );
+        if CHANNELS == 2 {
+            self.setting_manager.add_handler(
+                CategoryId::PerChannelEqualizer,
+                ChannelEqualizerSettingHandler::<
+                    T,
+                    CHANNELS,
+                    BANDS,
+                    VISIBLE_BANDS,
+                    MIN_VOLUME,
+                    MAX_VOLUME,
+                    FRACTION_DIGITS,
+                >::new(module_settings.custom_preset_id, module_settings.band_hz)
+                .with_tws(),
+            );
+        }
         self.setting_manager.add_handler(
             CategoryId::EqualizerImportExport,
-            ImportExportSettingHandler::new(profile_store, change_notify),
+            ImportExportSettingHandler::new(
+                profile_store,
+                database,
+                device_model,
+                change_notify,
+                module_settings.band_hz.to_vec(),
+            )
+            .await,
         );
         self.state_modifiers.push(state_modifier);
     }
@@ -625,8 +689,12 @@ impl<T: 'static> ModuleCollection<T> {
             + Sync,
     {
         let profile_store = Arc::new(
-            CustomEqualizerProfileStore::new(database, device_model, change_notify.to_owned())
-                .await,
+            CustomEqualizerProfileStore::new(
+                database.to_owned(),
+                device_model,
+                change_notify.to_owned(),
+            )
+            .await,
         );
         self.setting_manager.add_handler(
             CategoryId::Equalizer,
@@ -646,9 +714,30 @@ impl<T: 'static> ModuleCollection<T> {
                 module_settings.presets,
             ),
         );
+        if CHANNELS == 2 {
+            self.setting_manager.add_handler(
+                CategoryId::PerChannelEqualizer,
+                ChannelEqualizerSettingHandler::<
+                    T,
+                    CHANNELS,
+                    BANDS,
+                    VISIBLE_BANDS,
+                    MIN_VOLUME,
+                    MAX_VOLUME,
+                    FRACTION_DIGITS,
+                >::new(module_settings.custom_preset_id, module_settings.band_hz),
+            );
+        }
         self.setting_manager.add_handler(
             CategoryId::EqualizerImportExport,
-            ImportExportSettingHandler::new(profile_store, change_notify),
+            ImportExportSettingHandler::new(
+                profile_store,
+                database,
+                device_model,
+                change_notify,
+                module_settings.band_hz.to_vec(),
+            )
+            .await,
         );
         self.state_modifiers.push(state_modifier);
     }
@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use openscq30_lib_has::Has;
+use strum::IntoEnumIterator;
+
+use crate::{
+    api::settings::{self, Setting, SettingId, Value},
+    devices::soundcore::common::{
+        settings_manager::{SettingHandler, SettingHandlerResult},
+        structures::{EqualizerConfiguration, TwsStatus},
+    },
+};
+
+use super::{ChannelEqualizerSetting, setting_handler::merge_volume_adjustments};
+
+/// Separate left and right volume adjustments for devices with two equalizer channels. The regular
+/// `VolumeAdjustments` setting shows the left channel and sets both. Only use this when CHANNELS is 2.
+pub struct ChannelEqualizerSettingHandler<
+    StateT,
+    const CHANNELS: usize,
+    const BANDS: usize,
+    const VISIBLE_BANDS: usize,
+    const MIN_VOLUME: i16,
+    const MAX_VOLUME: i16,
+    const FRACTION_DIGITS: u8,
+> {
+    get_tws_status: Option<fn(&StateT) -> TwsStatus>,
+    custom_preset_id: u16,
+    band_hz: [u16; VISIBLE_BANDS],
+}
+
+impl<
+    StateT,
+    const CHANNELS: usize,
+    const BANDS: usize,
+    const VISIBLE_BANDS: usize,
+    const MIN_VOLUME: i16,
+    const MAX_VOLUME: i16,
+    const FRACTION_DIGITS: u8,
+>
+    ChannelEqualizerSettingHandler<
+        StateT,
+        CHANNELS,
+        BANDS,
+        VISIBLE_BANDS,
+        MIN_VOLUME,
+        MAX_VOLUME,
+        FRACTION_DIGITS,
+    >
+{
+    pub fn new(custom_preset_id: u16, band_hz: [u16; VISIBLE_BANDS]) -> Self {
+        Self {
+            get_tws_status: None,
+            custom_preset_id,
+            band_hz,
+        }
+    }
+
+    pub fn with_tws(mut self) -> Self
+    where
+        StateT: Has<TwsStatus>,
+    {
+        self.get_tws_status = Some(|state| *state.get());
+        self
+    }
+
+    fn is_tws_disconnected(&self, state: &StateT) -> bool {
+        self.get_tws_status
+            .is_some_and(|get_tws_status| !get_tws_status(state).is_connected)
+    }
+}
+
+impl ChannelEqualizerSetting {
+    fn channel(&self) -> usize {
+        match self {
+            Self::VolumeAdjustmentsLeft => 0,
+            Self::VolumeAdjustmentsRight => 1,
+        }
+    }
+}
+
+#[async_trait]
+impl<
+    StateT,
+    const CHANNELS: usize,
+    const BANDS: usize,
+    const VISIBLE_BANDS: usize,
+    const MIN_VOLUME: i16,
+    const MAX_VOLUME: i16,
+    const FRACTION_DIGITS: u8,
+> SettingHandler<StateT>
+    for ChannelEqualizerSettingHandler<
+        StateT,
+        CHANNELS,
+        BANDS,
+        VISIBLE_BANDS,
+        MIN_VOLUME,
+        MAX_VOLUME,
+        FRACTION_DIGITS,
+    >
+where
+    StateT: Has<EqualizerConfiguration<CHANNELS, BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>>
+        + Send,
+{
+    fn settings(&self) -> Vec<SettingId> {
+        ChannelEqualizerSetting::iter().map(Into::into).collect()
+    }
+
+    fn get(&self, state: &StateT, setting_id: &SettingId) -> Option<Setting> {
+        if self.is_tws_disconnected(state) {
+            return None;
+        }
+        let setting: ChannelEqualizerSetting = (*setting_id).try_into().ok()?;
+        let equalizer_configuration = state.get();
+        Some(Setting::Equalizer {
+            setting: settings::Equalizer {
+                band_hz: Cow::Owned(self.band_hz.to_vec()),
+                fraction_digits: FRACTION_DIGITS.into(),
+                min: MIN_VOLUME,
+                max: MAX_VOLUME,
+            },
+            value: equalizer_configuration.volume_adjustments()[setting.channel()]
+                .adjustments()
+                .to_vec(),
+        })
+    }
+
+    async fn set(
+        &self,
+        state: &mut StateT,
+        setting_id: &SettingId,
+        value: Value,
+    ) -> SettingHandlerResult<()> {
+        // We can't modify the equalizer configuration while TWS is disconnected
+        if self.is_tws_disconnected(state) {
+            return Ok(());
+        }
+        let setting: ChannelEqualizerSetting = (*setting_id)
+            .try_into()
+            .expect("already filtered to valid values only by SettingsManager");
+        let channel = setting.channel();
+        let values = value.try_as_i16_slice()?;
+        let equalizer_configuration = state.get_mut();
+        let mut volume_adjustments = *equalizer_configuration.volume_adjustments();
+        volume_adjustments[channel] =
+            merge_volume_adjustments(values, &volume_adjustments[channel]);
+        *equalizer_configuration =
+            EqualizerConfiguration::new(self.custom_preset_id, volume_adjustments);
+        Ok(())
+    }
+}
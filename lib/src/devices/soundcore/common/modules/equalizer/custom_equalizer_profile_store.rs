@@ -5,12 +5,12 @@ use tracing::warn;
 
 use crate::{
     devices::DeviceModel,
-    storage::{self, OpenSCQ30Database},
+    storage::{self, EqualizerProfile, OpenSCQ30Database},
 };
 
 pub struct CustomEqualizerProfileStore {
     database: Arc<OpenSCQ30Database>,
-    sender: watch::Sender<Vec<(String, Vec<i16>)>>,
+    sender: watch::Sender<Vec<EqualizerProfile>>,
     device_model: DeviceModel,
 }
 
@@ -42,19 +42,19 @@ impl CustomEqualizerProfileStore {
         }
     }
 
-    pub fn subscribe(&self) -> watch::Receiver<Vec<(String, Vec<i16>)>> {
+    pub fn subscribe(&self) -> watch::Receiver<Vec<EqualizerProfile>> {
         self.sender.subscribe()
     }
 
-    pub async fn upsert(&self, name: String, volume_adjustments: Vec<i16>) -> storage::Result<()> {
+    pub async fn upsert(&self, profile: EqualizerProfile) -> storage::Result<()> {
         self.database
-            .upsert_equalizer_profile(self.device_model, name, volume_adjustments)
+            .upsert_equalizer_profile(self.device_model, profile)
             .await?;
         self.refresh().await?;
         Ok(())
     }
 
-    pub async fn bulk_upsert(&self, profiles: Vec<(String, Vec<i16>)>) -> storage::Result<()> {
+    pub async fn bulk_upsert(&self, profiles: Vec<EqualizerProfile>) -> storage::Result<()> {
         self.database
             .upsert_equalizer_profiles(self.device_model, profiles)
             .await?;
@@ -5,20 +5,28 @@ use std::{
 };
 
 use async_trait::async_trait;
+use openscq30_i18n::Translate;
 use openscq30_lib_has::Has;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use tokio::sync::watch;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use crate::{
-    api::settings::{self, SettingId, Value},
-    devices::soundcore::common::{
-        modules::equalizer::custom_equalizer_profile_store::CustomEqualizerProfileStore,
-        settings_manager::{SettingHandler, SettingHandlerError, SettingHandlerResult},
-        structures::EqualizerConfiguration,
+    api::settings::{
+        self, ClippedBand, Select, SettingId, Value, equalizer_apo::EqualizerApoProfile,
+        share_string::SharedEqualizerProfile,
+    },
+    devices::{
+        DeviceModel,
+        soundcore::common::{
+            modules::equalizer::custom_equalizer_profile_store::CustomEqualizerProfileStore,
+            settings_manager::{SettingHandler, SettingHandlerError, SettingHandlerResult},
+            structures::EqualizerConfiguration,
+        },
     },
     i18n::fl,
+    storage::{EqualizerProfile, OpenSCQ30Database},
 };
 
 use super::ImportExportSetting;
@@ -31,16 +39,45 @@ pub struct ImportExportSettingHandler<
     const FRACTION_DIGITS: u8,
 > {
     profile_store: Arc<CustomEqualizerProfileStore>,
-    profiles_receiver: watch::Receiver<Vec<(String, Vec<i16>)>>,
+    profiles_receiver: watch::Receiver<Vec<EqualizerProfile>>,
     selected_profiles: Mutex<HashSet<String>>,
     change_notify: watch::Sender<()>,
+    band_hz: Vec<u16>,
+    last_equalizer_apo_import: Mutex<Option<EqualizerApoImport>>,
+    database: Arc<OpenSCQ30Database>,
+    /// Other models that have custom profiles which could be copied to this one
+    copyable_models: Arc<Mutex<Vec<DeviceModel>>>,
+    device_model: DeviceModel,
+    shared_profile: Mutex<Option<String>>,
+    last_shared_profile_import: Mutex<Option<SharedProfileImport>>,
 }
 
+/// Profiles imported from Equalizer APO configs without a name get this one
+const DEFAULT_EQUALIZER_APO_PROFILE_NAME: &str = "Equalizer APO";
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ExportedCustomProfile<'a> {
     pub name: Cow<'a, str>,
     pub volume_adjustments: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right_volume_adjustments: Option<Vec<f64>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EqualizerApoImport {
+    pub name: String,
+    pub clipped_bands: Vec<ClippedBand>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SharedProfileImport {
+    pub name: String,
+    /// Set when the profile was made for a different band layout and had to be converted
+    pub converted_from: Option<DeviceModel>,
+    pub clipped_bands: Vec<ClippedBand>,
 }
 
 impl<
@@ -51,16 +88,68 @@ impl<
     const FRACTION_DIGITS: u8,
 > ImportExportSettingHandler<CHANNELS, BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>
 {
-    #[instrument(skip(profile_store))]
-    pub fn new(
+    #[instrument(skip(profile_store, database))]
+    pub async fn new(
         profile_store: Arc<CustomEqualizerProfileStore>,
+        database: Arc<OpenSCQ30Database>,
+        device_model: DeviceModel,
         change_notify: watch::Sender<()>,
+        band_hz: Vec<u16>,
     ) -> Self {
+        let copyable_models = Arc::new(Mutex::new(
+            Self::fetch_copyable_models(&database, device_model).await,
+        ));
+        // The profile store is dropped along with the device, at which point changed() errors and the task ends
+        tokio::spawn({
+            let mut profiles_receiver = profile_store.subscribe();
+            let database = database.to_owned();
+            let copyable_models = copyable_models.to_owned();
+            let change_notify = change_notify.to_owned();
+            async move {
+                while profiles_receiver.changed().await.is_ok() {
+                    *copyable_models.lock().unwrap() =
+                        Self::fetch_copyable_models(&database, device_model).await;
+                    change_notify.send_replace(());
+                }
+            }
+        });
         Self {
             profiles_receiver: profile_store.subscribe(),
             profile_store,
             change_notify,
             selected_profiles: Default::default(),
+            band_hz,
+            last_equalizer_apo_import: Default::default(),
+            database,
+            copyable_models,
+            device_model,
+            shared_profile: Default::default(),
+            last_shared_profile_import: Default::default(),
+        }
+    }
+
+    async fn fetch_copyable_models(
+        database: &OpenSCQ30Database,
+        device_model: DeviceModel,
+    ) -> Vec<DeviceModel> {
+        database
+            .fetch_equalizer_profile_models()
+            .await
+            .unwrap_or_else(|err| {
+                warn!("error fetching models with custom equalizer profiles: {err:?}");
+                Vec::new()
+            })
+            .into_iter()
+            .filter(|model| *model != device_model)
+            .collect()
+    }
+
+    fn equalizer(&self) -> settings::Equalizer {
+        settings::Equalizer {
+            band_hz: Cow::Owned(self.band_hz.clone()),
+            fraction_digits: FRACTION_DIGITS.into(),
+            min: MIN_VOLUME,
+            max: MAX_VOLUME,
         }
     }
 }
@@ -82,7 +171,7 @@ where
         ImportExportSetting::iter().map(Into::into).collect()
     }
 
-    fn get(&self, _state: &T, setting_id: &SettingId) -> Option<settings::Setting> {
+    fn get(&self, state: &T, setting_id: &SettingId) -> Option<settings::Setting> {
         let setting = (*setting_id).try_into().ok()?;
         Some(match setting {
             ImportExportSetting::ImportCustomEqualizerProfiles => settings::Setting::ImportString {
@@ -93,8 +182,7 @@ where
                     .profiles_receiver
                     .borrow()
                     .iter()
-                    .map(|(name, _)| name)
-                    .cloned()
+                    .map(|profile| profile.name.clone())
                     .collect::<Vec<_>>();
                 let selected_profiles = self.selected_profiles.lock().unwrap();
                 settings::Setting::MultiSelect {
@@ -115,13 +203,16 @@ where
                 let selection = self.selected_profiles.lock().unwrap();
                 let exported_profiles = custom_profiles
                     .iter()
-                    .filter(|(name, _)| selection.contains(name))
-                    .map(|(name, values)| ExportedCustomProfile {
-                        name: name.into(),
-                        volume_adjustments: values
-                            .iter()
-                            .map(|i| *i as f64 / 10u32.pow(FRACTION_DIGITS.into()) as f64)
-                            .collect(),
+                    .filter(|profile| selection.contains(&profile.name))
+                    .map(|profile| ExportedCustomProfile {
+                        name: Cow::Borrowed(&profile.name),
+                        volume_adjustments: values_to_decimal::<FRACTION_DIGITS>(
+                            &profile.volume_adjustments,
+                        ),
+                        right_volume_adjustments: profile
+                            .right_volume_adjustments
+                            .as_deref()
+                            .map(values_to_decimal::<FRACTION_DIGITS>),
                     })
                     .collect::<Vec<_>>();
                 let json = serde_json::to_string(&exported_profiles).unwrap();
@@ -130,6 +221,115 @@ where
                     translated_value: json,
                 }
             }
+            ImportExportSetting::ShareCustomEqualizerProfile => {
+                let profile_names = self
+                    .profiles_receiver
+                    .borrow()
+                    .iter()
+                    .map(|profile| profile.name.clone())
+                    .collect::<Vec<_>>();
+                let shared_profile = self.shared_profile.lock().unwrap();
+                settings::Setting::OptionalSelect {
+                    value: shared_profile
+                        .as_ref()
+                        .filter(|name| profile_names.contains(name))
+                        .cloned()
+                        .map(Cow::from),
+                    setting: settings::Select {
+                        options: profile_names.iter().cloned().map(Cow::Owned).collect(),
+                        localized_options: profile_names,
+                    },
+                }
+            }
+            ImportExportSetting::ShareCustomEqualizerProfileOutput => {
+                let shared_profile = self.shared_profile.lock().unwrap();
+                let share_string = self
+                    .profiles_receiver
+                    .borrow()
+                    .iter()
+                    .find(|profile| shared_profile.as_ref() == Some(&profile.name))
+                    .map(|profile| {
+                        SharedEqualizerProfile {
+                            device_model: self.device_model,
+                            name: profile.name.clone(),
+                            band_hz: self.band_hz.clone(),
+                            fraction_digits: FRACTION_DIGITS,
+                            volume_adjustments: profile.volume_adjustments.clone(),
+                            right_volume_adjustments: profile.right_volume_adjustments.clone(),
+                        }
+                        .to_string()
+                    })
+                    .unwrap_or_default();
+                settings::Setting::Information {
+                    value: share_string.to_owned(),
+                    translated_value: share_string,
+                }
+            }
+            ImportExportSetting::ImportSharedEqualizerProfile => settings::Setting::ImportString {
+                confirmation_message: Some(fl!("import-shared-equalizer-profile-confirm")),
+            },
+            ImportExportSetting::ImportSharedEqualizerProfileResult => {
+                let last_import = self.last_shared_profile_import.lock().unwrap();
+                match &*last_import {
+                    Some(last_import) => settings::Setting::Information {
+                        value: serde_json::to_string(last_import).unwrap(),
+                        translated_value: last_import.translate(),
+                    },
+                    None => settings::Setting::Information {
+                        value: String::new(),
+                        translated_value: String::new(),
+                    },
+                }
+            }
+            ImportExportSetting::ImportEqualizerApoProfile => settings::Setting::ImportString {
+                confirmation_message: Some(fl!(
+                    "import-equalizer-apo-profile-confirm",
+                    name = DEFAULT_EQUALIZER_APO_PROFILE_NAME
+                )),
+            },
+            ImportExportSetting::ImportEqualizerApoProfileResult => {
+                let last_import = self.last_equalizer_apo_import.lock().unwrap();
+                match &*last_import {
+                    Some(last_import) => settings::Setting::Information {
+                        value: serde_json::to_string(last_import).unwrap(),
+                        translated_value: last_import.translate(),
+                    },
+                    None => settings::Setting::Information {
+                        value: String::new(),
+                        translated_value: String::new(),
+                    },
+                }
+            }
+            ImportExportSetting::ExportEqualizerApoProfile => {
+                let equalizer_configuration: &EqualizerConfiguration<
+                    CHANNELS,
+                    BANDS,
+                    MIN_VOLUME,
+                    MAX_VOLUME,
+                    FRACTION_DIGITS,
+                > = state.get();
+                let graphic_eq = self
+                    .equalizer()
+                    .frequency_response(
+                        equalizer_configuration
+                            .volume_adjustments_channel_1()
+                            .adjustments(),
+                    )
+                    .to_string();
+                settings::Setting::Information {
+                    value: graphic_eq.to_owned(),
+                    translated_value: graphic_eq,
+                }
+            }
+            // Selecting a model copies its profiles, so there's never a selection to display
+            ImportExportSetting::CopyCustomEqualizerProfilesFrom => {
+                settings::Setting::OptionalSelect {
+                    setting: Select::from_enum(
+                        self.copyable_models.lock().unwrap().iter().copied(),
+                    ),
+                    value: None,
+                }
+            }
         })
     }
 
@@ -149,18 +349,15 @@ where
                     .map_err(|err| SettingHandlerError::Other(Box::new(err)))?;
                 let profiles = exported_profiles
                     .into_iter()
-                    .map(|exported| {
-                        (
-                            exported.name.into_owned(),
-                            exported
-                                .volume_adjustments
-                                .into_iter()
-                                .map(|value| {
-                                    (value * 10u32.pow(FRACTION_DIGITS.into()) as f64).round()
-                                        as i16
-                                })
-                                .collect::<Vec<_>>(),
-                        )
+                    .map(|exported| EqualizerProfile {
+                        name: exported.name.into_owned(),
+                        volume_adjustments: decimal_to_values::<FRACTION_DIGITS>(
+                            &exported.volume_adjustments,
+                        ),
+                        right_volume_adjustments: exported
+                            .right_volume_adjustments
+                            .as_deref()
+                            .map(decimal_to_values::<FRACTION_DIGITS>),
                     })
                     .collect();
                 self.profile_store.bulk_upsert(profiles).await?;
@@ -171,10 +368,186 @@ where
                     values.into_iter().map(|cow| cow.into_owned()).collect();
                 self.change_notify.send_replace(());
             }
-            ImportExportSetting::ExportCustomEqualizerProfilesOutput => {
+            ImportExportSetting::ShareCustomEqualizerProfile => {
+                *self.shared_profile.lock().unwrap() =
+                    value.try_as_optional_str()?.map(ToOwned::to_owned);
+                self.change_notify.send_replace(());
+            }
+            ImportExportSetting::ImportSharedEqualizerProfile => {
+                let shared: SharedEqualizerProfile = value
+                    .try_as_str()?
+                    .parse()
+                    .map_err(|err| SettingHandlerError::Other(Box::new(err)))?;
+                // Devices with one channel have nowhere to put the right channel
+                let shared_right_volume_adjustments = shared
+                    .right_volume_adjustments
+                    .clone()
+                    .filter(|_| CHANNELS == 2);
+                let equalizer = self.equalizer();
+                let (profile, import) = if shared.fits(&equalizer) {
+                    (
+                        EqualizerProfile {
+                            name: shared.name.clone(),
+                            volume_adjustments: shared.volume_adjustments,
+                            right_volume_adjustments: shared_right_volume_adjustments,
+                        },
+                        SharedProfileImport {
+                            name: shared.name,
+                            converted_from: None,
+                            clipped_bands: Vec::new(),
+                        },
+                    )
+                } else {
+                    let source_equalizer = shared.equalizer();
+                    let left = equalizer.resample(&source_equalizer, &shared.volume_adjustments);
+                    let right = shared_right_volume_adjustments.map(|right_volume_adjustments| {
+                        equalizer.resample(&source_equalizer, &right_volume_adjustments)
+                    });
+                    let mut clipped_bands = left.clipped_bands;
+                    if let Some(right) = &right {
+                        for band in &right.clipped_bands {
+                            if !clipped_bands.iter().any(|it| it.band_hz == band.band_hz) {
+                                clipped_bands.push(band.clone());
+                            }
+                        }
+                    }
+                    (
+                        EqualizerProfile {
+                            name: shared.name.clone(),
+                            volume_adjustments: left.volume_adjustments,
+                            right_volume_adjustments: right.map(|right| right.volume_adjustments),
+                        },
+                        SharedProfileImport {
+                            name: shared.name,
+                            converted_from: Some(shared.device_model),
+                            clipped_bands,
+                        },
+                    )
+                };
+                self.profile_store.upsert(profile).await?;
+                *self.last_shared_profile_import.lock().unwrap() = Some(import);
+                self.change_notify.send_replace(());
+            }
+            ImportExportSetting::ImportEqualizerApoProfile => {
+                let profile: EqualizerApoProfile = value
+                    .try_as_str()?
+                    .parse()
+                    .map_err(|err| SettingHandlerError::Other(Box::new(err)))?;
+                let fit = self.equalizer().fit(|hz| profile.gain_db_at(hz));
+                let name = profile
+                    .name
+                    .unwrap_or_else(|| DEFAULT_EQUALIZER_APO_PROFILE_NAME.to_owned());
+                self.profile_store
+                    .upsert(EqualizerProfile::new(name.clone(), fit.volume_adjustments))
+                    .await?;
+                *self.last_equalizer_apo_import.lock().unwrap() = Some(EqualizerApoImport {
+                    name,
+                    clipped_bands: fit.clipped_bands,
+                });
+                self.change_notify.send_replace(());
+            }
+            ImportExportSetting::CopyCustomEqualizerProfilesFrom => {
+                let Some(source_model) = value.try_as_optional_enum_variant::<DeviceModel>()?
+                else {
+                    return Ok(());
+                };
+                let source_equalizer = source_model
+                    .equalizer()
+                    .ok_or(SettingHandlerError::MissingData)?;
+                let equalizer = self.equalizer();
+                let profiles = self
+                    .database
+                    .fetch_all_equalizer_profiles(source_model)
+                    .await?
+                    .into_iter()
+                    .map(|profile| EqualizerProfile {
+                        volume_adjustments: equalizer
+                            .resample(&source_equalizer, &profile.volume_adjustments)
+                            .volume_adjustments,
+                        right_volume_adjustments: profile.right_volume_adjustments.map(
+                            |right_volume_adjustments| {
+                                equalizer
+                                    .resample(&source_equalizer, &right_volume_adjustments)
+                                    .volume_adjustments
+                            },
+                        ),
+                        name: profile.name,
+                    })
+                    .collect();
+                self.profile_store.bulk_upsert(profiles).await?;
+            }
+            ImportExportSetting::ExportCustomEqualizerProfilesOutput
+            | ImportExportSetting::ShareCustomEqualizerProfileOutput
+            | ImportExportSetting::ImportSharedEqualizerProfileResult
+            | ImportExportSetting::ImportEqualizerApoProfileResult
+            | ImportExportSetting::ExportEqualizerApoProfile => {
                 return Err(SettingHandlerError::ReadOnly);
             }
         }
         Ok(())
     }
 }
+
+impl EqualizerApoImport {
+    fn translate(&self) -> String {
+        if self.clipped_bands.is_empty() {
+            return fl!("equalizer-apo-profile-imported", name = self.name.as_str());
+        }
+        fl!(
+            "equalizer-apo-profile-imported-with-clipping",
+            name = self.name.as_str(),
+            bands = format_clipped_bands(&self.clipped_bands)
+        )
+    }
+}
+
+impl SharedProfileImport {
+    fn translate(&self) -> String {
+        let Some(converted_from) = self.converted_from else {
+            return fl!(
+                "shared-equalizer-profile-imported",
+                name = self.name.as_str()
+            );
+        };
+        if self.clipped_bands.is_empty() {
+            return fl!(
+                "shared-equalizer-profile-converted",
+                name = self.name.as_str(),
+                model = converted_from.translate()
+            );
+        }
+        fl!(
+            "shared-equalizer-profile-converted-with-clipping",
+            name = self.name.as_str(),
+            model = converted_from.translate(),
+            bands = format_clipped_bands(&self.clipped_bands)
+        )
+    }
+}
+
+fn format_clipped_bands(clipped_bands: &[ClippedBand]) -> String {
+    clipped_bands
+        .iter()
+        .map(|band| {
+            format!(
+                "{} Hz ({:+.1} dB → {:+.1} dB)",
+                band.band_hz, band.target_db, band.clipped_db
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn values_to_decimal<const FRACTION_DIGITS: u8>(values: &[i16]) -> Vec<f64> {
+    values
+        .iter()
+        .map(|i| *i as f64 / 10u32.pow(FRACTION_DIGITS.into()) as f64)
+        .collect()
+}
+
+fn decimal_to_values<const FRACTION_DIGITS: u8>(values: &[f64]) -> Vec<i16> {
+    values
+        .iter()
+        .map(|value| (value * 10u32.pow(FRACTION_DIGITS.into()) as f64).round() as i16)
+        .collect()
+}
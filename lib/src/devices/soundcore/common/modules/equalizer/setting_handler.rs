@@ -14,6 +14,7 @@ use crate::{
         settings_manager::{SettingHandler, SettingHandlerResult},
         structures::{EqualizerConfiguration, TwsStatus, VolumeAdjustments},
     },
+    storage::EqualizerProfile,
 };
 
 use super::EqualizerSetting;
@@ -29,7 +30,7 @@ pub struct EqualizerSettingHandler<
     const FRACTION_DIGITS: u8,
 > {
     profile_store: Arc<CustomEqualizerProfileStore>,
-    custom_profiles_receiver: watch::Receiver<Vec<(String, Vec<i16>)>>,
+    custom_profiles_receiver: watch::Receiver<Vec<EqualizerProfile>>,
     get_tws_status: Option<fn(&StateT) -> TwsStatus>,
     custom_preset_id: u16,
     band_hz: [u16; VISIBLE_BANDS],
@@ -192,7 +193,7 @@ fn get_inner<
     band_hz: [u16; VISIBLE_BANDS],
     presets: &[EqualizerPreset<PRESET_BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>],
     custom_preset_id: u16,
-    custom_profiles_receiver: &watch::Receiver<Vec<(String, Vec<i16>)>>,
+    custom_profiles_receiver: &watch::Receiver<Vec<EqualizerProfile>>,
     setting_id: &SettingId,
 ) -> Option<crate::api::settings::Setting> {
     let setting = (*setting_id).try_into().ok()?;
@@ -223,11 +224,11 @@ fn get_inner<
                     settings::Select {
                         options: custom_profiles
                             .iter()
-                            .map(|(name, _)| name.to_owned().into())
+                            .map(|profile| profile.name.to_owned().into())
                             .collect(),
                         localized_options: custom_profiles
                             .iter()
-                            .map(|(name, _)| name.to_owned())
+                            .map(|profile| profile.name.to_owned())
                             .collect(),
                     }
                 },
@@ -235,12 +236,16 @@ fn get_inner<
                     .then(|| {
                         custom_profiles
                             .iter()
-                            .find(|(_, v)| {
-                                v == equalizer_configuration
-                                    .volume_adjustments_channel_1()
-                                    .adjustments()
+                            .find(|profile| {
+                                equalizer_configuration
+                                    .volume_adjustments()
+                                    .iter()
+                                    .enumerate()
+                                    .all(|(channel, volume_adjustments)| {
+                                        profile.channel(channel) == volume_adjustments.adjustments()
+                                    })
                             })
-                            .map(|(name, _)| name.clone().into())
+                            .map(|profile| profile.name.clone().into())
                     })
                     .flatten(),
             }
@@ -278,7 +283,7 @@ async fn set_inner<
     >,
     presets: &[EqualizerPreset<PRESET_BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>],
     custom_preset_id: u16,
-    custom_profiles_receiver: &watch::Receiver<Vec<(String, Vec<i16>)>>,
+    custom_profiles_receiver: &watch::Receiver<Vec<EqualizerProfile>>,
     profile_store: &CustomEqualizerProfileStore,
     setting_id: &SettingId,
     value: Value,
@@ -314,31 +319,39 @@ async fn set_inner<
         }
         EqualizerSetting::CustomEqualizerProfile => {
             if let Ok(name) = value.try_as_str() {
-                if let Some(volume_adjustments) = custom_profiles_receiver
+                if let Some(profile) = custom_profiles_receiver
                     .borrow()
                     .iter()
-                    .find(|(n, _)| n == name)
-                    .map(|(_, volume_adjustments)| volume_adjustments)
+                    .find(|profile| profile.name == name)
                 {
                     *equalizer_configuration = EqualizerConfiguration::new(
                         custom_preset_id,
-                        values_to_volume_adjustments(
-                            volume_adjustments,
-                            equalizer_configuration.volume_adjustments(),
-                        ),
+                        array::from_fn(|channel| {
+                            merge_volume_adjustments(
+                                profile.channel(channel),
+                                &equalizer_configuration.volume_adjustments()[channel],
+                            )
+                        }),
                     );
                 }
             } else if let Value::ModifiableSelectCommand(command) = value {
                 match command {
                     settings::ModifiableSelectCommand::Add(name) => {
+                        let volume_adjustments = equalizer_configuration
+                            .volume_adjustments_channel_1()
+                            .adjustments()
+                            .to_vec();
+                        let right_volume_adjustments = equalizer_configuration
+                            .volume_adjustments()
+                            .get(1)
+                            .map(|right| right.adjustments().to_vec())
+                            .filter(|right| *right != volume_adjustments);
                         profile_store
-                            .upsert(
-                                name.into_owned(),
-                                equalizer_configuration
-                                    .volume_adjustments_channel_1()
-                                    .adjustments()
-                                    .to_vec(),
-                            )
+                            .upsert(EqualizerProfile {
+                                name: name.into_owned(),
+                                volume_adjustments,
+                                right_volume_adjustments,
+                            })
                             .await?;
                     }
                     settings::ModifiableSelectCommand::Remove(name) => {
@@ -372,15 +385,24 @@ fn values_to_volume_adjustments<
     existing_volume_adjustments: &[VolumeAdjustments<BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>;
          CHANNELS],
 ) -> [VolumeAdjustments<BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>; CHANNELS] {
+    array::from_fn(|channel| {
+        merge_volume_adjustments(values, &existing_volume_adjustments[channel])
+    })
+}
+
+pub(super) fn merge_volume_adjustments<
+    const BANDS: usize,
+    const MIN_VOLUME: i16,
+    const MAX_VOLUME: i16,
+    const FRACTION_DIGITS: u8,
+>(
+    values: &[i16],
+    existing_volume_adjustments: &VolumeAdjustments<BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS>,
+) -> VolumeAdjustments<BANDS, MIN_VOLUME, MAX_VOLUME, FRACTION_DIGITS> {
     // Some devices have extra bands, but those aren't exposed to the user, so I have no idea what they're for
     // We can just add back in whatever was there before (we're only showing the user the first 8 bands)
-    array::from_fn(|band| {
-        let band_adjustments = existing_volume_adjustments[band].adjustments();
-        VolumeAdjustments::new(array::from_fn(|channel| {
-            values
-                .get(channel)
-                .copied()
-                .unwrap_or(band_adjustments[channel])
-        }))
-    })
+    let existing = existing_volume_adjustments.adjustments();
+    VolumeAdjustments::new(array::from_fn(|band| {
+        values.get(band).copied().unwrap_or(existing[band])
+    }))
 }
@@ -122,7 +122,7 @@ flag!(
     VoicePrompt,
     FlagConfiguration {
         setting_id: SettingId::VoicePrompt,
-        set_command: packet::Command([0x01, 0x90]),
+        set_command: packet::outbound::SET_VOICE_PROMPT_COMMAND,
         update_command: Some(packet::Command([0x01, 0x10])),
     },
 );
@@ -131,7 +131,7 @@ flag!(
     Ldac,
     FlagConfiguration {
         setting_id: SettingId::Ldac,
-        set_command: packet::Command([0x01, 0xFF,]),
+        set_command: packet::outbound::SET_LDAC_COMMAND,
         update_command: Some(packet::Command([0x01, 0x7F,])),
     },
 );
@@ -67,7 +67,7 @@ where
         for step in path {
             self.packet_io
                 .send_with_response(&packet::Outbound::new(
-                    packet::Command([0x06, 0x81]),
+                    packet::outbound::SetSoundModes::COMMAND,
                     step.bytes(),
                 ))
                 .await?;
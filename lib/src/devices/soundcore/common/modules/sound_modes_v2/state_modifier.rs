@@ -43,7 +43,7 @@ where
         }
         self.packet_io
             .send_with_response(&packet::Outbound::new(
-                packet::Command([0x06, 0x81]),
+                packet::outbound::SetSoundModes::COMMAND,
                 target_sound_modes.bytes(),
             ))
             .await?;
@@ -1,4 +1,4 @@
-mod checksum;
+pub mod checksum;
 pub mod inbound;
 mod multi_queue;
 pub mod outbound;
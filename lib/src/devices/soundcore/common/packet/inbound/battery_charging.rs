@@ -6,7 +6,7 @@ use nom::{
 };
 
 use crate::devices::soundcore::common::{
-    packet::{self, Command},
+    packet::{self, Command, outbound::ToPacket},
     structures::IsBatteryCharging,
 };
 
@@ -64,6 +64,30 @@ impl FromPacketBody for DualBatteryCharging {
     }
 }
 
+impl ToPacket for SingleBatteryCharging {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        Self::COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        vec![self.is_charging as u8]
+    }
+}
+
+impl ToPacket for DualBatteryCharging {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        Self::COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        vec![self.left as u8, self.right as u8]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nom_language::error::VerboseError;
@@ -5,7 +5,7 @@ use nom::{
 };
 
 use crate::devices::soundcore::common::{
-    packet::{self, Command},
+    packet::{self, Command, outbound::ToPacket},
     structures::BatteryLevel,
 };
 
@@ -67,6 +67,30 @@ impl FromPacketBody for DualBatteryLevel {
     }
 }
 
+impl ToPacket for SingleBatteryLevel {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        Self::COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        vec![self.level.0]
+    }
+}
+
+impl ToPacket for DualBatteryLevel {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        Self::COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        vec![self.left.0, self.right.0]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nom_language::error::VerboseError;
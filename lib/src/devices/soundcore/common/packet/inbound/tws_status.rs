@@ -5,7 +5,7 @@ use nom::{
 };
 
 use crate::devices::soundcore::common::{
-    packet::{self, Command},
+    packet::{self, Command, outbound::ToPacket},
     structures,
 };
 
@@ -32,6 +32,18 @@ impl FromPacketBody for TwsStatus {
     }
 }
 
+impl ToPacket for TwsStatus {
+    type DirectionMarker = packet::InboundMarker;
+
+    fn command(&self) -> Command {
+        Self::COMMAND
+    }
+
+    fn body(&self) -> Vec<u8> {
+        self.0.bytes().to_vec()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nom_language::error::VerboseError;
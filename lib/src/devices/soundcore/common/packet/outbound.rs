@@ -49,4 +49,6 @@ pub const SET_WEARING_TONE_COMMAND: packet::Command = packet::Command([0x01, 0x8
 pub const SET_TOUCH_LOCK_COMMAND: packet::Command = packet::Command([0x04, 0x86]);
 pub const SET_LOW_BATTERY_PROMPT_COMMAND: packet::Command = packet::Command([0x10, 0x82]);
 pub const SET_WEARING_DETECTION_COMMAND: packet::Command = packet::Command([0x01, 0x81]);
+pub const SET_VOICE_PROMPT_COMMAND: packet::Command = packet::Command([0x01, 0x90]);
+pub const SET_LDAC_COMMAND: packet::Command = packet::Command([0x01, 0xFF]);
 pub const REQUEST_LDAC_STATE_COMMAND: packet::Command = packet::Command([0x01, 0x7F]);
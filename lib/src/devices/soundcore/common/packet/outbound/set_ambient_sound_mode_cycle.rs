@@ -3,6 +3,8 @@ use crate::devices::soundcore::common::{
     structures::{AmbientSoundModeCycle, AmbientSoundModeCycleTws},
 };
 
+pub const SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND: packet::Command = packet::Command([0x06, 0x82]);
+
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SetAmbientSoundModeCycle {
     pub cycle: AmbientSoundModeCycle,
@@ -12,7 +14,7 @@ impl ToPacket for SetAmbientSoundModeCycle {
     type DirectionMarker = packet::OutboundMarker;
 
     fn command(&self) -> packet::Command {
-        packet::Command([0x06, 0x82])
+        SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND
     }
 
     fn body(&self) -> Vec<u8> {
@@ -21,5 +23,8 @@ impl ToPacket for SetAmbientSoundModeCycle {
 }
 
 pub fn set_ambient_sound_mode_cycle_tws(tws_cycle: AmbientSoundModeCycleTws) -> packet::Outbound {
-    packet::Outbound::new(packet::Command([0x06, 0x82]), vec![u8::from(tws_cycle)])
+    packet::Outbound::new(
+        SET_AMBIENT_SOUND_MODE_CYCLE_COMMAND,
+        vec![u8::from(tws_cycle)],
+    )
 }
@@ -3,6 +3,11 @@ use crate::devices::soundcore::common::{
     structures::button_configuration::{ButtonParseSettings, ButtonSide, ButtonStatusCollection},
 };
 
+pub const SET_BUTTON_CONFIGURATION_COMMAND: packet::Command = packet::Command([0x04, 0x81]);
+pub const RESET_BUTTON_CONFIGURATIONS_COMMAND: packet::Command = packet::Command([0x04, 0x82]);
+pub const SET_BUTTON_CONFIGURATION_ENABLED_COMMAND: packet::Command = packet::Command([0x04, 0x83]);
+pub const SET_ALL_BUTTON_CONFIGURATIONS_COMMAND: packet::Command = packet::Command([0x04, 0x84]);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SetButtonConfiguration {
     pub button_id: u8,
@@ -14,7 +19,7 @@ impl ToPacket for SetButtonConfiguration {
     type DirectionMarker = packet::OutboundMarker;
 
     fn command(&self) -> packet::Command {
-        packet::Command([0x04, 0x81])
+        SET_BUTTON_CONFIGURATION_COMMAND
     }
 
     fn body(&self) -> Vec<u8> {
@@ -29,7 +34,7 @@ impl ToPacket for ResetButtonConfigurationsToDefault {
     type DirectionMarker = packet::OutboundMarker;
 
     fn command(&self) -> packet::Command {
-        packet::Command([0x04, 0x82])
+        RESET_BUTTON_CONFIGURATIONS_COMMAND
     }
 
     fn body(&self) -> Vec<u8> {
@@ -51,7 +56,7 @@ impl ToPacket for SetButtonConfigurationEnabled {
         // 1: button id
         // 2: 0 for disabled, 1 for enabled
         // 00 02 01
-        packet::Command([0x04, 0x83])
+        SET_BUTTON_CONFIGURATION_ENABLED_COMMAND
     }
 
     fn body(&self) -> Vec<u8> {
@@ -69,7 +74,7 @@ impl<const N: usize> ToPacket for SetAllButtonConfigurations<'_, N> {
     type DirectionMarker = packet::OutboundMarker;
 
     fn command(&self) -> packet::Command {
-        packet::Command([0x04, 0x84])
+        SET_ALL_BUTTON_CONFIGURATIONS_COMMAND
     }
 
     fn body(&self) -> Vec<u8> {
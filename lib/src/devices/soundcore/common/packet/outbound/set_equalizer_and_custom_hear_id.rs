@@ -5,6 +5,10 @@ use crate::devices::soundcore::common::{
 
 use super::outbound_packet::ToPacket;
 
+pub const SET_EQUALIZER_AND_CUSTOM_HEAR_ID_COMMAND: packet::Command = packet::Command([0x03, 0x86]);
+pub const SET_EQUALIZER_AND_CUSTOM_HEAR_ID_WITH_GENRE_COMMAND: packet::Command =
+    packet::Command([0x03, 0x87]);
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SetEqualizerAndCustomHearId<
     'a,
@@ -49,9 +53,9 @@ impl<
     fn command(&self) -> packet::Command {
         // TODO does this apply to all devices?
         if self.age_range.supports_hear_id() || self.force_supports_hear_id {
-            packet::Command([0x03, 0x87])
+            SET_EQUALIZER_AND_CUSTOM_HEAR_ID_WITH_GENRE_COMMAND
         } else {
-            packet::Command([0x03, 0x86])
+            SET_EQUALIZER_AND_CUSTOM_HEAR_ID_COMMAND
         }
     }
 
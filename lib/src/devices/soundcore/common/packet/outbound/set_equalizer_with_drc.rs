@@ -1,5 +1,7 @@
 use crate::devices::soundcore::common::{packet, structures::EqualizerConfiguration};
 
+pub const SET_EQUALIZER_WITH_DRC_COMMAND: packet::Command = packet::Command([0x02, 0x83]);
+
 pub fn set_equalizer_with_drc<
     const CHANNELS: usize,
     const BANDS: usize,
@@ -16,7 +18,7 @@ pub fn set_equalizer_with_drc<
     >,
 ) -> packet::Outbound {
     packet::Outbound::new(
-        packet::Command([0x02, 0x83]),
+        SET_EQUALIZER_WITH_DRC_COMMAND,
         equalizer_configuration
             .bytes()
             .chain(
@@ -3,6 +3,9 @@ use crate::devices::soundcore::common::{
     structures::DecibelReadingRefreshRate,
 };
 
+pub const SET_LIMIT_HIGH_VOLUME_COMMAND: Command = Command([0x20, 0x82]);
+pub const SET_LIMIT_HIGH_VOLUME_REFRESH_RATE_COMMAND: Command = Command([0x20, 0x81]);
+
 pub fn set_limit_high_volume(enabled: bool, db_limit: u8) -> packet::Outbound {
     debug_assert_eq!(
         db_limit / 5 * 5,
@@ -13,11 +16,17 @@ pub fn set_limit_high_volume(enabled: bool, db_limit: u8) -> packet::Outbound {
         (75..=100).contains(&db_limit),
         "db limit should be between 75 and 100"
     );
-    packet::Outbound::new(Command([0x20, 0x82]), vec![enabled.into(), db_limit])
+    packet::Outbound::new(
+        SET_LIMIT_HIGH_VOLUME_COMMAND,
+        vec![enabled.into(), db_limit],
+    )
 }
 
 pub fn set_limit_high_volume_refresh_rate(
     refresh_rate: DecibelReadingRefreshRate,
 ) -> packet::Outbound {
-    packet::Outbound::new(Command([0x20, 0x81]), vec![refresh_rate as u8])
+    packet::Outbound::new(
+        SET_LIMIT_HIGH_VOLUME_REFRESH_RATE_COMMAND,
+        vec![refresh_rate as u8],
+    )
 }
@@ -9,7 +9,7 @@ use tracing::{Instrument, info_span, trace};
 
 use crate::{
     api::{
-        connection::{ConnectionStatus, RfcommConnection},
+        connection::{ConnectionStatus, TransportConnection},
         device,
     },
     devices::soundcore::common::packet::{self, ChecksumKind, Command},
@@ -19,7 +19,7 @@ use super::multi_queue::MultiQueue;
 
 pub struct PacketIOController {
     checksum_kind: ChecksumKind,
-    connection: Arc<dyn RfcommConnection + Send + Sync>,
+    connection: TransportConnection,
     packet_queues: Arc<MultiQueue<Command, packet::Inbound>>,
     handle: JoinHandle<()>,
 }
@@ -35,7 +35,7 @@ impl PacketIOController {
     /// In addition to the PacketIOController, also returns a channel that all packets received
     /// that weren't a result of send_with_response will be forwarded to.
     pub async fn new(
-        connection: Arc<dyn RfcommConnection + Send + Sync>,
+        connection: TransportConnection,
         checksum_kind: ChecksumKind,
     ) -> device::Result<(Self, mpsc::Receiver<packet::Inbound>)> {
         let packet_queues = Arc::new(MultiQueue::new());
@@ -204,6 +204,7 @@ mod tests {
 
     use crate::{
         api::connection::test_stub::StubRfcommConnection,
+        connection_backend::mock::gatt::MockGattConnection,
         devices::soundcore::common::packet::{
             self,
             outbound::{SetAmbientSoundModeCycle, SetSoundModes, ToPacket},
@@ -216,10 +217,13 @@ mod tests {
     async fn test_send_multiple() {
         let (connection, sender, _receiver) = StubRfcommConnection::new();
         let controller = Arc::new(
-            PacketIOController::new(Arc::new(connection), ChecksumKind::Suffix)
-                .await
-                .unwrap()
-                .0,
+            PacketIOController::new(
+                TransportConnection::Rfcomm(Arc::new(connection)),
+                ChecksumKind::Suffix,
+            )
+            .await
+            .unwrap()
+            .0,
         );
 
         let handle1 = tokio::spawn({
@@ -268,10 +272,13 @@ mod tests {
     async fn test_out_of_order_responses() {
         let (connection, sender, _receiver) = StubRfcommConnection::new();
         let controller = Arc::new(
-            PacketIOController::new(Arc::new(connection), ChecksumKind::Suffix)
-                .await
-                .unwrap()
-                .0,
+            PacketIOController::new(
+                TransportConnection::Rfcomm(Arc::new(connection)),
+                ChecksumKind::Suffix,
+            )
+            .await
+            .unwrap()
+            .0,
         );
 
         let set_cycle_packet = SetAmbientSoundModeCycle::default().to_packet();
@@ -320,10 +327,13 @@ mod tests {
     async fn test_fragmented_packet() {
         let (connection, sender, _receiver) = StubRfcommConnection::new();
         let packet_io = Arc::new(
-            PacketIOController::new(Arc::new(connection), ChecksumKind::Suffix)
-                .await
-                .unwrap()
-                .0,
+            PacketIOController::new(
+                TransportConnection::Rfcomm(Arc::new(connection)),
+                ChecksumKind::Suffix,
+            )
+            .await
+            .unwrap()
+            .0,
         );
 
         tokio::spawn(async move {
@@ -342,10 +352,13 @@ mod tests {
     async fn test_merged_packets() {
         let (connection, sender, _receiver) = StubRfcommConnection::new();
         let packet_io = Arc::new(
-            PacketIOController::new(Arc::new(connection), ChecksumKind::Suffix)
-                .await
-                .unwrap()
-                .0,
+            PacketIOController::new(
+                TransportConnection::Rfcomm(Arc::new(connection)),
+                ChecksumKind::Suffix,
+            )
+            .await
+            .unwrap()
+            .0,
         );
 
         let set_sound_modes: packet::Outbound =
@@ -382,10 +395,13 @@ mod tests {
     async fn test_garbage_data_recovery() {
         let (connection, sender, _receiver) = StubRfcommConnection::new();
         let packet_io = Arc::new(
-            PacketIOController::new(Arc::new(connection), ChecksumKind::Suffix)
-                .await
-                .unwrap()
-                .0,
+            PacketIOController::new(
+                TransportConnection::Rfcomm(Arc::new(connection)),
+                ChecksumKind::Suffix,
+            )
+            .await
+            .unwrap()
+            .0,
         );
 
         let set_sound_modes: packet::Outbound =
@@ -407,4 +423,40 @@ mod tests {
             .await
             .expect("we should recover from garbage data being sent and receive the ack");
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn gatt_connection() {
+        let (inbound_sender, inbound_receiver) = mpsc::channel(100);
+        let (outbound_sender, mut outbound_receiver) = mpsc::channel(100);
+        let packet_io = PacketIOController::new(
+            TransportConnection::Gatt(Arc::new(MockGattConnection::new(
+                inbound_receiver,
+                outbound_sender,
+            ))),
+            ChecksumKind::Suffix,
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let set_sound_modes: packet::Outbound = SetSoundModes::default().to_packet();
+        let set_sound_modes_ack = set_sound_modes.ack();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            // notifications are limited by the MTU, so packets may be split across multiple of them
+            let data = set_sound_modes_ack.bytes_with_checksum();
+            let (first, second) = data.split_at(4);
+            inbound_sender.send(first.to_vec()).await.unwrap();
+            inbound_sender.send(second.to_vec()).await.unwrap();
+        });
+
+        packet_io
+            .send_with_response(&set_sound_modes)
+            .await
+            .expect("we should receive the ACK packet over GATT");
+        assert_eq!(
+            set_sound_modes.bytes_with_checksum(),
+            outbound_receiver.recv().await.unwrap(),
+        );
+    }
 }
@@ -0,0 +1,609 @@
+//! Turns raw Soundcore packets into something human readable for debugging.
+//!
+//! Most packets have the same body on every device, but some, such as state updates, can only be parsed when the
+//! device model is known, so a [`Decoder`] is created for a specific [`DeviceModel`].
+use std::fmt::Debug;
+
+use nom_language::error::VerboseError;
+
+use crate::devices::{
+    DeviceModel,
+    soundcore::{
+        a3004, a3027, a3028, a3031, a3033, a3035, a3040, a3062, a3116, a3130, a3330, a3909, a3926,
+        a3930, a3931, a3933, a3936, a3945, a3947, a3948, a3949, a3951, a3952, a3954, a3955, a3957,
+        a3959, a3968, a6611,
+        common::packet::{
+            self,
+            checksum::calculate_checksum,
+            inbound::{self, FromPacketBody},
+        },
+    },
+};
+
+pub use crate::devices::soundcore::common::packet::{
+    ChecksumKind, Command, Direction, Inbound, Outbound,
+};
+
+/// 5 byte direction, 2 byte command, 2 byte length
+const HEADER_LENGTH: usize = 9;
+
+type DecodeFn = fn(&[u8]) -> Result<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedItem {
+    Packet {
+        offset: usize,
+        packet: DecodedPacket,
+    },
+    /// Bytes that are not part of any packet
+    Garbage { offset: usize, bytes: Vec<u8> },
+    /// A packet that ends before its length says it should
+    Truncated { offset: usize, bytes: Vec<u8> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedPacket {
+    pub direction: Direction,
+    pub command: Command,
+    pub body: Vec<u8>,
+    pub checksum: Checksum,
+    pub contents: PacketContents,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    Valid,
+    Invalid {
+        expected: u8,
+        actual: u8,
+    },
+    /// The model doesn't use checksums
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketContents {
+    /// Empty response to an outbound packet
+    Ack {
+        name: &'static str,
+    },
+    /// `fields` is the pretty printed [`Debug`] representation of the parsed body
+    Decoded {
+        name: &'static str,
+        fields: String,
+    },
+    /// The command is known, but its body is not parsed
+    Undecoded {
+        name: &'static str,
+    },
+    /// The command is known, but its body failed to parse
+    Invalid {
+        name: &'static str,
+        error: String,
+    },
+    UnknownCommand,
+}
+
+#[derive(Clone, Copy)]
+pub struct Decoder {
+    checksum_kind: ChecksumKind,
+    state_update: Option<DecodeFn>,
+    sound_modes: Option<DecodeFn>,
+}
+
+impl Decoder {
+    pub fn new(model: DeviceModel) -> Self {
+        let common_sound_modes: Option<DecodeFn> = Some(decode_as::<inbound::SoundModes>);
+        let (state_update, sound_modes): (DecodeFn, Option<DecodeFn>) = match model {
+            DeviceModel::SoundcoreA3004 => (
+                decode_as::<a3004::packets::A3004StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3027 | DeviceModel::SoundcoreA3030 => (
+                decode_as::<a3027::packets::A3027StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3028 | DeviceModel::SoundcoreA3029 => (
+                decode_as::<a3028::packets::A3028StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3031 => (
+                decode_as::<a3031::packets::A3031StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3033 => {
+                (decode_as::<a3033::packets::A3033StateUpdatePacket>, None)
+            }
+            DeviceModel::SoundcoreA3035 => (
+                decode_as::<a3035::packets::inbound::A3035StateUpdatePacket>,
+                Some(decode_as::<a3035::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3040 => (
+                decode_as::<a3040::packets::A3040StateUpdatePacket>,
+                Some(decode_as::<a3040::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3062 => (
+                decode_as::<a3062::packets::inbound::A3062StateUpdatePacket>,
+                Some(decode_as::<a3062::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3116 => (
+                decode_as::<a3116::packets::inbound::A3116StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3909 => (
+                decode_as::<a3909::packets::inbound::A3909StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3926 => {
+                (decode_as::<a3926::packets::A3926StateUpdatePacket>, None)
+            }
+            DeviceModel::SoundcoreA3930 => (
+                decode_as::<a3930::packets::A3930StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3931 | DeviceModel::SoundcoreA3935 => (
+                decode_as::<a3931::packets::A3931StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3933 | DeviceModel::SoundcoreA3939 => (
+                decode_as::<a3933::packets::inbound::A3933StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3936 => (
+                decode_as::<a3936::packets::A3936StateUpdatePacket>,
+                Some(decode_as::<a3936::structures::A3936SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3945 => {
+                (decode_as::<a3945::packets::A3945StateUpdatePacket>, None)
+            }
+            DeviceModel::SoundcoreA3947 => (
+                decode_as::<a3947::packets::A3947StateUpdatePacket>,
+                Some(decode_as::<a3947::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3948 => (
+                decode_as::<a3948::packets::inbound::A3948StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3949 => (
+                decode_as::<a3949::packets::inbound::A3949StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3951 => (
+                decode_as::<a3951::packets::A3951StateUpdatePacket>,
+                common_sound_modes,
+            ),
+            DeviceModel::SoundcoreA3952 => (
+                decode_as::<a3952::packets::inbound::A3952StateUpdatePacket>,
+                Some(decode_as::<a3952::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3954 => (
+                decode_as::<a3954::packets::inbound::A3954StateUpdatePacket>,
+                Some(decode_as::<a3954::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3955 => (
+                decode_as::<a3955::packets::inbound::A3955StateUpdatePacket>,
+                Some(decode_as::<a3955::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3957 => (
+                decode_as::<a3957::packets::inbound::A3957StateUpdatePacket>,
+                Some(decode_as::<a3957::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3958 | DeviceModel::SoundcoreA3959 => (
+                decode_as::<a3959::packets::inbound::A3959StateUpdate>,
+                Some(decode_as::<a3959::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA3968 => (
+                decode_as::<a3968::packets::inbound::A3968StateUpdatePacket>,
+                Some(decode_as::<a3968::structures::SoundModes>),
+            ),
+            DeviceModel::SoundcoreA6611 => (
+                decode_as::<a6611::packets::inbound::A6611StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3330 => (
+                decode_as::<a3330::packets::inbound::A3330StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreA3130 => (
+                decode_as::<a3130::packets::inbound::A3130StateUpdatePacket>,
+                None,
+            ),
+            DeviceModel::SoundcoreDevelopment => {
+                return Self {
+                    checksum_kind: ChecksumKind::Suffix,
+                    state_update: None,
+                    sound_modes: None,
+                };
+            }
+        };
+        Self {
+            checksum_kind: match model {
+                DeviceModel::SoundcoreA3116 => ChecksumKind::None,
+                _ => ChecksumKind::Suffix,
+            },
+            state_update: Some(state_update),
+            sound_modes,
+        }
+    }
+
+    pub fn checksum_kind(&self) -> ChecksumKind {
+        self.checksum_kind
+    }
+
+    /// Splits `data` into packets and decodes each of them. Anything that isn't a packet is returned as
+    /// [`DecodedItem::Garbage`] so that no bytes go missing from the output.
+    pub fn decode(&self, data: &[u8]) -> Vec<DecodedItem> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let remaining = &data[offset..];
+            let Some(start) = find_packet_start(remaining) else {
+                items.push(DecodedItem::Garbage {
+                    offset,
+                    bytes: remaining.to_vec(),
+                });
+                break;
+            };
+            if start != 0 {
+                items.push(DecodedItem::Garbage {
+                    offset,
+                    bytes: remaining[..start].to_vec(),
+                });
+                offset += start;
+                continue;
+            }
+
+            match self.take_packet(remaining) {
+                Some((length, packet)) => {
+                    items.push(DecodedItem::Packet { offset, packet });
+                    offset += length;
+                }
+                None => {
+                    items.push(DecodedItem::Truncated {
+                        offset,
+                        bytes: remaining.to_vec(),
+                    });
+                    break;
+                }
+            }
+        }
+        items
+    }
+
+    /// `input` must start with a direction. Returns the packet and its length, or None if it is incomplete.
+    fn take_packet(&self, input: &[u8]) -> Option<(usize, DecodedPacket)> {
+        let direction = if input.starts_with(&Direction::Inbound.bytes()) {
+            Direction::Inbound
+        } else {
+            Direction::Outbound
+        };
+        let result = match direction {
+            Direction::Inbound => {
+                packet::Inbound::take::<VerboseError<_>>(self.checksum_kind)(input)
+                    .map(|(remaining, packet)| (remaining.len(), packet.command, packet.body))
+            }
+            Direction::Outbound => {
+                packet::Outbound::take::<VerboseError<_>>(self.checksum_kind)(input)
+                    .map(|(remaining, packet)| (remaining.len(), packet.command, packet.body))
+            }
+        };
+
+        let (length, command, body, checksum) = match result {
+            Ok((remaining_length, command, body)) => (
+                input.len() - remaining_length,
+                command,
+                body,
+                match self.checksum_kind {
+                    ChecksumKind::None => Checksum::Absent,
+                    ChecksumKind::Suffix => Checksum::Valid,
+                },
+            ),
+            Err(nom::Err::Incomplete(_)) => return None,
+            // The direction was already checked and the length was long enough, so the checksum must be wrong.
+            // Parse the packet again without checking the checksum so that it can still be decoded.
+            Err(_) => {
+                let length = u16::from_le_bytes([input[7], input[8]]) as usize;
+                let length = length.max(HEADER_LENGTH + 1);
+                (
+                    length,
+                    Command([input[5], input[6]]),
+                    input[HEADER_LENGTH..length - 1].to_vec(),
+                    Checksum::Invalid {
+                        expected: calculate_checksum(&input[..length - 1]),
+                        actual: input[length - 1],
+                    },
+                )
+            }
+        };
+        let contents = self.decode_body(direction, command, &body);
+        Some((
+            length,
+            DecodedPacket {
+                direction,
+                command,
+                body,
+                checksum,
+                contents,
+            },
+        ))
+    }
+
+    /// Decodes the body of a packet that has already been split out of the byte stream.
+    pub fn decode_body(
+        &self,
+        direction: Direction,
+        command: Command,
+        body: &[u8],
+    ) -> PacketContents {
+        if direction == Direction::Inbound
+            && body.is_empty()
+            && let Some(name) = command_name(Direction::Outbound, command)
+        {
+            return PacketContents::Ack { name };
+        }
+        let Some(name) = command_name(direction, command) else {
+            return PacketContents::UnknownCommand;
+        };
+
+        let decode_fn: Option<DecodeFn> = match (direction, command) {
+            (Direction::Inbound, inbound::STATE_COMMAND) => self.state_update,
+            (Direction::Inbound, inbound::SoundModes::COMMAND)
+            | (Direction::Outbound, packet::outbound::SetSoundModes::COMMAND) => self.sound_modes,
+            (Direction::Inbound, inbound::TwsStatus::COMMAND) => {
+                Some(decode_as::<inbound::TwsStatus>)
+            }
+            (Direction::Inbound, inbound::SingleBatteryLevel::COMMAND) => {
+                Some(decode_battery_level)
+            }
+            (Direction::Inbound, inbound::SingleBatteryCharging::COMMAND) => {
+                Some(decode_battery_charging)
+            }
+            (Direction::Inbound, inbound::SerialNumberAndFirmwareVersion::COMMAND) => {
+                Some(decode_as::<inbound::SerialNumberAndFirmwareVersion>)
+            }
+            (Direction::Inbound, inbound::LdacState::COMMAND) => {
+                Some(decode_as::<inbound::LdacState>)
+            }
+            (Direction::Inbound, inbound::DualConnectionsDevicePacket::COMMAND) => {
+                Some(decode_as::<inbound::DualConnectionsDevicePacket>)
+            }
+            (Direction::Inbound, inbound::ChineseVoicePrompt::COMMAND) => {
+                Some(decode_as::<inbound::ChineseVoicePrompt>)
+            }
+            _ => None,
+        };
+        match decode_fn {
+            Some(decode_fn) => match decode_fn(body) {
+                Ok(fields) => PacketContents::Decoded { name, fields },
+                Err(error) => PacketContents::Invalid { name, error },
+            },
+            None => PacketContents::Undecoded { name },
+        }
+    }
+}
+
+fn find_packet_start(data: &[u8]) -> Option<usize> {
+    let inbound = Direction::Inbound.bytes();
+    let outbound = Direction::Outbound.bytes();
+    data.windows(inbound.len())
+        .position(|window| window == inbound || window == outbound)
+}
+
+fn decode_as<T: FromPacketBody + Debug>(body: &[u8]) -> Result<String, String> {
+    T::take::<VerboseError<_>>(body)
+        .map(|(_, packet)| format!("{packet:#?}"))
+        .map_err(|err| format!("{err:?}"))
+}
+
+// Single and dual battery packets share a command, but both parsers consume the whole body, so only one can succeed
+fn decode_battery_level(body: &[u8]) -> Result<String, String> {
+    decode_as::<inbound::DualBatteryLevel>(body)
+        .or_else(|_| decode_as::<inbound::SingleBatteryLevel>(body))
+}
+
+fn decode_battery_charging(body: &[u8]) -> Result<String, String> {
+    decode_as::<inbound::DualBatteryCharging>(body)
+        .or_else(|_| decode_as::<inbound::SingleBatteryCharging>(body))
+}
+
+/// Names are only a best guess for commands that mean different things on different models
+pub fn command_name(direction: Direction, command: Command) -> Option<&'static str> {
+    let name = match direction {
+        Direction::Inbound => match command.0 {
+            [0x01, 0x01] => "state update",
+            [0x01, 0x02] => "TWS status",
+            [0x01, 0x03] => "battery level",
+            [0x01, 0x04] => "battery charging",
+            [0x01, 0x05] => "serial number and firmware version",
+            [0x01, 0x0F] => "Chinese voice prompt",
+            [0x01, 0x10] => "voice prompt",
+            [0x01, 0x11] => "gaming mode",
+            [0x01, 0x7F] => "LDAC state",
+            [0x06, 0x01] => "sound modes",
+            [0x0B, 0x01] => "dual connections devices",
+            _ => return None,
+        },
+        Direction::Outbound => match command.0 {
+            [0x01, 0x01] => "request state",
+            [0x01, 0x03] => "request battery level",
+            [0x01, 0x04] => "request battery charging",
+            [0x01, 0x05] => "request serial number and firmware version",
+            [0x01, 0x10] => "request voice prompt",
+            [0x01, 0x7F] => "request LDAC state",
+            [0x01, 0x81] => "set wearing detection or auto play/pause",
+            [0x01, 0x83] => "set touch tone",
+            [0x01, 0x86] => "set auto power off",
+            [0x01, 0x87] => "set gaming mode",
+            [0x01, 0x8C] => "set wearing tone",
+            [0x01, 0x90] => "set voice prompt",
+            [0x01, 0xFF] => "set LDAC",
+            [0x02, 0x81] => "set equalizer",
+            [0x02, 0x83] => "set equalizer with DRC",
+            [0x02, 0x86] => "set surround sound",
+            [0x03, 0x86] => "set equalizer and custom HearID (old)",
+            [0x03, 0x87] => "set equalizer and custom HearID",
+            [0x04, 0x81] => "set button action",
+            [0x04, 0x82] => "reset button configuration",
+            [0x04, 0x83] => "set button enabled",
+            [0x04, 0x84] => "set all button configurations",
+            [0x04, 0x86] => "set touch lock",
+            [0x06, 0x81] => "set sound modes",
+            [0x06, 0x82] => "set ambient sound mode cycle",
+            [0x0B, 0x01] => "request dual connections devices",
+            [0x0B, 0x81] => "dual connections disconnect",
+            [0x0B, 0x82] => "dual connections connect",
+            [0x0B, 0x83] => "dual connections forget",
+            [0x0B, 0x84] => "set dual connections enabled",
+            [0x10, 0x82] => "set low battery prompt",
+            [0x10, 0x86] => "set sound leak compensation",
+            [0x20, 0x81] => "set limit high volume refresh rate",
+            [0x20, 0x82] => "set limit high volume",
+            _ => return None,
+        },
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::devices::soundcore::common::{
+        packet::outbound::{RequestState, SetSoundModes, ToPacket},
+        structures::{AmbientSoundMode, SoundModes},
+    };
+
+    use super::*;
+
+    fn decode_one(model: DeviceModel, data: &[u8]) -> DecodedPacket {
+        match <[DecodedItem; 1]>::try_from(Decoder::new(model).decode(data)) {
+            Ok([DecodedItem::Packet { packet, .. }]) => packet,
+            items => panic!("expected one packet, got {items:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_state_update() {
+        let packet = decode_one(
+            DeviceModel::SoundcoreA3028,
+            &a3028::packets::A3028StateUpdatePacket::default()
+                .to_packet()
+                .bytes(ChecksumKind::Suffix),
+        );
+        assert_eq!(Direction::Inbound, packet.direction);
+        assert_eq!(Checksum::Valid, packet.checksum);
+        let PacketContents::Decoded { name, fields } = packet.contents else {
+            panic!("{:?}", packet.contents);
+        };
+        assert_eq!("state update", name);
+        assert!(fields.contains("equalizer_configuration"), "{fields}");
+    }
+
+    #[test]
+    fn decodes_outbound_sound_modes() {
+        let packet = decode_one(
+            DeviceModel::SoundcoreA3028,
+            &SetSoundModes(SoundModes {
+                ambient_sound_mode: AmbientSoundMode::Transparency,
+                ..Default::default()
+            })
+            .to_packet()
+            .bytes(ChecksumKind::Suffix),
+        );
+        let PacketContents::Decoded { name, fields } = packet.contents else {
+            panic!("{:?}", packet.contents);
+        };
+        assert_eq!("set sound modes", name);
+        assert!(fields.contains("Transparency"), "{fields}");
+    }
+
+    #[test]
+    fn decodes_acks() {
+        let packet = decode_one(
+            DeviceModel::SoundcoreA3028,
+            &RequestState.to_packet().ack().bytes(ChecksumKind::Suffix),
+        );
+        assert_eq!(
+            PacketContents::Ack {
+                name: "request state"
+            },
+            packet.contents,
+        );
+    }
+
+    #[test]
+    fn flags_checksum_failures() {
+        let mut bytes = RequestState.to_packet().bytes(ChecksumKind::Suffix);
+        let checksum = bytes.last_mut().unwrap();
+        let expected = *checksum;
+        *checksum = checksum.wrapping_add(1);
+
+        let packet = decode_one(DeviceModel::SoundcoreA3028, &bytes);
+        assert_eq!(
+            Checksum::Invalid {
+                expected,
+                actual: expected.wrapping_add(1),
+            },
+            packet.checksum,
+        );
+        assert_eq!(
+            PacketContents::Undecoded {
+                name: "request state"
+            },
+            packet.contents,
+        );
+    }
+
+    #[test]
+    fn flags_unknown_commands() {
+        let packet = decode_one(
+            DeviceModel::SoundcoreA3028,
+            &packet::Inbound::new(Command([0x7F, 0x7F]), vec![1, 2, 3]).bytes(ChecksumKind::Suffix),
+        );
+        assert_eq!(PacketContents::UnknownCommand, packet.contents);
+    }
+
+    #[test]
+    fn uses_model_checksum_kind() {
+        let packet = decode_one(
+            DeviceModel::SoundcoreA3116,
+            &a3116::packets::inbound::A3116StateUpdatePacket::default()
+                .to_packet()
+                .bytes(ChecksumKind::None),
+        );
+        assert_eq!(Checksum::Absent, packet.checksum);
+        assert!(
+            matches!(packet.contents, PacketContents::Decoded { .. }),
+            "{:?}",
+            packet.contents,
+        );
+    }
+
+    #[test]
+    fn splits_garbage_and_truncated_packets() {
+        let request_state = RequestState.to_packet().bytes(ChecksumKind::Suffix);
+        let mut data = vec![0xAA, 0xBB];
+        data.extend(&request_state);
+        data.extend(&request_state[..4 + 5]);
+
+        let items = Decoder::new(DeviceModel::SoundcoreA3028).decode(&data);
+        assert_eq!(3, items.len(), "{items:?}");
+        assert_eq!(
+            DecodedItem::Garbage {
+                offset: 0,
+                bytes: vec![0xAA, 0xBB],
+            },
+            items[0],
+        );
+        assert!(
+            matches!(items[1], DecodedItem::Packet { offset: 2, .. }),
+            "{:?}",
+            items[1],
+        );
+        assert_eq!(
+            DecodedItem::Truncated {
+                offset: 2 + request_state.len(),
+                bytes: request_state[..9].to_vec(),
+            },
+            items[2],
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Figures out the [`DeviceModel`] of a device so that the user doesn't have to know it.
+//!
+//! Newer devices offer a vendor RFCOMM service with the model number at the end of its uuid, such as
+//! `0cf12d31-fac3-4553-bd80-d6832e7b3959` for the A3959. Older devices only offer the standard serial port service, and
+//! not every vendor uuid matches the model number, so when that doesn't work we connect and look for the serial number
+//! instead, which usually starts with the model number.
+use std::{collections::HashSet, str::FromStr};
+
+use macaddr::MacAddr6;
+use nom::Parser;
+use nom_language::error::VerboseError;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        connection::{RfcommBackend, TransportConnection},
+        device,
+    },
+    devices::{
+        DeviceModel,
+        soundcore::{
+            self,
+            common::{
+                device::SoundcoreDeviceConfig,
+                packet::{
+                    self, PacketIOController,
+                    inbound::TryToPacket,
+                    outbound::{RequestSerialNumberAndFirmwareVersion, RequestState, ToPacket},
+                },
+                structures::{FirmwareVersion, SerialNumber},
+            },
+        },
+    },
+};
+
+/// Returns `None` if neither the service uuids nor the device's responses say which model it is.
+pub async fn detect_model(
+    backend: &(dyn RfcommBackend + Send + Sync),
+    mac_address: MacAddr6,
+) -> device::Result<Option<DeviceModel>> {
+    let service_uuids = backend.service_uuids(mac_address).await?;
+    debug!("found RFCOMM services: {service_uuids:?}");
+    if let Some(model) = model_from_service_uuids(&service_uuids) {
+        return Ok(Some(model));
+    }
+
+    let config = SoundcoreDeviceConfig::default();
+    let connection = backend
+        .connect(
+            mac_address,
+            SoundcoreDeviceConfig::DEFAULT_RFCOMM_SERVICE_SELECTION_STRATEGY,
+        )
+        .await?;
+    probe_model(
+        TransportConnection::Rfcomm(connection),
+        config.checksum_kind,
+    )
+    .await
+}
+
+async fn probe_model(
+    connection: TransportConnection,
+    checksum_kind: packet::ChecksumKind,
+) -> device::Result<Option<DeviceModel>> {
+    let (packet_io, _packet_receiver) = PacketIOController::new(connection, checksum_kind).await?;
+
+    if let Some(model) = probe_serial_number(&packet_io)
+        .await
+        .and_then(|serial_number| model_from_serial_number(&serial_number))
+    {
+        return Ok(Some(model));
+    }
+
+    let state_update_packet = packet_io
+        .send_with_response(&RequestState.to_packet())
+        .await?;
+    Ok(model_from_state_update_body(&state_update_packet.body))
+}
+
+/// Devices that don't know this command either ignore it or ack it with an empty body, so failure is expected.
+async fn probe_serial_number(packet_io: &PacketIOController) -> Option<SerialNumber> {
+    let packet: packet::inbound::SerialNumberAndFirmwareVersion = packet_io
+        .send_with_response(&RequestSerialNumberAndFirmwareVersion.to_packet())
+        .await
+        .ok()?
+        .try_to_packet()
+        .ok()?;
+    Some(packet.serial_number)
+}
+
+pub fn model_from_service_uuids(service_uuids: &HashSet<Uuid>) -> Option<DeviceModel> {
+    service_uuids
+        .iter()
+        .filter(|uuid| soundcore::is_soundcore_vendor_rfcomm_uuid(uuid))
+        .find_map(|uuid| {
+            // The last 5 hex digits are b followed by the model number for A series devices
+            let suffix = uuid.as_u128() & 0xfffff;
+            let model_number = format!("{suffix:05x}").strip_prefix('b')?.to_owned();
+            model_from_model_number(&model_number)
+        })
+}
+
+pub fn model_from_serial_number(serial_number: &SerialNumber) -> Option<DeviceModel> {
+    model_from_model_number(serial_number.as_str().get(0..4)?)
+}
+
+/// State update packets have a different layout for every device, but the serial number comes directly after a
+/// firmware version in all of the ones that include it, so we can search for that pattern without knowing the layout.
+fn model_from_state_update_body(body: &[u8]) -> Option<DeviceModel> {
+    (0..body.len()).find_map(|offset| {
+        let (_, (_, serial_number)) = (
+            FirmwareVersion::take::<VerboseError<_>>,
+            SerialNumber::take::<VerboseError<_>>,
+        )
+            .parse_complete(&body[offset..])
+            .ok()?;
+        model_from_serial_number(&serial_number)
+    })
+}
+
+fn model_from_model_number(model_number: &str) -> Option<DeviceModel> {
+    if model_number.len() != 4 || !model_number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    DeviceModel::from_str(&format!("SoundcoreA{model_number}")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use crate::connection_backend::device_definition::{
+        DeviceDefinition, DeviceDefinitionRfcommBackend,
+    };
+
+    use super::*;
+
+    async fn detect_model_from_faker_definition(file_name: &str) -> Option<DeviceModel> {
+        let definition = DeviceDefinition::load(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../tools/soundcore-device-faker/devices")
+                .join(file_name),
+        )
+        .unwrap();
+        let mac_address = definition.mac_address;
+        let backend = DeviceDefinitionRfcommBackend::new(Arc::new(definition));
+        detect_model(&backend, mac_address).await.unwrap()
+    }
+
+    #[test]
+    fn maps_vendor_rfcomm_uuid_to_model() {
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3959),
+            model_from_service_uuids(&HashSet::from([
+                soundcore::RFCOMM_UUID,
+                uuid::uuid!("0cf12d31-fac3-4553-bd80-d6832e7b3959"),
+            ])),
+        );
+    }
+
+    #[test]
+    fn ignores_uuids_that_do_not_contain_a_model_number() {
+        assert_eq!(
+            None,
+            model_from_service_uuids(&HashSet::from([
+                soundcore::RFCOMM_UUID,
+                uuid::uuid!("0cf12d31-fac3-4553-bd80-d6832e7b395b"),
+                uuid::uuid!("0cf12d31-fac3-4553-bd80-d6832e71202c"),
+            ])),
+        );
+    }
+
+    #[test]
+    fn maps_serial_number_to_model() {
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3028),
+            model_from_serial_number(&SerialNumber::from("30286DC893444798")),
+        );
+        assert_eq!(
+            None,
+            model_from_serial_number(&SerialNumber::from("AFN4FZ1F18100661")),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detects_model_from_service_uuid() {
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3330),
+            detect_model_from_faker_definition("a3330.toml").await,
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detects_model_from_serial_number_packet() {
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3909),
+            detect_model_from_faker_definition("a3909.toml").await,
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detects_model_from_state_update_packet() {
+        // Only offers the standard serial port service
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3028),
+            detect_model_from_faker_definition("a3028.toml").await,
+        );
+        // The vendor uuid doesn't match the model number
+        assert_eq!(
+            Some(DeviceModel::SoundcoreA3959),
+            detect_model_from_faker_definition("a3959.toml").await,
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn returns_none_when_model_is_unknown() {
+        assert_eq!(
+            None,
+            detect_model_from_faker_definition("d1202c.toml").await
+        );
+    }
+}
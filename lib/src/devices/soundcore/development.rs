@@ -5,7 +5,11 @@ use crate::{
     devices::{
         DeviceModel,
         soundcore::{
-            common::{demo::DemoConnectionRegistry, device::SoundcoreDeviceConfig, packet},
+            common::{
+                demo::{DemoConnectionRegistry, DemoEvents, DemoState},
+                device::SoundcoreDeviceConfig,
+                packet,
+            },
             development::device::SoundcoreDevelopmentDeviceRegistry,
         },
     },
@@ -25,13 +29,15 @@ pub fn device_registry(
 pub fn demo_device_registry(
     _database: Arc<storage::OpenSCQ30Database>,
     device_model: DeviceModel,
+    events: DemoEvents,
 ) -> SoundcoreDevelopmentDeviceRegistry {
+    let state = DemoState::from(HashMap::from([(
+        packet::inbound::STATE_COMMAND,
+        packet::Inbound::new(packet::inbound::STATE_COMMAND, vec![1, 2, 3]),
+    )]));
     SoundcoreDevelopmentDeviceRegistry::new(Arc::new(DemoConnectionRegistry::new(
         device_model,
-        HashMap::from([(
-            packet::inbound::STATE_COMMAND,
-            packet::Inbound::new(packet::inbound::STATE_COMMAND, vec![1, 2, 3]),
-        )]),
+        state.with_events(events),
         SoundcoreDeviceConfig::default(),
     )))
 }
@@ -6,7 +6,7 @@ use tokio::sync::watch;
 
 use crate::{
     api::{
-        connection::{ConnectionDescriptor, ConnectionStatus, RfcommBackend, RfcommConnection},
+        connection::{ConnectionDescriptor, ConnectionStatus, RfcommBackend, TransportConnection},
         device::{self, OpenSCQ30Device, OpenSCQ30DeviceRegistry},
         settings::{CategoryId, Setting, SettingId, Value},
     },
@@ -59,22 +59,23 @@ impl OpenSCQ30DeviceRegistry for SoundcoreDevelopmentDeviceRegistry {
                 }),
             )
             .await?;
-        let device = SoundcoreDevelopmentDevice::new(connection).await?;
+        let device =
+            SoundcoreDevelopmentDevice::new(TransportConnection::Rfcomm(connection)).await?;
         Ok(Arc::new(device))
     }
 }
 
 pub struct SoundcoreDevelopmentDevice {
     packet_io: PacketIOController,
-    backend: Arc<dyn RfcommConnection + Send + Sync>,
+    backend: TransportConnection,
     state_update_packet: Option<packet::Inbound>,
     changes_signal: watch::Sender<()>,
 }
 
 impl SoundcoreDevelopmentDevice {
-    async fn new(connection: Arc<dyn RfcommConnection + Send + Sync>) -> device::Result<Self> {
+    async fn new(connection: TransportConnection) -> device::Result<Self> {
         let (packet_io, _packet_receiver) =
-            PacketIOController::new(connection.to_owned(), packet::ChecksumKind::Suffix).await?;
+            PacketIOController::new(connection.clone(), packet::ChecksumKind::Suffix).await?;
         let state_update_packet = packet_io
             .send_with_response(&RequestState.to_packet())
             .await
@@ -9,12 +9,15 @@ mod connection_backend;
 mod devices;
 pub mod i18n;
 pub(crate) mod macros;
-pub(crate) mod serialization;
+pub mod serialization;
 pub mod storage;
 pub mod util;
 
 pub use api::*;
 pub use connection_backend::*;
-pub use devices::DeviceModel;
+pub use devices::{
+    DeviceModel,
+    soundcore::{common::demo::DemoEvents, decode},
+};
 
 extern crate self as openscq30_lib;
@@ -1,3 +1,5 @@
+//! Serde helpers for the formats OpenSCQ30 reads and writes.
+
 pub mod mac_addr {
     use std::str::FromStr;
 
@@ -46,3 +48,97 @@ pub mod mac_addr {
         }
     }
 }
+
+pub mod optional_mac_addr {
+    use macaddr::MacAddr6;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        mac_address: &Option<MacAddr6>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match mac_address {
+            Some(mac_address) => super::mac_addr::serialize(mac_address, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<MacAddr6>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::mac_addr")] MacAddr6);
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(mac_address)| mac_address))
+    }
+}
+
+/// Formats for moving data between machines, which start with a version number so that data written by a newer
+/// version of OpenSCQ30 is rejected with a clear error rather than being misread.
+pub mod versioned {
+    use std::panic::Location;
+
+    use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+    use crate::macros::impl_from_source_error_with_location;
+
+    /// A format's version should be incremented whenever a change is made that older versions can't read.
+    #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+    #[error(
+        "{format} version {version} is not supported, the newest supported version is {newest_supported_version}"
+    )]
+    pub struct UnsupportedVersionError {
+        /// What the format is called, such as "backup"
+        pub format: &'static str,
+        pub version: u32,
+        pub newest_supported_version: u32,
+    }
+
+    pub fn check_version(
+        format: &'static str,
+        version: u32,
+        newest_supported_version: u32,
+    ) -> Result<(), UnsupportedVersionError> {
+        if version > newest_supported_version {
+            return Err(UnsupportedVersionError {
+                format,
+                version,
+                newest_supported_version,
+            });
+        }
+        Ok(())
+    }
+
+    #[derive(thiserror::Error, Debug)]
+    pub enum JsonFormatError {
+        #[error(transparent)]
+        UnsupportedVersion(#[from] UnsupportedVersionError),
+        #[error("failed to parse json")]
+        JsonError {
+            source: serde_json::Error,
+            location: &'static Location<'static>,
+        },
+    }
+
+    impl_from_source_error_with_location!(JsonFormatError::JsonError(serde_json::Error));
+
+    pub fn to_json(value: &impl Serialize) -> String {
+        serde_json::to_string_pretty(value).expect("serialization should not fail")
+    }
+
+    /// Parses JSON with a top level `version` field, rejecting versions newer than `newest_supported_version`.
+    pub fn from_json<T: DeserializeOwned>(
+        format: &'static str,
+        newest_supported_version: u32,
+        json: &str,
+    ) -> Result<T, JsonFormatError> {
+        // Check the version before anything else so that the error is about the version rather than whatever part of
+        // the format changed
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        let Version { version } = serde_json::from_str(json)?;
+        check_version(format, version, newest_supported_version)?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
@@ -0,0 +1,365 @@
+use indexmap::IndexMap;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, IntoStaticStr, VariantArray};
+
+use crate::{devices::DeviceModel, serialization::versioned};
+
+use super::{
+    Error, PairedDevice, QuickPreset,
+    equalizer_profile::{self, EqualizerProfile},
+    paired_device, quick_preset,
+    type_conversions::SqliteDeviceModel,
+};
+
+/// See [`versioned::UnsupportedVersionError`] for when this changes.
+///
+/// - 2: equalizer profiles can have separate right channel volume adjustments, which version 1 would drop
+pub const BACKUP_VERSION: u32 = 2;
+
+/// Everything stored in the database, in a format that can be moved between machines.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub version: u32,
+    pub paired_devices: Vec<PairedDevice>,
+    pub quick_presets: IndexMap<DeviceModel, Vec<QuickPreset>>,
+    pub equalizer_profiles: IndexMap<DeviceModel, Vec<EqualizerProfile>>,
+}
+
+pub type BackupFormatError = versioned::JsonFormatError;
+
+impl Backup {
+    pub fn to_json(&self) -> String {
+        versioned::to_json(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, BackupFormatError> {
+        versioned::from_json("backup", BACKUP_VERSION, json)
+    }
+}
+
+/// How a backup is combined with what is already in the database.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    IntoStaticStr,
+    VariantArray,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum BackupImportMode {
+    /// Keep existing data, overwriting anything with the same mac address or name as something in the backup
+    #[default]
+    Merge,
+    /// Delete all existing data before restoring the backup
+    Replace,
+}
+
+pub fn export(connection: &mut Connection) -> Result<Backup, Error> {
+    // Read everything in one transaction so that the backup is a consistent snapshot
+    let tx = connection.transaction()?;
+    let paired_devices = paired_device::fetch_all(&tx)?;
+
+    let mut quick_presets = IndexMap::new();
+    for model in DeviceModel::VARIANTS.iter().copied() {
+        let model_quick_presets = quick_preset::fetch_all(&tx, model)?;
+        if !model_quick_presets.is_empty() {
+            quick_presets.insert(model, model_quick_presets);
+        }
+    }
+
+    let mut equalizer_profiles = IndexMap::new();
+    for model in DeviceModel::VARIANTS.iter().copied() {
+        let model_equalizer_profiles = equalizer_profile::fetch_all(&tx, model)?;
+        if !model_equalizer_profiles.is_empty() {
+            equalizer_profiles.insert(model, model_equalizer_profiles);
+        }
+    }
+    tx.commit()?;
+
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        paired_devices,
+        quick_presets,
+        equalizer_profiles,
+    })
+}
+
+/// Restores a backup. Either everything is restored, or if something fails, nothing is.
+pub fn import(
+    connection: &mut Connection,
+    backup: Backup,
+    mode: BackupImportMode,
+) -> Result<(), Error> {
+    let tx = connection.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    if mode == BackupImportMode::Replace {
+        tx.execute("DELETE FROM paired_device", ())?;
+        tx.execute("DELETE FROM quick_preset", ())?;
+        tx.execute("DELETE FROM equalizer_profile", ())?;
+    }
+
+    for paired_device in backup.paired_devices {
+        paired_device::upsert(&tx, paired_device)?;
+    }
+    for (model, quick_presets) in backup.quick_presets {
+        for quick_preset in quick_presets {
+            quick_preset::replace(&tx, model, quick_preset)?;
+        }
+    }
+    for (model, equalizer_profiles) in backup.equalizer_profiles {
+        for profile in equalizer_profiles {
+            // Upserting fails when the name matches one existing profile and the volume adjustments match another,
+            // so get the one with the matching name out of the way first
+            tx.execute(
+                "DELETE FROM equalizer_profile WHERE device_model = ?1 AND name = ?2",
+                (SqliteDeviceModel(model), &profile.name),
+            )?;
+            equalizer_profile::upsert(&tx, model, profile)?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use macaddr::MacAddr6;
+
+    use crate::{
+        api::settings::SettingId,
+        storage::{OpenSCQ30Database, QuickPresetField, migration},
+    };
+
+    use super::*;
+
+    async fn database_with_data(
+        mac_address: MacAddr6,
+        quick_preset_name: &str,
+        equalizer_profile_name: &str,
+        volume_adjustments: Vec<i16>,
+    ) -> OpenSCQ30Database {
+        let db = OpenSCQ30Database::new_in_memory().await.unwrap();
+        db.upsert_paired_device(PairedDevice {
+            mac_address,
+            model: DeviceModel::SoundcoreA3028,
+            is_demo: true,
+            adapter_address: None,
+        })
+        .await
+        .unwrap();
+        db.upsert_quick_preset(
+            DeviceModel::SoundcoreA3028,
+            QuickPreset {
+                name: quick_preset_name.to_owned(),
+                fields: vec![QuickPresetField {
+                    setting_id: SettingId::AmbientSoundMode,
+                    value: Cow::from("NoiseCanceling").into(),
+                    is_enabled: true,
+                }],
+            },
+        )
+        .await
+        .unwrap();
+        db.upsert_equalizer_profile(
+            DeviceModel::SoundcoreA3028,
+            EqualizerProfile::new(equalizer_profile_name.to_owned(), volume_adjustments),
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn round_trips_between_databases() {
+        let source = database_with_data(
+            MacAddr6::new(0, 0, 0, 0, 0, 1),
+            "Commute",
+            "Bass",
+            vec![10, 20, 30, 40, 50, 60, 70, 80],
+        )
+        .await;
+        let backup = source.export_backup().await.unwrap();
+        let backup = Backup::from_json(&backup.to_json()).unwrap();
+
+        let destination = OpenSCQ30Database::new_in_memory().await.unwrap();
+        destination
+            .import_backup(backup.clone(), BackupImportMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(backup, destination.export_backup().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn merge_keeps_existing_data() {
+        let source = database_with_data(
+            MacAddr6::new(0, 0, 0, 0, 0, 1),
+            "Commute",
+            "Bass",
+            vec![10, 20, 30, 40, 50, 60, 70, 80],
+        )
+        .await;
+        let destination = database_with_data(
+            MacAddr6::new(0, 0, 0, 0, 0, 2),
+            "Gym",
+            "Treble",
+            vec![0, 0, 0, 0, 0, 0, 60, 60],
+        )
+        .await;
+        destination
+            .import_backup(
+                source.export_backup().await.unwrap(),
+                BackupImportMode::Merge,
+            )
+            .await
+            .unwrap();
+
+        let merged = destination.export_backup().await.unwrap();
+        assert_eq!(2, merged.paired_devices.len());
+        assert_eq!(
+            vec!["Commute", "Gym"],
+            merged.quick_presets[&DeviceModel::SoundcoreA3028]
+                .iter()
+                .map(|quick_preset| quick_preset.name.as_str())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec!["Bass", "Treble"],
+            merged.equalizer_profiles[&DeviceModel::SoundcoreA3028]
+                .iter()
+                .map(|profile| profile.name.as_str())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[tokio::test]
+    async fn replace_removes_existing_data() {
+        let source = database_with_data(
+            MacAddr6::new(0, 0, 0, 0, 0, 1),
+            "Commute",
+            "Bass",
+            vec![10, 20, 30, 40, 50, 60, 70, 80],
+        )
+        .await;
+        let destination = database_with_data(
+            MacAddr6::new(0, 0, 0, 0, 0, 2),
+            "Gym",
+            "Treble",
+            vec![0, 0, 0, 0, 0, 0, 60, 60],
+        )
+        .await;
+        let backup = source.export_backup().await.unwrap();
+        destination
+            .import_backup(backup.clone(), BackupImportMode::Replace)
+            .await
+            .unwrap();
+        assert_eq!(backup, destination.export_backup().await.unwrap());
+    }
+
+    #[test]
+    fn failed_import_changes_nothing() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migration::migrate(&mut connection, migration::MIGRATIONS).unwrap();
+        let paired_device = PairedDevice {
+            mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
+            model: DeviceModel::SoundcoreA3028,
+            is_demo: true,
+            adapter_address: None,
+        };
+        paired_device::upsert(&connection, paired_device).unwrap();
+        // Make the last step of the import fail after everything else has already been written
+        connection
+            .execute_batch(
+                "CREATE TRIGGER fail_equalizer_profile_insert BEFORE INSERT ON equalizer_profile
+                    BEGIN SELECT RAISE(ABORT, 'fail'); END",
+            )
+            .unwrap();
+
+        let mut backup = export(&mut connection).unwrap();
+        backup.paired_devices = vec![PairedDevice {
+            mac_address: MacAddr6::new(0, 0, 0, 0, 0, 2),
+            ..paired_device
+        }];
+        backup.equalizer_profiles.insert(
+            DeviceModel::SoundcoreA3028,
+            vec![EqualizerProfile::new(
+                "Bass".to_owned(),
+                vec![10, 20, 30, 40, 50, 60, 70, 80],
+            )],
+        );
+        import(&mut connection, backup, BackupImportMode::Replace).unwrap_err();
+
+        assert_eq!(
+            vec![paired_device],
+            paired_device::fetch_all(&connection).unwrap(),
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let err = Backup::from_json(r#"{"version":3,"somethingNew":[]}"#).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BackupFormatError::UnsupportedVersion(versioned::UnsupportedVersionError {
+                    version: 3,
+                    ..
+                })
+            ),
+            "{err:?}",
+        );
+    }
+
+    #[test]
+    fn accepts_version_1() {
+        let backup = Backup::from_json(
+            r#"{"version":1,"pairedDevices":[],"quickPresets":{},"equalizerProfiles":{"SoundcoreA3028":[{"name":"Bass","volumeAdjustments":[10,20,30,40,50,60,70,80]}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![EqualizerProfile::new(
+                "Bass".to_owned(),
+                vec![10, 20, 30, 40, 50, 60, 70, 80],
+            )],
+            backup.equalizer_profiles[&DeviceModel::SoundcoreA3028],
+        );
+    }
+
+    #[tokio::test]
+    async fn version_1_rejects_backups_with_right_channel() {
+        let db = OpenSCQ30Database::new_in_memory().await.unwrap();
+        db.upsert_equalizer_profile(
+            DeviceModel::SoundcoreA3028,
+            EqualizerProfile {
+                right_volume_adjustments: Some(vec![0, 0, 0, 0, 0, 0, 60, 60]),
+                ..EqualizerProfile::new("Bass".to_owned(), vec![10, 20, 30, 40, 50, 60, 70, 80])
+            },
+        )
+        .await
+        .unwrap();
+        let json = db.export_backup().await.unwrap().to_json();
+
+        let err = versioned::from_json::<Backup>("backup", 1, &json).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BackupFormatError::UnsupportedVersion(versioned::UnsupportedVersionError {
+                    version: 2,
+                    ..
+                })
+            ),
+            "{err:?}",
+        );
+    }
+}
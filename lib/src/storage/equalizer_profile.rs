@@ -1,23 +1,63 @@
 use std::panic::Location;
 
-use rusqlite::Connection;
+use rusqlite::{Connection, Row};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::devices::DeviceModel;
 
 use super::{Error, type_conversions::SqliteDeviceModel};
 
-pub fn fetch(connection: &Connection, model: DeviceModel, name: String) -> Result<Vec<i16>, Error> {
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualizerProfile {
+    pub name: String,
+    pub volume_adjustments: Vec<i16>,
+    /// Only set when the right channel differs from the left, in which case `volume_adjustments` is the left channel.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right_volume_adjustments: Option<Vec<i16>>,
+}
+
+impl EqualizerProfile {
+    pub fn new(name: String, volume_adjustments: Vec<i16>) -> Self {
+        Self {
+            name,
+            volume_adjustments,
+            right_volume_adjustments: None,
+        }
+    }
+
+    /// Volume adjustments for a channel, where 0 is left and 1 is right.
+    pub fn channel(&self, channel: usize) -> &[i16] {
+        match (channel, &self.right_volume_adjustments) {
+            (1, Some(right_volume_adjustments)) => right_volume_adjustments,
+            _ => &self.volume_adjustments,
+        }
+    }
+
+    fn from_row(row: &Row) -> Result<Self, Error> {
+        let name = row.get(0)?;
+        let volume_adjustments = serde_json::from_str(row.get_ref(1)?.as_str()?)?;
+        let right_volume_adjustments = serde_json::from_str(row.get_ref(2)?.as_str()?)?;
+        Ok(Self {
+            name,
+            volume_adjustments,
+            right_volume_adjustments,
+        })
+    }
+}
+
+pub fn fetch(
+    connection: &Connection,
+    model: DeviceModel,
+    name: String,
+) -> Result<EqualizerProfile, Error> {
     let mut query = connection.prepare_cached(
-        r#"SELECT volume_adjustments FROM equalizer_profile WHERE device_model = ?1 AND name = ?2"#,
-    )?;
-    let mut rows = query.query_and_then(
-        (SqliteDeviceModel(model), name),
-        |row| -> Result<_, Error> {
-            let json = row.get_ref(0)?.as_str()?;
-            let volume_adjustments: Vec<i16> = serde_json::from_str(json)?;
-            Ok(volume_adjustments)
-        },
+        r#"SELECT name, volume_adjustments, right_volume_adjustments FROM equalizer_profile
+            WHERE device_model = ?1 AND name = ?2"#,
     )?;
+    let mut rows =
+        query.query_and_then((SqliteDeviceModel(model), name), EqualizerProfile::from_row)?;
     rows.next()
         .ok_or(Error::NotFound {
             location: Location::caller(),
@@ -28,38 +68,55 @@ pub fn fetch(connection: &Connection, model: DeviceModel, name: String) -> Resul
 pub fn fetch_all(
     connection: &Connection,
     model: DeviceModel,
-) -> Result<Vec<(String, Vec<i16>)>, Error> {
+) -> Result<Vec<EqualizerProfile>, Error> {
     let mut query = connection.prepare_cached(
-        r#"SELECT name, volume_adjustments FROM equalizer_profile WHERE device_model = ?1 ORDER BY name"#,
+        r#"SELECT name, volume_adjustments, right_volume_adjustments FROM equalizer_profile
+            WHERE device_model = ?1 ORDER BY name"#,
     )?;
     let rows = query.query([SqliteDeviceModel(model)])?;
-    rows.and_then(|row| -> Result<_, Error> {
-        let name = row.get(0)?;
-        let volume_adjustments_json = row.get_ref(1)?.as_str()?;
-        let volume_adjustments: Vec<i16> = serde_json::from_str(volume_adjustments_json)?;
-        Ok((name, volume_adjustments))
-    })
-    .collect::<Result<Vec<_>, _>>()
+    rows.and_then(EqualizerProfile::from_row)
+        .collect::<Result<Vec<_>, _>>()
+}
+
+/// Models that have at least one custom profile
+pub fn fetch_models(connection: &Connection) -> Result<Vec<DeviceModel>, Error> {
+    let mut query = connection.prepare_cached(
+        r#"SELECT DISTINCT device_model FROM equalizer_profile ORDER BY device_model"#,
+    )?;
+    let models = query
+        .query(())?
+        .mapped(|row| row.get::<_, SqliteDeviceModel>(0))
+        .filter_map(|result| match result {
+            Ok(model) => Some(model.0),
+            Err(err) => {
+                warn!("error parsing row: {err:?}");
+                None
+            }
+        })
+        .collect();
+    Ok(models)
 }
 
 pub fn upsert(
     connection: &Connection,
     model: DeviceModel,
-    name: String,
-    volume_adjustments: Vec<i16>,
+    profile: EqualizerProfile,
 ) -> Result<(), Error> {
-    let json = serde_json::to_string(&volume_adjustments).map_err(Error::from)?;
+    let json = serde_json::to_string(&profile.volume_adjustments).map_err(Error::from)?;
+    let right_json =
+        serde_json::to_string(&profile.right_volume_adjustments).map_err(Error::from)?;
     // by calling sqlite's json(...) function, we ensure it is minified so that formatting is standardized, making it okay to
     // perform equality comparisons. this is necessary for the unique index on volume_adjustments.
     connection.execute(
-        r#"INSERT INTO equalizer_profile (device_model, name, volume_adjustments)
-                VALUES (?1, ?2, json(?3))
+        r#"INSERT INTO equalizer_profile (device_model, name, volume_adjustments, right_volume_adjustments)
+                VALUES (?1, ?2, json(?3), json(?4))
             ON CONFLICT(device_model, name) DO UPDATE SET
-                volume_adjustments = excluded.volume_adjustments
-            ON CONFLICT(device_model, volume_adjustments) DO UPDATE SET
+                volume_adjustments = excluded.volume_adjustments,
+                right_volume_adjustments = excluded.right_volume_adjustments
+            ON CONFLICT(device_model, volume_adjustments, right_volume_adjustments) DO UPDATE SET
                 name = excluded.name
                 "#,
-        (SqliteDeviceModel(model), name, json),
+        (SqliteDeviceModel(model), profile.name, json, right_json),
     )?;
     Ok(())
 }
@@ -67,11 +124,11 @@ pub fn upsert(
 pub fn bulk_upsert(
     connection: &mut Connection,
     model: DeviceModel,
-    profiles: Vec<(String, Vec<i16>)>,
+    profiles: Vec<EqualizerProfile>,
 ) -> Result<(), Error> {
     let tx = connection.transaction()?;
-    for (name, volume_adjustments) in profiles {
-        upsert(&tx, model, name, volume_adjustments)?;
+    for profile in profiles {
+        upsert(&tx, model, profile)?;
     }
     tx.commit()?;
     Ok(())
@@ -84,3 +141,68 @@ pub fn delete(connection: &Connection, model: DeviceModel, name: String) -> Resu
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::OpenSCQ30Database;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fetch_with_right_channel() {
+        let db = OpenSCQ30Database::new_in_memory().await.unwrap();
+        let profile = EqualizerProfile {
+            name: "Split".to_owned(),
+            volume_adjustments: vec![0, 0, 0, 0, 0, 0, 0, 0],
+            right_volume_adjustments: Some(vec![10, 20, 30, 40, 50, 60, 70, 80]),
+        };
+        db.upsert_equalizer_profile(DeviceModel::SoundcoreA3933, profile.clone())
+            .await
+            .unwrap();
+
+        let fetched = db
+            .fetch_equalizer_profile(DeviceModel::SoundcoreA3933, "Split".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(profile, fetched);
+        assert_eq!(&[10, 20, 30, 40, 50, 60, 70, 80], fetched.channel(1));
+    }
+
+    #[tokio::test]
+    async fn upsert_only_deduplicates_matching_channels() {
+        let db = OpenSCQ30Database::new_in_memory().await.unwrap();
+        let profiles = vec![
+            EqualizerProfile::new("Both".to_owned(), vec![0, 0, 0, 0, 0, 0, 0, 0]),
+            EqualizerProfile {
+                name: "Split".to_owned(),
+                volume_adjustments: vec![0, 0, 0, 0, 0, 0, 0, 0],
+                right_volume_adjustments: Some(vec![10, 20, 30, 40, 50, 60, 70, 80]),
+            },
+        ];
+        db.upsert_equalizer_profiles(DeviceModel::SoundcoreA3933, profiles.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            profiles,
+            db.fetch_all_equalizer_profiles(DeviceModel::SoundcoreA3933)
+                .await
+                .unwrap(),
+        );
+
+        // Same values for both channels as an existing profile, so it gets renamed
+        db.upsert_equalizer_profile(
+            DeviceModel::SoundcoreA3933,
+            EqualizerProfile::new("Renamed".to_owned(), vec![0, 0, 0, 0, 0, 0, 0, 0]),
+        )
+        .await
+        .unwrap();
+        let names = db
+            .fetch_all_equalizer_profiles(DeviceModel::SoundcoreA3933)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|profile| profile.name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Renamed", "Split"], names);
+    }
+}
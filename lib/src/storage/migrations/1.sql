@@ -0,0 +1,2 @@
+-- The address rather than the name (hci0) is stored since names depend on the order adapters were found in
+ALTER TABLE paired_device ADD COLUMN adapter_address TEXT;
@@ -0,0 +1,5 @@
+-- JSON null when both channels use volume_adjustments. It isn't an SQL NULL so that the unique index still treats
+-- profiles with identical channels as duplicates.
+ALTER TABLE equalizer_profile ADD COLUMN right_volume_adjustments TEXT NOT NULL DEFAULT 'null' CHECK(json_valid(right_volume_adjustments));
+DROP INDEX idx_equalizer_profile_volume_adjustments;
+CREATE UNIQUE INDEX idx_equalizer_profile_volume_adjustments ON equalizer_profile (device_model, volume_adjustments, right_volume_adjustments);
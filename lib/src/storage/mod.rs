@@ -1,3 +1,4 @@
+mod backup;
 mod equalizer_profile;
 mod migration;
 mod paired_device;
@@ -22,6 +23,8 @@ use crate::{
     api::settings::SettingId, devices::DeviceModel, macros::impl_from_source_error_with_location,
 };
 
+pub use backup::{BACKUP_VERSION, Backup, BackupFormatError, BackupImportMode};
+pub use equalizer_profile::EqualizerProfile;
 pub use paired_device::PairedDevice;
 pub use quick_preset::{QuickPreset, QuickPresetField};
 
@@ -225,6 +228,10 @@ commands!(
         model: DeviceModel,
         quick_preset: QuickPreset,
     ) -> Result<()>;
+    quick_preset::replace => fn replace_quick_preset(
+        model: DeviceModel,
+        quick_preset: QuickPreset,
+    ) -> Result<()>;
     quick_preset::toggle_field => fn toggle_quick_preset_field(
         model: DeviceModel,
         name: String,
@@ -235,18 +242,20 @@ commands!(
     equalizer_profile::fetch => fn fetch_equalizer_profile(
         model: DeviceModel,
         name: String,
-    ) -> Result<Vec<i16>>;
+    ) -> Result<EqualizerProfile>;
     equalizer_profile::fetch_all => fn fetch_all_equalizer_profiles(
         model: DeviceModel,
-    ) -> Result<Vec<(String, Vec<i16>)>>;
+    ) -> Result<Vec<EqualizerProfile>>;
+    equalizer_profile::fetch_models => fn fetch_equalizer_profile_models() -> Result<Vec<DeviceModel>>;
     equalizer_profile::upsert => fn upsert_equalizer_profile(
         model: DeviceModel,
-        name: String,
-        volume_adjustments: Vec<i16>,
+        profile: EqualizerProfile,
     ) -> Result<()>;
     equalizer_profile::bulk_upsert => fn upsert_equalizer_profiles(
         model: DeviceModel,
-        name: Vec<(String, Vec<i16>)>,
+        profiles: Vec<EqualizerProfile>,
     ) -> Result<()>;
     equalizer_profile::delete => fn delete_equalizer_profile(model: DeviceModel, name: String) -> Result<()>;
+    backup::export => fn export_backup() -> Result<Backup>;
+    backup::import => fn import_backup(backup: Backup, mode: BackupImportMode) -> Result<()>;
 );
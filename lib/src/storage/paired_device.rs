@@ -17,12 +17,19 @@ pub struct PairedDevice {
     pub mac_address: MacAddr6,
     pub model: DeviceModel,
     pub is_demo: bool,
+    /// The bluetooth adapter the device was paired through, or `None` to use any adapter.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serialization::optional_mac_addr"
+    )]
+    pub adapter_address: Option<MacAddr6>,
 }
 
 #[instrument(skip(connection))]
 pub fn fetch_all(connection: &Connection) -> Result<Vec<PairedDevice>, Error> {
     let mut statement = connection.prepare_cached(
-        "SELECT mac_address, model, is_demo FROM paired_device ORDER BY model ASC",
+        "SELECT mac_address, model, is_demo, adapter_address FROM paired_device ORDER BY model ASC",
     )?;
     let devices = statement
         .query(())?
@@ -30,10 +37,12 @@ pub fn fetch_all(connection: &Connection) -> Result<Vec<PairedDevice>, Error> {
             let mac_address: SqliteMacAddr6 = row.get("mac_address")?;
             let model: SqliteDeviceModel = row.get("model")?;
             let is_demo: bool = row.get("is_demo")?;
+            let adapter_address: Option<SqliteMacAddr6> = row.get("adapter_address")?;
             Ok(PairedDevice {
                 mac_address: mac_address.0,
                 model: model.0,
                 is_demo,
+                adapter_address: adapter_address.map(|address| address.0),
             })
         })
         .filter_map(|result| match result {
@@ -53,7 +62,7 @@ pub fn fetch(
     mac_address: MacAddr6,
 ) -> Result<Option<PairedDevice>, Error> {
     let mut statement = connection.prepare_cached(
-        "SELECT mac_address, model, is_demo FROM paired_device WHERE mac_address = ?1",
+        "SELECT mac_address, model, is_demo, adapter_address FROM paired_device WHERE mac_address = ?1",
     )?;
     let devices = statement
         .query([SqliteMacAddr6(mac_address)])?
@@ -61,10 +70,12 @@ pub fn fetch(
             let mac_address: SqliteMacAddr6 = row.get("mac_address")?;
             let model: SqliteDeviceModel = row.get("model")?;
             let is_demo: bool = row.get("is_demo")?;
+            let adapter_address: Option<SqliteMacAddr6> = row.get("adapter_address")?;
             Ok(PairedDevice {
                 mac_address: mac_address.0,
                 model: model.0,
                 is_demo,
+                adapter_address: adapter_address.map(|address| address.0),
             })
         })
         .find_map(|result| match result {
@@ -79,11 +90,12 @@ pub fn fetch(
 
 pub fn insert(connection: &Connection, paired_device: PairedDevice) -> Result<(), Error> {
     connection.execute(
-        "INSERT INTO paired_device (mac_address, model, is_demo) VALUES (?1, ?2, ?3)",
+        "INSERT INTO paired_device (mac_address, model, is_demo, adapter_address) VALUES (?1, ?2, ?3, ?4)",
         (
             SqliteMacAddr6(paired_device.mac_address),
             SqliteDeviceModel(paired_device.model),
             paired_device.is_demo,
+            paired_device.adapter_address.map(SqliteMacAddr6),
         ),
     )?;
     Ok(())
@@ -91,16 +103,18 @@ pub fn insert(connection: &Connection, paired_device: PairedDevice) -> Result<()
 
 pub fn upsert(connection: &Connection, paired_device: PairedDevice) -> Result<(), Error> {
     connection.execute(
-        r#"INSERT INTO paired_device (mac_address, model, is_demo)
-                    VALUES (?1, ?2, ?3)
+        r#"INSERT INTO paired_device (mac_address, model, is_demo, adapter_address)
+                    VALUES (?1, ?2, ?3, ?4)
                 ON CONFLICT(mac_address) DO UPDATE SET
                     model = excluded.model,
                     is_demo = excluded.is_demo,
+                    adapter_address = excluded.adapter_address,
                     created_at = strftime('%s')"#,
         (
             SqliteMacAddr6(paired_device.mac_address),
             SqliteDeviceModel(paired_device.model),
             paired_device.is_demo,
+            paired_device.adapter_address.map(SqliteMacAddr6),
         ),
     )?;
     Ok(())
@@ -132,16 +146,19 @@ mod tests {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
                 model: DeviceModel::SoundcoreA3028,
                 is_demo: false,
+                adapter_address: None,
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 2),
                 model: DeviceModel::SoundcoreA3033,
                 is_demo: false,
+                adapter_address: None,
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 3),
                 model: DeviceModel::SoundcoreA3029,
                 is_demo: false,
+                adapter_address: None,
             },
         ];
         for device in &paired_devices {
@@ -163,16 +180,19 @@ mod tests {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
                 model: DeviceModel::SoundcoreA3028,
                 is_demo: false,
+                adapter_address: None,
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 2),
                 model: DeviceModel::SoundcoreA3033,
                 is_demo: false,
+                adapter_address: Some(MacAddr6::new(0, 0x1a, 0x7d, 0xda, 0x71, 0x13)),
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 3),
                 model: DeviceModel::SoundcoreA3029,
                 is_demo: false,
+                adapter_address: None,
             },
         ];
         for device in paired_devices {
@@ -193,11 +213,13 @@ mod tests {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
                 model: DeviceModel::SoundcoreA3028,
                 is_demo: false,
+                adapter_address: None,
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 2),
                 model: DeviceModel::SoundcoreA3033,
                 is_demo: false,
+                adapter_address: Some(MacAddr6::new(0, 0x1a, 0x7d, 0xda, 0x71, 0x13)),
             },
         ];
 
@@ -206,6 +228,7 @@ mod tests {
             mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
             model: DeviceModel::SoundcoreA3004,
             is_demo: true,
+            adapter_address: None,
         })
         .await
         .unwrap();
@@ -220,6 +243,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deserialize_without_adapter_address() {
+        let paired_device: PairedDevice = serde_json::from_str(
+            r#"{"macAddress":"00:00:00:00:00:01","model":"SoundcoreA3028","isDemo":false}"#,
+        )
+        .unwrap();
+        assert_eq!(None, paired_device.adapter_address);
+    }
+
     #[tokio::test]
     async fn test_delete() {
         let db = OpenSCQ30Database::new_in_memory().await.unwrap();
@@ -228,11 +260,13 @@ mod tests {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 1),
                 model: DeviceModel::SoundcoreA3028,
                 is_demo: false,
+                adapter_address: None,
             },
             PairedDevice {
                 mac_address: MacAddr6::new(0, 0, 0, 0, 0, 2),
                 model: DeviceModel::SoundcoreA3033,
                 is_demo: false,
+                adapter_address: None,
             },
         ];
         for device in &paired_devices {
@@ -162,6 +162,24 @@ pub fn upsert(
     Ok(())
 }
 
+/// Inserts the QuickPreset, replacing any existing quick preset with the same name, including whether or not each
+/// field is enabled.
+pub fn replace(
+    connection: &Connection,
+    model: DeviceModel,
+    quick_preset: QuickPreset,
+) -> Result<(), Error> {
+    let fields_json = serde_json::to_string(&quick_preset.fields)?;
+    connection.execute(
+        r#"INSERT INTO quick_preset (device_model, name, fields)
+                VALUES (?1, ?2, jsonb(?3))
+            ON CONFLICT(device_model, name) DO UPDATE SET
+                fields = excluded.fields"#,
+        (SqliteDeviceModel(model), quick_preset.name, fields_json),
+    )?;
+    Ok(())
+}
+
 fn enabled_fields(
     connection: &Connection,
     model: DeviceModel,
@@ -622,4 +640,26 @@ mod tests {
             "should be not found: {err:?}",
         );
     }
+
+    #[tokio::test]
+    async fn replace_overwrites_enabled_fields() {
+        let db = OpenSCQ30Database::new_in_memory().await.unwrap();
+        let mut preset = test_data().remove(0);
+        db.upsert_quick_preset(DeviceModel::SoundcoreA3004, preset.clone())
+            .await
+            .unwrap();
+
+        for field in &mut preset.fields {
+            field.is_enabled = !field.is_enabled;
+        }
+        db.replace_quick_preset(DeviceModel::SoundcoreA3004, preset.clone())
+            .await
+            .unwrap();
+
+        let fetched_preset = db
+            .fetch_quick_preset(DeviceModel::SoundcoreA3004, preset.name.clone())
+            .await
+            .unwrap();
+        assert_eq!(preset, fetched_preset);
+    }
 }
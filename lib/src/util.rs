@@ -1,3 +1,6 @@
 mod abort_on_drop;
+pub mod base64url;
+mod crc32;
 
 pub use abort_on_drop::AbortOnDropHandle;
+pub use crc32::crc32;
@@ -0,0 +1,111 @@
+//! base64url from [RFC 4648 section 5](https://datatracker.ietf.org/doc/html/rfc4648#section-5), without padding, since
+//! `=` gets in the way of copying and pasting.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("invalid character {0:?}")]
+    InvalidCharacter(char),
+    #[error("invalid length {0}, a single leftover character can't make up a whole byte")]
+    InvalidLength(usize),
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | (u32::from(*byte) << (16 - i * 8))
+        });
+        // n bytes need n + 1 characters, since each character holds 6 bits
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - i * 6)) & 0x3F;
+            encoded.push(ALPHABET[index as usize].into());
+        }
+    }
+    encoded
+}
+
+pub fn decode(encoded: &str) -> Result<Vec<u8>, DecodeError> {
+    let sextets = encoded
+        .chars()
+        .map(|c| {
+            u8::try_from(c)
+                .ok()
+                .and_then(|byte| ALPHABET.iter().position(|b| *b == byte))
+                .map(|index| index as u32)
+                .ok_or(DecodeError::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if sextets.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength(sextets.len()));
+    }
+    let mut bytes = Vec::with_capacity(sextets.len() * 3 / 4);
+    for chunk in sextets.chunks(4) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, sextet)| group | (sextet << (18 - i * 6)));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - i * 8)) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rfc_4648_test_vectors() {
+        for (decoded, encoded) in [
+            ("", ""),
+            ("f", "Zg"),
+            ("fo", "Zm8"),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg"),
+            ("fooba", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encoded, encode(decoded.as_bytes()));
+            assert_eq!(Ok(decoded.as_bytes().to_vec()), decode(encoded));
+        }
+    }
+
+    #[test]
+    fn uses_url_safe_alphabet() {
+        assert_eq!("-_8", encode(&[0xFB, 0xFF]));
+        assert_eq!(Ok(vec![0xFB, 0xFF]), decode("-_8"));
+    }
+
+    #[test]
+    fn round_trips_every_padding_length() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for len in 0..=12 {
+            let encoded = encode(&bytes[..len]);
+            // the characters that would otherwise be padding are left out
+            assert_eq!(len * 4 / 3 + usize::from(len % 3 != 0), encoded.len());
+            assert_eq!(Ok(&bytes[..len]), decode(&encoded).as_deref());
+        }
+    }
+
+    #[test]
+    fn round_trips_every_byte_value() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        assert_eq!(Ok(bytes.clone()), decode(&encode(&bytes)));
+    }
+
+    #[test]
+    fn rejects_single_leftover_character() {
+        assert_eq!(Err(DecodeError::InvalidLength(1)), decode("Z"));
+        assert_eq!(Err(DecodeError::InvalidLength(5)), decode("Zm9vY"));
+    }
+
+    #[test]
+    fn rejects_characters_outside_alphabet() {
+        assert_eq!(Err(DecodeError::InvalidCharacter('=')), decode("Zg=="));
+        assert_eq!(Err(DecodeError::InvalidCharacter('+')), decode("+/8"));
+        assert_eq!(Err(DecodeError::InvalidCharacter('♪')), decode("Zm♪v"));
+    }
+}